@@ -1 +1 @@
-
+fn main() {}
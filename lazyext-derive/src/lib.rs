@@ -0,0 +1,308 @@
+//! Derive macros for the extension traits of [`lazyext-slice`].
+//!
+//! [`lazyext-slice`]: https://docs.rs/lazyext-slice
+#![doc(html_root_url = "https://docs.rs/lazyext-derive/0.0.1")]
+#![deny(missing_docs)]
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Error, Fields, Ident, Index, Member};
+
+/// Derives `AsBytesRef` and `BytesExt` for a struct wrapping a byte buffer.
+///
+/// The struct must either have exactly one field, or mark the buffer field with `#[bytes]`.
+/// The field type must implement `AsBytesRef`, e.g. `Vec<u8>`, `Box<[u8]>`, `[u8; N]` or `Bytes`.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_derive::AsBytesRef;
+/// use lazyext_slice::BytesExt;
+///
+/// #[derive(AsBytesRef)]
+/// struct Key(Vec<u8>);
+///
+/// #[derive(AsBytesRef)]
+/// struct Entry {
+///     #[bytes]
+///     key: Vec<u8>,
+///     version: u64,
+/// }
+///
+/// let key = Key(b"hello".to_vec());
+/// assert!(key.has_prefix("he"));
+///
+/// let entry = Entry { key: b"world".to_vec(), version: 0 };
+/// assert_eq!(entry.longest_prefix("word"), b"wor");
+/// ```
+#[proc_macro_derive(AsBytesRef, attributes(bytes))]
+pub fn derive_as_bytes_ref(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_as_bytes_ref(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives `AsBytesMutRef` and `BytesMutExt` for a struct wrapping a mutable byte buffer.
+///
+/// The field is selected in the same way as for `#[derive(AsBytesRef)]`, which must be derived too.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_derive::{AsBytesMutRef, AsBytesRef};
+/// use lazyext_slice::BytesMutExt;
+///
+/// #[derive(AsBytesRef, AsBytesMutRef)]
+/// struct Block([u8; 8]);
+///
+/// let mut block = Block([0; 8]);
+/// assert_eq!(block.to_u32_slice_mut().len(), 2);
+/// ```
+#[proc_macro_derive(AsBytesMutRef, attributes(bytes))]
+pub fn derive_as_bytes_mut_ref(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_as_bytes_mut_ref(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives the typed slice traits (e.g. `AsU32Slice` and `U32SliceExt`) for a struct wrapping
+/// a numeric buffer.
+///
+/// The element type is selected by `#[slice(ty)]`, on the struct or on the buffer field.
+/// The struct must either have exactly one field, or mark the buffer field with `#[slice(ty)]`.
+/// The field type must implement `AsRef<[ty]>`, e.g. `Vec<ty>`, `Box<[ty]>` or `[ty; N]`.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_derive::AsSlice;
+/// use lazyext_slice::F32SliceExt;
+///
+/// #[derive(AsSlice)]
+/// #[slice(f32)]
+/// struct SampleBuffer(Vec<f32>);
+///
+/// let buf = SampleBuffer(vec![1.0, 2.0]);
+/// assert_eq!(buf.cast_bytes().len(), 8);
+/// ```
+#[proc_macro_derive(AsSlice, attributes(slice))]
+pub fn derive_as_slice(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_as_slice(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives the mutable typed slice traits (e.g. `AsU32SliceMut` and `U32SliceMutExt`) for a struct
+/// wrapping a numeric buffer.
+///
+/// The element type and field are selected in the same way as for `#[derive(AsSlice)]`,
+/// which must be derived too. The field type must implement `AsMut<[ty]>`.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_derive::{AsSlice, AsSliceMut};
+/// use lazyext_slice::U32SliceMutExt;
+///
+/// #[derive(AsSlice, AsSliceMut)]
+/// struct Offsets {
+///     #[slice(u32)]
+///     offsets: Vec<u32>,
+///     base: u64,
+/// }
+///
+/// let mut offsets = Offsets { offsets: vec![0; 4], base: 0 };
+/// offsets.cast_bytes_mut()[0] = 1;
+/// assert_eq!(offsets.offsets[0], u32::from_ne_bytes([1, 0, 0, 0]));
+/// ```
+#[proc_macro_derive(AsSliceMut, attributes(slice))]
+pub fn derive_as_slice_mut(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_as_slice_mut(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_as_bytes_ref(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let field = select_field(input, "bytes")?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::lazyext_slice::AsBytesRef for #name #ty_generics #where_clause {
+            #[inline]
+            fn as_bytes_ref(&self) -> &[u8] {
+                ::lazyext_slice::AsBytesRef::as_bytes_ref(&self.#field)
+            }
+        }
+
+        impl #impl_generics ::lazyext_slice::BytesExt for #name #ty_generics #where_clause {}
+    })
+}
+
+fn expand_as_bytes_mut_ref(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let field = select_field(input, "bytes")?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::lazyext_slice::AsBytesMutRef for #name #ty_generics #where_clause {
+            #[inline]
+            fn as_bytes_mut_ref(&mut self) -> &mut [u8] {
+                ::lazyext_slice::AsBytesMutRef::as_bytes_mut_ref(&mut self.#field)
+            }
+        }
+
+        impl #impl_generics ::lazyext_slice::BytesMutExt for #name #ty_generics #where_clause {}
+    })
+}
+
+fn expand_as_slice(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let field = select_field(input, "slice")?;
+    let (ty, prefix) = slice_type(input)?;
+    let as_trait = Ident::new(&format!("As{}Slice", prefix), ty.span());
+    let ext_trait = Ident::new(&format!("{}SliceExt", prefix), ty.span());
+    let method = Ident::new(&format!("as_{}_slice", ty), ty.span());
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::lazyext_slice::#as_trait for #name #ty_generics #where_clause {
+            #[inline]
+            fn #method(&self) -> &[#ty] {
+                ::core::convert::AsRef::<[#ty]>::as_ref(&self.#field)
+            }
+        }
+
+        impl #impl_generics ::lazyext_slice::#ext_trait for #name #ty_generics #where_clause {}
+    })
+}
+
+fn expand_as_slice_mut(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let field = select_field(input, "slice")?;
+    let (ty, prefix) = slice_type(input)?;
+    let as_trait = Ident::new(&format!("As{}SliceMut", prefix), ty.span());
+    let ext_trait = Ident::new(&format!("{}SliceMutExt", prefix), ty.span());
+    let method = Ident::new(&format!("as_{}_slice_mut", ty), ty.span());
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::lazyext_slice::#as_trait for #name #ty_generics #where_clause {
+            #[inline]
+            fn #method(&mut self) -> &mut [#ty] {
+                ::core::convert::AsMut::<[#ty]>::as_mut(&mut self.#field)
+            }
+        }
+
+        impl #impl_generics ::lazyext_slice::#ext_trait for #name #ty_generics #where_clause {}
+    })
+}
+
+/// Returns the element type given by `#[slice(ty)]` and the prefix of its trait names (e.g. `USize`).
+fn slice_type(input: &DeriveInput) -> syn::Result<(Ident, &'static str)> {
+    let mut attrs = input.attrs.iter().filter(|a| a.path().is_ident("slice"));
+    if let Data::Struct(data) = &input.data {
+        let field_attrs = data
+            .fields
+            .iter()
+            .flat_map(|f| f.attrs.iter())
+            .filter(|a| a.path().is_ident("slice"));
+        return match attrs.next().or_else(|| field_attrs.clone().next()) {
+            Some(attr) => parse_slice_type(attr),
+            None => Err(Error::new_spanned(
+                &input.ident,
+                "missing #[slice(ty)] attribute, e.g. #[slice(u32)]",
+            )),
+        };
+    }
+    Err(Error::new_spanned(
+        &input.ident,
+        "lazyext-derive only supports structs",
+    ))
+}
+
+fn parse_slice_type(attr: &Attribute) -> syn::Result<(Ident, &'static str)> {
+    let ty: Ident = attr.parse_args()?;
+    let prefix = match ty.to_string().as_str() {
+        "u16" => "U16",
+        "u32" => "U32",
+        "u64" => "U64",
+        "u128" => "U128",
+        "usize" => "USize",
+        "i8" => "I8",
+        "i16" => "I16",
+        "i32" => "I32",
+        "i64" => "I64",
+        "i128" => "I128",
+        "isize" => "ISize",
+        "f32" => "F32",
+        "f64" => "F64",
+        "u8" => {
+            return Err(Error::new_spanned(
+                ty,
+                "use #[derive(AsBytesRef)] for u8 buffers",
+            ))
+        }
+        _ => {
+            return Err(Error::new_spanned(
+                ty,
+                "unsupported slice type, expected one of u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32 or f64",
+            ))
+        }
+    };
+    Ok((ty, prefix))
+}
+
+/// Finds the only field of the struct, or the one marked with `#[attr]`.
+fn select_field(input: &DeriveInput, attr: &str) -> syn::Result<Member> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "lazyext-derive only supports structs",
+            ))
+        }
+    };
+
+    let members = match fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|f| (Member::Named(f.ident.clone().unwrap()), f))
+            .collect::<Vec<_>>(),
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(i, f)| (Member::Unnamed(Index::from(i)), f))
+            .collect::<Vec<_>>(),
+        Fields::Unit => Vec::new(),
+    };
+
+    let mut marked = members
+        .iter()
+        .filter(|(_, f)| f.attrs.iter().any(|a| a.path().is_ident(attr)));
+    match (marked.next(), marked.next()) {
+        (Some((member, _)), None) => return Ok(member.clone()),
+        (Some(_), Some((_, f))) => {
+            return Err(Error::new_spanned(
+                f,
+                format!("only one field can be marked with #[{}]", attr),
+            ))
+        }
+        _ => {}
+    }
+
+    match members.as_slice() {
+        [(member, _)] => Ok(member.clone()),
+        _ => Err(Error::new_spanned(
+            &input.ident,
+            format!(
+                "struct must have exactly one field, or mark one field with #[{}]",
+                attr
+            ),
+        )),
+    }
+}
@@ -0,0 +1,41 @@
+use lazyext_derive::{AsBytesMutRef, AsBytesRef};
+use lazyext_slice::{AsBytesRef, BytesExt, BytesMutExt};
+
+#[derive(AsBytesRef, AsBytesMutRef)]
+struct Key(Vec<u8>);
+
+#[derive(AsBytesRef)]
+struct Value<'a> {
+    #[bytes]
+    data: &'a [u8],
+    _version: u64,
+}
+
+#[derive(AsBytesRef, AsBytesMutRef)]
+struct Wrapper<T: AsBytesRef + lazyext_slice::AsBytesMutRef> {
+    inner: T,
+}
+
+#[test]
+fn test_derive_newtype() {
+    let mut key = Key(vec![0, 1, 0, 2]);
+    assert_eq!(key.as_bytes_ref(), &[0, 1, 0, 2]);
+    assert!(key.has_prefix([0u8, 1]));
+    assert_eq!(key.to_be_u16_vec(), vec![1, 2]);
+    assert_eq!(key.to_u16_slice_mut().len(), 2);
+}
+
+#[test]
+fn test_derive_marked_field() {
+    let value = Value {
+        data: b"hello",
+        _version: 1,
+    };
+    assert!(value.bytes_eq("hello"));
+}
+
+#[test]
+fn test_derive_generic() {
+    let w = Wrapper { inner: [1u8; 4] };
+    assert_eq!(w.to_ne_u32_vec(), vec![u32::from_ne_bytes([1; 4])]);
+}
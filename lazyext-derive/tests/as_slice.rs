@@ -0,0 +1,41 @@
+use lazyext_derive::{AsSlice, AsSliceMut};
+use lazyext_slice::{AsF32Slice, AsI16SliceMut, F32SliceExt, I16SliceMutExt, USizeSliceExt};
+
+#[derive(AsSlice)]
+#[slice(f32)]
+struct SampleBuffer(Vec<f32>);
+
+#[derive(AsSlice, AsSliceMut)]
+struct Pcm {
+    #[slice(i16)]
+    samples: Box<[i16]>,
+    _rate: u32,
+}
+
+#[derive(AsSlice)]
+#[slice(usize)]
+struct Offsets<const N: usize>([usize; N]);
+
+#[test]
+fn test_derive_as_slice() {
+    let buf = SampleBuffer(vec![1.0, 2.0]);
+    assert_eq!(buf.as_f32_slice(), &[1.0, 2.0]);
+    assert_eq!(buf.to_le_u8_vec(), [1.0f32.to_le_bytes(), 2.0f32.to_le_bytes()].concat());
+}
+
+#[test]
+fn test_derive_as_slice_mut() {
+    let mut pcm = Pcm {
+        samples: vec![0i16; 4].into_boxed_slice(),
+        _rate: 44100,
+    };
+    pcm.as_i16_slice_mut()[1] = 7;
+    assert_eq!(pcm.samples[1], 7);
+    assert_eq!(pcm.cast_bytes_mut().len(), 8);
+}
+
+#[test]
+fn test_derive_const_generic() {
+    let offsets = Offsets([1usize, 2, 3]);
+    assert!(offsets.has_prefix([1usize, 2]));
+}
@@ -1,3 +1,6 @@
+#[doc(hidden)]
+pub use paste as __paste;
+
 /// `#[cfg(windows)]`
 #[macro_export]
 macro_rules! cfg_windows {
@@ -22,6 +25,110 @@ macro_rules! cfg_unix {
     }
 }
 
+/// `#[cfg(target_arch = "wasm32")]`
+#[macro_export]
+macro_rules! cfg_wasm {
+    ($($item:item)*) => {
+        $(
+            #[cfg(target_arch = "wasm32")]
+            #[cfg_attr(docsrs, doc(cfg(target_arch = "wasm32")))]
+            $item
+        )*
+    }
+}
+
+/// `#[cfg(target_arch = "x86_64")]`
+#[macro_export]
+macro_rules! cfg_x86_64 {
+    ($($item:item)*) => {
+        $(
+            #[cfg(target_arch = "x86_64")]
+            #[cfg_attr(docsrs, doc(cfg(target_arch = "x86_64")))]
+            $item
+        )*
+    }
+}
+
+/// `#[cfg(target_arch = "aarch64")]`
+#[macro_export]
+macro_rules! cfg_aarch64 {
+    ($($item:item)*) => {
+        $(
+            #[cfg(target_arch = "aarch64")]
+            #[cfg_attr(docsrs, doc(cfg(target_arch = "aarch64")))]
+            $item
+        )*
+    }
+}
+
+/// `#[cfg(target_pointer_width = "32")]`
+#[macro_export]
+macro_rules! cfg_32bit {
+    ($($item:item)*) => {
+        $(
+            #[cfg(target_pointer_width = "32")]
+            #[cfg_attr(docsrs, doc(cfg(target_pointer_width = "32")))]
+            $item
+        )*
+    }
+}
+
+/// `#[cfg(target_pointer_width = "64")]`
+#[macro_export]
+macro_rules! cfg_64bit {
+    ($($item:item)*) => {
+        $(
+            #[cfg(target_pointer_width = "64")]
+            #[cfg_attr(docsrs, doc(cfg(target_pointer_width = "64")))]
+            $item
+        )*
+    }
+}
+
+/// `#[cfg(loom)]`
+#[macro_export]
+macro_rules! cfg_loom {
+    ($($item:item)*) => {
+        $(
+            #[cfg(loom)]
+            $item
+        )*
+    }
+}
+
+/// `#[cfg(not(loom))]`
+#[macro_export]
+macro_rules! cfg_not_loom {
+    ($($item:item)*) => {
+        $(
+            #[cfg(not(loom))]
+            $item
+        )*
+    }
+}
+
+/// `#[cfg(miri)]`
+#[macro_export]
+macro_rules! cfg_miri {
+    ($($item:item)*) => {
+        $(
+            #[cfg(miri)]
+            $item
+        )*
+    }
+}
+
+/// `#[cfg(not(miri))]`
+#[macro_export]
+macro_rules! cfg_not_miri {
+    ($($item:item)*) => {
+        $(
+            #[cfg(not(miri))]
+            $item
+        )*
+    }
+}
+
 /// `#[cfg(test)]`
 #[macro_export]
 macro_rules! cfg_test {
@@ -33,3 +140,440 @@ macro_rules! cfg_test {
         )*
     }
 }
+
+/// `#[cfg(feature = "...")]`
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_macros::cfg_feature;
+///
+/// cfg_feature!("std", {
+///     pub fn only_with_std() {}
+/// });
+/// ```
+#[macro_export]
+macro_rules! cfg_feature {
+    ($feature:literal, { $($item:item)* }) => {
+        $(
+            #[cfg(feature = $feature)]
+            #[cfg_attr(docsrs, doc(cfg(feature = $feature)))]
+            $item
+        )*
+    }
+}
+
+/// `#[cfg(feature = "...")]` for statements and expressions.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_macros::cfg_feature_expr;
+///
+/// let mut x = 0;
+/// cfg_feature_expr!("std", {
+///     x += 1;
+/// });
+/// # let _ = x;
+/// ```
+#[macro_export]
+macro_rules! cfg_feature_expr {
+    ($feature:literal, { $($expr:expr;)* }) => {
+        $(
+            #[cfg(feature = $feature)]
+            $expr;
+        )*
+    }
+}
+
+/// `#[cfg(not(feature = "..."))]` for statements and expressions.
+#[macro_export]
+macro_rules! cfg_not_feature_expr {
+    ($feature:literal, { $($expr:expr;)* }) => {
+        $(
+            #[cfg(not(feature = $feature))]
+            $expr;
+        )*
+    }
+}
+
+/// Implements `AsBytesRef` + `BytesExt` (and optionally `AsBytesMutRef` + `BytesMutExt`)
+/// of `lazyext-slice` for a type, for the cases where `#[derive(AsBytesRef)]` cannot be applied
+/// (e.g. foreign field access, feature-gated types).
+///
+/// The crate using this macro must depend on `lazyext-slice`.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_macros::impl_bytes_ext;
+/// use lazyext_slice::{BytesExt, BytesMutExt};
+///
+/// struct Page {
+///     header: u32,
+///     data: Vec<u8>,
+/// }
+///
+/// struct Frame(Vec<u8>);
+///
+/// impl_bytes_ext!(Page => |s| s.data.as_slice());
+/// impl_bytes_ext!(Frame => |s| s.0.as_slice(), mut |s| s.0.as_mut_slice());
+///
+/// let page = Page { header: 0, data: b"hello".to_vec() };
+/// assert!(page.has_prefix("he"));
+///
+/// let mut frame = Frame(vec![0; 4]);
+/// assert_eq!(frame.to_u32_slice_mut().len(), 1);
+/// ```
+#[macro_export]
+macro_rules! impl_bytes_ext {
+    ($ty:ty => |$this:ident| $body:expr) => {
+        impl ::lazyext_slice::AsBytesRef for $ty {
+            #[inline]
+            fn as_bytes_ref(&self) -> &[u8] {
+                let $this = self;
+                $body
+            }
+        }
+
+        impl ::lazyext_slice::BytesExt for $ty {}
+    };
+    ($ty:ty => |$this:ident| $body:expr, mut |$this_mut:ident| $body_mut:expr) => {
+        $crate::impl_bytes_ext!($ty => |$this| $body);
+
+        impl ::lazyext_slice::AsBytesMutRef for $ty {
+            #[inline]
+            fn as_bytes_mut_ref(&mut self) -> &mut [u8] {
+                let $this_mut = self;
+                $body_mut
+            }
+        }
+
+        impl ::lazyext_slice::BytesMutExt for $ty {}
+    };
+}
+
+/// Generates the full `{be, le, ne}` × types matrix of unit tests.
+///
+/// The test body is a macro taking the endian (`be`, `le` or `ne`) and the type as idents,
+/// for every combination a `#[test] fn <body>_<endian>_<type>()` calling `body!(endian, type)` is generated.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_macros::test_endian_matrix;
+///
+/// macro_rules! roundtrip {
+///     ($endian:ident, $ty:ident) => {
+///         lazyext_macros::__paste::paste! {
+///             let v: $ty = 42;
+///             assert_eq!($ty::[<from_ $endian _bytes>](v.[<to_ $endian _bytes>]()), v);
+///         }
+///     };
+/// }
+///
+/// test_endian_matrix!(roundtrip, [u16, u32, i64]);
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! test_endian_matrix {
+    ($body:ident, [$($ty:ident),+ $(,)?]) => {
+        $crate::test_endian_matrix!(@endian $body, be, [$($ty),+]);
+        $crate::test_endian_matrix!(@endian $body, le, [$($ty),+]);
+        $crate::test_endian_matrix!(@endian $body, ne, [$($ty),+]);
+    };
+    (@endian $body:ident, $endian:ident, [$($ty:ident),+]) => {
+        $crate::__paste::paste! {
+            $(
+                #[test]
+                fn [<$body _ $endian _ $ty>]() {
+                    $body!($endian, $ty);
+                }
+            )+
+        }
+    };
+}
+
+/// Asserts the size of a type at compile time.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_macros::const_assert_size;
+///
+/// const_assert_size!(u32, 4);
+/// const_assert_size!([u16; 3], 6);
+/// ```
+///
+/// ```compile_fail
+/// use lazyext_macros::const_assert_size;
+///
+/// const_assert_size!(u32, 8);
+/// ```
+#[macro_export]
+macro_rules! const_assert_size {
+    ($ty:ty, $size:expr $(,)?) => {
+        const _: () = assert!(
+            ::core::mem::size_of::<$ty>() == $size,
+            concat!("size of `", stringify!($ty), "` is not ", stringify!($size))
+        );
+    };
+}
+
+/// Asserts the alignment of a type at compile time, either exactly (`n`) or as an upper bound (`<= n`).
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_macros::const_assert_align;
+///
+/// const_assert_align!(u8, 1);
+/// const_assert_align!(u64, <= 8);
+/// ```
+///
+/// ```compile_fail
+/// use lazyext_macros::const_assert_align;
+///
+/// const_assert_align!(u32, 1);
+/// ```
+#[macro_export]
+macro_rules! const_assert_align {
+    ($ty:ty, <= $align:expr $(,)?) => {
+        const _: () = assert!(
+            ::core::mem::align_of::<$ty>() <= $align,
+            concat!("alignment of `", stringify!($ty), "` is greater than ", stringify!($align))
+        );
+    };
+    ($ty:ty, $align:expr $(,)?) => {
+        const _: () = assert!(
+            ::core::mem::align_of::<$ty>() == $align,
+            concat!("alignment of `", stringify!($ty), "` is not ", stringify!($align))
+        );
+    };
+}
+
+/// Generates a `[u8; N]` backed newtype for fixed-width binary identifiers (hashes, handles, UUIDs).
+///
+/// The generated type derives `Clone`, `Copy`, `PartialEq`, `Eq`, `Hash`, `PartialOrd` and `Ord`,
+/// and implements `AsBytesRef` + `BytesExt` of `lazyext-slice`, hex `Debug`, `AsRef<[u8]>`,
+/// `From<[u8; N]>`, `new`, `as_array` and a fallible `try_from_slice`.
+///
+/// The crate using this macro must depend on `lazyext-slice`.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_macros::byte_newtype;
+/// use lazyext_slice::BytesExt;
+///
+/// byte_newtype!(
+///     /// A handle to a block in a table file.
+///     pub BlockHandle, 4
+/// );
+///
+/// let handle = BlockHandle::from([0xde, 0xad, 0xbe, 0xef]);
+/// assert_eq!(format!("{:?}", handle), "BlockHandle(deadbeef)");
+/// assert!(handle.has_prefix([0xde]));
+/// assert_eq!(BlockHandle::try_from_slice(&[0xde, 0xad, 0xbe, 0xef]).unwrap(), handle);
+/// assert!(BlockHandle::try_from_slice(&[0; 3]).is_err());
+/// ```
+#[macro_export]
+macro_rules! byte_newtype {
+    ($(#[$meta:meta])* $vis:vis $name:ident, $len:expr $(,)?) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[repr(transparent)]
+        $vis struct $name([u8; $len]);
+
+        impl $name {
+            /// The size of the identifier in bytes.
+            pub const SIZE: usize = $len;
+
+            /// Creates a new identifier from the given bytes.
+            #[inline]
+            pub const fn new(bytes: [u8; $len]) -> Self {
+                Self(bytes)
+            }
+
+            /// Returns the underlying bytes.
+            #[inline]
+            pub const fn as_array(&self) -> &[u8; $len] {
+                &self.0
+            }
+
+            /// Copies the identifier from a slice, fails if the length of the slice is not `SIZE`.
+            #[inline]
+            pub fn try_from_slice(
+                src: &[u8],
+            ) -> ::core::result::Result<Self, ::core::array::TryFromSliceError> {
+                <[u8; $len] as ::core::convert::TryFrom<&[u8]>>::try_from(src).map(Self)
+            }
+        }
+
+        impl ::core::convert::From<[u8; $len]> for $name {
+            #[inline]
+            fn from(bytes: [u8; $len]) -> Self {
+                Self(bytes)
+            }
+        }
+
+        impl ::core::convert::From<$name> for [u8; $len] {
+            #[inline]
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl ::core::convert::AsRef<[u8]> for $name {
+            #[inline]
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl ::core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(concat!(stringify!($name), "("))?;
+                for b in self.0.iter() {
+                    write!(f, "{:02x}", b)?;
+                }
+                f.write_str(")")
+            }
+        }
+
+        $crate::impl_bytes_ext!($name => |s| &s.0);
+    };
+}
+
+/// Generates a zero-copy accessor struct over a fixed binary layout, with a typed getter (and a
+/// `set_` setter for mutable buffers) per field and a `SIZE` constant.
+///
+/// Each field is declared as `name: type endian`, with a primitive numeric type and one of
+/// `be`, `le` or `ne`. The fields are packed in order, without padding. The generated
+/// `Name<B>` wraps any `B: AsBytesRef` (setters need `B: AsBytesMutRef`), checks once in `new`
+/// that the buffer holds at least `SIZE` bytes, and implements `Debug` with the decoded fields.
+///
+/// The crate using this macro must depend on `lazyext-slice`.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_macros::binary_layout;
+///
+/// binary_layout!(
+///     /// The header of a block.
+///     pub BlockHeader {
+///         magic: u32 be,
+///         version: u16 le,
+///         flags: u8 ne,
+///         len: u64 be,
+///     }
+/// );
+///
+/// assert_eq!(BlockHeader::<&[u8]>::SIZE, 15);
+///
+/// let mut buf = [0u8; 16];
+/// let mut header = BlockHeader::new(&mut buf[..]).unwrap();
+/// header.set_magic(0xcafe_babe);
+/// header.set_version(2);
+/// header.set_len(1024);
+/// assert_eq!(header.magic(), 0xcafe_babe);
+/// assert_eq!(&buf[..6], &[0xca, 0xfe, 0xba, 0xbe, 2, 0]);
+///
+/// let header = BlockHeader::new(&buf[..]).unwrap();
+/// assert_eq!(header.len(), 1024);
+/// assert_eq!(
+///     format!("{:?}", header),
+///     "BlockHeader { magic: 3405691582, version: 2, flags: 0, len: 1024 }"
+/// );
+/// assert!(BlockHeader::new(&buf[..4]).is_err());
+/// ```
+#[macro_export]
+macro_rules! binary_layout {
+    (@getters $offset:expr;) => {};
+    (@getters $offset:expr; $field:ident: $ty:ident $endian:ident $(, $rest_field:ident: $rest_ty:ident $rest_endian:ident)*) => {
+        $crate::__paste::paste! {
+            #[doc = concat!("Reads `", stringify!($field), "` (", stringify!($ty), ", ", stringify!($endian), ").")]
+            #[inline]
+            pub fn $field(&self) -> $ty {
+                const OFFSET: usize = $offset;
+                let src = &::lazyext_slice::AsBytesRef::as_bytes_ref(&self.buf)
+                    [OFFSET..OFFSET + ::core::mem::size_of::<$ty>()];
+                match <[u8; ::core::mem::size_of::<$ty>()] as ::core::convert::TryFrom<&[u8]>>::try_from(src) {
+                    Ok(bytes) => $ty::[<from_ $endian _bytes>](bytes),
+                    Err(_) => unreachable!(),
+                }
+            }
+        }
+
+        $crate::binary_layout!(@getters $offset + ::core::mem::size_of::<$ty>(); $($rest_field: $rest_ty $rest_endian),*);
+    };
+    (@setters $offset:expr;) => {};
+    (@setters $offset:expr; $field:ident: $ty:ident $endian:ident $(, $rest_field:ident: $rest_ty:ident $rest_endian:ident)*) => {
+        $crate::__paste::paste! {
+            #[doc = concat!("Writes `", stringify!($field), "` (", stringify!($ty), ", ", stringify!($endian), ").")]
+            #[inline]
+            pub fn [<set_ $field>](&mut self, v: $ty) {
+                const OFFSET: usize = $offset;
+                ::lazyext_slice::AsBytesMutRef::as_bytes_mut_ref(&mut self.buf)
+                    [OFFSET..OFFSET + ::core::mem::size_of::<$ty>()]
+                    .copy_from_slice(&v.[<to_ $endian _bytes>]());
+            }
+        }
+
+        $crate::binary_layout!(@setters $offset + ::core::mem::size_of::<$ty>(); $($rest_field: $rest_ty $rest_endian),*);
+    };
+    ($(#[$meta:meta])* $vis:vis $name:ident { $($field:ident: $ty:ident $endian:ident),+ $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy)]
+        $vis struct $name<B> {
+            buf: B,
+        }
+
+        impl<B> $name<B> {
+            /// The size of the layout in bytes.
+            pub const SIZE: usize = 0 $(+ ::core::mem::size_of::<$ty>())+;
+
+            /// Returns the wrapped buffer.
+            #[inline]
+            pub fn into_inner(self) -> B {
+                self.buf
+            }
+        }
+
+        impl<B: ::lazyext_slice::AsBytesRef> $name<B> {
+            /// Wraps `buf`, fails if it is shorter than `SIZE`.
+            pub fn new(buf: B) -> ::core::result::Result<Self, ::lazyext_slice::ReadError> {
+                let remaining = ::lazyext_slice::AsBytesRef::as_bytes_ref(&buf).len();
+                if remaining < Self::SIZE {
+                    return Err(::lazyext_slice::ReadError::UnexpectedEof {
+                        offset: 0,
+                        needed: Self::SIZE,
+                        remaining,
+                    });
+                }
+                Ok(Self { buf })
+            }
+
+            /// Returns the bytes of the layout.
+            #[inline]
+            pub fn as_bytes(&self) -> &[u8] {
+                &::lazyext_slice::AsBytesRef::as_bytes_ref(&self.buf)[..Self::SIZE]
+            }
+
+            $crate::binary_layout!(@getters 0; $($field: $ty $endian),+);
+        }
+
+        impl<B: ::lazyext_slice::AsBytesMutRef> $name<B> {
+            $crate::binary_layout!(@setters 0; $($field: $ty $endian),+);
+        }
+
+        impl<B: ::lazyext_slice::AsBytesRef> ::core::fmt::Debug for $name<B> {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    $(.field(stringify!($field), &self.$field()))+
+                    .finish()
+            }
+        }
+    };
+}
@@ -0,0 +1,99 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use lazyext_slice::bench::{equal_pair, random_bytes, random_elems, BENCH_SIZES};
+use lazyext_slice::{const_eq, BeSlice, BytesExt, ExtendFromBytesExt, SliceReader, U32SliceExt};
+
+/// The number of elements decoded per chunk by the chunked APIs.
+const CHUNK_LEN: usize = 1024;
+
+fn decode_be_u32(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode_be_u32");
+    for size in BENCH_SIZES {
+        let src = random_bytes(size, 1);
+        group.throughput(Throughput::Bytes(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("to_vec", size), &src, |b, src| {
+            b.iter(|| black_box(src.to_be_u32_vec()))
+        });
+        group.bench_with_input(BenchmarkId::new("chunked_into", size), &src, |b, src| {
+            let mut buf = Vec::with_capacity(CHUNK_LEN);
+            b.iter(|| {
+                let mut chunks = src.chunked_to_be_u32_vec(CHUNK_LEN);
+                let mut sum = 0u32;
+                while chunks.next_chunk_into(&mut buf) {
+                    sum = buf.iter().fold(sum, |acc, v| acc.wrapping_add(*v));
+                }
+                black_box(sum)
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("extend", size), &src, |b, src| {
+            let mut buf: Vec<u32> = Vec::with_capacity(size / 4);
+            b.iter(|| {
+                buf.clear();
+                black_box(buf.extend_from_be_bytes(src.as_slice()).unwrap())
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("view_iter", size), &src, |b, src| {
+            b.iter(|| {
+                let view = BeSlice::<u32>::new(src).unwrap();
+                black_box(view.iter().fold(0u32, |acc, v| acc.wrapping_add(v)))
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("reader", size), &src, |b, src| {
+            b.iter(|| {
+                let mut reader = SliceReader::new(src);
+                let mut sum = 0u32;
+                while let Ok(v) = reader.read_u32_be() {
+                    sum = sum.wrapping_add(v);
+                }
+                black_box(sum)
+            })
+        });
+    }
+    group.finish();
+}
+
+fn decode_ne_u32(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode_ne_u32");
+    for size in BENCH_SIZES {
+        let elems: Vec<u32> = random_elems(size / 4, 2);
+        let src = elems.cast_bytes();
+        group.throughput(Throughput::Bytes(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("to_vec", size), src, |b, src| {
+            b.iter(|| black_box(src.to_ne_u32_vec()))
+        });
+        group.bench_with_input(BenchmarkId::new("cast_to", size), src, |b, src| {
+            b.iter(|| {
+                let vals = src.cast_to::<u32>();
+                black_box(vals.iter().fold(0u32, |acc, v| acc.wrapping_add(*v)))
+            })
+        });
+    }
+    group.finish();
+}
+
+fn compare(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compare");
+    for size in BENCH_SIZES {
+        let (a, b) = equal_pair(size, 3);
+        let mask = vec![0xffu8; size];
+        group.throughput(Throughput::Bytes(size as u64));
+
+        group.bench_function(BenchmarkId::new("slice_eq", size), |bench| {
+            bench.iter(|| black_box(black_box(&a[..]) == black_box(&b[..])))
+        });
+        group.bench_function(BenchmarkId::new("bytes_eq", size), |bench| {
+            bench.iter(|| black_box(black_box(&a).bytes_eq(black_box(&b))))
+        });
+        group.bench_function(BenchmarkId::new("const_eq", size), |bench| {
+            bench.iter(|| black_box(const_eq(black_box(&a), black_box(&b))))
+        });
+        group.bench_function(BenchmarkId::new("eq_masked", size), |bench| {
+            bench.iter(|| black_box(black_box(&a).eq_masked(black_box(&b), &mask)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, decode_be_u32, decode_ne_u32, compare);
+criterion_main!(benches);
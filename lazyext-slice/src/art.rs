@@ -0,0 +1,533 @@
+//! An adaptive radix tree over byte-string keys.
+//!
+//! Paths without branches are compressed into the prefix of a node, and the children of a node
+//! are a sorted array while it has few of them, and a 256-slot table indexed by the next byte of
+//! the key once it has many.
+
+use crate::{AsBytesRef, BytesExt};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+
+/// Above this many children, a node switches to the 256-slot table.
+const SPARSE_MAX: usize = 48;
+
+/// Below this many children, a node switches back to the sorted array.
+const DENSE_MIN: usize = 32;
+
+enum Children<V> {
+    /// Sorted by the dispatch byte.
+    Sparse(Vec<(u8, Node<V>)>),
+    Dense {
+        slots: Box<[Option<Box<Node<V>>>]>,
+        len: usize,
+    },
+}
+
+impl<V> Children<V> {
+    fn len(&self) -> usize {
+        match self {
+            Self::Sparse(children) => children.len(),
+            Self::Dense { len, .. } => *len,
+        }
+    }
+
+    fn get(&self, b: u8) -> Option<&Node<V>> {
+        match self {
+            Self::Sparse(children) => children
+                .binary_search_by_key(&b, |(k, _)| *k)
+                .ok()
+                .map(|i| &children[i].1),
+            Self::Dense { slots, .. } => slots[b as usize].as_deref(),
+        }
+    }
+
+    fn get_mut(&mut self, b: u8) -> Option<&mut Node<V>> {
+        match self {
+            Self::Sparse(children) => match children.binary_search_by_key(&b, |(k, _)| *k) {
+                Ok(i) => Some(&mut children[i].1),
+                Err(_) => None,
+            },
+            Self::Dense { slots, .. } => slots[b as usize].as_deref_mut(),
+        }
+    }
+
+    /// Adds the child of `b`, which must not exist.
+    fn insert(&mut self, b: u8, node: Node<V>) {
+        match self {
+            Self::Sparse(children) if children.len() == SPARSE_MAX => {
+                let mut slots = (0..256).map(|_| None).collect::<Box<[_]>>();
+                for (k, child) in children.drain(..) {
+                    slots[k as usize] = Some(Box::new(child));
+                }
+                slots[b as usize] = Some(Box::new(node));
+                *self = Self::Dense {
+                    slots,
+                    len: SPARSE_MAX + 1,
+                };
+            }
+            Self::Sparse(children) => {
+                let i = children.partition_point(|(k, _)| *k < b);
+                children.insert(i, (b, node));
+            }
+            Self::Dense { slots, len } => {
+                slots[b as usize] = Some(Box::new(node));
+                *len += 1;
+            }
+        }
+    }
+
+    fn remove(&mut self, b: u8) -> Option<Node<V>> {
+        match self {
+            Self::Sparse(children) => {
+                let i = children.binary_search_by_key(&b, |(k, _)| *k).ok()?;
+                Some(children.remove(i).1)
+            }
+            Self::Dense { slots, len } => {
+                let node = *slots[b as usize].take()?;
+                *len -= 1;
+                if *len < DENSE_MIN {
+                    let children = slots
+                        .iter_mut()
+                        .enumerate()
+                        .filter_map(|(k, slot)| slot.take().map(|child| (k as u8, *child)))
+                        .collect();
+                    *self = Self::Sparse(children);
+                }
+                Some(node)
+            }
+        }
+    }
+
+    /// Returns the first child at or after `cursor`, with the cursor past it.
+    fn next_from(&self, cursor: usize) -> Option<(usize, u8, &Node<V>)> {
+        match self {
+            Self::Sparse(children) => children.get(cursor).map(|(b, n)| (cursor + 1, *b, n)),
+            Self::Dense { slots, .. } => slots
+                .iter()
+                .enumerate()
+                .skip(cursor)
+                .find_map(|(b, slot)| slot.as_deref().map(|n| (b + 1, b as u8, n))),
+        }
+    }
+}
+
+struct Node<V> {
+    /// The compressed path from the dispatch byte of the node to its value and children.
+    prefix: Vec<u8>,
+    value: Option<V>,
+    children: Children<V>,
+}
+
+impl<V> Node<V> {
+    const fn empty() -> Self {
+        Self {
+            prefix: Vec::new(),
+            value: None,
+            children: Children::Sparse(Vec::new()),
+        }
+    }
+
+    fn insert(&mut self, key: &[u8], value: V) -> Option<V> {
+        let common = self.prefix.longest_prefix(key).len();
+        if common < self.prefix.len() {
+            self.split(common);
+        }
+        match key[common..].split_first() {
+            None => self.value.replace(value),
+            Some((&b, rest)) => match self.children.get_mut(b) {
+                Some(child) => child.insert(rest, value),
+                None => {
+                    let leaf = Node {
+                        prefix: rest.to_vec(),
+                        value: Some(value),
+                        children: Children::Sparse(Vec::new()),
+                    };
+                    self.children.insert(b, leaf);
+                    None
+                }
+            },
+        }
+    }
+
+    /// Moves the part of the prefix after `at`, the value and the children into a new child.
+    fn split(&mut self, at: usize) {
+        let suffix = self.prefix.split_off(at + 1);
+        let b = self.prefix.pop().unwrap();
+        let child = Node {
+            prefix: suffix,
+            value: self.value.take(),
+            children: core::mem::replace(&mut self.children, Children::Sparse(Vec::new())),
+        };
+        self.children.insert(b, child);
+    }
+
+    fn remove(&mut self, key: &[u8]) -> Option<V> {
+        let key = key.strip_prefix(self.prefix.as_slice())?;
+        let removed = match key.split_first() {
+            None => self.value.take()?,
+            Some((&b, rest)) => {
+                let child = self.children.get_mut(b)?;
+                let removed = child.remove(rest)?;
+                if child.value.is_none() && child.children.len() == 0 {
+                    self.children.remove(b);
+                }
+                removed
+            }
+        };
+
+        // Merge with the only child, so paths stay compressed.
+        if self.value.is_none() && self.children.len() == 1 {
+            let (_, b, _) = self.children.next_from(0).unwrap();
+            let child = self.children.remove(b).unwrap();
+            self.prefix.push(b);
+            self.prefix.extend_from_slice(&child.prefix);
+            self.value = child.value;
+            self.children = child.children;
+        }
+        Some(removed)
+    }
+}
+
+/// A sorted map from byte strings to values with prefix queries, for routers, IP prefix
+/// tables and pattern matchers.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::ArtMap;
+///
+/// let mut routes = ArtMap::new();
+/// routes.insert("/", "root");
+/// routes.insert("/api/", "api");
+/// routes.insert("/api/users", "users");
+/// routes.insert("/static/", "static");
+///
+/// assert_eq!(routes.get("/api/"), Some(&"api"));
+/// assert_eq!(routes.longest_prefix_match("/api/users/42"), Some((10, &"users")));
+/// assert_eq!(routes.longest_prefix_match("/apix"), Some((1, &"root")));
+///
+/// let api = routes.prefix_iter("/api").map(|(k, _)| k).collect::<Vec<_>>();
+/// assert_eq!(api, [b"/api/".to_vec(), b"/api/users".to_vec()]);
+/// ```
+pub struct ArtMap<V> {
+    root: Node<V>,
+    len: usize,
+}
+
+impl<V> Default for ArtMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> ArtMap<V> {
+    /// Creates an empty map.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            root: Node::empty(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of entries.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the map has no entry.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts an entry, and returns the previous value of `key`.
+    pub fn insert(&mut self, key: impl AsBytesRef, value: V) -> Option<V> {
+        let old = self.root.insert(key.as_bytes_ref(), value);
+        self.len += old.is_none() as usize;
+        old
+    }
+
+    /// Removes the entry of `key`, and returns its value.
+    pub fn remove(&mut self, key: impl AsBytesRef) -> Option<V> {
+        let removed = self.root.remove(key.as_bytes_ref())?;
+        self.len -= 1;
+        if self.len == 0 {
+            self.root = Node::empty();
+        }
+        Some(removed)
+    }
+
+    /// Returns the value of `key`.
+    pub fn get(&self, key: impl AsBytesRef) -> Option<&V> {
+        let mut key = key.as_bytes_ref();
+        let mut node = &self.root;
+        loop {
+            key = key.strip_prefix(node.prefix.as_slice())?;
+            match key.split_first() {
+                None => return node.value.as_ref(),
+                Some((&b, rest)) => {
+                    node = node.children.get(b)?;
+                    key = rest;
+                }
+            }
+        }
+    }
+
+    /// Returns a mutable reference to the value of `key`.
+    pub fn get_mut(&mut self, key: impl AsBytesRef) -> Option<&mut V> {
+        let mut key = key.as_bytes_ref();
+        let mut node = &mut self.root;
+        loop {
+            key = key.strip_prefix(node.prefix.as_slice())?;
+            match key.split_first() {
+                None => return node.value.as_mut(),
+                Some((&b, rest)) => {
+                    node = node.children.get_mut(b)?;
+                    key = rest;
+                }
+            }
+        }
+    }
+
+    /// Returns whether the map has an entry for `key`.
+    #[inline]
+    pub fn contains_key(&self, key: impl AsBytesRef) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Finds the longest key of the map which is a prefix of `key`, and returns its length and
+    /// value.
+    pub fn longest_prefix_match(&self, key: impl AsBytesRef) -> Option<(usize, &V)> {
+        let key = key.as_bytes_ref();
+        let mut best = None;
+        let mut node = &self.root;
+        let mut matched = 0;
+        while key[matched..].starts_with(&node.prefix) {
+            matched += node.prefix.len();
+            if let Some(value) = &node.value {
+                best = Some((matched, value));
+            }
+            match key.get(matched).and_then(|&b| node.children.get(b)) {
+                Some(child) => node = child,
+                None => break,
+            }
+            matched += 1;
+        }
+        best
+    }
+
+    /// Returns an iterator over the entries, in key order.
+    #[inline]
+    pub fn iter(&self) -> ArtIter<'_, V> {
+        ArtIter::new(Some(&self.root), self.root.prefix.clone())
+    }
+
+    /// Returns an iterator over the entries whose key starts with `prefix`, in key order.
+    pub fn prefix_iter(&self, prefix: impl AsBytesRef) -> ArtIter<'_, V> {
+        let mut rest = prefix.as_bytes_ref();
+        let mut path = Vec::with_capacity(rest.len());
+        let mut node = &self.root;
+        loop {
+            if rest.len() <= node.prefix.len() {
+                if !node.prefix.starts_with(rest) {
+                    return ArtIter::new(None, path);
+                }
+                path.extend_from_slice(&node.prefix);
+                return ArtIter::new(Some(node), path);
+            }
+            rest = match rest.strip_prefix(node.prefix.as_slice()) {
+                Some(rest) => rest,
+                None => return ArtIter::new(None, path),
+            };
+            path.extend_from_slice(&node.prefix);
+            match node.children.get(rest[0]) {
+                Some(child) => {
+                    path.push(rest[0]);
+                    node = child;
+                    rest = &rest[1..];
+                }
+                None => return ArtIter::new(None, path),
+            }
+        }
+    }
+}
+
+impl<V: fmt::Debug> fmt::Debug for ArtMap<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: AsBytesRef, V> Extend<(K, V)> for ArtMap<V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(k, v)| {
+            self.insert(k, v);
+        })
+    }
+}
+
+impl<K: AsBytesRef, V> FromIterator<(K, V)> for ArtMap<V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<'a, V> IntoIterator for &'a ArtMap<V> {
+    type Item = (Vec<u8>, &'a V);
+    type IntoIter = ArtIter<'a, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the entries of an [`ArtMap`], in key order.
+///
+/// [`ArtMap`]: struct.ArtMap.html
+pub struct ArtIter<'a, V> {
+    /// The nodes being visited, with the key length at their children and the next child.
+    stack: Vec<(&'a Node<V>, usize, usize)>,
+    /// The node to enter next, its key is already in `key`.
+    enter: Option<&'a Node<V>>,
+    key: Vec<u8>,
+}
+
+impl<'a, V> ArtIter<'a, V> {
+    fn new(enter: Option<&'a Node<V>>, key: Vec<u8>) -> Self {
+        Self {
+            stack: Vec::new(),
+            enter,
+            key,
+        }
+    }
+}
+
+impl<'a, V> Iterator for ArtIter<'a, V> {
+    type Item = (Vec<u8>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(node) = self.enter.take() {
+                self.stack.push((node, self.key.len(), 0));
+                if let Some(value) = &node.value {
+                    return Some((self.key.clone(), value));
+                }
+            }
+
+            let (node, key_len, cursor) = self.stack.last_mut()?;
+            self.key.truncate(*key_len);
+            match node.children.next_from(*cursor) {
+                Some((next, b, child)) => {
+                    *cursor = next;
+                    self.key.push(b);
+                    self.key.extend_from_slice(&child.prefix);
+                    self.enter = Some(child);
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+impl<V> FusedIterator for ArtIter<'_, V> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::String;
+
+    fn keys<V>(iter: ArtIter<'_, V>) -> Vec<String> {
+        iter.map(|(k, _)| String::from_utf8(k).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_art_insert_remove() {
+        let mut map = ArtMap::new();
+        for (i, k) in [
+            "romane", "romanus", "romulus", "rubens", "ruber", "rubicon", "r",
+        ]
+        .iter()
+        .enumerate()
+        {
+            assert_eq!(map.insert(*k, i), None);
+        }
+        assert_eq!(map.insert("ruber", 10), Some(4));
+        assert_eq!(map.len(), 7);
+        assert_eq!(map.get("ruber"), Some(&10));
+        assert_eq!(map.get("rom"), None);
+        assert_eq!(map.get("rubicons"), None);
+        *map.get_mut("r").unwrap() += 1;
+        assert_eq!(map.get("r"), Some(&7));
+        assert_eq!(
+            keys(map.iter()),
+            ["r", "romane", "romanus", "romulus", "rubens", "ruber", "rubicon"]
+        );
+
+        assert_eq!(map.remove("rom"), None);
+        assert_eq!(map.remove("romanus"), Some(1));
+        assert_eq!(map.remove("romulus"), Some(2));
+        // "romane" is the only key left under "ro", merged back into one node.
+        assert_eq!(map.root.children.get(b'o').unwrap().prefix, b"mane");
+        assert_eq!(map.len(), 5);
+        for k in ["r", "romane", "rubens", "ruber", "rubicon"] {
+            assert!(map.remove(k).is_some());
+        }
+        assert!(map.is_empty());
+        assert!(map.iter().next().is_none());
+    }
+
+    #[test]
+    fn test_art_prefixes() {
+        let map = ["", "a", "ab", "abc", "abd", "b"]
+            .iter()
+            .map(|k| (*k, k.len()))
+            .collect::<ArtMap<_>>();
+        assert_eq!(keys(map.prefix_iter("ab")), ["ab", "abc", "abd"]);
+        assert_eq!(keys(map.prefix_iter("abc")), ["abc"]);
+        assert_eq!(keys(map.prefix_iter("abe")), [] as [&str; 0]);
+        assert_eq!(keys(map.prefix_iter("")).len(), 6);
+
+        assert_eq!(map.longest_prefix_match("abcd"), Some((3, &3)));
+        assert_eq!(map.longest_prefix_match("abx"), Some((2, &2)));
+        assert_eq!(map.longest_prefix_match("c"), Some((0, &0)));
+        let map = [("10.0.", 1)].into_iter().collect::<ArtMap<_>>();
+        assert_eq!(map.longest_prefix_match("10.1.0.1"), None);
+    }
+
+    #[test]
+    fn test_art_dense_nodes() {
+        let mut map = ArtMap::new();
+        map.insert("z", 0);
+        for b in 0..=255u8 {
+            map.insert([b'k', b], b);
+        }
+        assert!(matches!(
+            map.root.children.get(b'k').unwrap().children,
+            Children::Dense { len: 256, .. }
+        ));
+        assert!(map
+            .iter()
+            .take(256)
+            .map(|(k, v)| (k[1], *v))
+            .eq((0..=255).map(|b| (b, b))));
+        for b in 0..240u8 {
+            map.remove([b'k', b]);
+        }
+        assert!(matches!(
+            map.root.children.get(b'k').unwrap().children,
+            Children::Sparse(_)
+        ));
+        assert_eq!(map.get([b'k', 250]), Some(&250));
+        assert_eq!(format!("{:?}", map.prefix_iter("k").count()), "16");
+    }
+}
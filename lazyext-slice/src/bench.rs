@@ -0,0 +1,143 @@
+//! Deterministic data generators for benchmarking the conversion and comparison APIs.
+//!
+//! The crate's own `benches/` suite uses them, and they are public so downstream crates can
+//! compare e.g. `to_be_u32_vec` with `chunked_to_be_u32_vec` on the same inputs.
+
+use crate::Pod;
+use alloc::vec::Vec;
+use core::mem;
+use core::slice::from_raw_parts_mut;
+
+/// The input sizes, in bytes, the crate's benchmarks run with: from a cache line to
+/// larger than most L2 caches.
+pub const BENCH_SIZES: [usize; 4] = [64, 1024, 16 * 1024, 256 * 1024];
+
+/// A small, seedable SplitMix64 generator, so benchmark inputs are the same across runs.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::bench::BenchRng;
+///
+/// let mut a = BenchRng::new(7);
+/// let mut b = BenchRng::new(7);
+/// assert_eq!(a.next_u64(), b.next_u64());
+/// ```
+#[derive(Debug, Clone)]
+pub struct BenchRng {
+    state: u64,
+}
+
+impl BenchRng {
+    /// Creates a generator from `seed`.
+    #[inline]
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Returns the next pseudo-random `u64`.
+    #[inline]
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Fills `dst` with pseudo-random bytes.
+    pub fn fill_bytes(&mut self, dst: &mut [u8]) {
+        let mut chunks = dst.chunks_exact_mut(8);
+        for chunk in &mut chunks {
+            chunk.copy_from_slice(&self.next_u64().to_le_bytes());
+        }
+        let rest = chunks.into_remainder();
+        let len = rest.len();
+        rest.copy_from_slice(&self.next_u64().to_le_bytes()[..len]);
+    }
+}
+
+/// Returns `len` pseudo-random bytes generated from `seed`.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::bench::random_bytes;
+///
+/// assert_eq!(random_bytes(100, 1).len(), 100);
+/// assert_eq!(random_bytes(100, 1), random_bytes(100, 1));
+/// assert_ne!(random_bytes(100, 1), random_bytes(100, 2));
+/// ```
+pub fn random_bytes(len: usize, seed: u64) -> Vec<u8> {
+    let mut out = alloc::vec![0; len];
+    BenchRng::new(seed).fill_bytes(&mut out);
+    out
+}
+
+/// Returns `len` values with pseudo-random bit patterns generated from `seed`.
+///
+/// Their bytes (e.g. from `cast_bytes`) are aligned for `T`, so they can be used as the input
+/// of `cast_to::<T>` without a misalignment panic.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::bench::random_elems;
+///
+/// let vals: Vec<u32> = random_elems(16, 1);
+/// assert_eq!(vals.len(), 16);
+/// assert_eq!(vals, random_elems::<u32>(16, 1));
+/// ```
+pub fn random_elems<T: Pod>(len: usize, seed: u64) -> Vec<T> {
+    let mut out = Vec::<T>::with_capacity(len);
+    // SAFETY: `Pod` types accept any bit pattern, and the capacity holds `len` values.
+    unsafe {
+        let bytes = from_raw_parts_mut(out.as_mut_ptr() as *mut u8, len * mem::size_of::<T>());
+        BenchRng::new(seed).fill_bytes(bytes);
+        out.set_len(len);
+    }
+    out
+}
+
+/// Returns two separately allocated, equal copies of `len` pseudo-random bytes, the worst case
+/// input for the comparison APIs, which have to look at every byte.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::bench::equal_pair;
+///
+/// let (a, b) = equal_pair(33, 1);
+/// assert_eq!(a, b);
+/// assert_ne!(a.as_ptr(), b.as_ptr());
+/// ```
+pub fn equal_pair(len: usize, seed: u64) -> (Vec<u8>, Vec<u8>) {
+    let a = random_bytes(len, seed);
+    let b = a.clone();
+    (a, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_bytes_tail() {
+        let mut rng = BenchRng::new(3);
+        let mut full = [0u8; 16];
+        rng.fill_bytes(&mut full);
+
+        let mut rng = BenchRng::new(3);
+        let mut short = [0u8; 13];
+        rng.fill_bytes(&mut short);
+        assert_eq!(short[..8], full[..8]);
+        assert_eq!(short[8..], full[8..13]);
+    }
+
+    #[test]
+    fn test_random_elems_aligned() {
+        let vals: Vec<u64> = random_elems(BENCH_SIZES[0] / 8, 9);
+        assert_eq!(vals.as_ptr() as usize % mem::align_of::<u64>(), 0);
+        assert!(vals.iter().any(|v| *v != 0));
+    }
+}
@@ -0,0 +1,630 @@
+//! A compressed bitmap of `u32`s in the layout of [Roaring bitmaps](https://roaringbitmap.org).
+//!
+//! Values are grouped by their high 16 bits into containers, which hold the low 16 bits either
+//! as a sorted array (up to 4096 values) or as a bitset of 65536 bits.
+
+use crate::{ReadError, SliceReader, VecByteWriterExt};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::ops::{BitAnd, BitOr};
+
+/// The largest array container, above it a bitset is smaller.
+const ARRAY_MAX: usize = 4096;
+
+/// The number of words of a bitset container.
+const BITSET_WORDS: usize = 1024;
+
+/// The cookie of the portable format, for bitmaps without run containers.
+const SERIAL_COOKIE_NO_RUN: u32 = 12346;
+
+/// The error returned by [`Bitmap::from_bytes`].
+///
+/// [`Bitmap::from_bytes`]: struct.Bitmap.html#method.from_bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitmapError {
+    /// The input is truncated.
+    Read(ReadError),
+    /// The input does not start with the cookie of a bitmap without run containers.
+    UnsupportedCookie(u32),
+    /// The container is out of order, unsorted, or does not match its cardinality.
+    InvalidContainer {
+        /// The index of the container.
+        index: usize,
+    },
+}
+
+impl From<ReadError> for BitmapError {
+    fn from(e: ReadError) -> Self {
+        Self::Read(e)
+    }
+}
+
+impl fmt::Display for BitmapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read(e) => write!(f, "invalid bitmap: {}", e),
+            Self::UnsupportedCookie(cookie) => write!(f, "unsupported bitmap cookie {}", cookie),
+            Self::InvalidContainer { index } => write!(f, "invalid bitmap container {}", index),
+        }
+    }
+}
+
+cfg_feature!("std", {
+    impl std::error::Error for BitmapError {}
+});
+
+#[derive(Clone, PartialEq, Eq)]
+enum Container {
+    Array(Vec<u16>),
+    Bitset {
+        words: Box<[u64; BITSET_WORDS]>,
+        len: usize,
+    },
+}
+
+impl Container {
+    /// Returns the container of a bitset, as an array if it is small enough.
+    fn from_words(words: Box<[u64; BITSET_WORDS]>) -> Self {
+        let len = words.iter().map(|w| w.count_ones() as usize).sum();
+        if len > ARRAY_MAX {
+            return Self::Bitset { words, len };
+        }
+        let mut array = Vec::with_capacity(len);
+        array.extend(ContainerIter::bitset(&words));
+        Self::Array(array)
+    }
+
+    fn to_words(&self) -> Box<[u64; BITSET_WORDS]> {
+        match self {
+            Self::Array(array) => {
+                let mut words = Box::new([0; BITSET_WORDS]);
+                for &v in array {
+                    words[v as usize / 64] |= 1 << (v % 64);
+                }
+                words
+            }
+            Self::Bitset { words, .. } => words.clone(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Array(array) => array.len(),
+            Self::Bitset { len, .. } => *len,
+        }
+    }
+
+    fn contains(&self, v: u16) -> bool {
+        match self {
+            Self::Array(array) => array.binary_search(&v).is_ok(),
+            Self::Bitset { words, .. } => words[v as usize / 64] & (1 << (v % 64)) != 0,
+        }
+    }
+
+    fn insert(&mut self, v: u16) -> bool {
+        match self {
+            Self::Array(array) => match array.binary_search(&v) {
+                Ok(_) => false,
+                Err(_) if array.len() == ARRAY_MAX => {
+                    *self = Self::Bitset {
+                        words: self.to_words(),
+                        len: ARRAY_MAX,
+                    };
+                    self.insert(v)
+                }
+                Err(pos) => {
+                    array.insert(pos, v);
+                    true
+                }
+            },
+            Self::Bitset { words, len } => {
+                let (word, bit) = (&mut words[v as usize / 64], 1 << (v % 64));
+                let inserted = *word & bit == 0;
+                *word |= bit;
+                *len += inserted as usize;
+                inserted
+            }
+        }
+    }
+
+    fn remove(&mut self, v: u16) -> bool {
+        match self {
+            Self::Array(array) => match array.binary_search(&v) {
+                Ok(pos) => {
+                    array.remove(pos);
+                    true
+                }
+                Err(_) => false,
+            },
+            Self::Bitset { words, len } => {
+                let (word, bit) = (&mut words[v as usize / 64], 1 << (v % 64));
+                let removed = *word & bit != 0;
+                *word &= !bit;
+                *len -= removed as usize;
+                if *len <= ARRAY_MAX {
+                    *self =
+                        Self::from_words(core::mem::replace(words, Box::new([0; BITSET_WORDS])));
+                }
+                removed
+            }
+        }
+    }
+
+    fn union(&self, other: &Self) -> Self {
+        match (self, other) {
+            (Self::Array(a), Self::Array(b)) if a.len() + b.len() <= ARRAY_MAX => {
+                let mut merged = Vec::with_capacity(a.len() + b.len());
+                let (mut i, mut j) = (0, 0);
+                while i < a.len() && j < b.len() {
+                    let (x, y) = (a[i], b[j]);
+                    merged.push(x.min(y));
+                    i += (x <= y) as usize;
+                    j += (y <= x) as usize;
+                }
+                merged.extend_from_slice(&a[i..]);
+                merged.extend_from_slice(&b[j..]);
+                Self::Array(merged)
+            }
+            _ => {
+                let mut words = self.to_words();
+                match other {
+                    Self::Array(array) => array
+                        .iter()
+                        .for_each(|&v| words[v as usize / 64] |= 1 << (v % 64)),
+                    Self::Bitset { words: other, .. } => words
+                        .iter_mut()
+                        .zip(other.iter())
+                        .for_each(|(w, o)| *w |= o),
+                }
+                Self::from_words(words)
+            }
+        }
+    }
+
+    fn intersection(&self, other: &Self) -> Self {
+        match (self, other) {
+            (Self::Array(array), other) | (other, Self::Array(array)) => Self::Array(
+                array
+                    .iter()
+                    .copied()
+                    .filter(|&v| other.contains(v))
+                    .collect(),
+            ),
+            (Self::Bitset { words: a, .. }, Self::Bitset { words: b, .. }) => {
+                let mut words = a.clone();
+                words.iter_mut().zip(b.iter()).for_each(|(w, o)| *w &= o);
+                Self::from_words(words)
+            }
+        }
+    }
+}
+
+/// A compressed set of `u32`s, for posting lists and tombstone sets which would waste memory as
+/// plain bitsets.
+///
+/// It serializes to the portable format of Roaring bitmaps (without run containers), so the
+/// bytes can be exchanged with the other Roaring implementations.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::Bitmap;
+///
+/// let a = [1, 5, 70_000, u32::MAX].into_iter().collect::<Bitmap>();
+/// let mut b = Bitmap::new();
+/// b.extend(0..10);
+///
+/// assert!(a.contains(70_000));
+/// assert_eq!((&a & &b).iter().collect::<Vec<_>>(), [1, 5]);
+/// assert_eq!((&a | &b).len(), 12);
+/// assert_eq!(Bitmap::from_bytes(&a.to_bytes()).unwrap(), a);
+/// ```
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Bitmap {
+    /// The containers, sorted by the high 16 bits of their values.
+    containers: Vec<(u16, Container)>,
+}
+
+impl Bitmap {
+    /// Creates an empty bitmap.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            containers: Vec::new(),
+        }
+    }
+
+    /// Returns the number of values.
+    pub fn len(&self) -> u64 {
+        self.containers.iter().map(|(_, c)| c.len() as u64).sum()
+    }
+
+    /// Returns whether the bitmap has no value.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.containers.is_empty()
+    }
+
+    #[inline]
+    fn find(&self, high: u16) -> Result<usize, usize> {
+        self.containers.binary_search_by_key(&high, |(k, _)| *k)
+    }
+
+    /// Returns whether `v` is in the bitmap.
+    pub fn contains(&self, v: u32) -> bool {
+        match self.find((v >> 16) as u16) {
+            Ok(i) => self.containers[i].1.contains(v as u16),
+            Err(_) => false,
+        }
+    }
+
+    /// Adds `v`, and returns whether it was not in the bitmap.
+    pub fn insert(&mut self, v: u32) -> bool {
+        let high = (v >> 16) as u16;
+        match self.find(high) {
+            Ok(i) => self.containers[i].1.insert(v as u16),
+            Err(i) => {
+                let container = Container::Array(alloc::vec![v as u16]);
+                self.containers.insert(i, (high, container));
+                true
+            }
+        }
+    }
+
+    /// Removes `v`, and returns whether it was in the bitmap.
+    pub fn remove(&mut self, v: u32) -> bool {
+        let i = match self.find((v >> 16) as u16) {
+            Ok(i) => i,
+            Err(_) => return false,
+        };
+        let removed = self.containers[i].1.remove(v as u16);
+        if self.containers[i].1.len() == 0 {
+            self.containers.remove(i);
+        }
+        removed
+    }
+
+    /// Returns the values in either bitmap.
+    pub fn union(&self, other: &Self) -> Self {
+        let (a, b) = (&self.containers, &other.containers);
+        let mut containers = Vec::with_capacity(a.len().max(b.len()));
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() && j < b.len() {
+            match a[i].0.cmp(&b[j].0) {
+                core::cmp::Ordering::Less => {
+                    containers.push(a[i].clone());
+                    i += 1;
+                }
+                core::cmp::Ordering::Greater => {
+                    containers.push(b[j].clone());
+                    j += 1;
+                }
+                core::cmp::Ordering::Equal => {
+                    containers.push((a[i].0, a[i].1.union(&b[j].1)));
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        containers.extend_from_slice(&a[i..]);
+        containers.extend_from_slice(&b[j..]);
+        Self { containers }
+    }
+
+    /// Returns the values in both bitmaps.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let containers = self
+            .containers
+            .iter()
+            .filter_map(|(high, c)| {
+                let i = other.find(*high).ok()?;
+                let c = c.intersection(&other.containers[i].1);
+                (c.len() > 0).then_some((*high, c))
+            })
+            .collect();
+        Self { containers }
+    }
+
+    /// Returns an iterator over the values, in ascending order.
+    #[inline]
+    pub fn iter(&self) -> BitmapIter<'_> {
+        BitmapIter {
+            containers: self.containers.iter(),
+            current: None,
+        }
+    }
+
+    /// Serializes the bitmap in the portable format of Roaring bitmaps.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let n = self.containers.len();
+        let mut dst = Vec::new();
+        dst.push_u32_le(SERIAL_COOKIE_NO_RUN);
+        dst.push_u32_le(n as u32);
+        for (high, c) in &self.containers {
+            dst.push_u16_le(*high);
+            dst.push_u16_le((c.len() - 1) as u16);
+        }
+        // The offsets of the containers from the start of the bitmap.
+        let mut offset = dst.len() + 4 * n;
+        for (_, c) in &self.containers {
+            dst.push_u32_le(offset as u32);
+            offset += match c {
+                Container::Array(array) => 2 * array.len(),
+                Container::Bitset { .. } => 8 * BITSET_WORDS,
+            };
+        }
+        for (_, c) in &self.containers {
+            match c {
+                Container::Array(array) => array.iter().for_each(|&v| dst.push_u16_le(v)),
+                Container::Bitset { words, .. } => words.iter().for_each(|&w| dst.push_u64_le(w)),
+            }
+        }
+        dst
+    }
+
+    /// Deserializes a bitmap written by [`to_bytes`], or by another implementation of Roaring
+    /// bitmaps if it has no run containers.
+    ///
+    /// [`to_bytes`]: struct.Bitmap.html#method.to_bytes
+    pub fn from_bytes(src: &[u8]) -> Result<Self, BitmapError> {
+        let mut r = SliceReader::new(src);
+        let cookie = r.read_u32_le()?;
+        if cookie != SERIAL_COOKIE_NO_RUN {
+            return Err(BitmapError::UnsupportedCookie(cookie));
+        }
+        let n = r.read_u32_le()? as usize;
+        let mut header = SliceReader::new(r.take(n.saturating_mul(4))?);
+        // The offsets are redundant with the cardinalities.
+        r.skip(n.saturating_mul(4))?;
+
+        let mut containers: Vec<(u16, Container)> = Vec::with_capacity(n.min(1 << 16));
+        for index in 0..n {
+            let invalid = BitmapError::InvalidContainer { index };
+            let high = header.read_u16_le()?;
+            let len = header.read_u16_le()? as usize + 1;
+            if containers.last().is_some_and(|(last, _)| *last >= high) {
+                return Err(invalid);
+            }
+
+            let container = if len <= ARRAY_MAX {
+                let mut array = Vec::with_capacity(len);
+                for _ in 0..len {
+                    array.push(r.read_u16_le()?);
+                }
+                if array.windows(2).any(|w| w[0] >= w[1]) {
+                    return Err(invalid);
+                }
+                Container::Array(array)
+            } else {
+                let mut words = Box::new([0; BITSET_WORDS]);
+                for w in words.iter_mut() {
+                    *w = r.read_u64_le()?;
+                }
+                let container = Container::from_words(words);
+                if container.len() != len {
+                    return Err(invalid);
+                }
+                container
+            };
+            containers.push((high, container));
+        }
+        Ok(Self { containers })
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+#[cfg_attr(docsrs, doc(cfg(feature = "arbitrary")))]
+impl<'a> arbitrary::Arbitrary<'a> for Bitmap {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        u.arbitrary_iter::<u32>()?.collect()
+    }
+}
+
+impl fmt::Debug for Bitmap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl BitOr for &Bitmap {
+    type Output = Bitmap;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Bitmap {
+        self.union(rhs)
+    }
+}
+
+impl BitAnd for &Bitmap {
+    type Output = Bitmap;
+
+    #[inline]
+    fn bitand(self, rhs: Self) -> Bitmap {
+        self.intersection(rhs)
+    }
+}
+
+impl Extend<u32> for Bitmap {
+    fn extend<I: IntoIterator<Item = u32>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|v| {
+            self.insert(v);
+        })
+    }
+}
+
+impl FromIterator<u32> for Bitmap {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        let mut bitmap = Self::new();
+        bitmap.extend(iter);
+        bitmap
+    }
+}
+
+impl<'a> IntoIterator for &'a Bitmap {
+    type Item = u32;
+    type IntoIter = BitmapIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+enum ContainerIter<'a> {
+    Array(core::slice::Iter<'a, u16>),
+    Bitset {
+        words: &'a [u64; BITSET_WORDS],
+        index: usize,
+        word: u64,
+    },
+}
+
+impl<'a> ContainerIter<'a> {
+    fn bitset(words: &'a [u64; BITSET_WORDS]) -> Self {
+        Self::Bitset {
+            words,
+            index: 0,
+            word: words[0],
+        }
+    }
+}
+
+impl Iterator for ContainerIter<'_> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<u16> {
+        match self {
+            Self::Array(iter) => iter.next().copied(),
+            Self::Bitset { words, index, word } => {
+                while *word == 0 {
+                    *index += 1;
+                    *word = *words.get(*index)?;
+                }
+                let bit = word.trailing_zeros();
+                *word &= *word - 1;
+                Some((*index * 64) as u16 + bit as u16)
+            }
+        }
+    }
+}
+
+/// An iterator over the values of a [`Bitmap`], in ascending order.
+///
+/// [`Bitmap`]: struct.Bitmap.html
+pub struct BitmapIter<'a> {
+    containers: core::slice::Iter<'a, (u16, Container)>,
+    current: Option<(u16, ContainerIter<'a>)>,
+}
+
+impl Iterator for BitmapIter<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        loop {
+            if let Some((high, iter)) = &mut self.current {
+                if let Some(low) = iter.next() {
+                    return Some((*high as u32) << 16 | low as u32);
+                }
+            }
+            let (high, c) = self.containers.next()?;
+            let iter = match c {
+                Container::Array(array) => ContainerIter::Array(array.iter()),
+                Container::Bitset { words, .. } => ContainerIter::bitset(words),
+            };
+            self.current = Some((*high, iter));
+        }
+    }
+}
+
+impl FusedIterator for BitmapIter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitmap_containers() {
+        let mut bitmap = Bitmap::new();
+        // Large enough for the first container to become a bitset.
+        for v in (0..20_000).step_by(2) {
+            assert!(bitmap.insert(v));
+        }
+        assert!(!bitmap.insert(0));
+        bitmap.insert(1 << 20);
+        assert!(matches!(bitmap.containers[0].1, Container::Bitset { .. }));
+        assert_eq!(bitmap.len(), 10_001);
+        assert!(bitmap.contains(19_998) && !bitmap.contains(19_999));
+
+        for v in (0..20_000).step_by(4) {
+            assert!(bitmap.remove(v));
+        }
+        assert!(!bitmap.remove(0));
+        assert!(matches!(bitmap.containers[0].1, Container::Bitset { .. }));
+        // Back to an array once at most 4096 values are left.
+        for v in (16_002..20_000).step_by(4) {
+            assert!(bitmap.remove(v));
+        }
+        assert!(matches!(bitmap.containers[0].1, Container::Array(_)));
+        assert_eq!(bitmap.len(), 4_001);
+        assert!(bitmap.remove(1 << 20));
+        assert_eq!(bitmap.containers.len(), 1);
+
+        let values = bitmap.iter().collect::<Vec<_>>();
+        assert_eq!(values.len(), 4_000);
+        assert!(values.iter().all(|v| v % 4 == 2 && *v < 16_000));
+    }
+
+    #[test]
+    fn test_bitmap_set_ops() {
+        let evens = (0..10_000).step_by(2).collect::<Bitmap>();
+        let threes = (0..10_000).step_by(3).chain([1 << 16]).collect::<Bitmap>();
+        let small = [4, 6, 9].into_iter().collect::<Bitmap>();
+
+        let union = &evens | &threes;
+        assert_eq!(
+            union.iter().collect::<Vec<_>>(),
+            (0..10_000)
+                .filter(|v| v % 2 == 0 || v % 3 == 0)
+                .chain([1 << 16])
+                .collect::<Vec<_>>()
+        );
+        let both = &evens & &threes;
+        assert_eq!(both.len(), (0..10_000).step_by(6).count() as u64);
+        assert_eq!((&small & &evens).iter().collect::<Vec<_>>(), [4, 6]);
+        assert_eq!(&small | &Bitmap::new(), small);
+        assert!((&small & &Bitmap::new()).is_empty());
+    }
+
+    #[test]
+    fn test_bitmap_bytes() {
+        let bitmap = (0..5_000)
+            .map(|v| v * 3)
+            .chain([70_000, u32::MAX])
+            .collect::<Bitmap>();
+        let bytes = bitmap.to_bytes();
+        assert_eq!(&bytes[..8], &[0x3a, 0x30, 0, 0, 3, 0, 0, 0][..]);
+        assert_eq!(Bitmap::from_bytes(&bytes), Ok(bitmap));
+        assert_eq!(
+            Bitmap::from_bytes(&Bitmap::new().to_bytes()),
+            Ok(Bitmap::new())
+        );
+
+        assert!(matches!(
+            Bitmap::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(BitmapError::Read(_))
+        ));
+        assert_eq!(
+            Bitmap::from_bytes(&[0x3b, 0x30, 0, 0]),
+            Err(BitmapError::UnsupportedCookie(12347))
+        );
+        let unsorted = [
+            0x3a, 0x30, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 16, 0, 0, 0, 2, 0, 1, 0,
+        ];
+        assert_eq!(
+            Bitmap::from_bytes(&unsorted),
+            Err(BitmapError::InvalidContainer { index: 0 })
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! A checksummed envelope for blocks and records: `crc32 (4) | len (4) | payload`, both
+//! little-endian, with the CRC-32 covering the length and the payload, so a corrupted length is
+//! detected as well.
+
+use crate::{AsBytesMutRef, AsBytesRef, BlockError, Checksum, Crc32};
+
+/// The size of the header in front of the payload of a block.
+pub const BLOCK_HEADER_LEN: usize = 8;
+
+#[inline]
+pub(crate) fn block_crc(len: [u8; 4], payload: &[u8]) -> u32 {
+    let mut crc = Crc32::default();
+    crc.update(&len);
+    crc.update(payload);
+    crc.finalize()
+}
+
+#[inline]
+pub(crate) fn block_header(payload: &[u8]) -> [u8; BLOCK_HEADER_LEN] {
+    let len = u32::try_from(payload.len())
+        .unwrap_or_else(|_| panic!("block payload of {} bytes is too large", payload.len()))
+        .to_le_bytes();
+    let crc = block_crc(len, payload).to_le_bytes();
+    [
+        crc[0], crc[1], crc[2], crc[3], len[0], len[1], len[2], len[3],
+    ]
+}
+
+/// Writes `payload` as a checksummed block to the front of `dst`, and returns the size of the
+/// block, i.e. `BLOCK_HEADER_LEN + payload.len()`.
+///
+/// # Panics
+///
+/// Panics if the payload is larger than `u32::MAX` bytes.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::{read_block_with_crc, write_block_with_crc, BlockError};
+///
+/// let mut buf = [0u8; 32];
+/// let n = write_block_with_crc(&mut buf, "put k v")?;
+/// assert_eq!(n, 15);
+/// assert_eq!(read_block_with_crc(&buf)?, b"put k v");
+///
+/// buf[10] ^= 1;
+/// assert!(matches!(read_block_with_crc(&buf), Err(BlockError::ChecksumMismatch { .. })));
+/// # Ok::<(), BlockError>(())
+/// ```
+pub fn write_block_with_crc(
+    dst: &mut impl AsBytesMutRef,
+    payload: impl AsBytesRef,
+) -> Result<usize, BlockError> {
+    let dst = dst.as_bytes_mut_ref();
+    let payload = payload.as_bytes_ref();
+    let needed = BLOCK_HEADER_LEN + payload.len();
+    if dst.len() < needed {
+        return Err(BlockError::TooShort {
+            needed,
+            len: dst.len(),
+        });
+    }
+    dst[..BLOCK_HEADER_LEN].copy_from_slice(&block_header(payload));
+    dst[BLOCK_HEADER_LEN..needed].copy_from_slice(payload);
+    Ok(needed)
+}
+
+/// Reads and verifies the checksummed block at the front of `src`, and returns its payload.
+/// The block spans `BLOCK_HEADER_LEN + payload.len()` bytes, the bytes after it are ignored.
+pub fn read_block_with_crc(src: &[u8]) -> Result<&[u8], BlockError> {
+    let too_short = |needed| BlockError::TooShort {
+        needed,
+        len: src.len(),
+    };
+    let header = src
+        .get(..BLOCK_HEADER_LEN)
+        .ok_or_else(|| too_short(BLOCK_HEADER_LEN))?;
+    let expected = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+    let len = [header[4], header[5], header[6], header[7]];
+    let needed = (u32::from_le_bytes(len) as usize).saturating_add(BLOCK_HEADER_LEN);
+    let payload = src
+        .get(BLOCK_HEADER_LEN..needed)
+        .ok_or_else(|| too_short(needed))?;
+    let actual = block_crc(len, payload);
+    if actual != expected {
+        return Err(BlockError::ChecksumMismatch { expected, actual });
+    }
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_roundtrip() {
+        let mut buf = [0u8; 16];
+        assert_eq!(write_block_with_crc(&mut buf, ""), Ok(8));
+        assert_eq!(read_block_with_crc(&buf), Ok(&[][..]));
+        assert_eq!(write_block_with_crc(&mut buf, [7u8; 8]), Ok(16));
+        assert_eq!(read_block_with_crc(&buf), Ok(&[7u8; 8][..]));
+        assert_eq!(
+            write_block_with_crc(&mut buf, [7u8; 9]),
+            Err(BlockError::TooShort {
+                needed: 17,
+                len: 16
+            })
+        );
+    }
+
+    #[test]
+    fn test_block_corruption() {
+        let mut buf = [0u8; 16];
+        write_block_with_crc(&mut buf, "abcd").unwrap();
+        assert_eq!(
+            read_block_with_crc(&buf[..11]),
+            Err(BlockError::TooShort {
+                needed: 12,
+                len: 11
+            })
+        );
+
+        // A corrupted length is caught by the checksum, not read as a shorter payload.
+        buf[4] = 3;
+        assert!(matches!(
+            read_block_with_crc(&buf),
+            Err(BlockError::ChecksumMismatch { .. })
+        ));
+        buf[4] = 0xff;
+        assert_eq!(
+            read_block_with_crc(&buf),
+            Err(BlockError::TooShort {
+                needed: 263,
+                len: 16
+            })
+        );
+    }
+}
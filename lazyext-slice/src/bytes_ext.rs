@@ -1,17 +1,82 @@
 #[cfg(feature = "alloc")]
+use crate::{ChunkedDecoder, CsvFields, DnsNameError};
+use crate::pod::{cast_slice, cast_slice_mut, try_cast_ref, try_cast_slice, try_cast_slice_mut};
+use crate::{
+    Adler32, BeSlice, BeSliceMut, CdcChunks, Checksum, Endian, Fletcher16, Fletcher32, GroupBy,
+    LayoutError, LeSlice, LeSliceMut, OffsetTableError, Pod, Records, Runs, SliceReader,
+    SplitNul, TlvFormat, TlvIter, UuidVariant,
+};
+#[cfg(feature = "alloc")]
 use alloc::borrow::Cow;
+use core::cmp::Ordering;
+#[cfg(feature = "std")]
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4};
+#[cfg(feature = "half")]
+use half::{bf16, f16};
 use core::mem;
+use core::ops::RangeBounds;
+#[cfg(feature = "alloc")]
 use core::ptr::slice_from_raw_parts;
 use core::slice::{from_raw_parts, from_raw_parts_mut};
 
-macro_rules! cfg_bytes {
-    ($($item:item)*) => {
+macro_rules! as_endian_view_suite {
+    ($([$ty:ty, $ty_literal: literal]), +$(,)?) => {
         $(
-            #[cfg(feature = "bytes")]
-            #[cfg_attr(docsrs, doc(cfg(feature = "bytes")))]
-            $item
+        paste! {
+            #[doc = concat!("Returns a zero-copy view of the bytes as big-endian ", $ty_literal, "s, decoding on access.")]
+            ///
+            /// # Panics
+            #[doc = concat!("Panics if the length of the bytes is not a multiple of the size of ", $ty_literal, ", see [`BeSlice::new`] for the fallible version.")]
+            ///
+            /// [`BeSlice::new`]: struct.BeSlice.html#method.new
+            #[inline]
+            fn [<as_be_ $ty s>](&self) -> BeSlice<'_, $ty> {
+                BeSlice::new(self.as_bytes_ref()).unwrap_or_else(|e| panic!("{}", e))
+            }
+
+            #[doc = concat!("Returns a zero-copy view of the bytes as little-endian ", $ty_literal, "s, decoding on access.")]
+            ///
+            /// # Panics
+            #[doc = concat!("Panics if the length of the bytes is not a multiple of the size of ", $ty_literal, ", see [`LeSlice::new`] for the fallible version.")]
+            ///
+            /// [`LeSlice::new`]: struct.LeSlice.html#method.new
+            #[inline]
+            fn [<as_le_ $ty s>](&self) -> LeSlice<'_, $ty> {
+                LeSlice::new(self.as_bytes_ref()).unwrap_or_else(|e| panic!("{}", e))
+            }
+        }
         )*
-    }
+    };
+}
+
+macro_rules! as_endian_view_mut_suite {
+    ($([$ty:ty, $ty_literal: literal]), +$(,)?) => {
+        $(
+        paste! {
+            #[doc = concat!("Returns a zero-copy mutable view of the bytes as big-endian ", $ty_literal, "s, encoding on write.")]
+            ///
+            /// # Panics
+            #[doc = concat!("Panics if the length of the bytes is not a multiple of the size of ", $ty_literal, ", see [`BeSliceMut::new`] for the fallible version.")]
+            ///
+            /// [`BeSliceMut::new`]: struct.BeSliceMut.html#method.new
+            #[inline]
+            fn [<as_be_ $ty s_mut>](&mut self) -> BeSliceMut<'_, $ty> {
+                BeSliceMut::new(self.as_bytes_mut_ref()).unwrap_or_else(|e| panic!("{}", e))
+            }
+
+            #[doc = concat!("Returns a zero-copy mutable view of the bytes as little-endian ", $ty_literal, "s, encoding on write.")]
+            ///
+            /// # Panics
+            #[doc = concat!("Panics if the length of the bytes is not a multiple of the size of ", $ty_literal, ", see [`LeSliceMut::new`] for the fallible version.")]
+            ///
+            /// [`LeSliceMut::new`]: struct.LeSliceMut.html#method.new
+            #[inline]
+            fn [<as_le_ $ty s_mut>](&mut self) -> LeSliceMut<'_, $ty> {
+                LeSliceMut::new(self.as_bytes_mut_ref()).unwrap_or_else(|e| panic!("{}", e))
+            }
+        }
+        )*
+    };
 }
 
 #[cfg(feature = "alloc")]
@@ -20,7 +85,7 @@ macro_rules! to_x_vec_impl {
         const SIZE: usize = mem::size_of::<$typ>();
         let src = $this.as_bytes_ref();
         assert_eq!(
-            src.len() % SIZE,
+            src.len() % mem::size_of::<$typ>(),
             0,
             "invalid length of u8 slice: {}",
             src.len()
@@ -73,7 +138,7 @@ macro_rules! to_x_slice_lossy_impl {
         const SIZE: usize = mem::size_of::<$typ>();
         let src = $this.as_bytes_ref();
         assert_eq!(
-            src.len() % SIZE,
+            src.len() % mem::size_of::<$typ>(),
             0,
             "invalid length of u8 slice: {}",
             src.len()
@@ -116,7 +181,7 @@ macro_rules! to_x_slice_lossy_impl_suite {
     ($([$ty: ty, $ty_literal: literal]), +$(,)?) => {
         $(
         paste! {
-            #[doc = concat!("convert u8 slice to Cow<'_, [", $ty_literal, "]> in native-endian (zero-copy)")]
+            #[doc = concat!("convert u8 slice to `Cow<'_, [", $ty_literal, "]>` in native-endian (zero-copy)")]
             fn [<to_ $ty _slice_lossy>](&self) -> Cow<'_, [$ty]> {
                 to_x_slice_lossy_impl!(self, $ty)
             }
@@ -125,16 +190,71 @@ macro_rules! to_x_slice_lossy_impl_suite {
     };
 }
 
+#[cfg(feature = "alloc")]
+macro_rules! chunked_to_x_vec_impl_suite {
+    ($([$ty:ty, $ty_literal: literal]), +$(,)?) => {
+        $(
+        paste! {
+            #[doc = concat!("Decodes u8 slice to ", $ty_literal, " vecs of at most `chunk_len` elements in big-endian, chunk by chunk")]
+            #[inline]
+            fn [<chunked_to_be_ $ty _vec>](&self, chunk_len: usize) -> ChunkedDecoder<'_, $ty> {
+                ChunkedDecoder::new(self.as_bytes_ref(), chunk_len, |b| $ty::from_be_bytes(b.try_into().unwrap()))
+            }
+
+            #[doc = concat!("Decodes u8 slice to ", $ty_literal, " vecs of at most `chunk_len` elements in little-endian, chunk by chunk")]
+            #[inline]
+            fn [<chunked_to_le_ $ty _vec>](&self, chunk_len: usize) -> ChunkedDecoder<'_, $ty> {
+                ChunkedDecoder::new(self.as_bytes_ref(), chunk_len, |b| $ty::from_le_bytes(b.try_into().unwrap()))
+            }
+
+            #[doc = concat!("Decodes u8 slice to ", $ty_literal, " vecs of at most `chunk_len` elements in native-endian, chunk by chunk")]
+            #[inline]
+            fn [<chunked_to_ne_ $ty _vec>](&self, chunk_len: usize) -> ChunkedDecoder<'_, $ty> {
+                ChunkedDecoder::new(self.as_bytes_ref(), chunk_len, |b| $ty::from_ne_bytes(b.try_into().unwrap()))
+            }
+        }
+        )*
+    };
+}
+
 macro_rules! to_x_impl_suites {
     ($([$ty: ty, $ty_literal: literal]), +$(,)?) => {
-        cfg_alloc!(to_x_vec_impl_suite!($([$ty, $ty_literal],)*););
-        cfg_alloc!(to_x_slice_lossy_impl_suite!($([$ty, $ty_literal],)*););
+        cfg_feature!("alloc", { to_x_vec_impl_suite!($([$ty, $ty_literal],)*); });
+        cfg_feature!("alloc", { chunked_to_x_vec_impl_suite!($([$ty, $ty_literal],)*); });
+        cfg_feature!("alloc", { to_x_slice_lossy_impl_suite!($([$ty, $ty_literal],)*); });
         to_x_slice_impl_suite!(from_raw_parts, AsBytesRef::as_bytes_ref::as_ptr, const, $([$ty, $ty_literal],)*);
     };
 }
 
 // const MAX_BRUTE_FORCE: usize = 64;
 
+fn masked_eq(a: &[u8], b: &[u8], mask: &[u8]) -> bool {
+    assert_eq!(
+        mask.len(),
+        b.len(),
+        "invalid length of mask: {}, expected {}",
+        mask.len(),
+        b.len()
+    );
+    a.iter().zip(b).zip(mask).all(|((a, b), m)| a & m == b & m)
+}
+
+#[inline]
+fn mac_bytes(src: &[u8]) -> &[u8] {
+    assert!(
+        src.len() == 6 || src.len() == 8,
+        "invalid length of mac address: {}",
+        src.len()
+    );
+    src
+}
+
+#[inline]
+fn uuid_bytes(src: &[u8]) -> &[u8] {
+    assert_eq!(src.len(), 16, "invalid length of uuid: {}", src.len());
+    src
+}
+
 /// Converts to `&'a [u8]`
 pub trait AsBytesRef {
     /// Converts to a u8 slice
@@ -186,6 +306,624 @@ pub trait BytesExt: AsBytesRef {
 
     impl_psfix_suites!(AsBytesRef::as_bytes_ref, u8, "u8");
 
+    impl_group_suites!(AsBytesRef::as_bytes_ref, u8);
+
+    impl_split_suites!(AsBytesRef::as_bytes_ref, u8);
+
+    impl_get_suites!(AsBytesRef::as_bytes_ref, u8);
+
+    impl_gather_suites!(AsBytesRef::as_bytes_ref, u8);
+
+    impl_map_suites!(AsBytesRef::as_bytes_ref, u8);
+
+    impl_rand_suites!(AsBytesRef::as_bytes_ref, u8);
+
+    /// Returns whether the bytes equal `other` on the bits selected by `mask`,
+    /// i.e. `self[i] & mask[i] == other[i] & mask[i]` for every `i`.
+    ///
+    /// Returns `false` if the lengths of the bytes and `other` differ.
+    ///
+    /// # Panics
+    /// Panics if the length of `mask` differs from the length of `other`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::BytesExt;
+    ///
+    /// // ignore the version nibble of the second byte
+    /// assert!([0xca, 0x12, 0xfe].eq_masked([0xca, 0x1f, 0xfe], [0xff, 0xf0, 0xff]));
+    /// assert!(![0xca, 0x12, 0xfe].eq_masked([0xca, 0x22, 0xfe], [0xff, 0xf0, 0xff]));
+    /// ```
+    fn eq_masked(&self, other: impl AsBytesRef, mask: impl AsBytesRef) -> bool {
+        let src = self.as_bytes_ref();
+        let other = other.as_bytes_ref();
+        src.len() == other.len() && masked_eq(src, other, mask.as_bytes_ref())
+    }
+
+    /// Returns whether the bytes begin with `prefix` on the bits selected by `mask`,
+    /// see [`eq_masked`].
+    ///
+    /// # Panics
+    /// Panics if the length of `mask` differs from the length of `prefix`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::BytesExt;
+    ///
+    /// // match the magic and any version byte
+    /// assert!(b"LZX\x02payload".has_prefix_masked(*b"LZX\x00", [0xff, 0xff, 0xff, 0x00]));
+    /// ```
+    ///
+    /// [`eq_masked`]: trait.BytesExt.html#method.eq_masked
+    fn has_prefix_masked(&self, prefix: impl AsBytesRef, mask: impl AsBytesRef) -> bool {
+        let src = self.as_bytes_ref();
+        let prefix = prefix.as_bytes_ref();
+        src.len() >= prefix.len() && masked_eq(&src[..prefix.len()], prefix, mask.as_bytes_ref())
+    }
+
+    /// Computes the checksum of the bytes with the algorithm `C`, e.g. [`Crc32`].
+    ///
+    /// [`Crc32`]: struct.Crc32.html
+    #[inline]
+    fn checksum<C: Checksum>(&self) -> C::Output {
+        let mut c = C::default();
+        c.update(self.as_bytes_ref());
+        c.finalize()
+    }
+
+    /// Computes the Adler-32 checksum of the bytes, see [`Adler32`].
+    ///
+    /// [`Adler32`]: struct.Adler32.html
+    #[inline]
+    fn adler32(&self) -> u32 {
+        self.checksum::<Adler32>()
+    }
+
+    /// Computes the Fletcher-16 checksum of the bytes, see [`Fletcher16`].
+    ///
+    /// [`Fletcher16`]: struct.Fletcher16.html
+    #[inline]
+    fn fletcher16(&self) -> u16 {
+        self.checksum::<Fletcher16>()
+    }
+
+    /// Computes the Fletcher-32 checksum of the bytes, see [`Fletcher32`].
+    ///
+    /// [`Fletcher32`]: struct.Fletcher32.html
+    #[inline]
+    fn fletcher32(&self) -> u32 {
+        self.checksum::<Fletcher32>()
+    }
+
+    /// Splits the bytes into content-defined chunks, see [`CdcChunks`].
+    ///
+    /// # Panics
+    /// Panics unless `0 < min_size <= avg_size <= max_size`.
+    ///
+    /// [`CdcChunks`]: struct.CdcChunks.html
+    #[inline]
+    fn cdc_chunks(&self, min_size: usize, avg_size: usize, max_size: usize) -> CdcChunks<'_> {
+        CdcChunks::new(self.as_bytes_ref(), min_size, avg_size, max_size)
+    }
+
+    /// Returns the index of the first occurrence of `needle` in the bytes, using the Rabin-Karp
+    /// algorithm (see [`RollingHash`]), which runs in linear time even for very long needles.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::BytesExt;
+    ///
+    /// assert_eq!(b"abracadabra".find_subslice_rabin_karp("cad"), Some(4));
+    /// ```
+    ///
+    /// [`RollingHash`]: struct.RollingHash.html
+    #[inline]
+    fn find_subslice_rabin_karp(&self, needle: impl AsBytesRef) -> Option<usize> {
+        crate::rolling::find_rabin_karp(self.as_bytes_ref(), needle.as_bytes_ref())
+    }
+
+    /// Returns the index of the last occurrence of `needle`, or `None` if it is not present.
+    ///
+    /// The search runs backwards from the end with the Boyer-Moore-Horspool algorithm, so finding
+    /// a footer near the end of a large block does not scan the whole block.
+    /// An empty `needle` matches at the end.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::BytesExt;
+    ///
+    /// let block = b"MAGIC...payload...MAGIC\x01";
+    /// assert_eq!(block.last_index_of("MAGIC"), Some(18));
+    /// assert_eq!(block.rfind_all_subslices("MAGIC"), [18, 0]);
+    /// ```
+    #[inline]
+    fn last_index_of(&self, needle: impl AsBytesRef) -> Option<usize> {
+        let src = self.as_bytes_ref();
+        crate::search::rfind_horspool(src, needle.as_bytes_ref(), src.len())
+    }
+
+    /// Returns the indexes of all the (possibly overlapping) occurrences of `needle`, from the last
+    /// to the first, see [`last_index_of`].
+    ///
+    /// [`last_index_of`]: trait.BytesExt.html#method.last_index_of
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn rfind_all_subslices(&self, needle: impl AsBytesRef) -> Vec<usize> {
+        let src = self.as_bytes_ref();
+        let needle = needle.as_bytes_ref();
+        let mut indexes = Vec::new();
+        let mut end = src.len();
+        while let Some(i) = crate::search::rfind_horspool(src, needle, end) {
+            indexes.push(i);
+            match (i + needle.len()).checked_sub(1) {
+                Some(next) => end = next,
+                None => break,
+            }
+        }
+        indexes
+    }
+
+    /// Reinterprets the bytes as a slice of `T` in native-endian (zero-copy), the generic form of
+    /// the `to_*_slice` methods.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the bytes is not a multiple of the size of `T`, or if they are
+    /// not aligned for `T`, see [`try_cast_to`].
+    ///
+    /// [`try_cast_to`]: trait.BytesExt.html#method.try_cast_to
+    #[inline]
+    fn cast_to<T: Pod>(&self) -> &[T] {
+        cast_slice(self.as_bytes_ref())
+    }
+
+    /// Reinterprets the bytes as a slice of `T` in native-endian (zero-copy), or returns why
+    /// they cannot be, see [`validate_layout`].
+    ///
+    /// [`validate_layout`]: fn.validate_layout.html
+    #[inline]
+    fn try_cast_to<T: Pod>(&self) -> Result<&[T], LayoutError> {
+        try_cast_slice(self.as_bytes_ref())
+    }
+
+    /// Reinterprets the bytes as a single `T` (zero-copy), e.g. a `#[repr(C)]` header
+    /// implementing [`Pod`]. The bytes must be exactly as large as `T` and aligned for it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::{BytesExt, LayoutError};
+    ///
+    /// #[repr(align(8))]
+    /// struct Aligned([u8; 16]);
+    ///
+    /// let buf = Aligned([1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0]);
+    /// let bytes = &buf.0[..];
+    /// assert_eq!((&bytes[8..]).try_cast_ref::<u64>(), Ok(&u64::from_le_bytes([2, 0, 0, 0, 0, 0, 0, 0])));
+    /// assert!(matches!(bytes.try_cast_ref::<u64>(), Err(LayoutError::InvalidLength(_))));
+    /// ```
+    ///
+    /// [`Pod`]: trait.Pod.html
+    #[inline]
+    fn try_cast_ref<T: Pod>(&self) -> Result<&T, LayoutError> {
+        try_cast_ref(self.as_bytes_ref())
+    }
+
+    /// Reads a table of `count` offsets of `width` bytes each from the front of the bytes, as
+    /// found at the start of many block formats, and validates it.
+    ///
+    /// The offsets must be non-decreasing and must not point past the end of the bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is not between 1 and 8.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::{BytesExt, Endian};
+    ///
+    /// let block = [0u8, 4, 0, 6, b'a', b'b', b'c', b'd'];
+    /// assert_eq!(block.read_offset_table(2, 2, Endian::Big), Ok(vec![4, 6]));
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn read_offset_table(
+        &self,
+        count: usize,
+        width: usize,
+        endian: Endian,
+    ) -> Result<Vec<usize>, OffsetTableError> {
+        crate::offsets::read_offset_table(self.as_bytes_ref(), count, width, endian)
+    }
+
+    /// Converts 4 bytes in network order to an IPv4 address, or returns `None` for any other length.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    fn to_ipv4_addr(&self) -> Option<Ipv4Addr> {
+        <[u8; 4]>::try_from(self.as_bytes_ref()).ok().map(Ipv4Addr::from)
+    }
+
+    /// Converts 16 bytes in network order to an IPv6 address, or returns `None` for any other length.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    fn to_ipv6_addr(&self) -> Option<Ipv6Addr> {
+        <[u8; 16]>::try_from(self.as_bytes_ref()).ok().map(Ipv6Addr::from)
+    }
+
+    /// Converts 6 bytes, an IPv4 address followed by a big-endian port, to a socket address,
+    /// or returns `None` for any other length.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::BytesExt;
+    /// use std::net::{Ipv4Addr, SocketAddrV4};
+    ///
+    /// let addr = [127, 0, 0, 1, 0x1f, 0x90].to_socket_addr_v4();
+    /// assert_eq!(addr, Some(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8080)));
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    fn to_socket_addr_v4(&self) -> Option<SocketAddrV4> {
+        let [a, b, c, d, hi, lo] = <[u8; 6]>::try_from(self.as_bytes_ref()).ok()?;
+        Some(SocketAddrV4::new(
+            Ipv4Addr::new(a, b, c, d),
+            u16::from_be_bytes([hi, lo]),
+        ))
+    }
+
+    /// Decodes the wire-format DNS name at `offset`, following compression pointers, as in the
+    /// question and resource records of a DNS message.
+    ///
+    /// Returns the dotted name (`"."` for the root, with bytes outside printable ASCII written as
+    /// `\DDD` escapes) and the offset just past the name, which is past the first compression
+    /// pointer if there is one. Pointers must point before the labels they continue, which rules
+    /// out loops.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::BytesExt;
+    ///
+    /// let msg = b"\x07example\x03com\x00\x03www\xc0\x00";
+    /// assert_eq!(msg.decode_dns_name(13).unwrap(), ("www.example.com".to_string(), 19));
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn decode_dns_name(&self, offset: usize) -> Result<(String, usize), DnsNameError> {
+        crate::dns::decode_dns_name(self.as_bytes_ref(), offset)
+    }
+
+    /// Returns the bytes from `offset` up to, but excluding, the next NUL, or `None` if `offset`
+    /// is out of bounds or no NUL follows it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::BytesExt;
+    ///
+    /// let strtab = b"\0.text\0.data\0";
+    /// assert_eq!(strtab.take_cstr_at(7), Some(&b".data"[..]));
+    /// assert_eq!(strtab.take_cstr_at(14), None);
+    /// ```
+    #[inline]
+    fn take_cstr_at(&self, offset: usize) -> Option<&[u8]> {
+        crate::cstr::take_cstr_at(self.as_bytes_ref(), offset)
+    }
+
+    /// Returns an iterator over the NUL-terminated strings of the bytes, see [`SplitNul`].
+    ///
+    /// [`SplitNul`]: struct.SplitNul.html
+    #[inline]
+    fn split_nul(&self) -> SplitNul<'_> {
+        SplitNul::new(self.as_bytes_ref())
+    }
+
+    /// Decodes the bytes up to the first NUL (or all of them, if there is none) as UTF-8,
+    /// replacing invalid sequences with `U+FFFD`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::BytesExt;
+    ///
+    /// let mut name = [0u8; 16];
+    /// name[..6].copy_from_slice(b"eth0\xff!");
+    /// assert_eq!(name.as_cstr_lossy(), "eth0\u{fffd}!");
+    /// assert_eq!(b"no nul".as_cstr_lossy(), "no nul");
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[inline]
+    fn as_cstr_lossy(&self) -> Cow<'_, str> {
+        let src = self.as_bytes_ref();
+        let end = src.iter().position(|b| *b == 0).unwrap_or(src.len());
+        String::from_utf8_lossy(&src[..end])
+    }
+
+    /// Returns an iterator over the records of `record_len` bytes, see [`Records`].
+    ///
+    /// # Panics
+    /// Panics if `record_len` is 0.
+    ///
+    /// [`Records`]: struct.Records.html
+    #[inline]
+    fn records(&self, record_len: usize) -> Records<'_> {
+        Records::new(self.as_bytes_ref(), record_len)
+    }
+
+    /// Returns an iterator over the `(type, value)` entries of the bytes in the type-length-value
+    /// layout `format`, see [`TlvIter`].
+    ///
+    /// [`TlvIter`]: struct.TlvIter.html
+    #[inline]
+    fn tlv_iter(&self, format: TlvFormat) -> TlvIter<'_> {
+        TlvIter::new(self.as_bytes_ref(), format)
+    }
+
+    /// Returns a [`SliceReader`] consuming the bytes from the front.
+    ///
+    /// [`SliceReader`]: struct.SliceReader.html
+    #[inline]
+    fn reader(&self) -> SliceReader<'_> {
+        SliceReader::new(self.as_bytes_ref())
+    }
+
+    /// Trims the leading and trailing whitespace, for records mixing text and binary data.
+    ///
+    /// Unicode whitespace (e.g. U+00A0 or U+3000) is trimmed where the edges are valid UTF-8,
+    /// and only ASCII whitespace is trimmed otherwise, so invalid UTF-8 stops the trimming.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::BytesExt;
+    ///
+    /// assert_eq!("\u{3000} key\u{a0}\n".trim_utf8_whitespace(), b"key");
+    /// assert_eq!(b" \xff\x00 ".trim_utf8_whitespace(), b"\xff\x00");
+    /// ```
+    #[inline]
+    fn trim_utf8_whitespace(&self) -> &[u8] {
+        crate::trim::trim_utf8_whitespace(self.as_bytes_ref())
+    }
+
+    /// Splits a CSV record into its fields, see [`CsvFields`].
+    ///
+    /// [`CsvFields`]: struct.CsvFields.html
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[inline]
+    fn csv_fields(&self, delimiter: u8, quote: u8) -> CsvFields<'_> {
+        CsvFields::new(self.as_bytes_ref(), delimiter, quote)
+    }
+
+    /// Compresses the bytes into an LZ4 block (without the frame format or the length prefix).
+    #[cfg(feature = "lz4")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "lz4")))]
+    #[inline]
+    fn compress_lz4(&self) -> Vec<u8> {
+        lz4_flex::block::compress(self.as_bytes_ref())
+    }
+
+    /// Decompresses an LZ4 block, `expected_len` is the length of the uncompressed data.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::BytesExt;
+    ///
+    /// let data = b"hello hello hello hello".to_vec();
+    /// let compressed = data.compress_lz4();
+    /// assert_eq!(compressed.decompress_lz4(data.len()).unwrap(), data);
+    /// ```
+    #[cfg(feature = "lz4")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "lz4")))]
+    #[inline]
+    fn decompress_lz4(
+        &self,
+        expected_len: usize,
+    ) -> Result<Vec<u8>, lz4_flex::block::DecompressError> {
+        lz4_flex::block::decompress(self.as_bytes_ref(), expected_len)
+    }
+
+    /// Compresses the bytes in the Snappy raw format (without the framing format).
+    #[cfg(feature = "snappy")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "snappy")))]
+    #[inline]
+    fn compress_snappy(&self) -> Result<Vec<u8>, snap::Error> {
+        snap::raw::Encoder::new().compress_vec(self.as_bytes_ref())
+    }
+
+    /// Decompresses data in the Snappy raw format, which records the uncompressed length.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::BytesExt;
+    ///
+    /// let data = b"hello hello hello hello".to_vec();
+    /// let compressed = data.compress_snappy().unwrap();
+    /// assert_eq!(compressed.decompress_snappy().unwrap(), data);
+    /// ```
+    #[cfg(feature = "snappy")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "snappy")))]
+    #[inline]
+    fn decompress_snappy(&self) -> Result<Vec<u8>, snap::Error> {
+        snap::raw::Decoder::new().decompress_vec(self.as_bytes_ref())
+    }
+
+    /// Compares two byte slices as big-endian unsigned integers, leading zeros are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::BytesExt;
+    /// use core::cmp::Ordering;
+    ///
+    /// assert_eq!([0u8, 0, 1].be_cmp([1u8]), Ordering::Equal);
+    /// assert_eq!([1u8, 0].be_cmp([0xffu8]), Ordering::Greater);
+    /// ```
+    fn be_cmp(&self, other: impl AsBytesRef) -> Ordering {
+        fn trim(src: &[u8]) -> &[u8] {
+            let zeros = src.iter().take_while(|b| **b == 0).count();
+            &src[zeros..]
+        }
+
+        let a = trim(self.as_bytes_ref());
+        let b = trim(other.as_bytes_ref());
+        a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+    }
+
+    /// Formats the 16 bytes as a lowercase hyphenated UUID, e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+    ///
+    /// # Panics
+    /// Panics if the length of the bytes is not 16.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn as_uuid_string(&self) -> String {
+        let src = uuid_bytes(self.as_bytes_ref());
+        let mut s = String::with_capacity(36);
+        crate::uuid::write_uuid(src, &mut s).unwrap();
+        s
+    }
+
+    /// Returns the version of the 16 bytes as a UUID, e.g. `4` for random UUIDs.
+    ///
+    /// # Panics
+    /// Panics if the length of the bytes is not 16.
+    #[inline]
+    fn uuid_version(&self) -> u8 {
+        uuid_bytes(self.as_bytes_ref())[6] >> 4
+    }
+
+    /// Returns the variant of the 16 bytes as a UUID.
+    ///
+    /// # Panics
+    /// Panics if the length of the bytes is not 16.
+    #[inline]
+    fn uuid_variant(&self) -> UuidVariant {
+        UuidVariant::from_byte(uuid_bytes(self.as_bytes_ref())[8])
+    }
+
+    /// Formats the 6 bytes of a MAC address (or the 8 bytes of an EUI-64) as lowercase
+    /// colon-separated hex, e.g. `00:1a:2b:3c:4d:5e`.
+    ///
+    /// # Panics
+    /// Panics if the length of the bytes is not 6 or 8.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn format_mac(&self) -> String {
+        let src = mac_bytes(self.as_bytes_ref());
+        let mut s = String::with_capacity(src.len() * 3 - 1);
+        crate::mac::write_mac(src, &mut s).unwrap();
+        s
+    }
+
+    /// Returns the organizationally unique identifier, the first 3 bytes of a MAC address or EUI-64.
+    ///
+    /// # Panics
+    /// Panics if the length of the bytes is not 6 or 8.
+    #[inline]
+    fn oui(&self) -> [u8; 3] {
+        let src = mac_bytes(self.as_bytes_ref());
+        [src[0], src[1], src[2]]
+    }
+
+    /// Returns `true` if the MAC address (or EUI-64) is a group address, i.e. the least
+    /// significant bit of its first byte is set.
+    ///
+    /// # Panics
+    /// Panics if the length of the bytes is not 6 or 8.
+    #[inline]
+    fn is_multicast_mac(&self) -> bool {
+        mac_bytes(self.as_bytes_ref())[0] & 0x01 != 0
+    }
+
+    /// Returns `true` if the MAC address (or EUI-64) is locally administered rather than
+    /// assigned from an OUI, i.e. the second least significant bit of its first byte is set.
+    ///
+    /// # Panics
+    /// Panics if the length of the bytes is not 6 or 8.
+    #[inline]
+    fn is_local_mac(&self) -> bool {
+        mac_bytes(self.as_bytes_ref())[0] & 0x02 != 0
+    }
+
+    /// Expands the 6 bytes of a MAC address to an EUI-64 by inserting `ff:fe` after the OUI.
+    ///
+    /// Flip bit `0x02` of the first byte of the result for the modified EUI-64 of IPv6
+    /// interface identifiers.
+    ///
+    /// # Panics
+    /// Panics if the length of the bytes is not 6.
+    fn mac_to_eui64(&self) -> [u8; 8] {
+        let src = self.as_bytes_ref();
+        assert_eq!(src.len(), 6, "invalid length of mac address: {}", src.len());
+        [src[0], src[1], src[2], 0xff, 0xfe, src[3], src[4], src[5]]
+    }
+
+    /// Treats the bytes as a big-endian unsigned integer and adds one to a copy of it,
+    /// e.g. to build the exclusive upper bound of a key range.
+    ///
+    /// The result has the same length as the bytes. The returned `bool` is the carry,
+    /// i.e. whether the addition overflowed (the result then wraps to all zeros).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::BytesExt;
+    ///
+    /// assert_eq!(b"ab".be_add_one(), (b"ac".to_vec(), false));
+    /// assert_eq!([0u8, 0xff].be_add_one(), (vec![1, 0], false));
+    /// assert_eq!([0xffu8, 0xff].be_add_one(), (vec![0, 0], true));
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn be_add_one(&self) -> (Vec<u8>, bool) {
+        let mut v = self.as_bytes_ref().to_vec();
+        let carry = v.be_increment_in_place();
+        (v, carry)
+    }
+
+    /// Adds two byte slices as big-endian unsigned integers.
+    ///
+    /// The result has the length of the longer slice. The returned `bool` is the carry,
+    /// i.e. whether the sum does not fit in that length.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::BytesExt;
+    ///
+    /// assert_eq!([0x01u8, 0xff].be_add([0x01u8]), (vec![0x02, 0x00], false));
+    /// assert_eq!([0xffu8].be_add([0x00u8, 0x01]), (vec![0x01, 0x00], false));
+    /// assert_eq!([0xffu8].be_add([0x01u8]), (vec![0x00], true));
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn be_add(&self, other: impl AsBytesRef) -> (Vec<u8>, bool) {
+        let a = self.as_bytes_ref();
+        let b = other.as_bytes_ref();
+        let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+
+        let mut sum = long.to_vec();
+        let mut carry = 0u16;
+        let mut short = short.iter().rev();
+        for byte in sum.iter_mut().rev() {
+            let v = *byte as u16 + short.next().map_or(0, |b| *b as u16) + carry;
+            *byte = v as u8;
+            carry = v >> 8;
+        }
+        (sum, carry != 0)
+    }
+
     to_x_impl_suites!(
         [u16, "u16"],
         [u32, "u32"],
@@ -201,10 +939,187 @@ pub trait BytesExt: AsBytesRef {
         [f32, "f32"],
         [f64, "f64"]
     );
+
+    #[cfg(feature = "half")]
+    to_x_impl_suites!([f16, "f16"], [bf16, "bf16"]);
+
+    packed_read_suite!([u24, u32, 3], [u48, u64, 6]);
+
+    as_endian_view_suite!(
+        [u16, "u16"],
+        [u32, "u32"],
+        [u64, "u64"],
+        [u128, "u128"],
+        [i16, "i16"],
+        [i32, "i32"],
+        [i64, "i64"],
+        [i128, "i128"],
+        [f32, "f32"],
+        [f64, "f64"],
+    );
+
+    #[cfg(feature = "alloc")]
+    packed_to_vec_suite!([u24, u32, 3], [u48, u64, 6]);
 }
 
 /// Extensions for mutable bytes
 pub trait BytesMutExt: AsBytesMutRef + BytesExt {
+    impl_partition_suites!(AsBytesMutRef::as_bytes_mut_ref, u8);
+
+    impl_permutation_suites!(AsBytesMutRef::as_bytes_mut_ref);
+
+    impl_reorder_suites!(AsBytesMutRef::as_bytes_mut_ref);
+
+    impl_gather_suites!(mut AsBytesMutRef::as_bytes_mut_ref, u8);
+
+    impl_rand_suites!(mut AsBytesMutRef::as_bytes_mut_ref);
+
+    packed_write_suite!([u24, u32, 3], [u48, u64, 6]);
+
+    as_endian_view_mut_suite!(
+        [u16, "u16"],
+        [u32, "u32"],
+        [u64, "u64"],
+        [u128, "u128"],
+        [i16, "i16"],
+        [i32, "i32"],
+        [i64, "i64"],
+        [i128, "i128"],
+        [f32, "f32"],
+        [f64, "f64"],
+    );
+
+    /// Writes `offsets` as a table of `width`-byte entries to the front of the bytes, the
+    /// counterpart of [`read_offset_table`]. Returns the number of bytes written.
+    ///
+    /// The offsets must be non-decreasing and fit in `width` bytes. Nothing is written on errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is not between 1 and 8.
+    ///
+    /// [`read_offset_table`]: trait.BytesExt.html#method.read_offset_table
+    fn write_offset_table(
+        &mut self,
+        offsets: &[usize],
+        width: usize,
+        endian: Endian,
+    ) -> Result<usize, OffsetTableError> {
+        crate::offsets::write_offset_table(self.as_bytes_mut_ref(), offsets, width, endian)
+    }
+
+    /// Reinterprets the bytes as a mutable slice of `T` in native-endian (zero-copy).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the bytes is not a multiple of the size of `T`, or if they are
+    /// not aligned for `T`, see [`try_cast_to_mut`].
+    ///
+    /// [`try_cast_to_mut`]: trait.BytesMutExt.html#method.try_cast_to_mut
+    #[inline]
+    fn cast_to_mut<T: Pod>(&mut self) -> &mut [T] {
+        cast_slice_mut(self.as_bytes_mut_ref())
+    }
+
+    /// Reinterprets the bytes as a mutable slice of `T` in native-endian (zero-copy), or returns
+    /// why they cannot be, see [`validate_layout`].
+    ///
+    /// [`validate_layout`]: fn.validate_layout.html
+    #[inline]
+    fn try_cast_to_mut<T: Pod>(&mut self) -> Result<&mut [T], LayoutError> {
+        try_cast_slice_mut(self.as_bytes_mut_ref())
+    }
+
+    /// Overwrites the bytes with zeros, in a way the compiler will not optimize away.
+    #[cfg(feature = "zeroize")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "zeroize")))]
+    #[inline]
+    fn zeroize(&mut self) {
+        zeroize::Zeroize::zeroize(self.as_bytes_mut_ref())
+    }
+
+    /// Treats the bytes as a big-endian unsigned integer and adds one to it in place,
+    /// e.g. to advance a counter nonce.
+    ///
+    /// Returns the carry, i.e. whether the addition overflowed (the bytes then wrap to all zeros).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::BytesMutExt;
+    ///
+    /// let mut nonce = [0u8, 0xff];
+    /// assert!(!nonce.be_increment_in_place());
+    /// assert_eq!(nonce, [1, 0]);
+    /// ```
+    fn be_increment_in_place(&mut self) -> bool {
+        for byte in self.as_bytes_mut_ref().iter_mut().rev() {
+            let (v, overflow) = byte.overflowing_add(1);
+            *byte = v;
+            if !overflow {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Fills the bytes by repeating `pattern`, the last repetition is truncated if needed.
+    ///
+    /// # Panics
+    /// Panics if `pattern` is empty and the bytes are not.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::BytesMutExt;
+    ///
+    /// let mut buf = [0u8; 7];
+    /// buf.fill_with_pattern([0xde, 0xad, 0xbe]);
+    /// assert_eq!(buf, [0xde, 0xad, 0xbe, 0xde, 0xad, 0xbe, 0xde]);
+    /// ```
+    fn fill_with_pattern(&mut self, pattern: impl AsBytesRef) {
+        let pattern = pattern.as_bytes_ref();
+        let dst = self.as_bytes_mut_ref();
+        if dst.is_empty() {
+            return;
+        }
+        assert!(!pattern.is_empty(), "pattern must not be empty");
+
+        for chunk in dst.chunks_mut(pattern.len()) {
+            chunk.copy_from_slice(&pattern[..chunk.len()]);
+        }
+    }
+
+    /// Sets every byte in `range` to `byte`, e.g. to pad a region of a block.
+    ///
+    /// # Panics
+    /// Panics if `range` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::BytesMutExt;
+    ///
+    /// let mut buf = [1u8; 6];
+    /// buf.fill_range(2..4, 0);
+    /// assert_eq!(buf, [1, 1, 0, 0, 1, 1]);
+    /// buf.fill_range(4.., 9);
+    /// assert_eq!(buf, [1, 1, 0, 0, 9, 9]);
+    /// ```
+    #[inline]
+    fn fill_range(&mut self, range: impl RangeBounds<usize>, byte: u8) {
+        let bounds = (range.start_bound().cloned(), range.end_bound().cloned());
+        self.as_bytes_mut_ref()[bounds].fill(byte);
+    }
+
+    /// Fills the bytes with random data from the thread-local random number generator.
+    #[cfg(feature = "rand")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rand")))]
+    #[inline]
+    fn fill_random(&mut self) {
+        rand::RngCore::fill_bytes(&mut rand::thread_rng(), self.as_bytes_mut_ref())
+    }
+
     to_x_slice_impl_suite!(
         mut from_raw_parts_mut,
         AsBytesMutRef::as_bytes_mut_ref::as_mut_ptr,
@@ -223,31 +1138,40 @@ pub trait BytesMutExt: AsBytesMutRef + BytesExt {
         [f32, "f32"],
         [f64, "f64"]
     );
+
+    #[cfg(feature = "half")]
+    to_x_slice_impl_suite!(
+        mut from_raw_parts_mut,
+        AsBytesMutRef::as_bytes_mut_ref::as_mut_ptr,
+        mut,
+        [f16, "f16"],
+        [bf16, "bf16"]
+    );
 }
 
-impl<'a> AsBytesRef for &'a [u8] {
+impl AsBytesRef for &[u8] {
     fn as_bytes_ref(&self) -> &[u8] {
         self
     }
 }
 
-impl<'a> BytesExt for &'a [u8] {}
+impl BytesExt for &[u8] {}
 
-impl<'a> AsBytesRef for &'a mut [u8] {
+impl AsBytesRef for &mut [u8] {
     fn as_bytes_ref(&self) -> &[u8] {
         self
     }
 }
 
-impl<'a> AsBytesMutRef for &'a mut [u8] {
+impl AsBytesMutRef for &mut [u8] {
     fn as_bytes_mut_ref(&mut self) -> &mut [u8] {
         self
     }
 }
 
-impl<'a> BytesExt for &'a mut [u8] {}
+impl BytesExt for &mut [u8] {}
 
-impl<'a> BytesMutExt for &'a mut [u8] {}
+impl BytesMutExt for &mut [u8] {}
 
 impl<const N: usize> AsBytesRef for [u8; N] {
     fn as_bytes_ref(&self) -> &[u8] {
@@ -265,7 +1189,7 @@ impl<const N: usize> BytesExt for [u8; N] {}
 
 impl<const N: usize> BytesMutExt for [u8; N] {}
 
-cfg_alloc! {
+cfg_feature!("alloc", {
     impl AsBytesRef for Box<[u8]> {
         fn as_bytes_ref(&self) -> &[u8] {
             self.as_ref()
@@ -282,54 +1206,54 @@ cfg_alloc! {
 
     impl BytesMutExt for Box<[u8]> {}
 
-    impl<'a> AsBytesRef for &'a Box<[u8]> {
+    impl AsBytesRef for &Box<[u8]> {
         fn as_bytes_ref(&self) -> &[u8] {
             self.as_ref()
         }
     }
 
-    impl<'a> BytesExt for &'a Box<[u8]> {}
+    impl BytesExt for &Box<[u8]> {}
 
-    impl<'a> AsBytesRef for &'a mut Box<[u8]> {
+    impl AsBytesRef for &mut Box<[u8]> {
         fn as_bytes_ref(&self) -> &[u8] {
             self.as_ref()
         }
     }
 
-    impl<'a> AsBytesMutRef for &'a mut Box<[u8]> {
+    impl AsBytesMutRef for &mut Box<[u8]> {
         fn as_bytes_mut_ref(&mut self) -> &mut [u8] {
             self.as_mut()
         }
     }
 
-    impl<'a> BytesExt for &'a mut Box<[u8]> {}
+    impl BytesExt for &mut Box<[u8]> {}
 
-    impl<'a> BytesMutExt for &'a mut Box<[u8]> {}
+    impl BytesMutExt for &mut Box<[u8]> {}
 
 
-    impl<'a> AsBytesRef for &'a Vec<u8> {
+    impl AsBytesRef for &Vec<u8> {
         fn as_bytes_ref(&self) -> &[u8] {
             self.as_slice()
         }
     }
 
-    impl<'a> BytesExt for &'a Vec<u8> {}
+    impl BytesExt for &Vec<u8> {}
 
-    impl<'a> AsBytesRef for &'a mut Vec<u8> {
+    impl AsBytesRef for &mut Vec<u8> {
         fn as_bytes_ref(&self) -> &[u8] {
             self.as_slice()
         }
     }
 
-    impl<'a> AsBytesMutRef for &'a mut Vec<u8> {
+    impl AsBytesMutRef for &mut Vec<u8> {
         fn as_bytes_mut_ref(&mut self) -> &mut [u8] {
             self.as_mut_slice()
         }
     }
 
-    impl<'a> BytesExt for &'a mut Vec<u8> {}
+    impl BytesExt for &mut Vec<u8> {}
 
-    impl<'a> BytesMutExt for &'a mut Vec<u8> {}
+    impl BytesMutExt for &mut Vec<u8> {}
 
     impl AsBytesRef for Vec<u8> {
         fn as_bytes_ref(&self) -> &[u8] {
@@ -346,7 +1270,7 @@ cfg_alloc! {
     impl BytesExt for Vec<u8> {}
 
     impl BytesMutExt for Vec<u8> {}
-}
+});
 
 impl AsBytesRef for String {
     fn as_bytes_ref(&self) -> &[u8] {
@@ -356,39 +1280,39 @@ impl AsBytesRef for String {
 
 impl BytesExt for String {}
 
-impl<'a> AsBytesRef for &'a String {
+impl AsBytesRef for &String {
     fn as_bytes_ref(&self) -> &[u8] {
         self.as_bytes()
     }
 }
 
-impl<'a> AsBytesRef for &'a mut String {
+impl AsBytesRef for &mut String {
     fn as_bytes_ref(&self) -> &[u8] {
         self.as_bytes()
     }
 }
 
-impl<'a> AsBytesRef for &'a str {
+impl AsBytesRef for &str {
     fn as_bytes_ref(&self) -> &[u8] {
         self.as_bytes()
     }
 }
 
-impl<'a> AsBytesRef for &'a mut str {
+impl AsBytesRef for &mut str {
     fn as_bytes_ref(&self) -> &[u8] {
         self.as_bytes()
     }
 }
 
-impl<'a> BytesExt for &'a String {}
+impl BytesExt for &String {}
 
-impl<'a> BytesExt for &'a mut String {}
+impl BytesExt for &mut String {}
 
-impl<'a> BytesExt for &'a str {}
+impl BytesExt for &str {}
 
-impl<'a> BytesExt for &'a mut str {}
+impl BytesExt for &mut str {}
 
-cfg_bytes! {
+cfg_feature!("bytes", {
     use bytes::{Bytes, BytesMut};
 
     impl AsBytesRef for Bytes {
@@ -399,13 +1323,13 @@ cfg_bytes! {
 
     impl BytesExt for Bytes {}
 
-    impl<'a> AsBytesRef for &'a Bytes {
+    impl AsBytesRef for &Bytes {
         fn as_bytes_ref(&self) -> &[u8] {
             self.as_ref()
         }
     }
 
-    impl<'a> BytesExt for &'a Bytes {}
+    impl BytesExt for &Bytes {}
 
     impl AsBytesRef for BytesMut {
         fn as_bytes_ref(&self) -> &[u8] {
@@ -423,35 +1347,384 @@ cfg_bytes! {
 
     impl BytesMutExt for BytesMut {}
 
-    impl<'a> AsBytesRef for &'a BytesMut {
+    impl AsBytesRef for &BytesMut {
         fn as_bytes_ref(&self) -> &[u8] {
             self.as_ref()
         }
     }
 
-    impl<'a> BytesExt for &'a BytesMut {}
+    impl BytesExt for &BytesMut {}
 
-    impl<'a> AsBytesRef for &'a mut BytesMut {
+    impl AsBytesRef for &mut BytesMut {
         fn as_bytes_ref(&self) -> &[u8] {
             self.as_ref()
         }
     }
 
-    impl<'a> AsBytesMutRef for &'a mut BytesMut {
+    impl AsBytesMutRef for &mut BytesMut {
         fn as_bytes_mut_ref(&mut self) -> &mut [u8] {
             self.as_mut()
         }
     }
 
-    impl<'a> BytesExt for &'a mut BytesMut {}
+    impl BytesExt for &mut BytesMut {}
 
-    impl<'a> BytesMutExt for &'a mut BytesMut {}
+    impl BytesMutExt for &mut BytesMut {}
 
-}
+});
 
 #[cfg(test)]
 mod tests {
-    use super::BytesExt;
+    use super::{BytesExt, BytesMutExt};
+    use lazyext_macros::test_endian_matrix;
+
+    macro_rules! to_vec_roundtrip {
+        ($endian:ident, $ty:ident) => {
+            paste! {
+                let vals = (0..8).map(|v| v as $ty).collect::<Vec<$ty>>();
+                let bytes = vals
+                    .iter()
+                    .flat_map(|v| v.[<to_ $endian _bytes>]())
+                    .collect::<Vec<u8>>();
+                assert_eq!(bytes.[<to_ $endian _ $ty _vec>](), vals);
+                assert_eq!(bytes.as_slice().[<to_ $endian _ $ty _vec>](), vals);
+            }
+        };
+    }
+
+    test_endian_matrix!(
+        to_vec_roundtrip,
+        [u16, u32, usize, u64, u128, i8, i16, i32, i64, isize, i128, f32, f64]
+    );
+
+    #[test]
+    fn test_fill() {
+        let mut buf = vec![0u8; 5];
+        buf.fill_with_pattern("ab");
+        assert_eq!(buf, b"ababa");
+        buf.fill_range(..=1, b'x');
+        assert_eq!(buf, b"xxaba");
+        buf.fill_range(.., b'y');
+        assert_eq!(buf, b"yyyyy");
+
+        let mut empty = [0u8; 0];
+        empty.fill_with_pattern([0u8; 0]);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_fill_random() {
+        let mut buf = [0u8; 32];
+        buf.fill_random();
+        assert_ne!(buf, [0u8; 32]);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_rand() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let shards = b"abcdef";
+        assert!(shards.contains(shards.choose(&mut rng).unwrap()));
+        assert!([0u8; 0].choose(&mut rng).is_none());
+
+        let mut picked = shards.sample(&mut rng, 4);
+        picked.sort();
+        picked.dedup();
+        assert_eq!(picked.len(), 4);
+        assert_eq!(shards.sample(&mut rng, 10).len(), 6);
+
+        let mut buf = *shards;
+        buf.shuffle_in_place(&mut rng);
+        buf.sort();
+        assert_eq!(&buf, shards);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fill_empty_pattern() {
+        [0u8; 4].fill_with_pattern([0u8; 0]);
+    }
+
+    #[test]
+    fn test_be_arithmetic() {
+        use core::cmp::Ordering;
+
+        assert_eq!([0u8; 0].be_cmp([0u8]), Ordering::Equal);
+        assert_eq!([1u8, 2].be_cmp([1u8, 3]), Ordering::Less);
+        assert_eq!([0u8, 2, 0].be_cmp([0xffu8]), Ordering::Greater);
+
+        assert_eq!([0u8; 0].be_add_one(), (vec![], true));
+        let mut v = vec![0xffu8; 3];
+        assert!(v.be_increment_in_place());
+        assert_eq!(v, [0, 0, 0]);
+
+        let (sum, carry) = [0xffu8, 0xff].be_add([0xffu8, 0xff]);
+        assert_eq!(sum, [0xff, 0xfe]);
+        assert!(carry);
+        let (sum, _) = 0x1234_5678u32
+            .to_be_bytes()
+            .be_add(0x0fed_cba9u32.to_be_bytes());
+        assert_eq!(sum, (0x1234_5678u32 + 0x0fed_cba9).to_be_bytes());
+    }
+
+    #[cfg(feature = "lz4")]
+    #[test]
+    fn test_lz4() {
+        let data = "LazyExt! ".repeat(100).into_bytes();
+        let compressed = data.compress_lz4();
+        assert!(compressed.len() < data.len());
+        assert_eq!(compressed.decompress_lz4(data.len()).unwrap(), data);
+        let truncated = &compressed[..compressed.len() / 2];
+        assert!(truncated.decompress_lz4(data.len()).is_err());
+    }
+
+    #[cfg(feature = "snappy")]
+    #[test]
+    fn test_snappy() {
+        let data = "LazyExt! ".repeat(100).into_bytes();
+        let compressed = data.compress_snappy().unwrap();
+        assert!(compressed.len() < data.len());
+        assert_eq!(compressed.decompress_snappy().unwrap(), data);
+        assert!(b"\xff\xff\xff".decompress_snappy().is_err());
+    }
+
+    #[test]
+    fn test_endian_views() {
+        let bytes = [0u8, 1, 0, 2];
+        assert_eq!(bytes.as_be_u16s().iter().collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(bytes.as_le_u16s()[1].get(), 0x0200);
+        assert_eq!(bytes.as_be_i32s().get(0), Some(0x0001_0002));
+    }
+
+    #[test]
+    fn test_endian_views_mut() {
+        let mut header = [0u8; 6];
+        let mut fields = header.as_be_u16s_mut();
+        fields.set(0, 0xcafe);
+        fields.set(2, 7);
+        assert_eq!(header, [0xca, 0xfe, 0, 0, 0, 7]);
+        header.as_le_u16s_mut().set(1, 0x0102);
+        assert_eq!(&header[2..4], &[2, 1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_endian_views_invalid_length() {
+        [0u8; 3].as_be_u16s();
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_net_addrs() {
+        use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4};
+
+        assert_eq!([10u8, 0, 0, 1].to_ipv4_addr(), Some(Ipv4Addr::new(10, 0, 0, 1)));
+        assert_eq!([10u8, 0, 0].to_ipv4_addr(), None);
+        let mut v6 = [0u8; 16];
+        v6[15] = 1;
+        assert_eq!(v6.to_ipv6_addr(), Some(Ipv6Addr::LOCALHOST));
+        assert_eq!((&v6[..15]).to_ipv6_addr(), None);
+        assert_eq!(
+            [192u8, 168, 1, 2, 0, 80].to_socket_addr_v4(),
+            Some(SocketAddrV4::new(Ipv4Addr::new(192, 168, 1, 2), 80))
+        );
+        assert_eq!([192u8, 168, 1, 2, 0].to_socket_addr_v4(), None);
+    }
+
+    #[test]
+    fn test_packed_ints() {
+        let mut buf = [0u8; 9];
+        buf.write_u24_be_at(0, 0x01_0203);
+        buf.write_u48_le_at(3, 0x0405_0607_0809);
+        assert_eq!(buf, [1, 2, 3, 9, 8, 7, 6, 5, 4]);
+        assert_eq!(buf.read_u24_be_at(0), Some(0x01_0203));
+        assert_eq!(buf.read_u24_le_at(0), Some(0x03_0201));
+        assert_eq!(buf.read_u48_le_at(3), Some(0x0405_0607_0809));
+        assert_eq!(buf.read_u48_be_at(4), None);
+        assert_eq!(buf.read_u24_be_at(usize::MAX), None);
+
+        #[cfg(feature = "alloc")]
+        {
+            let src: &[u8] = &buf[..6];
+            assert_eq!(src.to_be_u24_vec(), [0x01_0203, 0x09_0807]);
+            assert_eq!(src.to_le_u48_vec(), [0x0708_0903_0201]);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    #[should_panic]
+    fn test_packed_ints_invalid_length() {
+        let src: &[u8] = &[0; 4];
+        src.to_be_u24_vec();
+    }
+
+    #[test]
+    #[cfg(feature = "half")]
+    fn test_half() {
+        use crate::{BF16SliceExt, F16SliceExt};
+        use half::{bf16, f16};
+
+        let vals = [f16::from_f32(1.5), f16::from_f32(-2.0)];
+        let bytes = vals.iter().flat_map(|v| v.to_be_bytes()).collect::<Vec<u8>>();
+        assert_eq!(bytes.to_be_f16_vec(), vals);
+        assert_eq!(vals.to_be_u8_vec(), bytes);
+        assert_eq!(vals.cast_bytes().to_f16_slice(), vals);
+
+        let vals = vec![bf16::from_f32(0.25); 3];
+        assert_eq!(vals.to_le_u8_vec().to_le_bf16_vec(), vals);
+    }
+
+    #[test]
+    fn test_get_suites() {
+        let src: &[u8] = b"header:body";
+        let (head, rest) = src.take_front(6).unwrap();
+        assert_eq!((head, rest), (&b"header"[..], &b":body"[..]));
+        assert_eq!(src.try_split_at(11), Some((src, &b""[..])));
+        assert_eq!(src.try_split_at(12), None);
+        assert_eq!(src.take_front(12), None);
+        assert_eq!(src.get_range(7..), Some(&b"body"[..]));
+        assert_eq!(src.get_range(..=5), Some(&b"header"[..]));
+        assert_eq!(src.get_range(5..20), None);
+        assert_eq!(src.get_range(..), Some(src));
+    }
+
+    #[test]
+    fn test_reverse_search() {
+        let src: &[u8] = b"aaaa";
+        assert_eq!(src.rfind_all_subslices("aa"), [2, 1, 0]);
+        assert_eq!(src.rfind_all_subslices(""), [4, 3, 2, 1, 0]);
+        assert_eq!(src.last_index_of("b"), None);
+        assert!(src.rfind_all_subslices("aaaaa").is_empty());
+    }
+
+    #[test]
+    fn test_masked() {
+        assert!([1u8, 2].eq_masked([1u8, 3], [0xff, 0xfe]));
+        assert!(![1u8, 2].eq_masked([1u8, 2, 3], [0xff, 0xff, 0xff]));
+        assert!([0u8; 0].eq_masked([0u8; 0], [0u8; 0]));
+        assert!(b"abc".has_prefix_masked("x", [0]));
+        assert!(!b"abc".has_prefix_masked("abcd", [0; 4]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_masked_invalid_mask() {
+        b"abc".has_prefix_masked("ab", [0xff]);
+    }
+
+    #[test]
+    fn test_split_once_on() {
+        let line = b"key=value=more";
+        assert_eq!(
+            line.split_once_on("="),
+            Some((&b"key"[..], &b"value=more"[..]))
+        );
+        assert_eq!(
+            line.rsplit_once_on("="),
+            Some((&b"key=value"[..], &b"more"[..]))
+        );
+        assert_eq!(
+            line.split_once_on("=v"),
+            Some((&b"key"[..], &b"alue=more"[..]))
+        );
+        assert_eq!(line.split_once_on(":"), None);
+        assert_eq!(line.split_once_on(""), Some((&b""[..], &line[..])));
+        assert_eq!(line.rsplit_once_on(""), Some((&line[..], &b""[..])));
+    }
+
+    #[test]
+    fn test_partition_in_place() {
+        let mut buf = *b"a1b2c3";
+        let n = buf.partition_in_place(|b| b.is_ascii_digit());
+        assert_eq!(n, 3);
+        assert!(buf[..n].iter().all(u8::is_ascii_digit));
+        assert!(buf[n..].iter().all(u8::is_ascii_alphabetic));
+    }
+
+    #[test]
+    fn test_permutation_in_place() {
+        let mut buf = *b"aab";
+        let mut seen = vec![buf];
+        while buf.next_permutation_in_place() {
+            seen.push(buf);
+        }
+        assert_eq!(seen, [*b"aab", *b"aba", *b"baa"]);
+        // Wrapped around to the smallest permutation.
+        assert_eq!(&buf, b"aab");
+
+        assert!(!buf.prev_permutation_in_place());
+        assert_eq!(&buf, b"baa");
+        assert!(buf.prev_permutation_in_place());
+        assert_eq!(&buf, b"aba");
+
+        let mut empty: [u8; 0] = [];
+        assert!(!empty.next_permutation_in_place());
+        assert!(!empty.prev_permutation_in_place());
+    }
+
+    #[test]
+    fn test_reorder() {
+        let mut buf = *b"abcdef";
+        buf.rotate_left_by(8);
+        assert_eq!(&buf, b"cdefab");
+        buf.rotate_right_by(2);
+        assert_eq!(&buf, b"abcdef");
+
+        buf.reverse_chunks(2);
+        assert_eq!(&buf, b"efcdab");
+        buf.reverse_chunks(6);
+        assert_eq!(&buf, b"efcdab");
+
+        let mut buf = *b"abcdefg";
+        buf.swap_ranges(0..2, 4..7);
+        assert_eq!(&buf, b"efgcdab");
+        buf.swap_ranges(3..3, 0..1);
+        assert_eq!(&buf, b"fgecdab");
+
+        let mut empty: [u8; 0] = [];
+        empty.rotate_left_by(3);
+    }
+
+    #[test]
+    fn test_gather_scatter() {
+        let src = *b"hello";
+        assert_eq!(src.gather(&[4, 1, 0]), b"oeh");
+        assert_eq!(src.try_gather(&[5]).unwrap_err().position, 0);
+
+        let mut dst = [0u8; 3];
+        dst.scatter_from(&[2, 0, 2], b"abc");
+        assert_eq!(&dst, b"b\0c");
+    }
+
+    #[test]
+    fn test_map_into() {
+        let mut out = [' '; 3];
+        b"abc".map_into(&mut out, |b| b.to_ascii_uppercase() as char);
+        assert_eq!(out, ['A', 'B', 'C']);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_map_into_length_mismatch() {
+        b"abc".map_into(&mut [0u8; 2], |b| b);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_swap_ranges_overlap() {
+        let mut buf = *b"abcdef";
+        buf.swap_ranges(0..3, 2..4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_reverse_chunks_uneven() {
+        let mut buf = *b"abcde";
+        buf.reverse_chunks(2);
+    }
 
     #[test]
     fn test_has_prefix() {
@@ -0,0 +1,163 @@
+//! Content-defined chunking based on a Gear rolling hash.
+
+const fn gear_table() -> [u64; 256] {
+    // splitmix64, so the table is fixed without shipping 256 literals
+    let mut table = [0u64; 256];
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    let mut i = 0;
+    while i < 256 {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    table
+}
+
+static GEAR: [u64; 256] = gear_table();
+
+/// An iterator over content-defined chunks of a byte slice.
+///
+/// A chunk ends where the Gear rolling hash of the last (up to 64) bytes hits a pattern which
+/// occurs once every `avg_size` bytes on average, so inserting or removing bytes only changes the
+/// chunks around the edit, which makes the chunks dedup-friendly.
+/// Chunks are never shorter than `min_size` (except the last one) or longer than `max_size`.
+///
+/// It is returned by [`BytesExt::cdc_chunks`].
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::BytesExt;
+///
+/// let data = (0..100_000u32).flat_map(|i| (i * 7919).to_le_bytes()).collect::<Vec<u8>>();
+/// let chunks = data.cdc_chunks(1024, 4096, 16384).collect::<Vec<_>>();
+/// assert_eq!(chunks.concat(), data);
+/// assert!(chunks.iter().all(|c| c.len() <= 16384));
+/// ```
+///
+/// [`BytesExt::cdc_chunks`]: trait.BytesExt.html#method.cdc_chunks
+#[derive(Debug, Clone)]
+pub struct CdcChunks<'a> {
+    src: &'a [u8],
+    min_size: usize,
+    max_size: usize,
+    shift: u32,
+}
+
+impl<'a> CdcChunks<'a> {
+    /// # Panics
+    /// Panics unless `0 < min_size <= avg_size <= max_size`.
+    pub(crate) fn new(src: &'a [u8], min_size: usize, avg_size: usize, max_size: usize) -> Self {
+        assert!(
+            0 < min_size && min_size <= avg_size && avg_size <= max_size,
+            "invalid chunk sizes: min {}, avg {}, max {}",
+            min_size,
+            avg_size,
+            max_size
+        );
+        let bits = (usize::BITS - 1 - avg_size.leading_zeros()).min(63);
+        Self {
+            src,
+            min_size,
+            max_size,
+            shift: 64 - bits,
+        }
+    }
+
+    fn cut_point(&self) -> usize {
+        let src = self.src;
+        if src.len() <= self.min_size {
+            return src.len();
+        }
+
+        let end = src.len().min(self.max_size);
+        let mut hash = 0u64;
+        // the hash only depends on the last 64 bytes, warm it up before `min_size`
+        for b in &src[self.min_size.saturating_sub(64)..self.min_size] {
+            hash = (hash << 1).wrapping_add(GEAR[*b as usize]);
+        }
+        for (i, b) in src[self.min_size..end].iter().enumerate() {
+            hash = (hash << 1).wrapping_add(GEAR[*b as usize]);
+            if hash >> self.shift == 0 {
+                return self.min_size + i + 1;
+            }
+        }
+        end
+    }
+}
+
+impl<'a> Iterator for CdcChunks<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.src.is_empty() {
+            return None;
+        }
+
+        let (chunk, rest) = self.src.split_at(self.cut_point());
+        self.src = rest;
+        Some(chunk)
+    }
+}
+
+impl core::iter::FusedIterator for CdcChunks<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::BytesExt;
+
+    fn data(len: usize, seed: u32) -> Vec<u8> {
+        let mut x = seed;
+        (0..len)
+            .map(|_| {
+                x ^= x << 13;
+                x ^= x >> 17;
+                x ^= x << 5;
+                x as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_cdc_bounds() {
+        let src = data(1 << 20, 1);
+        let chunks = src.cdc_chunks(2048, 8192, 32768).collect::<Vec<_>>();
+        assert_eq!(chunks.concat(), src);
+        let (last, rest) = chunks.split_last().unwrap();
+        assert!(last.len() <= 32768);
+        assert!(rest.iter().all(|c| (2048..=32768).contains(&c.len())));
+        // roughly `avg_size` on average
+        let avg = src.len() / chunks.len();
+        assert!((4096..=16384).contains(&avg), "avg chunk size {}", avg);
+    }
+
+    #[test]
+    fn test_cdc_resync_after_insert() {
+        let src = data(1 << 18, 2);
+        let mut edited = src[..1000].to_vec();
+        edited.extend_from_slice(b"inserted bytes");
+        edited.extend_from_slice(&src[1000..]);
+
+        let a = src.cdc_chunks(512, 2048, 8192).collect::<Vec<_>>();
+        let b = edited.cdc_chunks(512, 2048, 8192).collect::<Vec<_>>();
+        let shared = a.iter().filter(|c| b.contains(c)).count();
+        assert!(
+            shared + 3 >= a.len(),
+            "{} of {} chunks shared",
+            shared,
+            a.len()
+        );
+    }
+
+    #[test]
+    fn test_cdc_small_input() {
+        assert_eq!([0u8; 0].cdc_chunks(1, 2, 4).count(), 0);
+        assert_eq!(
+            [1u8; 3].cdc_chunks(4, 8, 16).collect::<Vec<_>>(),
+            vec![&[1u8; 3][..]]
+        );
+    }
+}
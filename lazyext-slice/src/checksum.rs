@@ -0,0 +1,575 @@
+//! Checksum algorithms behind a common [`Checksum`] trait.
+//!
+//! [`Checksum`]: trait.Checksum.html
+
+/// A streaming checksum algorithm, so block formats can swap algorithms without changing call sites.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::{BytesExt, Checksum, Crc32, XxHash64};
+///
+/// let mut crc = Crc32::default();
+/// crc.update(b"1234");
+/// crc.update(b"56789");
+/// assert_eq!(crc.finalize(), 0xcbf43926);
+/// assert_eq!(b"123456789".checksum::<Crc32>(), 0xcbf43926);
+/// assert_eq!(b"abc".checksum::<XxHash64>(), 0x44bc2cf5ad770999);
+/// ```
+pub trait Checksum: Default {
+    /// The type of the checksum value.
+    type Output;
+
+    /// Feeds `data` to the checksum.
+    fn update(&mut self, data: &[u8]);
+
+    /// Returns the checksum of all the data fed so far.
+    fn finalize(&self) -> Self::Output;
+}
+
+const fn crc32_table(poly: u32) -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut j = 0;
+        while j < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ poly
+            } else {
+                crc >> 1
+            };
+            j += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+const fn crc64_table(poly: u64) -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u64;
+        let mut j = 0;
+        while j < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ poly
+            } else {
+                crc >> 1
+            };
+            j += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+static CRC32_TABLE: [u32; 256] = crc32_table(0xedb8_8320);
+static CRC64_TABLE: [u64; 256] = crc64_table(0xc96c_5795_d787_0f42);
+
+/// CRC-32 (IEEE 802.3, the one of zlib, gzip and PNG).
+#[derive(Debug, Clone, Copy)]
+pub struct Crc32(u32);
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self(!0)
+    }
+}
+
+impl Checksum for Crc32 {
+    type Output = u32;
+
+    fn update(&mut self, data: &[u8]) {
+        for b in data {
+            self.0 = CRC32_TABLE[((self.0 ^ *b as u32) & 0xff) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    #[inline]
+    fn finalize(&self) -> u32 {
+        !self.0
+    }
+}
+
+/// CRC-64/XZ (ECMA-182 polynomial, reflected, the one of xz).
+#[derive(Debug, Clone, Copy)]
+pub struct Crc64(u64);
+
+impl Default for Crc64 {
+    fn default() -> Self {
+        Self(!0)
+    }
+}
+
+impl Checksum for Crc64 {
+    type Output = u64;
+
+    fn update(&mut self, data: &[u8]) {
+        for b in data {
+            self.0 = CRC64_TABLE[((self.0 ^ *b as u64) & 0xff) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    #[inline]
+    fn finalize(&self) -> u64 {
+        !self.0
+    }
+}
+
+const PRIME64_1: u64 = 0x9e37_79b1_85eb_ca87;
+const PRIME64_2: u64 = 0xc2b2_ae3d_27d4_eb4f;
+const PRIME64_3: u64 = 0x1656_67b1_9e37_79f9;
+const PRIME64_4: u64 = 0x85eb_ca77_c2b2_ae63;
+const PRIME64_5: u64 = 0x27d4_eb2f_1656_67c5;
+
+/// XXH64, a fast non-cryptographic hash. `Default` uses the seed 0.
+#[derive(Debug, Clone, Copy)]
+pub struct XxHash64 {
+    seed: u64,
+    acc: [u64; 4],
+    total_len: u64,
+    buf: [u8; 32],
+    buf_len: usize,
+}
+
+impl Default for XxHash64 {
+    fn default() -> Self {
+        Self::with_seed(0)
+    }
+}
+
+impl XxHash64 {
+    /// Creates a hasher with the given seed.
+    pub const fn with_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            acc: [
+                seed.wrapping_add(PRIME64_1).wrapping_add(PRIME64_2),
+                seed.wrapping_add(PRIME64_2),
+                seed,
+                seed.wrapping_sub(PRIME64_1),
+            ],
+            total_len: 0,
+            buf: [0; 32],
+            buf_len: 0,
+        }
+    }
+
+    #[inline]
+    fn round(acc: u64, input: u64) -> u64 {
+        acc.wrapping_add(input.wrapping_mul(PRIME64_2))
+            .rotate_left(31)
+            .wrapping_mul(PRIME64_1)
+    }
+
+    #[inline]
+    fn merge_round(acc: u64, val: u64) -> u64 {
+        (acc ^ Self::round(0, val))
+            .wrapping_mul(PRIME64_1)
+            .wrapping_add(PRIME64_4)
+    }
+
+    #[inline]
+    fn stripe(&mut self, stripe: &[u8]) {
+        for (acc, lane) in self.acc.iter_mut().zip(stripe.chunks_exact(8)) {
+            *acc = Self::round(*acc, u64::from_le_bytes(lane.try_into().unwrap()));
+        }
+    }
+}
+
+impl Checksum for XxHash64 {
+    type Output = u64;
+
+    fn update(&mut self, mut data: &[u8]) {
+        self.total_len += data.len() as u64;
+
+        if self.buf_len > 0 {
+            let n = data.len().min(32 - self.buf_len);
+            self.buf[self.buf_len..self.buf_len + n].copy_from_slice(&data[..n]);
+            self.buf_len += n;
+            data = &data[n..];
+            if self.buf_len < 32 {
+                return;
+            }
+            let buf = self.buf;
+            self.stripe(&buf);
+            self.buf_len = 0;
+        }
+
+        let mut stripes = data.chunks_exact(32);
+        for stripe in &mut stripes {
+            self.stripe(stripe);
+        }
+        let rest = stripes.remainder();
+        self.buf[..rest.len()].copy_from_slice(rest);
+        self.buf_len = rest.len();
+    }
+
+    fn finalize(&self) -> u64 {
+        let mut h = if self.total_len >= 32 {
+            let [v1, v2, v3, v4] = self.acc;
+            let h = v1
+                .rotate_left(1)
+                .wrapping_add(v2.rotate_left(7))
+                .wrapping_add(v3.rotate_left(12))
+                .wrapping_add(v4.rotate_left(18));
+            self.acc.iter().fold(h, |h, v| Self::merge_round(h, *v))
+        } else {
+            self.seed.wrapping_add(PRIME64_5)
+        };
+        h = h.wrapping_add(self.total_len);
+
+        let mut rest = &self.buf[..self.buf_len];
+        while rest.len() >= 8 {
+            let k = Self::round(0, u64::from_le_bytes(rest[..8].try_into().unwrap()));
+            h = (h ^ k)
+                .rotate_left(27)
+                .wrapping_mul(PRIME64_1)
+                .wrapping_add(PRIME64_4);
+            rest = &rest[8..];
+        }
+        if rest.len() >= 4 {
+            let k = u32::from_le_bytes(rest[..4].try_into().unwrap()) as u64;
+            h = (h ^ k.wrapping_mul(PRIME64_1))
+                .rotate_left(23)
+                .wrapping_mul(PRIME64_2)
+                .wrapping_add(PRIME64_3);
+            rest = &rest[4..];
+        }
+        for b in rest {
+            h = (h ^ (*b as u64).wrapping_mul(PRIME64_5))
+                .rotate_left(11)
+                .wrapping_mul(PRIME64_1);
+        }
+
+        h ^= h >> 33;
+        h = h.wrapping_mul(PRIME64_2);
+        h ^= h >> 29;
+        h = h.wrapping_mul(PRIME64_3);
+        h ^ (h >> 32)
+    }
+}
+
+/// The modulus of Adler-32, the largest prime below 2^16.
+const ADLER_MOD: u32 = 65521;
+
+/// The number of bytes which can be summed into `u32`s before the sums must be reduced, the
+/// `NMAX` of zlib.
+const ADLER_NMAX: usize = 5552;
+
+/// The number of 16-bit words Fletcher-32 can sum into a `u32` before reducing.
+const FLETCHER32_NMAX: usize = 359;
+
+/// Removes `old` from the front of a window of `len` values and appends `new`, for the
+/// Fletcher-style sums `a = init + sum(x)` and `b = len * init + sum((len - i) * x_i)`.
+#[inline]
+fn roll_sums(a: &mut u32, b: &mut u32, len: u64, old: u32, new: u32, init: u32, m: u32) {
+    let old = old % m;
+    let len = (len % m as u64) as u32;
+    *a = (*a + m - old + new % m) % m;
+    // b' = b - len * old + a' - init, kept non-negative by adding multiples of `m`.
+    let sub = (len as u64 * old as u64 % m as u64) as u32;
+    *b = (*b + 2 * m - sub - init + *a) % m;
+}
+
+/// Adler-32 (RFC 1950, the checksum of zlib streams).
+///
+/// Besides streaming with [`update`], the checksum of a sliding window can be updated in
+/// constant time with [`roll`].
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::{Adler32, BytesExt, Checksum};
+///
+/// assert_eq!(b"Wikipedia".adler32(), 0x11e6_0398);
+///
+/// let mut window = Adler32::default();
+/// window.update(b"xWikipedi");
+/// window.roll(b'x', b'a');
+/// assert_eq!(window.finalize(), 0x11e6_0398);
+/// ```
+///
+/// [`update`]: trait.Checksum.html#tymethod.update
+/// [`roll`]: struct.Adler32.html#method.roll
+#[derive(Debug, Clone, Copy)]
+pub struct Adler32 {
+    a: u32,
+    b: u32,
+    len: u64,
+}
+
+impl Default for Adler32 {
+    fn default() -> Self {
+        Self { a: 1, b: 0, len: 0 }
+    }
+}
+
+impl Adler32 {
+    /// Slides the window the checksum covers by one byte: `old`, its first byte, leaves and
+    /// `new` is appended.
+    ///
+    /// The window is all the bytes fed with [`update`] so far, its length does not change.
+    ///
+    /// [`update`]: trait.Checksum.html#tymethod.update
+    #[inline]
+    pub fn roll(&mut self, old: u8, new: u8) {
+        roll_sums(
+            &mut self.a,
+            &mut self.b,
+            self.len,
+            old as u32,
+            new as u32,
+            1,
+            ADLER_MOD,
+        );
+    }
+}
+
+impl Checksum for Adler32 {
+    type Output = u32;
+
+    fn update(&mut self, data: &[u8]) {
+        for chunk in data.chunks(ADLER_NMAX) {
+            for b in chunk {
+                self.a += *b as u32;
+                self.b += self.a;
+            }
+            self.a %= ADLER_MOD;
+            self.b %= ADLER_MOD;
+        }
+        self.len += data.len() as u64;
+    }
+
+    #[inline]
+    fn finalize(&self) -> u32 {
+        (self.b << 16) | self.a
+    }
+}
+
+/// Fletcher-16, the sums of the bytes modulo 255.
+///
+/// The window of the checksum can be slid with [`roll`], like [`Adler32`].
+///
+/// [`roll`]: struct.Fletcher16.html#method.roll
+/// [`Adler32`]: struct.Adler32.html
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Fletcher16 {
+    a: u32,
+    b: u32,
+    len: u64,
+}
+
+impl Fletcher16 {
+    /// Slides the window the checksum covers by one byte: `old`, its first byte, leaves and
+    /// `new` is appended.
+    #[inline]
+    pub fn roll(&mut self, old: u8, new: u8) {
+        roll_sums(
+            &mut self.a,
+            &mut self.b,
+            self.len,
+            old as u32,
+            new as u32,
+            0,
+            255,
+        );
+    }
+}
+
+impl Checksum for Fletcher16 {
+    type Output = u16;
+
+    fn update(&mut self, data: &[u8]) {
+        for chunk in data.chunks(ADLER_NMAX) {
+            for b in chunk {
+                self.a += *b as u32;
+                self.b += self.a;
+            }
+            self.a %= 255;
+            self.b %= 255;
+        }
+        self.len += data.len() as u64;
+    }
+
+    #[inline]
+    fn finalize(&self) -> u16 {
+        ((self.b << 8) | self.a) as u16
+    }
+}
+
+/// Fletcher-32, the sums of the little-endian 16-bit words modulo 65535.
+///
+/// An odd trailing byte is summed as a word padded with a zero byte. The window of the checksum
+/// can be slid one word at a time with [`roll`].
+///
+/// [`roll`]: struct.Fletcher32.html#method.roll
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Fletcher32 {
+    a: u32,
+    b: u32,
+    /// The number of whole words summed.
+    len: u64,
+    /// The first byte of a word split across `update` calls.
+    pending: Option<u8>,
+}
+
+impl Fletcher32 {
+    /// Slides the window the checksum covers by one little-endian word: `old`, its first word,
+    /// leaves and `new` is appended.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an odd number of bytes has been fed.
+    #[inline]
+    pub fn roll(&mut self, old: [u8; 2], new: [u8; 2]) {
+        assert!(
+            self.pending.is_none(),
+            "cannot roll a fletcher-32 window of an odd number of bytes"
+        );
+        roll_sums(
+            &mut self.a,
+            &mut self.b,
+            self.len,
+            u16::from_le_bytes(old) as u32,
+            u16::from_le_bytes(new) as u32,
+            0,
+            65535,
+        );
+    }
+
+    fn sum_words(a: &mut u32, b: &mut u32, words: &[u8]) {
+        for chunk in words.chunks(FLETCHER32_NMAX * 2) {
+            for w in chunk.chunks_exact(2) {
+                *a += u16::from_le_bytes([w[0], w[1]]) as u32;
+                *b += *a;
+            }
+            *a %= 65535;
+            *b %= 65535;
+        }
+    }
+}
+
+impl Checksum for Fletcher32 {
+    type Output = u32;
+
+    fn update(&mut self, mut data: &[u8]) {
+        if let (Some(lo), [hi, rest @ ..]) = (self.pending, data) {
+            Self::sum_words(&mut self.a, &mut self.b, &[lo, *hi]);
+            self.pending = None;
+            self.len += 1;
+            data = rest;
+        }
+        let words = data.len() / 2 * 2;
+        Self::sum_words(&mut self.a, &mut self.b, &data[..words]);
+        self.len += (words / 2) as u64;
+        if let Some(b) = data.get(words) {
+            self.pending = Some(*b);
+        }
+    }
+
+    fn finalize(&self) -> u32 {
+        let (mut a, mut b) = (self.a, self.b);
+        if let Some(lo) = self.pending {
+            Self::sum_words(&mut a, &mut b, &[lo, 0]);
+        }
+        (b << 16) | a
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BytesExt;
+
+    #[test]
+    fn test_check_values() {
+        assert_eq!(b"123456789".checksum::<Crc32>(), 0xcbf4_3926);
+        assert_eq!(b"123456789".checksum::<Crc64>(), 0x995d_c9bb_df19_39fa);
+        assert_eq!(b"".checksum::<XxHash64>(), 0xef46_db37_51d8_e999);
+        assert_eq!(b"a".checksum::<XxHash64>(), 0xd24e_c4f1_a98c_6e5b);
+        assert_eq!(b"abc".checksum::<XxHash64>(), 0x44bc_2cf5_ad77_0999);
+        assert_eq!(b"".checksum::<Adler32>(), 1);
+        assert_eq!(b"123456789".checksum::<Adler32>(), 0x091e_01de);
+        assert_eq!(b"abcde".checksum::<Fletcher16>(), 0xc8f0);
+        assert_eq!(b"abcdef".checksum::<Fletcher16>(), 0x2057);
+        assert_eq!(b"abcde".checksum::<Fletcher32>(), 0xf04f_c729);
+        assert_eq!(b"abcdef".checksum::<Fletcher32>(), 0x5650_2d2a);
+        assert_eq!(b"abcdefgh".checksum::<Fletcher32>(), 0xebe1_9591);
+    }
+
+    #[test]
+    fn test_long_input() {
+        // Long enough for the sums to be reduced between chunks.
+        let data = vec![0xffu8; 100_000];
+        let mut a = 1u64;
+        let mut b = 0u64;
+        for x in &data {
+            a = (a + *x as u64) % 65521;
+            b = (b + a) % 65521;
+        }
+        assert_eq!(data.checksum::<Adler32>(), ((b << 16) | a) as u32);
+    }
+
+    #[test]
+    fn test_roll() {
+        let data = (0..=255u8).cycle().take(20_000).collect::<Vec<_>>();
+        let window = 7_000;
+
+        let mut adler = Adler32::default();
+        let mut f16 = Fletcher16::default();
+        adler.update(&data[..window]);
+        f16.update(&data[..window]);
+        for start in 1..=data.len() - window {
+            let (old, new) = (data[start - 1], data[start + window - 1]);
+            adler.roll(old, new);
+            f16.roll(old, new);
+            if start % 997 == 0 {
+                let expected = &data[start..start + window];
+                assert_eq!(adler.finalize(), expected.checksum::<Adler32>());
+                assert_eq!(f16.finalize(), expected.checksum::<Fletcher16>());
+            }
+        }
+
+        let mut f32 = Fletcher32::default();
+        f32.update(&data[..window]);
+        for start in (2..=data.len() - window).step_by(2) {
+            f32.roll(
+                [data[start - 2], data[start - 1]],
+                [data[start + window - 2], data[start + window - 1]],
+            );
+            if start % 1000 == 0 {
+                let expected = &data[start..start + window];
+                assert_eq!(f32.finalize(), expected.checksum::<Fletcher32>());
+            }
+        }
+    }
+
+    fn streaming_matches_oneshot<C>()
+    where
+        C: Checksum,
+        C::Output: PartialEq + core::fmt::Debug,
+    {
+        let data = (0..200u8).collect::<Vec<_>>();
+        for split in [0, 1, 7, 31, 32, 33, 100, 200] {
+            let mut c = C::default();
+            c.update(&data[..split]);
+            c.update(&data[split..]);
+            assert_eq!(c.finalize(), data.checksum::<C>());
+        }
+    }
+
+    #[test]
+    fn test_streaming() {
+        streaming_matches_oneshot::<Crc32>();
+        streaming_matches_oneshot::<Crc64>();
+        streaming_matches_oneshot::<XxHash64>();
+        streaming_matches_oneshot::<Adler32>();
+        streaming_matches_oneshot::<Fletcher16>();
+        streaming_matches_oneshot::<Fletcher32>();
+    }
+}
@@ -0,0 +1,140 @@
+use core::mem;
+
+/// An iterator which decodes a byte slice into typed chunks of at most `chunk_len` elements.
+///
+/// It is returned by the `chunked_to_{be,le,ne}_{ty}_vec` methods of [`BytesExt`], e.g.
+/// [`chunked_to_be_u32_vec`]. Iterating yields a fresh `Vec` per chunk, while [`next_chunk`]
+/// decodes into a buffer owned by the iterator, so converting a huge (e.g. mmapped) file only
+/// needs one allocation of `chunk_len` elements.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::BytesExt;
+///
+/// let bytes = [0u8, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 3];
+/// let mut chunks = bytes.chunked_to_be_u32_vec(2);
+/// assert_eq!(chunks.next_chunk(), Some(&[1u32, 2][..]));
+/// assert_eq!(chunks.next_chunk(), Some(&[3u32][..]));
+/// assert_eq!(chunks.next_chunk(), None);
+///
+/// let chunks = bytes.chunked_to_be_u32_vec(2).collect::<Vec<_>>();
+/// assert_eq!(chunks, vec![vec![1, 2], vec![3]]);
+/// ```
+///
+/// [`BytesExt`]: trait.BytesExt.html
+/// [`chunked_to_be_u32_vec`]: trait.BytesExt.html#method.chunked_to_be_u32_vec
+/// [`next_chunk`]: struct.ChunkedDecoder.html#method.next_chunk
+pub struct ChunkedDecoder<'a, T> {
+    src: &'a [u8],
+    chunk_len: usize,
+    decode: fn(&[u8]) -> T,
+    buf: Vec<T>,
+}
+
+impl<'a, T> ChunkedDecoder<'a, T> {
+    /// # Panics
+    /// Panics if `chunk_len` is 0 or the length of `src` is not a multiple of the size of `T`.
+    pub(crate) fn new(src: &'a [u8], chunk_len: usize, decode: fn(&[u8]) -> T) -> Self {
+        assert!(chunk_len > 0, "chunk_len must be greater than 0");
+        assert_eq!(
+            src.len() % mem::size_of::<T>(),
+            0,
+            "invalid length of u8 slice: {}",
+            src.len()
+        );
+        Self {
+            src,
+            chunk_len,
+            decode,
+            buf: Vec::new(),
+        }
+    }
+
+    /// Decodes the next chunk into the buffer of the iterator, reusing its allocation.
+    pub fn next_chunk(&mut self) -> Option<&[T]> {
+        let mut buf = mem::take(&mut self.buf);
+        let has_next = self.next_chunk_into(&mut buf);
+        self.buf = buf;
+        has_next.then_some(self.buf.as_slice())
+    }
+
+    /// Decodes the next chunk into `buf`, replacing its content.
+    ///
+    /// Returns `false` (leaving `buf` empty) when all chunks have been decoded.
+    pub fn next_chunk_into(&mut self, buf: &mut Vec<T>) -> bool {
+        buf.clear();
+        if self.src.is_empty() {
+            return false;
+        }
+
+        let size = mem::size_of::<T>();
+        let (chunk, rest) = self.src.split_at(self.src.len().min(self.chunk_len * size));
+        buf.extend(chunk.chunks_exact(size).map(self.decode));
+        self.src = rest;
+        true
+    }
+
+    /// Returns the number of elements which have not been decoded yet.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.src.len() / mem::size_of::<T>()
+    }
+}
+
+impl<T> Iterator for ChunkedDecoder<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buf = Vec::with_capacity(self.chunk_len.min(self.remaining()));
+        self.next_chunk_into(&mut buf).then_some(buf)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.remaining().div_ceil(self.chunk_len);
+        (n, Some(n))
+    }
+}
+
+impl<T> ExactSizeIterator for ChunkedDecoder<'_, T> {}
+
+impl<T> core::iter::FusedIterator for ChunkedDecoder<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::BytesExt;
+
+    #[test]
+    fn test_chunked_to_vec() {
+        let vals = (0..10u64).collect::<Vec<_>>();
+        let bytes = vals.iter().flat_map(|v| v.to_le_bytes()).collect::<Vec<_>>();
+
+        let chunks = bytes.chunked_to_le_u64_vec(4);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks.flatten().collect::<Vec<_>>(), vals);
+
+        let mut chunks = bytes.chunked_to_le_u64_vec(3);
+        let mut buf = Vec::new();
+        let mut decoded = Vec::new();
+        while chunks.next_chunk_into(&mut buf) {
+            assert!(buf.len() <= 3);
+            decoded.extend_from_slice(&buf);
+        }
+        assert!(buf.is_empty());
+        assert_eq!(decoded, vals);
+    }
+
+    #[test]
+    fn test_chunked_empty() {
+        let mut chunks = [0u8; 0].chunked_to_ne_f32_vec(8);
+        assert_eq!(chunks.len(), 0);
+        assert!(chunks.next_chunk().is_none());
+        assert!(chunks.next().is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_chunked_invalid_len() {
+        [0u8; 3].chunked_to_be_u16_vec(1);
+    }
+}
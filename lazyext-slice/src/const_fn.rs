@@ -0,0 +1,103 @@
+//! `const` versions of the byte slice checks of [`BytesExt`], for compile-time validation.
+//!
+//! [`BytesExt`]: trait.BytesExt.html
+
+/// Returns whether the two byte slices are equal, in const contexts.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::const_eq;
+///
+/// const MAGIC: &[u8] = b"LZX1";
+/// const _: () = assert!(const_eq(MAGIC, b"LZX1"));
+/// ```
+#[inline]
+pub const fn const_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && const_common_prefix_len(a, b) == a.len()
+}
+
+/// Returns whether `src` begins with `prefix`, in const contexts.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::const_has_prefix;
+///
+/// const PNG_HEADER: &[u8] = b"\x89PNG\r\n\x1a\n";
+/// const _: () = assert!(const_has_prefix(PNG_HEADER, b"\x89PNG"));
+/// ```
+#[inline]
+pub const fn const_has_prefix(src: &[u8], prefix: &[u8]) -> bool {
+    src.len() >= prefix.len() && const_common_prefix_len(src, prefix) == prefix.len()
+}
+
+/// Returns whether `src` ends with `suffix`, in const contexts.
+#[inline]
+pub const fn const_has_suffix(src: &[u8], suffix: &[u8]) -> bool {
+    if src.len() < suffix.len() {
+        return false;
+    }
+
+    let offset = src.len() - suffix.len();
+    let mut i = 0;
+    while i < suffix.len() {
+        if src[offset + i] != suffix[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Returns the length of the longest shared prefix of the two byte slices, in const contexts.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::const_common_prefix_len;
+///
+/// const LEN: usize = const_common_prefix_len(b"Hello, LazyExt!", b"Hello, Rust!");
+/// assert_eq!(LEN, "Hello, ".len());
+/// ```
+#[inline]
+pub const fn const_common_prefix_len(a: &[u8], b: &[u8]) -> usize {
+    let max = if a.len() < b.len() { a.len() } else { b.len() };
+    let mut i = 0;
+    while i < max && a[i] == b[i] {
+        i += 1;
+    }
+    i
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BytesExt;
+
+    const CASES: &[(&[u8], &[u8])] = &[
+        (b"", b""),
+        (b"", b"a"),
+        (b"abc", b""),
+        (b"abc", b"abc"),
+        (b"abc", b"ab"),
+        (b"ab", b"abc"),
+        (b"abc", b"bc"),
+        (b"abc", b"abd"),
+        (b"Hello, LazyExt!", b"LazyExt!"),
+    ];
+
+    #[test]
+    fn test_const_fns_agree_with_bytes_ext() {
+        for &(a, b) in CASES {
+            assert_eq!(const_eq(a, b), a.bytes_eq(b));
+            assert_eq!(const_has_prefix(a, b), a.has_prefix(b));
+            assert_eq!(const_has_suffix(a, b), a.has_suffix(b));
+            assert_eq!(const_common_prefix_len(a, b), a.longest_prefix(b).len());
+        }
+    }
+
+    const _: () = assert!(const_has_prefix(b"\x89PNG", b"\x89P"));
+    const _: () = assert!(const_has_suffix(b"abc", b"bc"));
+    const _: () = assert!(const_common_prefix_len(b"abc", b"abd") == 2);
+}
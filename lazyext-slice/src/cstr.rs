@@ -0,0 +1,97 @@
+use core::iter::FusedIterator;
+
+/// An iterator over the NUL-terminated strings of a byte slice, without their terminators.
+///
+/// It is returned by [`BytesExt::split_nul`]. Like `str::split_terminator`, a final terminator
+/// does not start an empty string, while bytes after the last NUL are yielded as a last,
+/// unterminated string.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::BytesExt;
+///
+/// // An ELF-style string table.
+/// let table = b"\0.text\0.data\0";
+/// let names = table.split_nul().collect::<Vec<_>>();
+/// assert_eq!(names, [&b""[..], b".text", b".data"]);
+///
+/// assert_eq!(b"a\0b".split_nul().collect::<Vec<_>>(), [b"a", b"b"]);
+/// assert_eq!(b"".split_nul().next(), None);
+/// ```
+///
+/// [`BytesExt::split_nul`]: trait.BytesExt.html#method.split_nul
+#[derive(Debug, Clone)]
+pub struct SplitNul<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> SplitNul<'a> {
+    #[inline]
+    pub(crate) const fn new(src: &'a [u8]) -> Self {
+        Self { rest: src }
+    }
+
+    /// Returns the bytes which have not been split yet.
+    #[inline]
+    pub const fn remaining(&self) -> &'a [u8] {
+        self.rest
+    }
+}
+
+impl<'a> Iterator for SplitNul<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        match self.rest.iter().position(|b| *b == 0) {
+            Some(i) => {
+                let s = &self.rest[..i];
+                self.rest = &self.rest[i + 1..];
+                Some(s)
+            }
+            None => Some(core::mem::take(&mut self.rest)),
+        }
+    }
+}
+
+impl FusedIterator for SplitNul<'_> {}
+
+/// Returns the bytes from `offset` up to, but excluding, the next NUL.
+#[inline]
+pub(crate) fn take_cstr_at(src: &[u8], offset: usize) -> Option<&[u8]> {
+    let rest = src.get(offset..)?;
+    rest.iter().position(|b| *b == 0).map(|i| &rest[..i])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_cstr_at() {
+        let src = b"abc\0de\0f";
+        assert_eq!(take_cstr_at(src, 0), Some(&b"abc"[..]));
+        assert_eq!(take_cstr_at(src, 1), Some(&b"bc"[..]));
+        assert_eq!(take_cstr_at(src, 3), Some(&b""[..]));
+        assert_eq!(take_cstr_at(src, 4), Some(&b"de"[..]));
+        // Unterminated or out of bounds.
+        assert_eq!(take_cstr_at(src, 7), None);
+        assert_eq!(take_cstr_at(src, 8), None);
+        assert_eq!(take_cstr_at(src, 9), None);
+    }
+
+    #[test]
+    fn test_split_nul() {
+        let mut iter = SplitNul::new(b"\0\0x\0");
+        assert_eq!(iter.next(), Some(&b""[..]));
+        assert_eq!(iter.remaining(), b"\0x\0");
+        assert_eq!(iter.next(), Some(&b""[..]));
+        assert_eq!(iter.next(), Some(&b"x"[..]));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+    }
+}
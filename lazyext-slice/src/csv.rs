@@ -0,0 +1,144 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+/// An iterator over the fields of one CSV record, handling quoted fields and escaped (doubled) quotes.
+///
+/// Fields are borrowed from the record unless unescaping requires a copy. Parsing is lenient:
+/// bytes after a closing quote are kept as part of the field, and an unterminated quoted field
+/// runs to the end of the record. The record should not contain its line terminator.
+///
+/// It is returned by [`BytesExt::csv_fields`].
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::BytesExt;
+///
+/// let record = br#"1,"Smith, John","say ""hi""",""#;
+/// let fields = record.csv_fields(b',', b'"').collect::<Vec<_>>();
+/// assert_eq!(fields, [&b"1"[..], b"Smith, John", br#"say "hi""#, b""]);
+/// ```
+///
+/// [`BytesExt::csv_fields`]: trait.BytesExt.html#method.csv_fields
+#[derive(Debug, Clone)]
+pub struct CsvFields<'a> {
+    rest: &'a [u8],
+    delimiter: u8,
+    quote: u8,
+    finished: bool,
+}
+
+impl<'a> CsvFields<'a> {
+    pub(crate) fn new(src: &'a [u8], delimiter: u8, quote: u8) -> Self {
+        Self {
+            rest: src,
+            delimiter,
+            quote,
+            finished: false,
+        }
+    }
+
+    /// Parses the quoted field at the start of `rest`, returning the unescaped content
+    /// and the index just past the closing quote.
+    fn quoted(&self) -> (Cow<'a, [u8]>, usize) {
+        let rest = self.rest;
+        let mut owned: Option<Vec<u8>> = None;
+        let mut start = 1;
+        loop {
+            let end = match rest[start..].iter().position(|&b| b == self.quote) {
+                Some(i) => start + i,
+                None => return (append(owned, &rest[start..]), rest.len()),
+            };
+            if rest.get(end + 1) == Some(&self.quote) {
+                owned
+                    .get_or_insert_with(Vec::new)
+                    .extend_from_slice(&rest[start..=end]);
+                start = end + 2;
+            } else {
+                return (append(owned, &rest[start..end]), end + 1);
+            }
+        }
+    }
+}
+
+/// Appends `tail` to `owned`, or borrows `tail` if nothing was copied yet.
+#[inline]
+fn append<'a>(owned: Option<Vec<u8>>, tail: &'a [u8]) -> Cow<'a, [u8]> {
+    match owned {
+        Some(mut v) => {
+            v.extend_from_slice(tail);
+            Cow::Owned(v)
+        }
+        None => Cow::Borrowed(tail),
+    }
+}
+
+impl<'a> Iterator for CsvFields<'a> {
+    type Item = Cow<'a, [u8]>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let rest = self.rest;
+        let (mut field, start) = if rest.first() == Some(&self.quote) {
+            self.quoted()
+        } else {
+            (Cow::Borrowed(&rest[..0]), 0)
+        };
+        let end = rest[start..]
+            .iter()
+            .position(|&b| b == self.delimiter)
+            .map_or(rest.len(), |i| start + i);
+        if start == 0 {
+            field = Cow::Borrowed(&rest[..end]);
+        } else if end > start {
+            field.to_mut().extend_from_slice(&rest[start..end]);
+        }
+
+        if end < rest.len() {
+            self.rest = &rest[end + 1..];
+        } else {
+            self.rest = &rest[rest.len()..];
+            self.finished = true;
+        }
+        Some(field)
+    }
+}
+
+impl core::iter::FusedIterator for CsvFields<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(src: &[u8]) -> Vec<Cow<'_, [u8]>> {
+        CsvFields::new(src, b',', b'"').collect()
+    }
+
+    #[test]
+    fn test_csv_fields() {
+        assert_eq!(fields(b"a,b,,c"), [&b"a"[..], b"b", b"", b"c"]);
+        assert_eq!(fields(b""), [&b""[..]]);
+        assert_eq!(fields(b","), [&b""[..], b""]);
+        assert_eq!(fields(br#""a,b""#), [&b"a,b"[..]]);
+        assert_eq!(fields(br#""""""#), [&br#"""#[..]]);
+        assert_eq!(fields(br#""ab"cd,e"#), [&b"abcd"[..], b"e"]);
+        assert_eq!(fields(br#""unterminated,x"#), [&b"unterminated,x"[..]]);
+        assert_eq!(fields(br#"mid"quote,x"#), [&br#"mid"quote"#[..], b"x"]);
+    }
+
+    #[test]
+    fn test_csv_fields_borrowed() {
+        let src = br#"plain,"quoted","esc""aped""#;
+        let fields = fields(src);
+        assert!(matches!(fields[0], Cow::Borrowed(_)));
+        assert!(matches!(fields[1], Cow::Borrowed(_)));
+        assert!(matches!(fields[2], Cow::Owned(_)));
+        assert_eq!(fields[2], &br#"esc"aped"#[..]);
+
+        let tsv = CsvFields::new(b"a\t'b\tc'", b'\t', b'\'').collect::<Vec<_>>();
+        assert_eq!(tsv, [&b"a"[..], b"b\tc"]);
+    }
+}
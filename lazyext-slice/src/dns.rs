@@ -0,0 +1,291 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+/// The maximum length of a label.
+const MAX_LABEL: usize = 63;
+
+/// The maximum length of a name in wire format.
+const MAX_NAME: usize = 255;
+
+/// The error returned when encoding or decoding a DNS name fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DnsNameError {
+    /// The name has an empty label, e.g. `a..b`.
+    EmptyLabel,
+    /// A label is longer than 63 bytes.
+    LabelTooLong(usize),
+    /// The name is longer than 255 bytes in wire format.
+    NameTooLong,
+    /// The text name has an invalid `\` escape at the given index.
+    InvalidEscape(usize),
+    /// The wire-format name ends unexpectedly at the given offset.
+    UnexpectedEof(usize),
+    /// The compression pointer at `offset` does not point before the labels it continues.
+    InvalidPointer {
+        /// The offset of the pointer.
+        offset: usize,
+        /// The offset it points to.
+        target: usize,
+    },
+    /// The label at `offset` has the reserved `0x40` or `0x80` type bits.
+    InvalidLabelType {
+        /// The offset of the label.
+        offset: usize,
+        /// The length byte of the label.
+        byte: u8,
+    },
+}
+
+impl fmt::Display for DnsNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyLabel => f.write_str("empty label in dns name"),
+            Self::LabelTooLong(len) => write!(f, "dns label of {} bytes, at most 63 allowed", len),
+            Self::NameTooLong => f.write_str("dns name longer than 255 bytes"),
+            Self::InvalidEscape(index) => write!(f, "invalid escape in dns name at {}", index),
+            Self::UnexpectedEof(offset) => {
+                write!(f, "dns name ends unexpectedly at offset {}", offset)
+            }
+            Self::InvalidPointer { offset, target } => write!(
+                f,
+                "dns compression pointer at offset {} points forward to {}",
+                offset, target
+            ),
+            Self::InvalidLabelType { offset, byte } => write!(
+                f,
+                "reserved dns label type {:#04x} at offset {}",
+                byte, offset
+            ),
+        }
+    }
+}
+
+cfg_feature!("std", {
+    impl std::error::Error for DnsNameError {}
+});
+
+/// Encodes a dotted DNS name into the wire format: length-prefixed labels ending with the
+/// empty root label.
+///
+/// A trailing dot is optional, `""` and `"."` encode the root name. The `\.`, `\\` and `\DDD`
+/// (decimal) escapes of the presentation format are accepted for bytes which cannot be written
+/// directly.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::{encode_dns_name, BytesExt};
+///
+/// let wire = encode_dns_name("www.example.com").unwrap();
+/// assert_eq!(wire, b"\x03www\x07example\x03com\x00");
+/// assert_eq!(wire.decode_dns_name(0).unwrap(), ("www.example.com".to_string(), 17));
+/// assert!(encode_dns_name("a..b").is_err());
+/// ```
+pub fn encode_dns_name(name: &str) -> Result<Vec<u8>, DnsNameError> {
+    let src = name.as_bytes();
+    // A trailing dot ends the name, unless it is escaped by an odd number of backslashes.
+    let src = match src {
+        [rest @ .., b'.'] if rest.iter().rev().take_while(|b| **b == b'\\').count() % 2 == 0 => {
+            rest
+        }
+        _ => src,
+    };
+
+    let mut out = Vec::with_capacity(src.len() + 2);
+    if src.is_empty() {
+        out.push(0);
+        return Ok(out);
+    }
+
+    let mut label_start = out.len();
+    out.push(0);
+    let mut i = 0;
+    while i <= src.len() {
+        let b = match src.get(i) {
+            None | Some(b'.') => {
+                close_label(&mut out, label_start)?;
+                if i < src.len() {
+                    label_start = out.len();
+                    out.push(0);
+                }
+                i += 1;
+                continue;
+            }
+            Some(b'\\') => {
+                let (b, len) = unescape(&src[i + 1..]).ok_or(DnsNameError::InvalidEscape(i))?;
+                i += len + 1;
+                b
+            }
+            Some(b) => {
+                i += 1;
+                *b
+            }
+        };
+        out.push(b);
+    }
+    out.push(0);
+
+    if out.len() > MAX_NAME {
+        return Err(DnsNameError::NameTooLong);
+    }
+    Ok(out)
+}
+
+fn close_label(out: &mut [u8], label_start: usize) -> Result<(), DnsNameError> {
+    match out.len() - label_start - 1 {
+        0 => Err(DnsNameError::EmptyLabel),
+        len if len > MAX_LABEL => Err(DnsNameError::LabelTooLong(len)),
+        len => {
+            out[label_start] = len as u8;
+            Ok(())
+        }
+    }
+}
+
+/// Decodes the escape following a `\`, returning the byte and the number of bytes it used.
+fn unescape(src: &[u8]) -> Option<(u8, usize)> {
+    match src {
+        [a @ b'0'..=b'9', b @ b'0'..=b'9', c @ b'0'..=b'9', ..] => {
+            let v = (a - b'0') as u16 * 100 + (b - b'0') as u16 * 10 + (c - b'0') as u16;
+            u8::try_from(v).ok().map(|v| (v, 3))
+        }
+        [b'0'..=b'9', ..] | [] => None,
+        [b, ..] => Some((*b, 1)),
+    }
+}
+
+/// Decodes the wire-format name at `offset` of `src`, following compression pointers, and
+/// returns it with the offset just past it (past the first pointer, if any).
+pub(crate) fn decode_dns_name(src: &[u8], offset: usize) -> Result<(String, usize), DnsNameError> {
+    let mut name = String::new();
+    let mut pos = offset;
+    // Pointers must point before the labels they continue, so decoding terminates.
+    let mut floor = offset;
+    let mut end = None;
+    let mut wire_len = 0;
+    loop {
+        let len = *src.get(pos).ok_or(DnsNameError::UnexpectedEof(pos))?;
+        match len & 0xc0 {
+            0xc0 => {
+                let low = *src
+                    .get(pos + 1)
+                    .ok_or(DnsNameError::UnexpectedEof(pos + 1))?;
+                let target = ((len as usize & 0x3f) << 8) | low as usize;
+                if target >= floor {
+                    return Err(DnsNameError::InvalidPointer {
+                        offset: pos,
+                        target,
+                    });
+                }
+                end.get_or_insert(pos + 2);
+                floor = target;
+                pos = target;
+            }
+            0x00 if len == 0 => {
+                if name.is_empty() {
+                    name.push('.');
+                }
+                return Ok((name, end.unwrap_or(pos + 1)));
+            }
+            0x00 => {
+                let label = src
+                    .get(pos + 1..pos + 1 + len as usize)
+                    .ok_or(DnsNameError::UnexpectedEof(src.len()))?;
+                wire_len += label.len() + 1;
+                if wire_len + 1 > MAX_NAME {
+                    return Err(DnsNameError::NameTooLong);
+                }
+                if !name.is_empty() {
+                    name.push('.');
+                }
+                for &b in label {
+                    match b {
+                        b'.' | b'\\' => {
+                            name.push('\\');
+                            name.push(b as char);
+                        }
+                        0x21..=0x7e => name.push(b as char),
+                        _ => write!(name, "\\{:03}", b).unwrap(),
+                    }
+                }
+                pos += 1 + label.len();
+            }
+            _ => {
+                return Err(DnsNameError::InvalidLabelType {
+                    offset: pos,
+                    byte: len,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_dns_name() {
+        assert_eq!(encode_dns_name(""), Ok(vec![0]));
+        assert_eq!(encode_dns_name("."), Ok(vec![0]));
+        assert_eq!(encode_dns_name("com."), Ok(b"\x03com\x00".to_vec()));
+        assert_eq!(encode_dns_name(r"a\.b.c"), Ok(b"\x03a.b\x01c\x00".to_vec()));
+        assert_eq!(encode_dns_name(r"a\032b"), Ok(b"\x03a b\x00".to_vec()));
+        assert_eq!(encode_dns_name(r"a\."), Ok(b"\x02a.\x00".to_vec()));
+        assert_eq!(encode_dns_name(r"a\\."), Ok(b"\x02a\\\x00".to_vec()));
+        assert_eq!(encode_dns_name(".a"), Err(DnsNameError::EmptyLabel));
+        assert_eq!(
+            encode_dns_name(r"a\256"),
+            Err(DnsNameError::InvalidEscape(1))
+        );
+        assert_eq!(
+            encode_dns_name(&"a".repeat(64)),
+            Err(DnsNameError::LabelTooLong(64))
+        );
+        let long = vec!["a".repeat(63); 4].join(".");
+        assert_eq!(encode_dns_name(&long), Err(DnsNameError::NameTooLong));
+    }
+
+    #[test]
+    fn test_decode_dns_name() {
+        // "example.com" at 0, then "www" + pointer to 0 at 13.
+        let msg = b"\x07example\x03com\x00\x03www\xc0\x00";
+        assert_eq!(
+            decode_dns_name(msg, 13),
+            Ok(("www.example.com".to_string(), 19))
+        );
+        assert_eq!(decode_dns_name(b"\x00", 0), Ok((".".to_string(), 1)));
+        assert_eq!(
+            decode_dns_name(b"\x03a.\xff\x00", 0),
+            Ok((r"a\.\255".to_string(), 5))
+        );
+
+        assert_eq!(
+            decode_dns_name(b"\xc0\x00", 0),
+            Err(DnsNameError::InvalidPointer {
+                offset: 0,
+                target: 0
+            })
+        );
+        assert_eq!(
+            decode_dns_name(b"\x03ab", 0),
+            Err(DnsNameError::UnexpectedEof(3))
+        );
+        assert_eq!(
+            decode_dns_name(b"\x41", 0),
+            Err(DnsNameError::InvalidLabelType {
+                offset: 0,
+                byte: 0x41
+            })
+        );
+    }
+
+    #[test]
+    fn test_dns_name_roundtrip() {
+        for name in ["a.b", r"x\.y.z", r"t\009b", "."] {
+            let wire = encode_dns_name(name).unwrap();
+            assert_eq!(decode_dns_name(&wire, 0).unwrap().0, name);
+        }
+    }
+}
@@ -0,0 +1,273 @@
+//! Elias-Fano encoding of sorted integer sequences.
+//!
+//! Each value is split into `l` low bits, stored verbatim in a packed array, and the remaining
+//! high bits, stored in unary as the gaps of a bit vector where value `i` sets bit
+//! `(v >> l) + i`. With `l = log2(max / n)` this takes less than `2 + log2(max / n)` bits
+//! per value, and value `i` is found with a `select1` over the high bits.
+
+use crate::{RankSelectBits, ReadError, SliceReader, VecByteWriterExt};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+/// Reads `width` (at most 64) bits at bit `pos` of `src`, least significant first.
+fn get_bits(src: &[u8], pos: usize, width: u32) -> u64 {
+    if width == 0 {
+        return 0;
+    }
+    let start = pos / 8;
+    let end = (start + 9).min(src.len());
+    let mut buf = [0; 16];
+    buf[..end - start].copy_from_slice(&src[start..end]);
+    let window = u128::from_le_bytes(buf) >> (pos % 8);
+    (window & ((1u128 << width) - 1)) as u64
+}
+
+/// Sets `width` (at most 64) bits at bit `pos` of the zeroed `dst`.
+fn set_bits(dst: &mut [u8], pos: usize, width: u32, v: u64) {
+    let bits = (v as u128 & ((1u128 << width) - 1)) << (pos % 8);
+    for (i, b) in bits.to_le_bytes().iter().enumerate() {
+        if *b != 0 {
+            dst[pos / 8 + i] |= b;
+        }
+    }
+}
+
+/// A compressed, randomly accessible sequence of non-decreasing `u64`s, for offset arrays and
+/// posting lists.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::EliasFano;
+///
+/// let offsets = [0, 12, 12, 40, 1000, 1_000_000];
+/// let ef = EliasFano::new(&offsets);
+/// assert_eq!(ef.len(), 6);
+/// assert_eq!(ef.get(3), Some(40));
+/// assert_eq!(ef.get(6), None);
+/// assert!(ef.iter().eq(offsets));
+///
+/// let restored = EliasFano::from_bytes(&ef.to_bytes()).unwrap();
+/// assert_eq!(restored.get(5), Some(1_000_000));
+/// ```
+#[derive(Debug, Clone)]
+pub struct EliasFano {
+    len: usize,
+    low_bits: u32,
+    lows: Vec<u8>,
+    highs: RankSelectBits<Vec<u8>>,
+}
+
+impl EliasFano {
+    /// Encodes `values`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` is not sorted in non-decreasing order.
+    pub fn new(values: &[u64]) -> Self {
+        assert!(
+            values.windows(2).all(|w| w[0] <= w[1]),
+            "values must be sorted in non-decreasing order"
+        );
+        let len = values.len();
+        let max = values.last().copied().unwrap_or(0);
+        let low_bits = match max / (len.max(1) as u64) {
+            0 => 0,
+            ratio => ratio.ilog2(),
+        };
+
+        let mut lows = vec![0; (len * low_bits as usize).div_ceil(8)];
+        let high_len = len + (max >> low_bits) as usize + 1;
+        let mut highs = vec![0u8; high_len.div_ceil(8)];
+        for (i, &v) in values.iter().enumerate() {
+            set_bits(&mut lows, i * low_bits as usize, low_bits, v);
+            let pos = (v >> low_bits) as usize + i;
+            highs[pos / 8] |= 1 << (pos % 8);
+        }
+        Self {
+            len,
+            low_bits,
+            lows,
+            highs: RankSelectBits::with_len(highs, high_len),
+        }
+    }
+
+    /// Returns the number of values.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether there is no value.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    fn low(&self, i: usize) -> u64 {
+        get_bits(&self.lows, i * self.low_bits as usize, self.low_bits)
+    }
+
+    /// Returns the `i`th value.
+    pub fn get(&self, i: usize) -> Option<u64> {
+        let pos = self.highs.select1(i)?;
+        Some((((pos - i) as u64) << self.low_bits) | self.low(i))
+    }
+
+    /// Returns an iterator over the values, which scans the high bits instead of selecting
+    /// every value.
+    #[inline]
+    pub fn iter(&self) -> EliasFanoIter<'_> {
+        EliasFanoIter {
+            ef: self,
+            index: 0,
+            pos: 0,
+        }
+    }
+
+    /// Serializes the sequence as `len (varint) | low bits (u8) | high bit length (varint) |
+    /// low bits | high bits`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let highs = self.highs.as_bits();
+        let mut dst = Vec::with_capacity(21 + self.lows.len() + highs.len());
+        dst.push_varint(self.len as u64);
+        dst.push(self.low_bits as u8);
+        dst.push_varint(self.highs.len() as u64);
+        dst.push_slice(&self.lows);
+        dst.push_slice(highs);
+        dst
+    }
+
+    /// Deserializes a sequence written by [`to_bytes`].
+    ///
+    /// Fails with [`ReadError::Mismatch`] if the bytes do not describe a valid sequence.
+    ///
+    /// [`to_bytes`]: struct.EliasFano.html#method.to_bytes
+    /// [`ReadError::Mismatch`]: enum.ReadError.html#variant.Mismatch
+    pub fn from_bytes(src: &[u8]) -> Result<Self, ReadError> {
+        let mut r = SliceReader::new(src);
+        let len = r.read_varint()? as usize;
+        let offset = r.offset();
+        let low_bits = r.read_u8()? as u32;
+        let high_len = r.read_varint()? as usize;
+        let invalid = ReadError::Mismatch { offset };
+        if low_bits > 63 || high_len < len {
+            return Err(invalid);
+        }
+
+        let lows = len
+            .checked_mul(low_bits as usize)
+            .map(|bits| bits.div_ceil(8))
+            .ok_or(invalid)?;
+        let lows = r.take(lows)?.to_vec();
+        let highs = r.take(high_len.div_ceil(8))?.to_vec();
+        let highs = RankSelectBits::with_len(highs, high_len);
+        if highs.count_ones() != len {
+            return Err(invalid);
+        }
+        Ok(Self {
+            len,
+            low_bits,
+            lows,
+            highs,
+        })
+    }
+}
+
+/// An iterator over the values of an [`EliasFano`] sequence.
+///
+/// [`EliasFano`]: struct.EliasFano.html
+#[derive(Debug, Clone)]
+pub struct EliasFanoIter<'a> {
+    ef: &'a EliasFano,
+    index: usize,
+    /// The position of the next bit to scan in the high bits.
+    pos: usize,
+}
+
+impl Iterator for EliasFanoIter<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if self.index == self.ef.len {
+            return None;
+        }
+        while !self.ef.highs.get(self.pos) {
+            self.pos += 1;
+        }
+        let high = (self.pos - self.index) as u64;
+        let v = (high << self.ef.low_bits) | self.ef.low(self.index);
+        self.index += 1;
+        self.pos += 1;
+        Some(v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let rest = self.ef.len - self.index;
+        (rest, Some(rest))
+    }
+}
+
+impl ExactSizeIterator for EliasFanoIter<'_> {}
+
+impl FusedIterator for EliasFanoIter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elias_fano() {
+        let sequences: [Vec<u64>; 5] = [
+            vec![],
+            vec![0],
+            vec![7; 10],
+            (0..1000).map(|i| i * i).collect(),
+            vec![1, 2, u64::MAX / 2, u64::MAX],
+        ];
+        for values in sequences {
+            let ef = EliasFano::new(&values);
+            assert_eq!(ef.len(), values.len());
+            for (i, &v) in values.iter().enumerate() {
+                assert_eq!(ef.get(i), Some(v));
+            }
+            assert_eq!(ef.get(values.len()), None);
+            assert!(ef.iter().eq(values.iter().copied()));
+
+            let restored = EliasFano::from_bytes(&ef.to_bytes()).unwrap();
+            assert!(restored.iter().eq(values.iter().copied()));
+        }
+    }
+
+    #[test]
+    fn test_elias_fano_size() {
+        // 1000 values below 1M take about 2 + log2(1000) bits each, not 64.
+        let values = (0..1000).map(|i| i * 1000 + i % 7).collect::<Vec<_>>();
+        let ef = EliasFano::new(&values);
+        assert_eq!(ef.low_bits, 9);
+        assert!(ef.to_bytes().len() < 1000 * 12 / 8 + 16);
+    }
+
+    #[test]
+    fn test_elias_fano_invalid_bytes() {
+        let bytes = EliasFano::new(&[1, 5, 9]).to_bytes();
+        assert!(matches!(
+            EliasFano::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(ReadError::UnexpectedEof { .. })
+        ));
+        let mut corrupted = bytes.clone();
+        *corrupted.last_mut().unwrap() = 0;
+        assert_eq!(
+            EliasFano::from_bytes(&corrupted).unwrap_err(),
+            ReadError::Mismatch { offset: 1 }
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_elias_fano_unsorted() {
+        EliasFano::new(&[2, 1]);
+    }
+}
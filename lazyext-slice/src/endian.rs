@@ -0,0 +1,80 @@
+/// A byte order, for the APIs taking it as a runtime argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endian {
+    /// Most significant byte first.
+    Big,
+    /// Least significant byte first.
+    Little,
+}
+
+impl Endian {
+    /// The byte order of the target.
+    #[cfg(target_endian = "big")]
+    pub const NATIVE: Self = Self::Big;
+
+    /// The byte order of the target.
+    #[cfg(target_endian = "little")]
+    pub const NATIVE: Self = Self::Little;
+}
+
+/// Primitives with a fixed-size byte representation in either byte order, the element types of
+/// [`BeSlice`] and [`LeSlice`].
+///
+/// # Safety
+///
+/// `Bytes` must be `[u8; N]` with `N` the size of `Self`, the views reinterpret byte slices as
+/// slices of it.
+///
+/// [`BeSlice`]: struct.BeSlice.html
+/// [`LeSlice`]: struct.LeSlice.html
+pub unsafe trait EndianScalar: Copy {
+    /// The byte representation, `[u8; size_of::<Self>()]`.
+    type Bytes: Copy + AsRef<[u8]>;
+
+    /// Decodes a big-endian value.
+    fn from_be_bytes(bytes: Self::Bytes) -> Self;
+
+    /// Decodes a little-endian value.
+    fn from_le_bytes(bytes: Self::Bytes) -> Self;
+
+    /// Encodes the value in big-endian.
+    fn to_be_bytes(self) -> Self::Bytes;
+
+    /// Encodes the value in little-endian.
+    fn to_le_bytes(self) -> Self::Bytes;
+}
+
+macro_rules! impl_endian_scalar {
+    ($($ty:ty), +$(,)?) => {
+        $(
+        unsafe impl EndianScalar for $ty {
+            type Bytes = [u8; core::mem::size_of::<$ty>()];
+
+            #[inline]
+            fn from_be_bytes(bytes: Self::Bytes) -> Self {
+                <$ty>::from_be_bytes(bytes)
+            }
+
+            #[inline]
+            fn from_le_bytes(bytes: Self::Bytes) -> Self {
+                <$ty>::from_le_bytes(bytes)
+            }
+
+            #[inline]
+            fn to_be_bytes(self) -> Self::Bytes {
+                <$ty>::to_be_bytes(self)
+            }
+
+            #[inline]
+            fn to_le_bytes(self) -> Self::Bytes {
+                <$ty>::to_le_bytes(self)
+            }
+        }
+        )*
+    };
+}
+
+impl_endian_scalar!(u16, u32, u64, u128, i16, i32, i64, i128, f32, f64);
+
+#[cfg(feature = "half")]
+impl_endian_scalar!(half::f16, half::bf16);
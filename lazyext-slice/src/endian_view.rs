@@ -0,0 +1,398 @@
+use crate::{EndianScalar, InvalidLength};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::mem;
+use core::ops::{Index, IndexMut};
+use core::slice::{self, from_raw_parts, from_raw_parts_mut};
+
+macro_rules! declare_endian_view {
+    ($endian:literal, $elem:ident, $view:ident, $view_mut:ident, $iter:ident, $from:ident, $to:ident) => {
+        #[doc = concat!("A `T` stored in ", $endian, " byte order, with an alignment of 1.")]
+        #[repr(transparent)]
+        #[derive(Clone, Copy)]
+        pub struct $elem<T: EndianScalar>(T::Bytes);
+
+        impl<T: EndianScalar> $elem<T> {
+            #[doc = concat!("Encodes `v` in ", $endian, ".")]
+            #[inline]
+            pub fn new(v: T) -> Self {
+                Self(v.$to())
+            }
+
+            /// Decodes the value.
+            #[inline]
+            pub fn get(&self) -> T {
+                T::$from(self.0)
+            }
+
+            /// Encodes `v` in place of the value.
+            #[inline]
+            pub fn set(&mut self, v: T) {
+                self.0 = v.$to();
+            }
+
+            /// Returns the encoded bytes.
+            #[inline]
+            pub fn as_bytes(&self) -> &[u8] {
+                self.0.as_ref()
+            }
+        }
+
+        impl<T: EndianScalar + fmt::Debug> fmt::Debug for $elem<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.get().fmt(f)
+            }
+        }
+
+        #[cfg(feature = "arbitrary")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "arbitrary")))]
+        impl<'a, T: EndianScalar + arbitrary::Arbitrary<'a>> arbitrary::Arbitrary<'a> for $elem<T> {
+            fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+                T::arbitrary(u).map(Self::new)
+            }
+
+            #[inline]
+            fn size_hint(depth: usize) -> (usize, Option<usize>) {
+                T::size_hint(depth)
+            }
+        }
+
+        #[doc = concat!("A zero-copy view of bytes as `T`s in ", $endian, " byte order, decoding on access.")]
+        ///
+        /// Unlike the `to_*_slice` methods of `BytesExt`, the view does not depend on the target's
+        /// byte order or on the alignment of the bytes.
+        #[derive(Clone, Copy)]
+        pub struct $view<'a, T: EndianScalar> {
+            data: &'a [$elem<T>],
+        }
+
+        impl<'a, T: EndianScalar> $view<'a, T> {
+            /// Creates a view of `src`.
+            ///
+            /// Returns an error if the length of `src` is not a multiple of the size of `T`.
+            pub fn new(src: &'a [u8]) -> Result<Self, InvalidLength> {
+                let size = mem::size_of::<T>();
+                if src.len() % size != 0 {
+                    return Err(InvalidLength {
+                        len: src.len(),
+                        elem_size: size,
+                    });
+                }
+                // The element type is a byte array, so any length-checked byte slice is valid.
+                let data = unsafe { from_raw_parts(src.as_ptr() as *const $elem<T>, src.len() / size) };
+                Ok(Self { data })
+            }
+
+            /// Returns the number of values.
+            #[inline]
+            pub fn len(&self) -> usize {
+                self.data.len()
+            }
+
+            /// Returns `true` if the view has no values.
+            #[inline]
+            pub fn is_empty(&self) -> bool {
+                self.data.is_empty()
+            }
+
+            /// Decodes the value at `index`, or returns `None` if it is out of bounds.
+            #[inline]
+            pub fn get(&self, index: usize) -> Option<T> {
+                self.data.get(index).map($elem::get)
+            }
+
+            /// Returns the encoded values.
+            #[inline]
+            pub fn as_slice(&self) -> &'a [$elem<T>] {
+                self.data
+            }
+
+            /// Returns the underlying bytes.
+            #[inline]
+            pub fn as_bytes(&self) -> &'a [u8] {
+                unsafe { from_raw_parts(self.data.as_ptr() as *const u8, mem::size_of_val(self.data)) }
+            }
+
+            /// Returns an iterator decoding the values.
+            #[inline]
+            pub fn iter(&self) -> $iter<'a, T> {
+                $iter(self.data.iter())
+            }
+
+            /// Decodes all the values into a vec.
+            #[cfg(feature = "alloc")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+            pub fn to_vec(&self) -> Vec<T> {
+                self.iter().collect()
+            }
+        }
+
+        impl<T: EndianScalar> Index<usize> for $view<'_, T> {
+            type Output = $elem<T>;
+
+            #[inline]
+            fn index(&self, index: usize) -> &Self::Output {
+                &self.data[index]
+            }
+        }
+
+        impl<'a, T: EndianScalar> IntoIterator for $view<'a, T> {
+            type Item = T;
+            type IntoIter = $iter<'a, T>;
+
+            #[inline]
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter()
+            }
+        }
+
+        impl<'a, T: EndianScalar> IntoIterator for &$view<'a, T> {
+            type Item = T;
+            type IntoIter = $iter<'a, T>;
+
+            #[inline]
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter()
+            }
+        }
+
+        impl<T: EndianScalar + fmt::Debug> fmt::Debug for $view<'_, T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_list().entries(self.iter()).finish()
+            }
+        }
+
+        #[doc = concat!("The mutable counterpart of [`", stringify!($view), "`], encoding on write.")]
+        ///
+        #[doc = concat!("[`", stringify!($view), "`]: struct.", stringify!($view), ".html")]
+        pub struct $view_mut<'a, T: EndianScalar> {
+            data: &'a mut [$elem<T>],
+        }
+
+        impl<'a, T: EndianScalar> $view_mut<'a, T> {
+            /// Creates a mutable view of `src`.
+            ///
+            /// Returns an error if the length of `src` is not a multiple of the size of `T`.
+            pub fn new(src: &'a mut [u8]) -> Result<Self, InvalidLength> {
+                let size = mem::size_of::<T>();
+                if src.len() % size != 0 {
+                    return Err(InvalidLength {
+                        len: src.len(),
+                        elem_size: size,
+                    });
+                }
+                let data = unsafe {
+                    from_raw_parts_mut(src.as_mut_ptr() as *mut $elem<T>, src.len() / size)
+                };
+                Ok(Self { data })
+            }
+
+            /// Returns the number of values.
+            #[inline]
+            pub fn len(&self) -> usize {
+                self.data.len()
+            }
+
+            /// Returns `true` if the view has no values.
+            #[inline]
+            pub fn is_empty(&self) -> bool {
+                self.data.is_empty()
+            }
+
+            /// Decodes the value at `index`, or returns `None` if it is out of bounds.
+            #[inline]
+            pub fn get(&self, index: usize) -> Option<T> {
+                self.data.get(index).map($elem::get)
+            }
+
+            #[doc = concat!("Encodes `value` in ", $endian, " at `index`.")]
+            ///
+            /// # Panics
+            ///
+            /// Panics if `index` is out of bounds.
+            #[inline]
+            pub fn set(&mut self, index: usize, value: T) {
+                self.data[index].set(value)
+            }
+
+            #[doc = concat!("Encodes the values of `src` from `index` on, in ", $endian, ".")]
+            ///
+            /// # Panics
+            ///
+            /// Panics if the values do not fit in the view.
+            pub fn set_from_slice(&mut self, index: usize, src: &[T]) {
+                for (dst, v) in self.data[index..index + src.len()].iter_mut().zip(src) {
+                    dst.set(*v);
+                }
+            }
+
+            /// Encodes `value` at every index.
+            pub fn fill(&mut self, value: T) {
+                self.data.fill($elem::new(value));
+            }
+
+            /// Returns a read-only view of the values.
+            #[inline]
+            pub fn as_view(&self) -> $view<'_, T> {
+                $view { data: self.data }
+            }
+
+            /// Returns an iterator decoding the values.
+            #[inline]
+            pub fn iter(&self) -> $iter<'_, T> {
+                $iter(self.data.iter())
+            }
+
+            /// Returns the encoded values.
+            #[inline]
+            pub fn as_mut_slice(&mut self) -> &mut [$elem<T>] {
+                self.data
+            }
+
+            /// Returns the underlying bytes.
+            #[inline]
+            pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+                let len = mem::size_of_val(self.data);
+                unsafe { from_raw_parts_mut(self.data.as_mut_ptr() as *mut u8, len) }
+            }
+        }
+
+        impl<T: EndianScalar> Index<usize> for $view_mut<'_, T> {
+            type Output = $elem<T>;
+
+            #[inline]
+            fn index(&self, index: usize) -> &Self::Output {
+                &self.data[index]
+            }
+        }
+
+        impl<T: EndianScalar> IndexMut<usize> for $view_mut<'_, T> {
+            #[inline]
+            fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+                &mut self.data[index]
+            }
+        }
+
+        impl<T: EndianScalar + fmt::Debug> fmt::Debug for $view_mut<'_, T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.as_view().fmt(f)
+            }
+        }
+
+        #[doc = concat!("An iterator decoding the values of a [`", stringify!($view), "`].")]
+        ///
+        #[doc = concat!("[`", stringify!($view), "`]: struct.", stringify!($view), ".html")]
+        #[derive(Clone)]
+        pub struct $iter<'a, T: EndianScalar>(slice::Iter<'a, $elem<T>>);
+
+        impl<T: EndianScalar> Iterator for $iter<'_, T> {
+            type Item = T;
+
+            #[inline]
+            fn next(&mut self) -> Option<T> {
+                self.0.next().map($elem::get)
+            }
+
+            #[inline]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.0.size_hint()
+            }
+        }
+
+        impl<T: EndianScalar> DoubleEndedIterator for $iter<'_, T> {
+            #[inline]
+            fn next_back(&mut self) -> Option<T> {
+                self.0.next_back().map($elem::get)
+            }
+        }
+
+        impl<T: EndianScalar> ExactSizeIterator for $iter<'_, T> {}
+
+        impl<T: EndianScalar> FusedIterator for $iter<'_, T> {}
+    };
+}
+
+declare_endian_view!(
+    "big-endian",
+    Be,
+    BeSlice,
+    BeSliceMut,
+    BeIter,
+    from_be_bytes,
+    to_be_bytes
+);
+declare_endian_view!(
+    "little-endian",
+    Le,
+    LeSlice,
+    LeSliceMut,
+    LeIter,
+    from_le_bytes,
+    to_le_bytes
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endian_views() {
+        let bytes = [0u8, 0, 1, 2, 0, 0, 0, 3, 0];
+        let be = BeSlice::<u32>::new(&bytes[1..]).unwrap();
+        assert_eq!(be.len(), 2);
+        assert_eq!(be.get(0), Some(0x0001_0200));
+        assert_eq!(be[1].get(), 0x0000_0300);
+        assert_eq!(be.get(2), None);
+        assert_eq!(be.iter().rev().collect::<Vec<_>>(), [0x300, 0x10200]);
+        assert_eq!(be.as_bytes(), &bytes[1..]);
+
+        let le = LeSlice::<u16>::new(&bytes[2..6]).unwrap();
+        assert_eq!(le.to_vec(), [0x0201, 0]);
+        assert_eq!(format!("{:?}", le), "[513, 0]");
+
+        assert_eq!(
+            BeSlice::<u64>::new(&bytes).unwrap_err(),
+            InvalidLength {
+                len: 9,
+                elem_size: 8
+            }
+        );
+    }
+
+    #[test]
+    fn test_endian_views_mut() {
+        let mut bytes = [0u8; 8];
+        let mut be = BeSliceMut::<u16>::new(&mut bytes).unwrap();
+        be.set(0, 0x0102);
+        be[1].set(0x0304);
+        be.set_from_slice(2, &[5, 6]);
+        assert_eq!(be.get(1), Some(0x0304));
+        assert_eq!(be.as_view().to_vec(), [0x0102, 0x0304, 5, 6]);
+        assert_eq!(bytes, [1, 2, 3, 4, 0, 5, 0, 6]);
+
+        let mut le = LeSliceMut::<u32>::new(&mut bytes).unwrap();
+        le.fill(1);
+        assert_eq!(le.iter().collect::<Vec<_>>(), [1, 1]);
+        le.as_bytes_mut()[7] = 2;
+        assert_eq!(le.get(1), Some(0x0200_0001));
+        assert!(LeSliceMut::<u32>::new(&mut bytes[1..]).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_endian_view_mut_set_out_of_bounds() {
+        let mut bytes = [0u8; 2];
+        BeSliceMut::<u16>::new(&mut bytes).unwrap().set(1, 0);
+    }
+
+    #[test]
+    fn test_endian_elem() {
+        let mut v = Be::new(-2i16);
+        assert_eq!(v.as_bytes(), &[0xff, 0xfe]);
+        v.set(1);
+        assert_eq!(v.get(), 1);
+        assert_eq!(Le::new(1.5f64).get(), 1.5);
+    }
+}
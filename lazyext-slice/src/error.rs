@@ -0,0 +1,269 @@
+use core::fmt;
+
+/// The error returned when the length of a byte slice is not a multiple of the size of the
+/// element type it is decoded into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidLength {
+    /// The length of the byte slice.
+    pub len: usize,
+    /// The size of the element type.
+    pub elem_size: usize,
+}
+
+impl fmt::Display for InvalidLength {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid length of u8 slice: {}, expected a multiple of {}",
+            self.len, self.elem_size
+        )
+    }
+}
+
+cfg_feature!("std", {
+    impl std::error::Error for InvalidLength {}
+});
+
+/// The error returned when a byte region cannot be viewed as a slice of (or a single) `T`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutError {
+    /// The length of the region does not fit the size of `T`.
+    InvalidLength(InvalidLength),
+    /// The start of the region is not aligned for `T`.
+    Misaligned {
+        /// The alignment of `T`.
+        align: usize,
+        /// The address of the region modulo `align`.
+        offset: usize,
+    },
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength(e) => e.fmt(f),
+            Self::Misaligned { align, offset } => write!(
+                f,
+                "misaligned u8 slice: address is {} bytes past a multiple of {}",
+                offset, align
+            ),
+        }
+    }
+}
+
+cfg_feature!("std", {
+    impl std::error::Error for LayoutError {}
+});
+
+/// The error returned by `try_gather` when an index is out of bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OobAt {
+    /// The position of the index in the index list.
+    pub position: usize,
+    /// The out of bounds index.
+    pub index: usize,
+    /// The length of the slice.
+    pub len: usize,
+}
+
+impl fmt::Display for OobAt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "index {} at position {} is out of bounds for length {}",
+            self.index, self.position, self.len
+        )
+    }
+}
+
+cfg_feature!("std", {
+    impl std::error::Error for OobAt {}
+});
+
+/// The error returned by the methods of [`SliceReader`].
+///
+/// Every variant records the `offset` of the reader when the read failed; the reader does not
+/// advance on errors.
+///
+/// [`SliceReader`]: struct.SliceReader.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadError {
+    /// Fewer bytes remain than the read needs.
+    UnexpectedEof {
+        /// The offset of the reader.
+        offset: usize,
+        /// The number of bytes the read needs.
+        needed: usize,
+        /// The number of bytes remaining.
+        remaining: usize,
+    },
+    /// The delimiter of `take_until` does not occur in the remaining bytes.
+    DelimiterNotFound {
+        /// The offset of the reader.
+        offset: usize,
+        /// The delimiter.
+        delimiter: u8,
+    },
+    /// The remaining bytes do not begin with the prefix given to `expect`.
+    Mismatch {
+        /// The offset of the reader.
+        offset: usize,
+    },
+    /// The varint is longer than 10 bytes or overflows a `u64`.
+    InvalidVarint {
+        /// The offset of the reader.
+        offset: usize,
+    },
+    /// The type byte of a MessagePack or CBOR header is reserved or malformed.
+    InvalidTag {
+        /// The offset of the reader.
+        offset: usize,
+        /// The type byte.
+        byte: u8,
+    },
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEof {
+                offset,
+                needed,
+                remaining,
+            } => write!(
+                f,
+                "unexpected end of input at offset {}: needed {} bytes, {} remaining",
+                offset, needed, remaining
+            ),
+            Self::DelimiterNotFound { offset, delimiter } => write!(
+                f,
+                "delimiter {:#04x} not found after offset {}",
+                delimiter, offset
+            ),
+            Self::Mismatch { offset } => write!(f, "unexpected bytes at offset {}", offset),
+            Self::InvalidVarint { offset } => write!(f, "invalid varint at offset {}", offset),
+            Self::InvalidTag { offset, byte } => {
+                write!(f, "invalid type byte {:#04x} at offset {}", byte, offset)
+            }
+        }
+    }
+}
+
+cfg_feature!("std", {
+    impl std::error::Error for ReadError {}
+});
+
+/// The error returned when reading or writing an offset table fails validation.
+///
+/// Indexes refer to the entries of the table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OffsetTableError {
+    /// The buffer is too short to hold the table.
+    TooShort {
+        /// The number of bytes the table needs.
+        needed: usize,
+        /// The length of the buffer.
+        len: usize,
+    },
+    /// The offset at `index` is smaller than the one before it.
+    NotMonotonic {
+        /// The index of the offending entry.
+        index: usize,
+    },
+    /// The offset at `index` points past the end of the buffer.
+    OutOfBounds {
+        /// The index of the offending entry.
+        index: usize,
+        /// The decoded offset.
+        offset: u64,
+        /// The length of the buffer.
+        len: usize,
+    },
+    /// The offset at `index` does not fit in `width` bytes.
+    Overflow {
+        /// The index of the offending entry.
+        index: usize,
+        /// The offset.
+        offset: u64,
+        /// The width of the table entries in bytes.
+        width: usize,
+    },
+}
+
+impl fmt::Display for OffsetTableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort { needed, len } => write!(
+                f,
+                "offset table needs {} bytes, the buffer has {}",
+                needed, len
+            ),
+            Self::NotMonotonic { index } => {
+                write!(
+                    f,
+                    "offset table entry {} is smaller than the previous one",
+                    index
+                )
+            }
+            Self::OutOfBounds { index, offset, len } => write!(
+                f,
+                "offset table entry {} ({}) is out of bounds for a buffer of {} bytes",
+                index, offset, len
+            ),
+            Self::Overflow {
+                index,
+                offset,
+                width,
+            } => write!(
+                f,
+                "offset table entry {} ({}) does not fit in {} bytes",
+                index, offset, width
+            ),
+        }
+    }
+}
+
+cfg_feature!("std", {
+    impl std::error::Error for OffsetTableError {}
+});
+
+/// The error returned by [`read_block_with_crc`] and [`write_block_with_crc`].
+///
+/// [`read_block_with_crc`]: fn.read_block_with_crc.html
+/// [`write_block_with_crc`]: fn.write_block_with_crc.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockError {
+    /// The buffer is too short for the block.
+    TooShort {
+        /// The number of bytes the block needs.
+        needed: usize,
+        /// The length of the buffer.
+        len: usize,
+    },
+    /// The stored checksum does not match the one of the block.
+    ChecksumMismatch {
+        /// The stored checksum.
+        expected: u32,
+        /// The checksum of the block as read.
+        actual: u32,
+    },
+}
+
+impl fmt::Display for BlockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort { needed, len } => {
+                write!(f, "block needs {} bytes, the buffer has {}", needed, len)
+            }
+            Self::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "block checksum mismatch: expected {:#010x}, got {:#010x}",
+                expected, actual
+            ),
+        }
+    }
+}
+
+cfg_feature!("std", {
+    impl std::error::Error for BlockError {}
+});
@@ -0,0 +1,118 @@
+use crate::{AsBytesRef, InvalidLength};
+use alloc::vec::Vec;
+use core::mem;
+
+/// Extensions for appending values decoded from bytes to an existing `Vec`, so streaming parsers
+/// can accumulate into one growing vec instead of concatenating many `to_be_*_vec` results.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::ExtendFromBytesExt;
+///
+/// let mut vals: Vec<u32> = Vec::new();
+/// assert_eq!(vals.extend_from_be_bytes([0, 0, 0, 1]), Ok(1));
+/// assert_eq!(vals.extend_from_be_bytes([0, 0, 0, 2, 0, 0, 0, 3]), Ok(2));
+/// assert!(vals.extend_from_be_bytes([0, 0, 4]).is_err());
+/// assert_eq!(vals, [1, 2, 3]);
+/// ```
+pub trait ExtendFromBytesExt {
+    /// Decodes the bytes as big-endian values and appends them, returning how many were appended.
+    ///
+    /// Nothing is appended if the length of the bytes is not a multiple of the size of the elements.
+    fn extend_from_be_bytes(&mut self, src: impl AsBytesRef) -> Result<usize, InvalidLength>;
+
+    /// Decodes the bytes as little-endian values and appends them, returning how many were appended.
+    ///
+    /// Nothing is appended if the length of the bytes is not a multiple of the size of the elements.
+    fn extend_from_le_bytes(&mut self, src: impl AsBytesRef) -> Result<usize, InvalidLength>;
+
+    /// Decodes the bytes as native-endian values and appends them, returning how many were appended.
+    ///
+    /// Nothing is appended if the length of the bytes is not a multiple of the size of the elements.
+    fn extend_from_ne_bytes(&mut self, src: impl AsBytesRef) -> Result<usize, InvalidLength>;
+}
+
+macro_rules! extend_from_x_bytes_impl {
+    ($this:ident, $src:ident, $ty:ty, $conv:ident) => {{
+        const SIZE: usize = mem::size_of::<$ty>();
+        let src = $src.as_bytes_ref();
+        if src.len() % mem::size_of::<$ty>() != 0 {
+            return Err(InvalidLength {
+                len: src.len(),
+                elem_size: SIZE,
+            });
+        }
+        $this.extend(
+            src.chunks_exact(SIZE)
+                .map(|b| <$ty>::$conv(b.try_into().unwrap())),
+        );
+        Ok(src.len() / SIZE)
+    }};
+}
+
+macro_rules! impl_extend_from_bytes_ext {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+        impl ExtendFromBytesExt for Vec<$ty> {
+            #[inline]
+            fn extend_from_be_bytes(&mut self, src: impl AsBytesRef) -> Result<usize, InvalidLength> {
+                extend_from_x_bytes_impl!(self, src, $ty, from_be_bytes)
+            }
+
+            #[inline]
+            fn extend_from_le_bytes(&mut self, src: impl AsBytesRef) -> Result<usize, InvalidLength> {
+                extend_from_x_bytes_impl!(self, src, $ty, from_le_bytes)
+            }
+
+            #[inline]
+            fn extend_from_ne_bytes(&mut self, src: impl AsBytesRef) -> Result<usize, InvalidLength> {
+                extend_from_x_bytes_impl!(self, src, $ty, from_ne_bytes)
+            }
+        }
+        )*
+    };
+}
+
+impl_extend_from_bytes_ext!(u16, u32, usize, u64, u128, i8, i16, i32, i64, isize, i128, f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lazyext_macros::test_endian_matrix;
+
+    macro_rules! extend_roundtrip {
+        ($endian:ident, $ty:ident) => {
+            paste! {
+                let vals = (0..8).map(|v| v as $ty).collect::<Vec<$ty>>();
+                let bytes = vals
+                    .iter()
+                    .flat_map(|v| v.[<to_ $endian _bytes>]())
+                    .collect::<Vec<u8>>();
+                let (a, b) = bytes.split_at(3 * mem::size_of::<$ty>());
+                let mut out = Vec::<$ty>::new();
+                assert_eq!(out.[<extend_from_ $endian _bytes>](a), Ok(3));
+                assert_eq!(out.[<extend_from_ $endian _bytes>](b), Ok(5));
+                assert_eq!(out, vals);
+            }
+        };
+    }
+
+    test_endian_matrix!(
+        extend_roundtrip,
+        [u16, u32, usize, u64, u128, i8, i16, i32, i64, isize, i128, f32, f64]
+    );
+
+    #[test]
+    fn test_extend_invalid_length() {
+        let mut out = vec![1u16];
+        assert_eq!(
+            out.extend_from_le_bytes([1u8, 2, 3]),
+            Err(InvalidLength {
+                len: 3,
+                elem_size: 2
+            })
+        );
+        assert_eq!(out, [1]);
+    }
+}
@@ -0,0 +1,112 @@
+/// An iterator over runs of consecutive equal elements, yielding each element with the length of its run.
+///
+/// It is returned by the `runs` method of the slice extension traits, e.g. [`BytesExt::runs`].
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::BytesExt;
+///
+/// let runs = b"aaabcc".runs().collect::<Vec<_>>();
+/// assert_eq!(runs, vec![(&b'a', 3), (&b'b', 1), (&b'c', 2)]);
+/// ```
+///
+/// [`BytesExt::runs`]: trait.BytesExt.html#method.runs
+#[derive(Debug, Clone)]
+pub struct Runs<'a, T> {
+    src: &'a [T],
+}
+
+impl<'a, T> Runs<'a, T> {
+    pub(crate) fn new(src: &'a [T]) -> Self {
+        Self { src }
+    }
+}
+
+impl<'a, T: PartialEq> Iterator for Runs<'a, T> {
+    type Item = (&'a T, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.src.first()?;
+        let len = self.src.iter().take_while(|v| *v == first).count();
+        self.src = &self.src[len..];
+        Some((first, len))
+    }
+}
+
+impl<T: PartialEq> core::iter::FusedIterator for Runs<'_, T> {}
+
+/// An iterator over the groups of consecutive elements for which the predicate holds pairwise,
+/// like `slice::chunk_by` (formerly `slice::group_by`).
+///
+/// It is returned by the `group_by` method of the slice extension traits, e.g. [`BytesExt::group_by`].
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::BytesExt;
+///
+/// // split into ascending runs
+/// let groups = [1u8, 2, 3, 2, 3, 1].group_by(|a, b| a < b).collect::<Vec<_>>();
+/// assert_eq!(groups, vec![&[1, 2, 3][..], &[2, 3], &[1]]);
+/// ```
+///
+/// [`BytesExt::group_by`]: trait.BytesExt.html#method.group_by
+pub struct GroupBy<'a, T, F> {
+    src: &'a [T],
+    pred: F,
+}
+
+impl<'a, T, F> GroupBy<'a, T, F> {
+    pub(crate) fn new(src: &'a [T], pred: F) -> Self {
+        Self { src, pred }
+    }
+}
+
+impl<'a, T, F: FnMut(&T, &T) -> bool> Iterator for GroupBy<'a, T, F> {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.src.is_empty() {
+            return None;
+        }
+
+        let mut len = 1;
+        while len < self.src.len() && (self.pred)(&self.src[len - 1], &self.src[len]) {
+            len += 1;
+        }
+        let (group, rest) = self.src.split_at(len);
+        self.src = rest;
+        Some(group)
+    }
+}
+
+impl<T, F: FnMut(&T, &T) -> bool> core::iter::FusedIterator for GroupBy<'_, T, F> {}
+
+impl<T: core::fmt::Debug, F> core::fmt::Debug for GroupBy<'_, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("GroupBy").field("src", &self.src).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BytesExt;
+
+    #[test]
+    fn test_runs() {
+        assert_eq!([0u8; 0].runs().count(), 0);
+        let encoded = b"aaaabbbcca"
+            .runs()
+            .map(|(b, n)| (*b, n))
+            .collect::<Vec<_>>();
+        assert_eq!(encoded, vec![(b'a', 4), (b'b', 3), (b'c', 2), (b'a', 1)]);
+    }
+
+    #[test]
+    fn test_group_by() {
+        let groups = b"aaBBc".group_by(|a, b| a.is_ascii_lowercase() == b.is_ascii_lowercase());
+        assert_eq!(groups.collect::<Vec<_>>(), vec![&b"aa"[..], b"BB", b"c"]);
+        assert_eq!([0u8; 0].group_by(|_, _| true).count(), 0);
+    }
+}
@@ -0,0 +1,319 @@
+//! Readers for the type headers of MessagePack and CBOR values, without decoding the payloads.
+
+use crate::{ReadError, SliceReader};
+
+/// The header of a MessagePack value, as read by [`SliceReader::read_msgpack_header`].
+///
+/// Scalars carry their value. For strings, binaries and extensions the payload of `len` bytes
+/// follows the header, and for arrays and maps the given number of elements (or key-value
+/// pairs) follows.
+///
+/// [`SliceReader::read_msgpack_header`]: struct.SliceReader.html#method.read_msgpack_header
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MsgpackHeader {
+    /// `nil`.
+    Nil,
+    /// A boolean.
+    Bool(bool),
+    /// A positive fixint or an unsigned integer.
+    UInt(u64),
+    /// A negative fixint or a signed integer.
+    Int(i64),
+    /// A 32-bit float.
+    F32(f32),
+    /// A 64-bit float.
+    F64(f64),
+    /// A UTF-8 string of `len` bytes.
+    Str(u32),
+    /// A binary of `len` bytes.
+    Bin(u32),
+    /// An array of `len` elements.
+    Array(u32),
+    /// A map of `len` key-value pairs.
+    Map(u32),
+    /// An extension of type `ty` with `len` bytes of data.
+    Ext {
+        /// The application-defined type.
+        ty: i8,
+        /// The length of the data.
+        len: u32,
+    },
+}
+
+/// The header of a CBOR (RFC 8949) data item, as read by [`SliceReader::read_cbor_header`].
+///
+/// `None` lengths mark indefinite-length items, which end with [`Break`].
+///
+/// [`SliceReader::read_cbor_header`]: struct.SliceReader.html#method.read_cbor_header
+/// [`Break`]: enum.CborHeader.html#variant.Break
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CborHeader {
+    /// An unsigned integer (major type 0).
+    Unsigned(u64),
+    /// A negative integer (major type 1), whose value is `-1 - n`.
+    Negative(u64),
+    /// A byte string of the given length (major type 2).
+    Bytes(Option<u64>),
+    /// A text string of the given length in bytes (major type 3).
+    Text(Option<u64>),
+    /// An array of the given number of items (major type 4).
+    Array(Option<u64>),
+    /// A map of the given number of key-value pairs (major type 5).
+    Map(Option<u64>),
+    /// A tag, followed by the tagged item (major type 6).
+    Tag(u64),
+    /// A simple value, e.g. `20` for `false`, `21` for `true` and `22` for `null` (major type 7).
+    Simple(u8),
+    /// A half, single or double precision float (major type 7).
+    Float(f64),
+    /// The end of an indefinite-length item.
+    Break,
+}
+
+impl<'a> SliceReader<'a> {
+    /// Reads the header of a MessagePack value, see [`MsgpackHeader`].
+    ///
+    /// Fails with [`ReadError::InvalidTag`] on the never-used type byte `0xc1`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::{BytesExt, MsgpackHeader, ReadError};
+    ///
+    /// // {"id": 7}
+    /// let msg = b"\x81\xa2id\x07";
+    /// let mut r = msg.reader();
+    /// assert_eq!(r.read_msgpack_header()?, MsgpackHeader::Map(1));
+    /// assert_eq!(r.read_msgpack_header()?, MsgpackHeader::Str(2));
+    /// r.skip(2)?;
+    /// assert_eq!(r.read_msgpack_header()?, MsgpackHeader::UInt(7));
+    /// # Ok::<(), ReadError>(())
+    /// ```
+    ///
+    /// [`MsgpackHeader`]: enum.MsgpackHeader.html
+    /// [`ReadError::InvalidTag`]: enum.ReadError.html#variant.InvalidTag
+    pub fn read_msgpack_header(&mut self) -> Result<MsgpackHeader, ReadError> {
+        use MsgpackHeader::*;
+
+        let offset = self.offset();
+        let mut r = *self;
+        let tag = r.read_u8()?;
+        let header = match tag {
+            0x00..=0x7f => UInt(tag as u64),
+            0x80..=0x8f => Map((tag & 0x0f) as u32),
+            0x90..=0x9f => Array((tag & 0x0f) as u32),
+            0xa0..=0xbf => Str((tag & 0x1f) as u32),
+            0xc0 => Nil,
+            0xc1 => return Err(ReadError::InvalidTag { offset, byte: tag }),
+            0xc2 => Bool(false),
+            0xc3 => Bool(true),
+            0xc4 => Bin(r.read_u8()? as u32),
+            0xc5 => Bin(r.read_u16_be()? as u32),
+            0xc6 => Bin(r.read_u32_be()?),
+            0xc7..=0xc9 => {
+                let len = match tag {
+                    0xc7 => r.read_u8()? as u32,
+                    0xc8 => r.read_u16_be()? as u32,
+                    _ => r.read_u32_be()?,
+                };
+                let [ty] = r.read_array::<1>()?;
+                Ext { ty: ty as i8, len }
+            }
+            0xca => F32(r.read_f32_be()?),
+            0xcb => F64(r.read_f64_be()?),
+            0xcc => UInt(r.read_u8()? as u64),
+            0xcd => UInt(r.read_u16_be()? as u64),
+            0xce => UInt(r.read_u32_be()? as u64),
+            0xcf => UInt(r.read_u64_be()?),
+            0xd0 => Int(r.read_i8_be()? as i64),
+            0xd1 => Int(r.read_i16_be()? as i64),
+            0xd2 => Int(r.read_i32_be()? as i64),
+            0xd3 => Int(r.read_i64_be()?),
+            0xd4..=0xd8 => {
+                let [ty] = r.read_array::<1>()?;
+                Ext {
+                    ty: ty as i8,
+                    len: 1 << (tag - 0xd4),
+                }
+            }
+            0xd9 => Str(r.read_u8()? as u32),
+            0xda => Str(r.read_u16_be()? as u32),
+            0xdb => Str(r.read_u32_be()?),
+            0xdc => Array(r.read_u16_be()? as u32),
+            0xdd => Array(r.read_u32_be()?),
+            0xde => Map(r.read_u16_be()? as u32),
+            0xdf => Map(r.read_u32_be()?),
+            0xe0..=0xff => Int(tag as i8 as i64),
+        };
+        *self = r;
+        Ok(header)
+    }
+
+    /// Reads the header of a CBOR data item, see [`CborHeader`].
+    ///
+    /// Fails with [`ReadError::InvalidTag`] on the reserved additional information values
+    /// 28 to 30, an indefinite length for a type which has none, or a two-byte simple value
+    /// below 32.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::{BytesExt, CborHeader, ReadError};
+    ///
+    /// // {"id": -500}
+    /// let msg = b"\xa1\x62id\x39\x01\xf3";
+    /// let mut r = msg.reader();
+    /// assert_eq!(r.read_cbor_header()?, CborHeader::Map(Some(1)));
+    /// assert_eq!(r.read_cbor_header()?, CborHeader::Text(Some(2)));
+    /// r.skip(2)?;
+    /// assert_eq!(r.read_cbor_header()?, CborHeader::Negative(499));
+    /// # Ok::<(), ReadError>(())
+    /// ```
+    ///
+    /// [`CborHeader`]: enum.CborHeader.html
+    /// [`ReadError::InvalidTag`]: enum.ReadError.html#variant.InvalidTag
+    pub fn read_cbor_header(&mut self) -> Result<CborHeader, ReadError> {
+        let offset = self.offset();
+        let mut r = *self;
+        let byte = r.read_u8()?;
+        let invalid = ReadError::InvalidTag { offset, byte };
+        let (major, info) = (byte >> 5, byte & 0x1f);
+
+        if major == 7 {
+            let header = match info {
+                0..=23 => CborHeader::Simple(info),
+                24 => match r.read_u8()? {
+                    0..=31 => return Err(invalid),
+                    v => CborHeader::Simple(v),
+                },
+                25 => CborHeader::Float(f16_to_f64(r.read_u16_be()?)),
+                26 => CborHeader::Float(r.read_f32_be()? as f64),
+                27 => CborHeader::Float(r.read_f64_be()?),
+                31 => CborHeader::Break,
+                _ => return Err(invalid),
+            };
+            *self = r;
+            return Ok(header);
+        }
+
+        let arg = match info {
+            0..=23 => Some(info as u64),
+            24 => Some(r.read_u8()? as u64),
+            25 => Some(r.read_u16_be()? as u64),
+            26 => Some(r.read_u32_be()? as u64),
+            27 => Some(r.read_u64_be()?),
+            31 if (2..=5).contains(&major) => None,
+            _ => return Err(invalid),
+        };
+        let header = match major {
+            2 => CborHeader::Bytes(arg),
+            3 => CborHeader::Text(arg),
+            4 => CborHeader::Array(arg),
+            5 => CborHeader::Map(arg),
+            // Only the types 2 to 5 can have an indefinite length.
+            0 => CborHeader::Unsigned(arg.unwrap()),
+            1 => CborHeader::Negative(arg.unwrap()),
+            _ => CborHeader::Tag(arg.unwrap()),
+        };
+        *self = r;
+        Ok(header)
+    }
+}
+
+/// Converts an IEEE 754 half precision float to a double.
+fn f16_to_f64(h: u16) -> f64 {
+    let sign = ((h >> 15) as u64) << 63;
+    let exp = (h >> 10) & 0x1f;
+    let mant = (h & 0x3ff) as u64;
+    match exp {
+        0 => {
+            // Subnormal: `mant * 2^-24`.
+            let v = mant as f64 * f64::from_bits((1023 - 24) << 52);
+            f64::from_bits(v.to_bits() | sign)
+        }
+        31 => f64::from_bits(sign | (0x7ff << 52) | (mant << 42)),
+        _ => f64::from_bits(sign | ((exp as u64 + 1023 - 15) << 52) | (mant << 42)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msgpack(src: &[u8]) -> Result<(MsgpackHeader, usize), ReadError> {
+        let mut r = SliceReader::new(src);
+        r.read_msgpack_header().map(|h| (h, r.offset()))
+    }
+
+    fn cbor(src: &[u8]) -> Result<(CborHeader, usize), ReadError> {
+        let mut r = SliceReader::new(src);
+        r.read_cbor_header().map(|h| (h, r.offset()))
+    }
+
+    #[test]
+    fn test_msgpack_header() {
+        use MsgpackHeader::*;
+
+        assert_eq!(msgpack(b"\xc0"), Ok((Nil, 1)));
+        assert_eq!(msgpack(b"\xff"), Ok((Int(-1), 1)));
+        assert_eq!(msgpack(b"\xd1\xff\x00"), Ok((Int(-256), 3)));
+        assert_eq!(
+            msgpack(b"\xcf\xff\xff\xff\xff\xff\xff\xff\xff"),
+            Ok((UInt(u64::MAX), 9))
+        );
+        assert_eq!(msgpack(b"\xcb\x3f\xf0\0\0\0\0\0\0"), Ok((F64(1.0), 9)));
+        assert_eq!(msgpack(b"\xda\x01\x00"), Ok((Str(256), 3)));
+        assert_eq!(msgpack(b"\xdd\0\x01\0\0"), Ok((Array(65536), 5)));
+        assert_eq!(msgpack(b"\xd6\xff"), Ok((Ext { ty: -1, len: 4 }, 2)));
+        assert_eq!(msgpack(b"\xc7\x03\x05"), Ok((Ext { ty: 5, len: 3 }, 3)));
+
+        assert_eq!(
+            msgpack(b"\xc1"),
+            Err(ReadError::InvalidTag {
+                offset: 0,
+                byte: 0xc1
+            })
+        );
+        // A truncated header does not advance the reader.
+        let mut r = SliceReader::new(b"\xcd\x01");
+        assert!(r.read_msgpack_header().is_err());
+        assert_eq!(r.offset(), 0);
+    }
+
+    #[test]
+    fn test_cbor_header() {
+        use CborHeader::*;
+
+        assert_eq!(cbor(b"\x17"), Ok((Unsigned(23), 1)));
+        assert_eq!(cbor(b"\x18\x18"), Ok((Unsigned(24), 2)));
+        assert_eq!(cbor(b"\x3b\0\0\0\0\0\0\0\x01"), Ok((Negative(1), 9)));
+        assert_eq!(cbor(b"\x5f"), Ok((Bytes(None), 1)));
+        assert_eq!(cbor(b"\x9a\0\x01\0\0"), Ok((Array(Some(65536)), 5)));
+        assert_eq!(cbor(b"\xc1\x1a"), Ok((Tag(1), 1)));
+        assert_eq!(cbor(b"\xf5"), Ok((Simple(21), 1)));
+        assert_eq!(cbor(b"\xf8\xff"), Ok((Simple(255), 2)));
+        assert_eq!(cbor(b"\xf9\x3c\x00"), Ok((Float(1.0), 3)));
+        assert_eq!(cbor(b"\xf9\xc4\x00"), Ok((Float(-4.0), 3)));
+        assert_eq!(cbor(b"\xfa\x47\xc3\x50\x00"), Ok((Float(100000.0), 5)));
+        assert_eq!(cbor(b"\xff"), Ok((Break, 1)));
+
+        for bad in [&b"\x1c"[..], b"\x1f", b"\xdf", b"\xf8\x10", b"\xfc"] {
+            assert_eq!(
+                cbor(bad),
+                Err(ReadError::InvalidTag {
+                    offset: 0,
+                    byte: bad[0]
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_f16_to_f64() {
+        assert_eq!(f16_to_f64(0x0001), 5.960464477539063e-8);
+        assert_eq!(f16_to_f64(0x7bff), 65504.0);
+        assert_eq!(f16_to_f64(0x8000).to_bits(), (-0.0f64).to_bits());
+        assert_eq!(f16_to_f64(0x7c00), f64::INFINITY);
+        assert!(f16_to_f64(0x7e00).is_nan());
+    }
+}
@@ -0,0 +1,66 @@
+//! Order-preserving key encodings, the encoded bytes sort (with `memcmp`) like the values.
+
+/// Encodes a unix timestamp in nanoseconds so keys sort oldest first.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::{decode_unix_nanos_asc, encode_unix_nanos_asc};
+///
+/// assert!(encode_unix_nanos_asc(1) < encode_unix_nanos_asc(2));
+/// assert_eq!(decode_unix_nanos_asc(encode_unix_nanos_asc(42)), 42);
+/// ```
+#[inline]
+pub const fn encode_unix_nanos_asc(nanos: u64) -> [u8; 8] {
+    nanos.to_be_bytes()
+}
+
+/// Decodes a timestamp encoded by [`encode_unix_nanos_asc`].
+///
+/// [`encode_unix_nanos_asc`]: fn.encode_unix_nanos_asc.html
+#[inline]
+pub const fn decode_unix_nanos_asc(src: [u8; 8]) -> u64 {
+    u64::from_be_bytes(src)
+}
+
+/// Encodes a unix timestamp in nanoseconds so keys sort newest first,
+/// by flipping all the bits of the big-endian encoding.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::{decode_unix_nanos_desc, encode_unix_nanos_desc};
+///
+/// assert!(encode_unix_nanos_desc(2) < encode_unix_nanos_desc(1));
+/// assert_eq!(decode_unix_nanos_desc(encode_unix_nanos_desc(42)), 42);
+/// ```
+#[inline]
+pub const fn encode_unix_nanos_desc(nanos: u64) -> [u8; 8] {
+    (!nanos).to_be_bytes()
+}
+
+/// Decodes a timestamp encoded by [`encode_unix_nanos_desc`].
+///
+/// [`encode_unix_nanos_desc`]: fn.encode_unix_nanos_desc.html
+#[inline]
+pub const fn decode_unix_nanos_desc(src: [u8; 8]) -> u64 {
+    !u64::from_be_bytes(src)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unix_nanos_order() {
+        let ts = [0, 1, 256, 1_641_427_200_000_000_000, u64::MAX];
+        for w in ts.windows(2) {
+            assert!(encode_unix_nanos_asc(w[0]) < encode_unix_nanos_asc(w[1]));
+            assert!(encode_unix_nanos_desc(w[0]) > encode_unix_nanos_desc(w[1]));
+        }
+        for t in ts {
+            assert_eq!(decode_unix_nanos_asc(encode_unix_nanos_asc(t)), t);
+            assert_eq!(decode_unix_nanos_desc(encode_unix_nanos_desc(t)), t);
+        }
+    }
+}
@@ -1,4 +1,8 @@
 //! Thousands of utility functions for slices and vec
+//!
+//! This crate is the single home of [`AsBytesRef`]/[`BytesExt`] (and their mutable counterparts):
+//! the other lazyext crates, the `lazyext` facade and the `impl_bytes_ext!` macro all reuse these traits
+//! instead of keeping their own copies.
 #![doc(html_root_url = "https://docs.rs/lazyext-slice/0.0.2")]
 #![deny(
     missing_docs,
@@ -13,15 +17,8 @@ extern crate alloc;
 #[macro_use]
 extern crate paste;
 
-macro_rules! cfg_alloc {
-    ($($item:item)*) => {
-        $(
-            #[cfg(feature = "alloc")]
-            #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
-            $item
-        )*
-    }
-}
+#[macro_use]
+extern crate lazyext_macros;
 
 macro_rules! has_prefix {
     ($trait:tt::$fn:tt) => {
@@ -47,12 +44,12 @@ macro_rules! has_suffix {
         fn has_suffix(&self, suffix: impl $trait) -> bool {
             let src = $trait::$fn(self);
             let suffix = $trait::$fn(&suffix);
-            let pl = suffix.len() - 1;
-            if src.len() <= pl {
+            let sl = suffix.len();
+            if src.len() < sl {
                 return false;
             }
 
-            src[pl..].eq(suffix)
+            src[src.len() - sl..].eq(suffix)
         }
     };
 }
@@ -66,7 +63,7 @@ macro_rules! longest_prefix {
             let k2 = $trait::$fn(&other);
             let max = k1.len().min(k2.len());
 
-            let mut n = max - 1;
+            let mut n = max;
             for i in 0..max {
                 if k1[i].ne(&k2[i]) {
                     n = i;
@@ -115,7 +112,7 @@ macro_rules! longest_suffix {
 #[cfg(feature = "alloc")]
 macro_rules! longest_prefix_lossy {
     ($trait:tt::$fn:tt, $ty: ty, $ty_literal: literal) => {
-        #[doc = concat!("Finds the longest shared prefix, return a Cow<'_, [", $ty_literal, "]>.")]
+        #[doc = concat!("Finds the longest shared prefix, return a `Cow<'_, [", $ty_literal, "]>`.")]
         #[inline]
         fn longest_prefix_lossy(&self, other: impl $trait) -> Cow<'_, [$ty]> {
             Cow::Borrowed(self.longest_prefix(other))
@@ -126,7 +123,7 @@ macro_rules! longest_prefix_lossy {
 #[cfg(feature = "alloc")]
 macro_rules! longest_suffix_lossy {
     ($trait:tt::$fn:tt, $ty: ty, $ty_literal: literal) => {
-        #[doc = concat!("Finds the longest shared suffix, return a Cow<'_, [", $ty_literal, "]>.")]
+        #[doc = concat!("Finds the longest shared suffix, return a `Cow<'_, [", $ty_literal, "]>`.")]
         #[inline]
         fn longest_suffix_lossy(&self, other: impl $trait) -> Cow<'_, [$ty]> {
             Cow::Borrowed(self.longest_suffix(other))
@@ -134,6 +131,372 @@ macro_rules! longest_suffix_lossy {
     };
 }
 
+macro_rules! impl_group_suites {
+    ($trait:tt::$fn:tt, $ty: ty) => {
+        /// Returns an iterator over runs of consecutive equal elements, see [`Runs`].
+        ///
+        /// [`Runs`]: struct.Runs.html
+        #[inline]
+        fn runs(&self) -> Runs<'_, $ty> {
+            Runs::new($trait::$fn(self))
+        }
+
+        /// Returns an iterator over the groups of consecutive elements for which `pred` holds pairwise,
+        /// see [`GroupBy`].
+        ///
+        /// [`GroupBy`]: struct.GroupBy.html
+        #[inline]
+        fn group_by<F: FnMut(&$ty, &$ty) -> bool>(&self, pred: F) -> GroupBy<'_, $ty, F> {
+            GroupBy::new($trait::$fn(self), pred)
+        }
+    };
+}
+
+macro_rules! impl_split_suites {
+    ($trait:tt::$fn:tt, $ty: ty) => {
+        /// Splits on the first occurrence of `sep`, returning the parts before and after it.
+        ///
+        /// An empty `sep` matches at the start, like `str::split_once`.
+        #[inline]
+        fn split_once_on(&self, sep: impl $trait) -> Option<(&[$ty], &[$ty])> {
+            let src = $trait::$fn(self);
+            let sep = $trait::$fn(&sep);
+            if sep.is_empty() {
+                return Some((&src[..0], src));
+            }
+            src.windows(sep.len())
+                .position(|w| w == sep)
+                .map(|i| (&src[..i], &src[i + sep.len()..]))
+        }
+
+        /// Splits on the last occurrence of `sep`, returning the parts before and after it.
+        ///
+        /// An empty `sep` matches at the end, like `str::rsplit_once`.
+        #[inline]
+        fn rsplit_once_on(&self, sep: impl $trait) -> Option<(&[$ty], &[$ty])> {
+            let src = $trait::$fn(self);
+            let sep = $trait::$fn(&sep);
+            if sep.is_empty() {
+                return Some((src, &src[src.len()..]));
+            }
+            src.windows(sep.len())
+                .rposition(|w| w == sep)
+                .map(|i| (&src[..i], &src[i + sep.len()..]))
+        }
+    };
+}
+
+macro_rules! impl_get_suites {
+    ($trait:tt::$fn:tt, $ty: ty) => {
+        /// Divides into two slices at `mid`, or returns `None` if `mid` is out of bounds.
+        #[inline]
+        fn try_split_at(&self, mid: usize) -> Option<(&[$ty], &[$ty])> {
+            let src = $trait::$fn(self);
+            (mid <= src.len()).then(|| src.split_at(mid))
+        }
+
+        /// Returns the elements in `range`, or `None` if it is out of bounds or decreasing.
+        #[inline]
+        fn get_range(&self, range: impl core::ops::RangeBounds<usize>) -> Option<&[$ty]> {
+            $trait::$fn(self).get((range.start_bound().cloned(), range.end_bound().cloned()))
+        }
+
+        /// Splits off the first `n` elements, returning them and the rest,
+        /// or `None` if there are fewer than `n` elements.
+        #[inline]
+        fn take_front(&self, n: usize) -> Option<(&[$ty], &[$ty])> {
+            self.try_split_at(n)
+        }
+    };
+}
+
+macro_rules! impl_gather_suites {
+    ($trait:tt::$fn:tt, $ty: ty) => {
+        cfg_feature!("alloc", {
+            /// Returns the elements at `indices`, in the order of `indices`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if an index is out of bounds, see [`try_gather`] for a fallible version.
+            ///
+            /// [`try_gather`]: #method.try_gather
+            fn gather(&self, indices: &[usize]) -> Vec<$ty> {
+                let src = $trait::$fn(self);
+                indices.iter().map(|&i| src[i]).collect()
+            }
+
+            /// Returns the elements at `indices`, in the order of `indices`, or the first index
+            /// which is out of bounds.
+            fn try_gather(&self, indices: &[usize]) -> Result<Vec<$ty>, crate::OobAt> {
+                let src = $trait::$fn(self);
+                indices
+                    .iter()
+                    .enumerate()
+                    .map(|(position, &index)| {
+                        src.get(index).copied().ok_or(crate::OobAt {
+                            position,
+                            index,
+                            len: src.len(),
+                        })
+                    })
+                    .collect()
+            }
+        });
+    };
+    (mut $trait:tt::$fn:tt, $ty: ty) => {
+        /// Writes `values[i]` to the element at `indices[i]`, the inverse of `gather`.
+        ///
+        /// If an index appears more than once, the last value written to it is kept.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `indices` and `values` have different lengths, or an index is out of bounds
+        /// (in which case the values before it have already been written).
+        fn scatter_from(&mut self, indices: &[usize], values: &[$ty]) {
+            assert_eq!(
+                indices.len(),
+                values.len(),
+                "indices and values have different lengths"
+            );
+            let dst = $trait::$fn(self);
+            for (&i, v) in indices.iter().zip(values) {
+                dst[i] = *v;
+            }
+        }
+    };
+}
+
+macro_rules! impl_map_suites {
+    ($trait:tt::$fn:tt, $ty: ty) => {
+        /// Writes `f` of each element to the element of `dst` at the same index, so the output
+        /// buffer can be reused instead of allocating a new vec.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `dst` has a different length.
+        fn map_into<U, F: FnMut($ty) -> U>(&self, dst: &mut [U], mut f: F) {
+            let src = $trait::$fn(self);
+            assert_eq!(
+                src.len(),
+                dst.len(),
+                "source and destination have different lengths"
+            );
+            for (d, s) in dst.iter_mut().zip(src) {
+                *d = f(*s);
+            }
+        }
+
+        cfg_feature!("rayon", {
+            /// Like [`map_into`], but maps chunks of the elements in parallel on the rayon thread
+            /// pool.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `dst` has a different length.
+            ///
+            /// [`map_into`]: #method.map_into
+            fn par_map_into<U, F>(&self, dst: &mut [U], f: F)
+            where
+                U: Send,
+                F: Fn($ty) -> U + Sync + Send,
+            {
+                use rayon::prelude::*;
+
+                /// The number of elements mapped by one task.
+                const CHUNK: usize = 4096;
+
+                let src = $trait::$fn(self);
+                assert_eq!(
+                    src.len(),
+                    dst.len(),
+                    "source and destination have different lengths"
+                );
+                dst.par_chunks_mut(CHUNK)
+                    .zip(src.par_chunks(CHUNK))
+                    .for_each(|(d, s)| {
+                        for (d, s) in d.iter_mut().zip(s) {
+                            *d = f(*s);
+                        }
+                    });
+            }
+        });
+    };
+}
+
+macro_rules! impl_partition_suites {
+    ($trait:tt::$fn:tt, $ty: ty) => {
+        /// Reorders the elements so all those matching `pred` come first, and returns how many matched.
+        ///
+        /// The relative order of the elements is not preserved.
+        fn partition_in_place<F: FnMut(&$ty) -> bool>(&mut self, mut pred: F) -> usize {
+            let src = $trait::$fn(self);
+            let mut matched = 0;
+            for i in 0..src.len() {
+                if pred(&src[i]) {
+                    src.swap(matched, i);
+                    matched += 1;
+                }
+            }
+            matched
+        }
+    };
+}
+
+macro_rules! impl_permutation_suites {
+    ($trait:tt::$fn:tt) => {
+        /// Rearranges the elements into the lexicographically next greater permutation, and
+        /// returns `true`.
+        ///
+        /// If the elements are already in the greatest permutation (in descending order), they
+        /// are rearranged into the smallest one (ascending order) and `false` is returned, so
+        /// starting from a sorted slice, `while s.next_permutation_in_place() {}` visits every
+        /// distinct permutation once. Elements are compared with `<`.
+        fn next_permutation_in_place(&mut self) -> bool {
+            let src = $trait::$fn(self);
+            let pivot = match (1..src.len()).rev().find(|&i| src[i - 1] < src[i]) {
+                Some(i) => i - 1,
+                None => {
+                    src.reverse();
+                    return false;
+                }
+            };
+            // The suffix after `pivot` is descending, so the last element greater than the pivot
+            // is the smallest one.
+            let successor = (pivot + 1..src.len()).rev().find(|&j| src[pivot] < src[j]).unwrap();
+            src.swap(pivot, successor);
+            src[pivot + 1..].reverse();
+            true
+        }
+
+        /// Rearranges the elements into the lexicographically previous smaller permutation, and
+        /// returns `true`.
+        ///
+        /// If the elements are already in the smallest permutation (in ascending order), they
+        /// are rearranged into the greatest one (descending order) and `false` is returned.
+        /// Elements are compared with `<`.
+        fn prev_permutation_in_place(&mut self) -> bool {
+            let src = $trait::$fn(self);
+            let pivot = match (1..src.len()).rev().find(|&i| src[i] < src[i - 1]) {
+                Some(i) => i - 1,
+                None => {
+                    src.reverse();
+                    return false;
+                }
+            };
+            let predecessor = (pivot + 1..src.len()).rev().find(|&j| src[j] < src[pivot]).unwrap();
+            src.swap(pivot, predecessor);
+            src[pivot + 1..].reverse();
+            true
+        }
+    };
+}
+
+macro_rules! impl_reorder_suites {
+    ($trait:tt::$fn:tt) => {
+        /// Rotates the elements in place so the element at `n` becomes the first one.
+        ///
+        /// Unlike `<[T]>::rotate_left`, `n` wraps around the length instead of panicking.
+        #[inline]
+        fn rotate_left_by(&mut self, n: usize) {
+            let src = $trait::$fn(self);
+            if !src.is_empty() {
+                let n = n % src.len();
+                src.rotate_left(n);
+            }
+        }
+
+        /// Rotates the elements in place so the last `n` elements come first.
+        ///
+        /// Unlike `<[T]>::rotate_right`, `n` wraps around the length instead of panicking.
+        #[inline]
+        fn rotate_right_by(&mut self, n: usize) {
+            let src = $trait::$fn(self);
+            if !src.is_empty() {
+                let n = n % src.len();
+                src.rotate_right(n);
+            }
+        }
+
+        /// Reverses the order of the consecutive chunks of `chunk` elements, keeping the order of
+        /// the elements within each chunk.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `chunk` is 0 or the length is not a multiple of `chunk`.
+        fn reverse_chunks(&mut self, chunk: usize) {
+            let src = $trait::$fn(self);
+            assert!(chunk > 0, "chunk must be greater than 0");
+            assert_eq!(
+                src.len() % chunk,
+                0,
+                "length {} is not a multiple of chunk {}",
+                src.len(),
+                chunk
+            );
+            src.reverse();
+            src.chunks_exact_mut(chunk).for_each(|c| c.reverse());
+        }
+
+        /// Swaps the elements of the ranges `a` and `b`, which may have different lengths; the
+        /// elements between them are shifted to make room.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the ranges overlap or are out of bounds.
+        fn swap_ranges(&mut self, a: core::ops::Range<usize>, b: core::ops::Range<usize>) {
+            let src = $trait::$fn(self);
+            let (a, b) = if a.start <= b.start { (a, b) } else { (b, a) };
+            assert!(
+                a.start <= a.end && b.start <= b.end && a.end <= b.start && b.end <= src.len(),
+                "invalid ranges {:?} and {:?} for length {}",
+                a,
+                b,
+                src.len()
+            );
+            // Reversing `a | mid | b` gives `rev(b) | rev(mid) | rev(a)`, then each part is
+            // reversed back.
+            let region = &mut src[a.start..b.end];
+            region.reverse();
+            let (b_len, mid_len) = (b.end - b.start, b.start - a.end);
+            let (head, rest) = region.split_at_mut(b_len);
+            let (mid, tail) = rest.split_at_mut(mid_len);
+            head.reverse();
+            mid.reverse();
+            tail.reverse();
+        }
+    };
+}
+
+macro_rules! impl_rand_suites {
+    ($trait:tt::$fn:tt, $ty: ty) => {
+        cfg_feature!("rand", {
+            /// Returns a random element, or `None` if the slice is empty.
+            #[inline]
+            fn choose<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Option<&$ty> {
+                rand::seq::SliceRandom::choose($trait::$fn(self), rng)
+            }
+
+            /// Returns `k` distinct random elements (all of them if `k` exceeds the length), in random order.
+            fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R, k: usize) -> Vec<&$ty> {
+                let src = $trait::$fn(self);
+                rand::seq::index::sample(rng, src.len(), k.min(src.len()))
+                    .into_iter()
+                    .map(|i| &src[i])
+                    .collect()
+            }
+        });
+    };
+    (mut $trait:tt::$fn:tt) => {
+        cfg_feature!("rand", {
+            /// Shuffles the elements in place.
+            #[inline]
+            fn shuffle_in_place<R: rand::Rng + ?Sized>(&mut self, rng: &mut R) {
+                rand::seq::SliceRandom::shuffle($trait::$fn(self), rng)
+            }
+        });
+    };
+}
+
 macro_rules! impl_psfix_suites {
     ($trait:tt::$fn:tt, $ty: ty, $ty_literal: literal) => {
         has_prefix!($trait::$fn);
@@ -144,15 +507,219 @@ macro_rules! impl_psfix_suites {
 
         longest_suffix!($trait::$fn, $ty);
 
-        cfg_alloc!{
+        cfg_feature!("alloc", {
             longest_prefix_lossy!($trait::$fn, $ty, $ty_literal);
             longest_suffix_lossy!($trait::$fn, $ty, $ty_literal);
-        }
+        });
+    };
+}
+
+// The zero-copy conversions reinterpret packed slices of one primitive as another,
+// which relies on every primitive having its usual size and an alignment no greater than it.
+const_assert_size!(u8, 1);
+const_assert_align!(u8, 1);
+const_assert_size!(u16, 2);
+const_assert_align!(u16, <= 2);
+const_assert_size!(u32, 4);
+const_assert_align!(u32, <= 4);
+const_assert_size!(u64, 8);
+const_assert_align!(u64, <= 8);
+const_assert_size!(u128, 16);
+const_assert_align!(u128, <= 16);
+const_assert_size!(i8, 1);
+const_assert_align!(i8, 1);
+const_assert_size!(i16, 2);
+const_assert_align!(i16, <= 2);
+const_assert_size!(i32, 4);
+const_assert_align!(i32, <= 4);
+const_assert_size!(i64, 8);
+const_assert_align!(i64, <= 8);
+const_assert_size!(i128, 16);
+const_assert_align!(i128, <= 16);
+const_assert_size!(f32, 4);
+const_assert_align!(f32, <= 4);
+const_assert_size!(f64, 8);
+const_assert_align!(f64, <= 8);
+
+cfg_feature!("half", {
+    const_assert_size!(half::f16, 2);
+    const_assert_align!(half::f16, <= 2);
+    const_assert_size!(half::bf16, 2);
+    const_assert_align!(half::bf16, <= 2);
+});
+
+cfg_32bit! {
+    const_assert_size!(usize, 4);
+    const_assert_size!(isize, 4);
+}
+
+cfg_64bit! {
+    const_assert_size!(usize, 8);
+    const_assert_size!(isize, 8);
+}
+
+const_assert_align!(usize, <= core::mem::size_of::<usize>());
+const_assert_align!(isize, <= core::mem::size_of::<isize>());
+
+/// Expands the items only when at least one `slice-*` feature is enabled.
+macro_rules! cfg_slice {
+    ($($item:item)*) => {
+        $(
+            #[cfg(any(
+                feature = "slice-u16",
+                feature = "slice-u32",
+                feature = "slice-u64",
+                feature = "slice-u128",
+                feature = "slice-usize",
+                feature = "slice-i8",
+                feature = "slice-i16",
+                feature = "slice-i32",
+                feature = "slice-i64",
+                feature = "slice-i128",
+                feature = "slice-isize",
+                feature = "slice-f32",
+                feature = "slice-f64",
+                feature = "half",
+            ))]
+            $item
+        )*
     };
 }
 
+#[macro_use]
+mod packed;
+mod block;
 mod bytes_ext;
-mod slice_ext;
+cfg_feature!("alloc", {
+    mod chunked;
+    pub use chunked::ChunkedDecoder;
+
+    mod csv;
+    pub use csv::CsvFields;
+
+    mod dns;
+    pub use dns::{encode_dns_name, DnsNameError};
+
+    mod vec_ext;
+    pub use vec_ext::{MemoryFootprint, VecExt};
+
+    mod writer;
+    pub use writer::VecByteWriterExt;
+
+    mod extend;
+    pub use extend::ExtendFromBytesExt;
+
+    mod owned;
+    pub use owned::OwnedSliceExt;
+
+    mod wal;
+    pub use wal::{WalError, WalReader, WalRecordType, WalWriter, WAL_BLOCK_SIZE, WAL_HEADER_LEN};
+
+    mod sst;
+    pub use sst::{SstBlockBuilder, SstBlockError, SstBlockIter, SST_RESTART_INTERVAL};
 
+    mod bitmap;
+    pub use bitmap::{Bitmap, BitmapError, BitmapIter};
+
+    mod rank_select;
+    pub use rank_select::RankSelectBits;
+
+    mod elias_fano;
+    pub use elias_fano::{EliasFano, EliasFanoIter};
+
+    mod art;
+    pub use art::{ArtIter, ArtMap};
+
+    mod small_bytes;
+    pub use small_bytes::SmallBytes;
+});
+cfg_feature!("zeroize", {
+    mod secret;
+    pub use secret::SecretBytes;
+});
+cfg_feature!("proptest", {
+    pub mod strategy;
+});
+cfg_feature!("bench-helpers", {
+    pub mod bench;
+});
+mod cdc;
+mod checksum;
+mod const_fn;
+mod cstr;
+mod endian;
+mod endian_view;
+mod error;
+mod group;
+mod header;
+mod key;
+mod mac;
+mod offsets;
+mod pod;
+mod reader;
+mod records;
+mod rolling;
+mod search;
+mod tlv;
+mod trim;
+mod uuid;
+mod versioned;
+cfg_slice! {
+    mod slice_ext;
+    pub use slice_ext::*;
+}
+
+pub use block::{read_block_with_crc, write_block_with_crc, BLOCK_HEADER_LEN};
 pub use bytes_ext::*;
-pub use slice_ext::*;
+pub use cdc::CdcChunks;
+pub use checksum::*;
+pub use const_fn::*;
+pub use cstr::SplitNul;
+pub use endian::{Endian, EndianScalar};
+pub use endian_view::{Be, BeIter, BeSlice, BeSliceMut, Le, LeIter, LeSlice, LeSliceMut};
+pub use error::{BlockError, InvalidLength, LayoutError, OffsetTableError, OobAt, ReadError};
+pub use group::{GroupBy, Runs};
+pub use header::{CborHeader, MsgpackHeader};
+pub use key::*;
+pub use mac::{parse_mac, ParseMacError};
+pub use pod::{validate_layout, Pod};
+cfg_feature!("alloc", {
+    pub use pod::{into_byte_vec, try_from_byte_vec};
+});
+pub use reader::SliceReader;
+pub use records::{FieldRecords, FieldSpec, Record, Records};
+pub use rolling::RollingHash;
+pub use tlv::{TlvFormat, TlvIter};
+pub use uuid::{parse_uuid, ParseUuidError, UuidVariant};
+pub use versioned::{Snapshot, SnapshotToken, VersionedBytes};
+
+/// Re-exports all the extension traits, so they can be imported in one line.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::prelude::*;
+///
+/// assert!(b"hello".has_prefix("he"));
+///
+/// #[cfg(feature = "slice-u16")]
+/// {
+///     let buf = vec![1u16, 2, 3];
+///     assert!(buf.has_prefix([1u16, 2]));
+///     assert_eq!(buf.cast_bytes().len(), 6);
+/// }
+/// ```
+pub mod prelude {
+    pub use crate::bytes_ext::*;
+    #[cfg(feature = "alloc")]
+    pub use crate::vec_ext::*;
+    #[cfg(feature = "alloc")]
+    pub use crate::writer::*;
+    #[cfg(feature = "alloc")]
+    pub use crate::extend::*;
+    #[cfg(feature = "alloc")]
+    pub use crate::owned::*;
+    cfg_slice! {
+        pub use crate::slice_ext::*;
+    }
+}
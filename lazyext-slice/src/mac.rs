@@ -0,0 +1,145 @@
+use core::fmt;
+
+/// The error returned by [`parse_mac`].
+///
+/// [`parse_mac`]: fn.parse_mac.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMacError {
+    /// The input has an unexpected length.
+    InvalidLength(usize),
+    /// The input contains a non-hex character at the given index.
+    InvalidCharacter {
+        /// The index of the character in the input.
+        index: usize,
+        /// The character.
+        byte: u8,
+    },
+    /// The separators are not at the expected positions, or are mixed.
+    InvalidGroups,
+}
+
+impl fmt::Display for ParseMacError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength(len) => {
+                write!(f, "invalid mac address length {}, expected 12, 14 or 17", len)
+            }
+            Self::InvalidCharacter { index, byte } => write!(
+                f,
+                "invalid mac address character {:?} at {}",
+                *byte as char, index
+            ),
+            Self::InvalidGroups => f.write_str(
+                "invalid mac address groups, expected aa:bb:cc:dd:ee:ff, aa-bb-cc-dd-ee-ff or aabb.ccdd.eeff",
+            ),
+        }
+    }
+}
+
+cfg_feature!("std", {
+    impl std::error::Error for ParseMacError {}
+});
+
+/// Parses a MAC address written as `aa:bb:cc:dd:ee:ff`, `aa-bb-cc-dd-ee-ff`, `aabb.ccdd.eeff`
+/// or as 12 bare hex digits. Hex digits are case-insensitive.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::{parse_mac, BytesExt};
+///
+/// let mac = parse_mac("00:1A:2b:3c:4d:5e").unwrap();
+/// assert_eq!(mac, [0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]);
+/// assert_eq!(parse_mac("001a.2b3c.4d5e").unwrap(), mac);
+/// assert_eq!(mac.oui(), [0x00, 0x1a, 0x2b]);
+/// assert!(parse_mac("00:1a:2b-3c:4d:5e").is_err());
+/// ```
+pub fn parse_mac(src: impl crate::AsBytesRef) -> Result<[u8; 6], ParseMacError> {
+    let src = src.as_bytes_ref();
+    // The separator and after how many hex digits it repeats.
+    let (sep, group) = match src.len() {
+        12 => (None, 12),
+        14 => (Some(b'.'), 4),
+        17 => match src[2] {
+            sep @ (b':' | b'-') => (Some(sep), 2),
+            _ => return Err(ParseMacError::InvalidGroups),
+        },
+        len => return Err(ParseMacError::InvalidLength(len)),
+    };
+
+    let mut out = [0u8; 6];
+    let mut digits = 0;
+    for (i, &b) in src.iter().enumerate() {
+        // Every `group + 1`th character is a separator.
+        if sep.is_some() && i % (group + 1) == group {
+            if Some(b) != sep {
+                return Err(ParseMacError::InvalidGroups);
+            }
+            continue;
+        }
+
+        let v = match b {
+            b'0'..=b'9' => b - b'0',
+            b'a'..=b'f' => b - b'a' + 10,
+            b'A'..=b'F' => b - b'A' + 10,
+            b':' | b'-' | b'.' => return Err(ParseMacError::InvalidGroups),
+            _ => return Err(ParseMacError::InvalidCharacter { index: i, byte: b }),
+        };
+        out[digits / 2] |= v << (4 * (1 - digits % 2));
+        digits += 1;
+    }
+    Ok(out)
+}
+
+/// Writes the bytes as lowercase colon-separated hex, e.g. `00:1a:2b:3c:4d:5e`.
+#[cfg(feature = "alloc")]
+pub(crate) fn write_mac(src: &[u8], f: &mut impl fmt::Write) -> fmt::Result {
+    for (i, b) in src.iter().enumerate() {
+        if i > 0 {
+            f.write_char(':')?;
+        }
+        write!(f, "{:02x}", b)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BytesExt;
+
+    #[test]
+    fn test_mac_roundtrip() {
+        let mac = parse_mac("02-00-5E-10-00-01").unwrap();
+        assert_eq!(mac.format_mac(), "02:00:5e:10:00:01");
+        assert_eq!(parse_mac("02005e100001"), Ok(mac));
+        assert!(mac.is_local_mac());
+        assert!(!mac.is_multicast_mac());
+
+        let eui = mac.mac_to_eui64();
+        assert_eq!(eui, [0x02, 0x00, 0x5e, 0xff, 0xfe, 0x10, 0x00, 0x01]);
+        assert_eq!(eui.format_mac(), "02:00:5e:ff:fe:10:00:01");
+        assert_eq!(eui.oui(), mac.oui());
+    }
+
+    #[test]
+    fn test_parse_mac_errors() {
+        assert_eq!(parse_mac("00:1a"), Err(ParseMacError::InvalidLength(5)));
+        assert_eq!(
+            parse_mac("001a:2b3c:4d5e"),
+            Err(ParseMacError::InvalidGroups)
+        );
+        assert_eq!(
+            parse_mac("00:1a:2b:3c:4d5e:"),
+            Err(ParseMacError::InvalidGroups)
+        );
+        assert_eq!(
+            parse_mac("00:1a:2b:3c:4d:5g"),
+            Err(ParseMacError::InvalidCharacter {
+                index: 16,
+                byte: b'g'
+            })
+        );
+        assert_eq!(parse_mac("01005e10000:"), Err(ParseMacError::InvalidGroups));
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{packed, Endian, OffsetTableError};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[inline]
+fn check_width(width: usize) {
+    assert!(
+        (1..=8).contains(&width),
+        "offset width must be between 1 and 8 bytes, got {}",
+        width
+    );
+}
+
+#[inline]
+fn table_len(count: usize, width: usize, len: usize) -> Result<usize, OffsetTableError> {
+    match count.checked_mul(width) {
+        Some(needed) if needed <= len => Ok(needed),
+        needed => Err(OffsetTableError::TooShort {
+            needed: needed.unwrap_or(usize::MAX),
+            len,
+        }),
+    }
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn read_offset_table(
+    src: &[u8],
+    count: usize,
+    width: usize,
+    endian: Endian,
+) -> Result<Vec<usize>, OffsetTableError> {
+    check_width(width);
+    let table = &src[..table_len(count, width, src.len())?];
+    let mut offsets = Vec::with_capacity(count);
+    for (index, entry) in table.chunks_exact(width).enumerate() {
+        let offset = match endian {
+            Endian::Big => packed::read_be(entry),
+            Endian::Little => packed::read_le(entry),
+        };
+        let offset = match usize::try_from(offset) {
+            Ok(offset) if offset <= src.len() => offset,
+            _ => {
+                return Err(OffsetTableError::OutOfBounds {
+                    index,
+                    offset,
+                    len: src.len(),
+                })
+            }
+        };
+        if offsets.last().is_some_and(|prev| *prev > offset) {
+            return Err(OffsetTableError::NotMonotonic { index });
+        }
+        offsets.push(offset);
+    }
+    Ok(offsets)
+}
+
+pub(crate) fn write_offset_table(
+    dst: &mut [u8],
+    offsets: &[usize],
+    width: usize,
+    endian: Endian,
+) -> Result<usize, OffsetTableError> {
+    check_width(width);
+    let len = table_len(offsets.len(), width, dst.len())?;
+    let max = u64::MAX >> (64 - 8 * width);
+    for (index, pair) in offsets.windows(2).enumerate() {
+        if pair[0] > pair[1] {
+            return Err(OffsetTableError::NotMonotonic { index: index + 1 });
+        }
+    }
+    if let Some((index, offset)) = offsets
+        .iter()
+        .enumerate()
+        .find(|(_, offset)| **offset as u64 > max)
+    {
+        return Err(OffsetTableError::Overflow {
+            index,
+            offset: *offset as u64,
+            width,
+        });
+    }
+    for (entry, offset) in dst[..len].chunks_exact_mut(width).zip(offsets) {
+        match endian {
+            Endian::Big => packed::write_be(entry, *offset as u64),
+            Endian::Little => packed::write_le(entry, *offset as u64),
+        }
+    }
+    Ok(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_offset_table() {
+        let mut buf = [0u8; 8];
+        assert_eq!(
+            write_offset_table(&mut buf, &[2, 300], 3, Endian::Little),
+            Ok(6)
+        );
+        assert_eq!(buf, [2, 0, 0, 0x2c, 1, 0, 0, 0]);
+        assert_eq!(
+            write_offset_table(&mut buf, &[256], 1, Endian::Big),
+            Err(OffsetTableError::Overflow {
+                index: 0,
+                offset: 256,
+                width: 1
+            })
+        );
+        assert_eq!(
+            write_offset_table(&mut buf, &[3, 2], 2, Endian::Big),
+            Err(OffsetTableError::NotMonotonic { index: 1 })
+        );
+        assert_eq!(
+            write_offset_table(&mut buf, &[0; 3], 4, Endian::Big),
+            Err(OffsetTableError::TooShort { needed: 12, len: 8 })
+        );
+        assert_eq!(buf[0], 2);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_read_offset_table() {
+        let mut buf = [0u8; 16];
+        assert_eq!(
+            write_offset_table(&mut buf, &[8, 8, 16], 2, Endian::Big),
+            Ok(6)
+        );
+        assert_eq!(
+            read_offset_table(&buf, 3, 2, Endian::Big),
+            Ok(vec![8, 8, 16])
+        );
+        assert_eq!(read_offset_table(&buf, 0, 8, Endian::Big), Ok(vec![]));
+        assert_eq!(
+            read_offset_table(&buf, 3, 2, Endian::Little),
+            Err(OffsetTableError::OutOfBounds {
+                index: 0,
+                offset: 0x800,
+                len: 16
+            })
+        );
+        assert_eq!(
+            read_offset_table(&buf, 9, 2, Endian::Big),
+            Err(OffsetTableError::TooShort {
+                needed: 18,
+                len: 16
+            })
+        );
+        assert_eq!(
+            read_offset_table(&buf, usize::MAX, 2, Endian::Big),
+            Err(OffsetTableError::TooShort {
+                needed: usize::MAX,
+                len: 16
+            })
+        );
+
+        buf[1] = 9;
+        assert_eq!(
+            read_offset_table(&buf, 3, 2, Endian::Big),
+            Err(OffsetTableError::NotMonotonic { index: 1 })
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_width() {
+        let _ = write_offset_table(&mut [0; 16], &[0], 9, Endian::Big);
+    }
+}
@@ -0,0 +1,128 @@
+use crate::pod::copy_from_bytes;
+use crate::{into_byte_vec, InvalidLength, Pod};
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::mem;
+use core::slice::from_raw_parts;
+
+/// Conversions of owned slices of [`Pod`] values into owned bytes, and back.
+///
+/// They consume the source, so the bytes are copied at most once: directly into the new
+/// allocation, without an intermediate `Vec`.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::OwnedSliceExt;
+/// use std::sync::Arc;
+///
+/// let shared: Arc<[u8]> = vec![1u16, 2].into_arc_bytes();
+/// assert_eq!(shared.len(), 4);
+/// assert_eq!(Vec::<u16>::from_arc_bytes(&shared), Ok(vec![1, 2]));
+///
+/// let boxed = b"abc".to_vec().into_boxed_u8_slice();
+/// assert_eq!(&*boxed, b"abc");
+/// ```
+///
+/// [`Pod`]: trait.Pod.html
+pub trait OwnedSliceExt: Sized {
+    /// Converts into the boxed slice of its bytes in native-endian.
+    ///
+    /// The allocation is reused when the elements have an alignment of 1 (and the capacity
+    /// matches the length), see [`into_byte_vec`].
+    ///
+    /// [`into_byte_vec`]: fn.into_byte_vec.html
+    fn into_boxed_u8_slice(self) -> Box<[u8]>;
+
+    /// Converts into a reference-counted slice of its bytes in native-endian, copying them
+    /// once into the shared allocation.
+    fn into_arc_bytes(self) -> Arc<[u8]>;
+
+    /// Copies the bytes of a shared slice into a new owned slice of the elements, or fails if
+    /// the length is not a multiple of the size of the elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the elements are zero-sized.
+    fn from_arc_bytes(src: &Arc<[u8]>) -> Result<Self, InvalidLength>;
+}
+
+/// Returns the bytes of `src` in native-endian.
+#[inline]
+fn bytes_of<T: Pod>(src: &[T]) -> &[u8] {
+    // SAFETY: `Pod` types have no padding.
+    unsafe { from_raw_parts(src.as_ptr() as *const u8, mem::size_of_val(src)) }
+}
+
+impl<T: Pod> OwnedSliceExt for Vec<T> {
+    #[inline]
+    fn into_boxed_u8_slice(self) -> Box<[u8]> {
+        into_byte_vec(self).into_boxed_slice()
+    }
+
+    #[inline]
+    fn into_arc_bytes(self) -> Arc<[u8]> {
+        Arc::from(bytes_of(&self))
+    }
+
+    #[inline]
+    fn from_arc_bytes(src: &Arc<[u8]>) -> Result<Self, InvalidLength> {
+        copy_from_bytes(src)
+    }
+}
+
+impl<T: Pod> OwnedSliceExt for Box<[T]> {
+    #[inline]
+    fn into_boxed_u8_slice(self) -> Box<[u8]> {
+        into_byte_vec(self.into_vec()).into_boxed_slice()
+    }
+
+    #[inline]
+    fn into_arc_bytes(self) -> Arc<[u8]> {
+        Arc::from(bytes_of(&self))
+    }
+
+    #[inline]
+    fn from_arc_bytes(src: &Arc<[u8]>) -> Result<Self, InvalidLength> {
+        copy_from_bytes(src).map(Vec::into_boxed_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boxed_u8_slice() {
+        let bytes = vec![1u8, 2, 3];
+        let ptr = bytes.as_ptr();
+        let boxed = bytes.into_boxed_u8_slice();
+        // An exactly sized `Vec<u8>` is boxed without copying.
+        assert_eq!(boxed.as_ptr(), ptr);
+
+        let boxed = vec![0x0102u16].into_boxed_u8_slice();
+        assert_eq!(&*boxed, 0x0102u16.to_ne_bytes());
+        assert_eq!(
+            Box::<[u16]>::from(&[7u16, 8][..])
+                .into_boxed_u8_slice()
+                .len(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_arc_bytes_roundtrip() {
+        let vals: Box<[u64]> = vec![u64::MAX, 1].into_boxed_slice();
+        let shared = vals.clone().into_arc_bytes();
+        assert_eq!(shared.len(), 16);
+        assert_eq!(Box::<[u64]>::from_arc_bytes(&shared), Ok(vals));
+        assert_eq!(
+            Vec::<u32>::from_arc_bytes(&Arc::from(&[0u8; 6][..])),
+            Err(InvalidLength {
+                len: 6,
+                elem_size: 4
+            })
+        );
+    }
+}
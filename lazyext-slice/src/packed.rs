@@ -0,0 +1,162 @@
+//! Helpers for integers packed into a number of bytes which is not a power of two, e.g. 24-bit samples.
+
+/// Decodes a big-endian unsigned integer of `src.len()` (at most 8) bytes.
+#[inline]
+pub(crate) fn read_be(src: &[u8]) -> u64 {
+    src.iter().fold(0, |acc, &b| acc << 8 | b as u64)
+}
+
+/// Decodes a little-endian unsigned integer of `src.len()` (at most 8) bytes.
+#[inline]
+pub(crate) fn read_le(src: &[u8]) -> u64 {
+    src.iter().rev().fold(0, |acc, &b| acc << 8 | b as u64)
+}
+
+/// Encodes the low `dst.len()` bytes of `v` in big-endian.
+#[inline]
+pub(crate) fn write_be(dst: &mut [u8], v: u64) {
+    for (i, b) in dst.iter_mut().rev().enumerate() {
+        *b = (v >> (8 * i)) as u8;
+    }
+}
+
+/// Encodes the low `dst.len()` bytes of `v` in little-endian.
+#[inline]
+pub(crate) fn write_le(dst: &mut [u8], v: u64) {
+    for (i, b) in dst.iter_mut().enumerate() {
+        *b = (v >> (8 * i)) as u8;
+    }
+}
+
+macro_rules! packed_read_suite {
+    ($([$name:ident, $ty:ty, $width:literal]), +$(,)?) => {
+        $(
+        paste! {
+            #[doc = concat!("Reads the ", stringify!($width), "-byte big-endian `", stringify!($name), "` at `offset`, or returns `None` if it is out of bounds.")]
+            #[inline]
+            fn [<read_ $name _be_at>](&self, offset: usize) -> Option<$ty> {
+                let src = self.as_bytes_ref().get(offset..offset.checked_add($width)?)?;
+                Some(crate::packed::read_be(src) as $ty)
+            }
+
+            #[doc = concat!("Reads the ", stringify!($width), "-byte little-endian `", stringify!($name), "` at `offset`, or returns `None` if it is out of bounds.")]
+            #[inline]
+            fn [<read_ $name _le_at>](&self, offset: usize) -> Option<$ty> {
+                let src = self.as_bytes_ref().get(offset..offset.checked_add($width)?)?;
+                Some(crate::packed::read_le(src) as $ty)
+            }
+        }
+        )*
+    };
+}
+
+#[cfg(feature = "alloc")]
+macro_rules! packed_to_vec_suite {
+    ($([$name:ident, $ty:ty, $width:literal]), +$(,)?) => {
+        $(
+        paste! {
+            #[doc = concat!("Decodes packed ", stringify!($width), "-byte big-endian `", stringify!($name), "`s into a `Vec<", stringify!($ty), ">`.")]
+            ///
+            /// # Panics
+            #[doc = concat!("Panics if the length of the bytes is not a multiple of ", stringify!($width), ".")]
+            fn [<to_be_ $name _vec>](&self) -> Vec<$ty> {
+                let src = self.as_bytes_ref();
+                assert_eq!(src.len() % $width, 0, "invalid length of u8 slice: {}", src.len());
+                src.chunks_exact($width).map(|c| crate::packed::read_be(c) as $ty).collect()
+            }
+
+            #[doc = concat!("Decodes packed ", stringify!($width), "-byte little-endian `", stringify!($name), "`s into a `Vec<", stringify!($ty), ">`.")]
+            ///
+            /// # Panics
+            #[doc = concat!("Panics if the length of the bytes is not a multiple of ", stringify!($width), ".")]
+            fn [<to_le_ $name _vec>](&self) -> Vec<$ty> {
+                let src = self.as_bytes_ref();
+                assert_eq!(src.len() % $width, 0, "invalid length of u8 slice: {}", src.len());
+                src.chunks_exact($width).map(|c| crate::packed::read_le(c) as $ty).collect()
+            }
+        }
+        )*
+    };
+}
+
+macro_rules! packed_write_suite {
+    ($([$name:ident, $ty:ty, $width:literal]), +$(,)?) => {
+        $(
+        paste! {
+            #[doc = concat!("Writes the low ", stringify!($width), " bytes of `v` in big-endian at `offset`.")]
+            ///
+            /// # Panics
+            /// Panics if the write is out of bounds.
+            #[inline]
+            fn [<write_ $name _be_at>](&mut self, offset: usize, v: $ty) {
+                crate::packed::write_be(&mut self.as_bytes_mut_ref()[offset..offset + $width], v as u64)
+            }
+
+            #[doc = concat!("Writes the low ", stringify!($width), " bytes of `v` in little-endian at `offset`.")]
+            ///
+            /// # Panics
+            /// Panics if the write is out of bounds.
+            #[inline]
+            fn [<write_ $name _le_at>](&mut self, offset: usize, v: $ty) {
+                crate::packed::write_le(&mut self.as_bytes_mut_ref()[offset..offset + $width], v as u64)
+            }
+        }
+        )*
+    };
+}
+
+#[cfg(feature = "alloc")]
+macro_rules! packed_push_suite {
+    ($([$name:ident, $ty:ty, $width:literal]), +$(,)?) => {
+        $(
+        paste! {
+            #[doc = concat!("Appends the low ", stringify!($width), " bytes of `v` in big-endian")]
+            fn [<push_ $name _be>](&mut self, v: $ty);
+
+            #[doc = concat!("Appends the low ", stringify!($width), " bytes of `v` in little-endian")]
+            fn [<push_ $name _le>](&mut self, v: $ty);
+        }
+        )*
+    };
+}
+
+#[cfg(feature = "alloc")]
+macro_rules! impl_packed_push_suite {
+    ($([$name:ident, $ty:ty, $width:literal]), +$(,)?) => {
+        $(
+        paste! {
+            #[inline]
+            fn [<push_ $name _be>](&mut self, v: $ty) {
+                let mut buf = [0; $width];
+                crate::packed::write_be(&mut buf, v as u64);
+                self.extend_from_slice(&buf);
+            }
+
+            #[inline]
+            fn [<push_ $name _le>](&mut self, v: $ty) {
+                let mut buf = [0; $width];
+                crate::packed::write_le(&mut buf, v as u64);
+                self.extend_from_slice(&buf);
+            }
+        }
+        )*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packed_roundtrip() {
+        let mut buf = [0u8; 6];
+        write_be(&mut buf[..3], 0x0a0b0c);
+        write_le(&mut buf[3..], 0x0a0b0c);
+        assert_eq!(buf, [0x0a, 0x0b, 0x0c, 0x0c, 0x0b, 0x0a]);
+        assert_eq!(read_be(&buf[..3]), 0x0a0b0c);
+        assert_eq!(read_le(&buf[3..]), 0x0a0b0c);
+
+        write_be(&mut buf, 0xffff_0102_0304_0506);
+        assert_eq!(read_be(&buf), 0x0102_0304_0506);
+    }
+}
@@ -0,0 +1,283 @@
+use crate::{InvalidLength, LayoutError};
+use core::mem;
+use core::slice::{from_raw_parts, from_raw_parts_mut};
+#[cfg(feature = "half")]
+use half::{bf16, f16};
+
+/// Plain old data: types for which every bit pattern is a valid value, so a byte buffer can be
+/// reinterpreted as a slice of them (see `cast_to` on the typed slice traits).
+///
+/// # Safety
+///
+/// The type must have no padding, no invalid bit patterns and no drop glue.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($ty:ty), +$(,)?) => {
+        $(
+        unsafe impl Pod for $ty {}
+        )*
+    };
+}
+
+impl_pod!(u8, u16, u32, usize, u64, u128, i8, i16, i32, isize, i64, i128, f32, f64);
+
+#[cfg(feature = "half")]
+impl_pod!(f16, bf16);
+
+/// Checks that `src` can be viewed as a slice of `T`: its length must be a multiple of the size
+/// of `T`, and its start must be aligned for `T`.
+///
+/// The types implementing [`Pod`] accept any bit pattern, so this is all it takes for the view
+/// to be sound. `#[repr(C)]` structures of `Pod` fields without padding can implement `Pod` to be
+/// validated and viewed as well.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::{validate_layout, LayoutError};
+///
+/// #[repr(align(4))]
+/// struct Aligned([u8; 16]);
+///
+/// let buf = Aligned([0; 16]);
+/// assert!(validate_layout::<u32>(&buf.0).is_ok());
+/// assert!(matches!(
+///     validate_layout::<u32>(&buf.0[..6]),
+///     Err(LayoutError::InvalidLength(_))
+/// ));
+/// assert_eq!(
+///     validate_layout::<u32>(&buf.0[1..5]),
+///     Err(LayoutError::Misaligned { align: 4, offset: 1 })
+/// );
+/// ```
+///
+/// [`Pod`]: trait.Pod.html
+pub fn validate_layout<T: Pod>(src: &[u8]) -> Result<(), LayoutError> {
+    let size = mem::size_of::<T>();
+    if let 1.. = src.len() % size {
+        return Err(LayoutError::InvalidLength(InvalidLength {
+            len: src.len(),
+            elem_size: size,
+        }));
+    }
+    let align = mem::align_of::<T>();
+    match src.as_ptr() as usize % align {
+        0 => Ok(()),
+        offset => Err(LayoutError::Misaligned { align, offset }),
+    }
+}
+
+/// Reinterprets `src` as a slice of `T` in native-endian, after [`validate_layout`].
+///
+/// [`validate_layout`]: fn.validate_layout.html
+#[inline]
+pub(crate) fn try_cast_slice<T: Pod>(src: &[u8]) -> Result<&[T], LayoutError> {
+    validate_layout::<T>(src)?;
+    Ok(unsafe { from_raw_parts(src.as_ptr() as *const T, src.len() / mem::size_of::<T>()) })
+}
+
+/// Reinterprets `src` as a mutable slice of `T` in native-endian, after [`validate_layout`].
+///
+/// [`validate_layout`]: fn.validate_layout.html
+#[inline]
+pub(crate) fn try_cast_slice_mut<T: Pod>(src: &mut [u8]) -> Result<&mut [T], LayoutError> {
+    validate_layout::<T>(src)?;
+    let len = src.len() / mem::size_of::<T>();
+    Ok(unsafe { from_raw_parts_mut(src.as_mut_ptr() as *mut T, len) })
+}
+
+/// Reinterprets `src`, which must be exactly as large as `T`, as a reference to `T`.
+#[inline]
+pub(crate) fn try_cast_ref<T: Pod>(src: &[u8]) -> Result<&T, LayoutError> {
+    if src.len() != mem::size_of::<T>() {
+        return Err(LayoutError::InvalidLength(InvalidLength {
+            len: src.len(),
+            elem_size: mem::size_of::<T>(),
+        }));
+    }
+    try_cast_slice(src).map(|s| &s[0])
+}
+
+/// Reinterprets `src` as a slice of `T` in native-endian.
+///
+/// # Panics
+///
+/// Panics if the length of `src` is not a multiple of the size of `T`, or if `src` is not
+/// aligned for `T`.
+#[inline]
+pub(crate) fn cast_slice<T: Pod>(src: &[u8]) -> &[T] {
+    try_cast_slice(src).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Reinterprets `src` as a mutable slice of `T` in native-endian.
+///
+/// # Panics
+///
+/// Panics if the length of `src` is not a multiple of the size of `T`, or if `src` is not
+/// aligned for `T`.
+#[inline]
+pub(crate) fn cast_slice_mut<T: Pod>(src: &mut [u8]) -> &mut [T] {
+    try_cast_slice_mut(src).unwrap_or_else(|e| panic!("{}", e))
+}
+
+cfg_feature!("alloc", {
+    use alloc::vec::Vec;
+
+    /// Converts a vec of `T` into the vec of its bytes in native-endian.
+    ///
+    /// The allocation is reused when `T` has an alignment of 1 (so it can be freed as a
+    /// `Vec<u8>`), otherwise the bytes are copied into a new vec. Either way nothing leaks and
+    /// the result owns a correctly described allocation.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::{into_byte_vec, try_from_byte_vec};
+    ///
+    /// let bytes = into_byte_vec(vec![1u32.to_be(), 2u32.to_be()]);
+    /// assert_eq!(bytes, [0, 0, 0, 1, 0, 0, 0, 2]);
+    ///
+    /// let vals = try_from_byte_vec::<u32>(bytes).unwrap();
+    /// assert_eq!(vals, [1u32.to_be(), 2u32.to_be()]);
+    /// assert!(try_from_byte_vec::<u32>(vec![0; 6]).is_err());
+    /// ```
+    pub fn into_byte_vec<T: Pod>(src: Vec<T>) -> Vec<u8> {
+        let len = mem::size_of_val(&*src);
+        if mem::align_of::<T>() == 1 {
+            let mut src = mem::ManuallyDrop::new(src);
+            let cap = src.capacity() * mem::size_of::<T>();
+            // SAFETY: the allocation has the alignment of `u8` and `cap` bytes, the first `len`
+            // of which are initialized, and `Pod` types have no padding.
+            return unsafe { Vec::from_raw_parts(src.as_mut_ptr() as *mut u8, len, cap) };
+        }
+        // SAFETY: `Pod` types have no padding.
+        unsafe { from_raw_parts(src.as_ptr() as *const u8, len) }.to_vec()
+    }
+
+    /// Converts a vec of bytes into a vec of `T` in native-endian, or fails if its length is
+    /// not a multiple of the size of `T`.
+    ///
+    /// The allocation is reused when `T` has an alignment of 1 and the capacity is a multiple
+    /// of the size of `T`, otherwise the bytes are copied into a new, correctly aligned vec.
+    /// See [`into_byte_vec`] for the reverse conversion.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is zero-sized.
+    ///
+    /// [`into_byte_vec`]: fn.into_byte_vec.html
+    pub fn try_from_byte_vec<T: Pod>(src: Vec<u8>) -> Result<Vec<T>, InvalidLength> {
+        let size = mem::size_of::<T>();
+        assert!(size > 0, "cannot convert bytes into a zero-sized type");
+        let reusable = match (src.len() % size, src.capacity() % size) {
+            (0, 0) => mem::align_of::<T>() == 1,
+            _ => false,
+        };
+        if !reusable {
+            return copy_from_bytes(&src);
+        }
+
+        let len = src.len() / size;
+        let mut src = mem::ManuallyDrop::new(src);
+        let cap = src.capacity() / size;
+        // SAFETY: `T` has the alignment of `u8`, the allocation holds exactly `cap` values of
+        // `T`, and `Pod` types accept any bit pattern.
+        Ok(unsafe { Vec::from_raw_parts(src.as_mut_ptr() as *mut T, len, cap) })
+    }
+
+    /// Copies `src` into a new vec of `T` in native-endian, or fails if its length is not a
+    /// multiple of the size of `T`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is zero-sized.
+    pub(crate) fn copy_from_bytes<T: Pod>(src: &[u8]) -> Result<Vec<T>, InvalidLength> {
+        let size = mem::size_of::<T>();
+        assert!(size > 0, "cannot convert bytes into a zero-sized type");
+        if let 1.. = src.len() % size {
+            return Err(InvalidLength {
+                len: src.len(),
+                elem_size: size,
+            });
+        }
+
+        let len = src.len() / size;
+        let mut out = Vec::<T>::with_capacity(len);
+        // SAFETY: the new allocation is aligned for `T` and has room for `len` values, which
+        // are initialized from the bytes as `Pod` types accept any bit pattern.
+        unsafe {
+            core::ptr::copy_nonoverlapping(src.as_ptr(), out.as_mut_ptr() as *mut u8, src.len());
+            out.set_len(len);
+        }
+        Ok(out)
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BytesExt, BytesMutExt};
+
+    #[repr(align(8))]
+    struct Aligned([u8; 16]);
+
+    #[test]
+    fn test_validate_layout() {
+        let mut buf = Aligned([0; 16]);
+        assert_eq!(validate_layout::<u64>(&buf.0), Ok(()));
+        assert_eq!(validate_layout::<u8>(&buf.0[3..]), Ok(()));
+        assert_eq!(
+            validate_layout::<u16>(&buf.0[1..3]),
+            Err(LayoutError::Misaligned {
+                align: 2,
+                offset: 1
+            })
+        );
+        assert_eq!(
+            (&buf.0[..12]).try_cast_to::<u64>(),
+            Err(LayoutError::InvalidLength(InvalidLength {
+                len: 12,
+                elem_size: 8
+            }))
+        );
+
+        (&mut buf.0[..]).try_cast_to_mut::<u32>().unwrap()[1] = u32::MAX;
+        assert_eq!(buf.0[4..8], [0xff; 4]);
+        assert_eq!((&buf.0[4..8]).try_cast_ref::<u32>(), Ok(&u32::MAX));
+    }
+
+    #[test]
+    #[should_panic(expected = "misaligned")]
+    fn test_cast_misaligned() {
+        let buf = Aligned([0; 16]);
+        (&buf.0[1..9]).cast_to::<u64>();
+    }
+
+    #[test]
+    fn test_byte_vec_roundtrip() {
+        let vals = vec![0x0102_0304u32, 0x0506_0708];
+        let bytes = into_byte_vec(vals.clone());
+        assert_eq!(bytes.len(), 8);
+        assert_eq!(try_from_byte_vec::<u32>(bytes), Ok(vals));
+
+        // Alignment 1: the allocation is reused.
+        let mut src = Vec::with_capacity(16);
+        src.extend_from_slice(&[1u8, 2, 3, 4]);
+        let ptr = src.as_ptr();
+        let signed = try_from_byte_vec::<i8>(src).unwrap();
+        assert_eq!(signed.as_ptr() as *const u8, ptr);
+        assert_eq!(signed.capacity(), 16);
+        let bytes = into_byte_vec(signed);
+        assert_eq!(bytes.as_ptr(), ptr);
+
+        assert_eq!(
+            try_from_byte_vec::<u64>(vec![0; 12]),
+            Err(InvalidLength {
+                len: 12,
+                elem_size: 8
+            })
+        );
+        assert!(try_from_byte_vec::<u16>(Vec::new()).unwrap().is_empty());
+    }
+}
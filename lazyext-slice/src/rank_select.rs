@@ -0,0 +1,291 @@
+//! A succinct rank/select directory over a bit vector.
+//!
+//! Bit `i` is bit `i % 8` (least significant first) of byte `i / 8`.
+
+use crate::{AsBytesRef, ReadError, SliceReader, VecByteWriterExt};
+use alloc::vec::Vec;
+
+/// The number of bits of a superblock of the rank directory.
+const BLOCK_BITS: usize = 512;
+
+/// The number of ones between the samples of the select hints.
+const SELECT_SAMPLE: usize = 4096;
+
+/// A bit vector with constant-time `rank1` and fast `select1`, for the index structures built
+/// over byte buffers (wavelet trees, Elias-Fano, succinct tries).
+///
+/// The bits are borrowed or owned as any [`AsBytesRef`], the directory adds about 12.5% of
+/// their size: a count of the ones before every 512 bits, and the superblock of every 4096th
+/// one to narrow down `select1`.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::RankSelectBits;
+///
+/// let bits = RankSelectBits::new([0b1010_0110u8, 0x01]);
+/// assert_eq!(bits.count_ones(), 5);
+/// assert_eq!(bits.rank1(3), 2);
+/// assert_eq!(bits.select1(3), Some(7));
+/// assert_eq!(bits.select1(4), Some(8));
+/// assert_eq!(bits.select1(5), None);
+///
+/// let bytes = bits.to_bytes();
+/// let restored = RankSelectBits::from_bytes(&bytes).unwrap();
+/// assert_eq!(restored.rank1(16), 5);
+/// ```
+///
+/// [`AsBytesRef`]: trait.AsBytesRef.html
+#[derive(Debug, Clone)]
+pub struct RankSelectBits<B> {
+    bits: B,
+    len: usize,
+    /// The number of ones before each superblock, plus the total.
+    blocks: Vec<usize>,
+    /// The superblock of every `SELECT_SAMPLE`th one.
+    samples: Vec<usize>,
+}
+
+impl<B: AsBytesRef> RankSelectBits<B> {
+    /// Builds the directory over all the bits of `bits`.
+    #[inline]
+    pub fn new(bits: B) -> Self {
+        let len = bits.as_bytes_ref().len() * 8;
+        Self::with_len(bits, len)
+    }
+
+    /// Builds the directory over the first `len` bits of `bits`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` has less than `len` bits.
+    pub fn with_len(bits: B, len: usize) -> Self {
+        assert!(
+            len <= bits.as_bytes_ref().len() * 8,
+            "bit length {} out of range",
+            len
+        );
+        let mut this = Self {
+            bits,
+            len,
+            blocks: Vec::with_capacity(len / BLOCK_BITS + 2),
+            samples: Vec::new(),
+        };
+
+        let words_per_block = BLOCK_BITS / 64;
+        let mut ones = 0;
+        for w in 0..len.div_ceil(64) {
+            if w % words_per_block == 0 {
+                this.blocks.push(ones);
+            }
+            let count = this.word(w).count_ones() as usize;
+            // The block of the first one of every sample.
+            if (ones + count).div_ceil(SELECT_SAMPLE) > ones.div_ceil(SELECT_SAMPLE) {
+                this.samples.push(w / words_per_block);
+            }
+            ones += count;
+        }
+        this.blocks.push(ones);
+        this
+    }
+
+    /// Returns the `w`th word of the bits, with the bits past the length cleared.
+    #[inline]
+    fn word(&self, w: usize) -> u64 {
+        let src = self.bits.as_bytes_ref();
+        let start = w * 8;
+        let end = (start + 8).min(src.len());
+        let mut buf = [0; 8];
+        buf[..end - start].copy_from_slice(&src[start..end]);
+        let word = u64::from_le_bytes(buf);
+        match self.len - w * 64 {
+            rest if rest < 64 => word & ((1 << rest) - 1),
+            _ => word,
+        }
+    }
+
+    /// Returns the number of bits.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether there is no bit.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of ones.
+    #[inline]
+    pub fn count_ones(&self) -> usize {
+        *self.blocks.last().unwrap()
+    }
+
+    /// Returns the underlying bits.
+    #[inline]
+    pub fn as_bits(&self) -> &B {
+        &self.bits
+    }
+
+    /// Returns the underlying bits, dropping the directory.
+    #[inline]
+    pub fn into_inner(self) -> B {
+        self.bits
+    }
+
+    /// Returns bit `i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of range.
+    #[inline]
+    pub fn get(&self, i: usize) -> bool {
+        assert!(i < self.len, "bit index {} out of range", i);
+        self.bits.as_bytes_ref()[i / 8] & (1 << (i % 8)) != 0
+    }
+
+    /// Returns the number of ones before bit `i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is greater than the length.
+    pub fn rank1(&self, i: usize) -> usize {
+        assert!(i <= self.len, "bit index {} out of range", i);
+        let block = i / BLOCK_BITS;
+        let mut rank = self.blocks[block];
+        for w in block * (BLOCK_BITS / 64)..i / 64 {
+            rank += self.word(w).count_ones() as usize;
+        }
+        let rest = i % 64;
+        if rest > 0 {
+            rank += (self.word(i / 64) & ((1 << rest) - 1)).count_ones() as usize;
+        }
+        rank
+    }
+
+    /// Returns the number of zeros before bit `i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is greater than the length.
+    #[inline]
+    pub fn rank0(&self, i: usize) -> usize {
+        i - self.rank1(i)
+    }
+
+    /// Returns the position of the `k`th one (from 0), or `None` if there are not more than `k`
+    /// ones.
+    pub fn select1(&self, k: usize) -> Option<usize> {
+        if k >= self.count_ones() {
+            return None;
+        }
+        // The sampled blocks bound the binary search over the directory.
+        let lo = self.samples[k / SELECT_SAMPLE];
+        let hi = self
+            .samples
+            .get(k / SELECT_SAMPLE + 1)
+            .map_or(self.blocks.len() - 1, |&b| b + 1);
+        let block = lo + self.blocks[lo..hi].partition_point(|&ones| ones <= k) - 1;
+
+        let mut rest = k - self.blocks[block];
+        let mut w = block * (BLOCK_BITS / 64);
+        loop {
+            let mut word = self.word(w);
+            let ones = word.count_ones() as usize;
+            if rest < ones {
+                for _ in 0..rest {
+                    word &= word - 1;
+                }
+                return Some(w * 64 + word.trailing_zeros() as usize);
+            }
+            rest -= ones;
+            w += 1;
+        }
+    }
+
+    /// Serializes the bits as `len (u64 LE) | bytes`, the directory is rebuilt by
+    /// [`from_bytes`].
+    ///
+    /// [`from_bytes`]: struct.RankSelectBits.html#method.from_bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let bytes = &self.bits.as_bytes_ref()[..self.len.div_ceil(8)];
+        let mut dst = Vec::with_capacity(8 + bytes.len());
+        dst.push_u64_le(self.len as u64);
+        dst.push_slice(bytes);
+        dst
+    }
+}
+
+impl<'a> RankSelectBits<&'a [u8]> {
+    /// Deserializes bits written by [`to_bytes`], borrowing them from `src`.
+    ///
+    /// [`to_bytes`]: struct.RankSelectBits.html#method.to_bytes
+    pub fn from_bytes(src: &'a [u8]) -> Result<Self, ReadError> {
+        let mut r = SliceReader::new(src);
+        let len = r.read_u64_le()?;
+        let nbytes = usize::try_from(len.div_ceil(8)).unwrap_or(usize::MAX);
+        let bits = r.take(nbytes)?;
+        Ok(Self::with_len(bits, len as usize))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A deterministic bit pattern, with dense and sparse regions.
+    fn pattern(n: usize) -> Vec<u8> {
+        (0..n)
+            .map(|i| match i / 1000 {
+                0 => 0xff,
+                1 => 0x00,
+                2 => (i * 37 % 256) as u8,
+                _ => (i % 97 == 0) as u8,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rank_select() {
+        for n in [0, 1, 64, 100, 5000] {
+            let bytes = pattern(n);
+            let bits = RankSelectBits::new(bytes.as_slice());
+            let mut ones = Vec::new();
+            for i in 0..bits.len() {
+                assert_eq!(bits.rank1(i), ones.len(), "rank1({}) of {}", i, n);
+                if bits.get(i) {
+                    ones.push(i);
+                }
+            }
+            assert_eq!(bits.rank1(bits.len()), ones.len());
+            assert_eq!(bits.count_ones(), ones.len());
+            for (k, &pos) in ones.iter().enumerate() {
+                assert_eq!(bits.select1(k), Some(pos));
+            }
+            assert_eq!(bits.select1(ones.len()), None);
+        }
+    }
+
+    #[test]
+    fn test_rank_select_len() {
+        let bits = RankSelectBits::with_len(vec![0xff; 9], 70);
+        assert_eq!(bits.count_ones(), 70);
+        assert_eq!(bits.rank0(70), 0);
+        assert_eq!(bits.select1(69), Some(69));
+        assert_eq!(bits.select1(70), None);
+
+        let bytes = bits.to_bytes();
+        assert_eq!(bytes.len(), 8 + 9);
+        let restored = RankSelectBits::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.len(), 70);
+        assert_eq!(restored.count_ones(), 70);
+        assert!(RankSelectBits::from_bytes(&bytes[..16]).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_rank_out_of_range() {
+        RankSelectBits::new([0u8]).rank1(9);
+    }
+}
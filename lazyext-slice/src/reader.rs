@@ -0,0 +1,289 @@
+use crate::{AsBytesRef, ReadError};
+#[cfg(feature = "std")]
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4};
+
+macro_rules! read_x_suite {
+    ($([$ty:ty, $ty_literal: literal]), +$(,)?) => {
+        $(
+        paste! {
+            #[doc = concat!("Reads a ", $ty_literal, " in big-endian.")]
+            #[inline]
+            pub fn [<read_ $ty _be>](&mut self) -> Result<$ty, ReadError> {
+                self.read_array().map($ty::from_be_bytes)
+            }
+
+            #[doc = concat!("Reads a ", $ty_literal, " in little-endian.")]
+            #[inline]
+            pub fn [<read_ $ty _le>](&mut self) -> Result<$ty, ReadError> {
+                self.read_array().map($ty::from_le_bytes)
+            }
+
+            #[doc = concat!("Reads a ", $ty_literal, " in native-endian.")]
+            #[inline]
+            pub fn [<read_ $ty _ne>](&mut self) -> Result<$ty, ReadError> {
+                self.read_array().map($ty::from_ne_bytes)
+            }
+        }
+        )*
+    };
+}
+
+/// A cursor consuming a byte slice from the front, for zero-copy parsing.
+///
+/// Every method advances the cursor on success and leaves it unchanged on error, so a failed
+/// read can be retried or reported with its [`offset`].
+///
+/// It is returned by [`BytesExt::reader`], and can also be created with [`SliceReader::new`].
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::{BytesExt, ReadError};
+///
+/// let msg = b"LZX\x01\x00\x05hello;rest";
+/// let mut r = msg.reader();
+/// r.expect("LZX")?;
+/// assert_eq!(r.read_u8()?, 1);
+/// let len = r.read_u16_be()? as usize;
+/// assert_eq!(r.take(len)?, b"hello");
+/// assert_eq!(r.take_until(b';')?, b"");
+/// assert_eq!(r.remaining(), b"rest");
+/// assert!(r.read_u64_le().is_err());
+/// # Ok::<(), ReadError>(())
+/// ```
+///
+/// [`offset`]: struct.SliceReader.html#method.offset
+/// [`BytesExt::reader`]: trait.BytesExt.html#method.reader
+/// [`SliceReader::new`]: struct.SliceReader.html#method.new
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SliceReader<'a> {
+    src: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> SliceReader<'a> {
+    /// Creates a reader at the start of `src`.
+    #[inline]
+    pub const fn new(src: &'a [u8]) -> Self {
+        Self { src, offset: 0 }
+    }
+
+    /// Returns the number of bytes consumed so far.
+    #[inline]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the bytes which have not been consumed.
+    #[inline]
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.src[self.offset..]
+    }
+
+    /// Returns whether all the bytes have been consumed.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.offset == self.src.len()
+    }
+
+    /// Consumes and returns the next `n` bytes.
+    pub fn take(&mut self, n: usize) -> Result<&'a [u8], ReadError> {
+        let rest = self.remaining();
+        if rest.len() < n {
+            return Err(ReadError::UnexpectedEof {
+                offset: self.offset,
+                needed: n,
+                remaining: rest.len(),
+            });
+        }
+        self.offset += n;
+        Ok(&rest[..n])
+    }
+
+    /// Consumes the next `n` bytes.
+    #[inline]
+    pub fn skip(&mut self, n: usize) -> Result<(), ReadError> {
+        self.take(n).map(|_| ())
+    }
+
+    /// Consumes the bytes up to and including the next `delimiter`, and returns them without it.
+    pub fn take_until(&mut self, delimiter: u8) -> Result<&'a [u8], ReadError> {
+        let rest = self.remaining();
+        match rest.iter().position(|&b| b == delimiter) {
+            Some(i) => {
+                self.offset += i + 1;
+                Ok(&rest[..i])
+            }
+            None => Err(ReadError::DelimiterNotFound {
+                offset: self.offset,
+                delimiter,
+            }),
+        }
+    }
+
+    /// Consumes `prefix`, or fails if the remaining bytes do not begin with it.
+    pub fn expect(&mut self, prefix: impl AsBytesRef) -> Result<(), ReadError> {
+        let prefix = prefix.as_bytes_ref();
+        if self.remaining().starts_with(prefix) {
+            self.offset += prefix.len();
+            Ok(())
+        } else {
+            Err(ReadError::Mismatch {
+                offset: self.offset,
+            })
+        }
+    }
+
+    /// Consumes the next `N` bytes as an array.
+    #[inline]
+    pub fn read_array<const N: usize>(&mut self) -> Result<[u8; N], ReadError> {
+        self.take(N).map(|b| b.try_into().unwrap())
+    }
+
+    /// Reads a u8.
+    #[inline]
+    pub fn read_u8(&mut self) -> Result<u8, ReadError> {
+        self.read_array::<1>().map(|[b]| b)
+    }
+
+    /// Reads an IPv4 address in network order.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    pub fn read_ipv4_addr(&mut self) -> Result<Ipv4Addr, ReadError> {
+        self.read_array::<4>().map(Ipv4Addr::from)
+    }
+
+    /// Reads an IPv6 address in network order.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    pub fn read_ipv6_addr(&mut self) -> Result<Ipv6Addr, ReadError> {
+        self.read_array::<16>().map(Ipv6Addr::from)
+    }
+
+    /// Reads an IPv4 address followed by a big-endian port.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn read_socket_addr_v4(&mut self) -> Result<SocketAddrV4, ReadError> {
+        let [a, b, c, d, hi, lo] = self.read_array::<6>()?;
+        Ok(SocketAddrV4::new(
+            Ipv4Addr::new(a, b, c, d),
+            u16::from_be_bytes([hi, lo]),
+        ))
+    }
+
+    /// Reads an unsigned LEB128 varint, as written by `VecByteWriterExt::push_varint`.
+    pub fn read_varint(&mut self) -> Result<u64, ReadError> {
+        let rest = self.remaining();
+        let mut v = 0u64;
+        for (i, &b) in rest.iter().enumerate().take(10) {
+            let bits = (b & 0x7f) as u64;
+            if i == 9 && b > 1 {
+                break;
+            }
+            v |= bits << (7 * i);
+            if b & 0x80 == 0 {
+                self.offset += i + 1;
+                return Ok(v);
+            }
+        }
+        if rest.len() < 10 && rest.iter().all(|b| b & 0x80 != 0) {
+            return Err(ReadError::UnexpectedEof {
+                offset: self.offset,
+                needed: rest.len() + 1,
+                remaining: rest.len(),
+            });
+        }
+        Err(ReadError::InvalidVarint {
+            offset: self.offset,
+        })
+    }
+
+    read_x_suite!(
+        [u16, "u16"],
+        [u32, "u32"],
+        [usize, "usize"],
+        [u64, "u64"],
+        [u128, "u128"],
+        [i8, "i8"],
+        [i16, "i16"],
+        [i32, "i32"],
+        [i64, "i64"],
+        [isize, "isize"],
+        [i128, "i128"],
+        [f32, "f32"],
+        [f64, "f64"],
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_read_net_addrs() {
+        use std::net::{Ipv4Addr, Ipv6Addr};
+
+        let mut src = [0u8; 24];
+        src[..4].copy_from_slice(&[1, 2, 3, 4]);
+        src[20..].copy_from_slice(&[8, 8, 8, 8]);
+        let mut r = SliceReader::new(&src);
+        assert_eq!(r.read_ipv4_addr(), Ok(Ipv4Addr::new(1, 2, 3, 4)));
+        assert_eq!(r.read_ipv6_addr(), Ok(Ipv6Addr::UNSPECIFIED));
+        assert!(r.read_socket_addr_v4().is_err());
+        assert_eq!(r.offset(), 20);
+        assert_eq!(r.read_ipv4_addr(), Ok(Ipv4Addr::new(8, 8, 8, 8)));
+    }
+
+    #[test]
+    fn test_reader_errors_do_not_advance() {
+        let mut r = SliceReader::new(b"abc");
+        r.skip(1).unwrap();
+        assert_eq!(
+            r.take(3),
+            Err(ReadError::UnexpectedEof {
+                offset: 1,
+                needed: 3,
+                remaining: 2
+            })
+        );
+        assert_eq!(r.expect("x"), Err(ReadError::Mismatch { offset: 1 }));
+        assert_eq!(
+            r.take_until(b'x'),
+            Err(ReadError::DelimiterNotFound {
+                offset: 1,
+                delimiter: b'x'
+            })
+        );
+        assert_eq!(r.offset(), 1);
+        assert_eq!(r.read_u16_le().unwrap(), u16::from_le_bytes(*b"bc"));
+        assert!(r.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_read_varint() {
+        use crate::VecByteWriterExt;
+
+        let mut buf = Vec::new();
+        for v in [0, 1, 127, 128, 300, u64::MAX] {
+            buf.push_varint(v);
+        }
+        let mut r = SliceReader::new(&buf);
+        for v in [0, 1, 127, 128, 300, u64::MAX] {
+            assert_eq!(r.read_varint(), Ok(v));
+        }
+        assert!(r.is_empty());
+
+        let mut r = SliceReader::new(&[0x80, 0x80]);
+        assert!(matches!(
+            r.read_varint(),
+            Err(ReadError::UnexpectedEof { .. })
+        ));
+        let mut r = SliceReader::new(&[0xff; 10]);
+        assert_eq!(r.read_varint(), Err(ReadError::InvalidVarint { offset: 0 }));
+        assert_eq!(r.offset(), 0);
+    }
+}
@@ -0,0 +1,275 @@
+use crate::SliceReader;
+use core::iter::FusedIterator;
+use core::slice::ChunksExact;
+
+/// The position of a field within a fixed-width record, see [`Records::with_fields`].
+///
+/// [`Records::with_fields`]: struct.Records.html#method.with_fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldSpec {
+    /// The offset of the field from the start of the record.
+    pub offset: usize,
+    /// The length of the field.
+    pub len: usize,
+}
+
+impl FieldSpec {
+    /// Creates the spec of a field of `len` bytes at `offset`.
+    #[inline]
+    pub const fn new(offset: usize, len: usize) -> Self {
+        Self { offset, len }
+    }
+
+    /// Returns the offset just past the field.
+    #[inline]
+    pub const fn end(&self) -> usize {
+        self.offset + self.len
+    }
+}
+
+/// An iterator over the fixed-width records of a byte slice.
+///
+/// It is returned by [`BytesExt::records`]. Trailing bytes which do not make up a whole record
+/// are not yielded, see [`remainder`].
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::{BytesExt, FieldSpec};
+///
+/// // Records of a 4-byte name padded with spaces and a big-endian u16 quantity.
+/// let data = b"nut \x00\x07bolt\x01\x00";
+/// const FIELDS: [FieldSpec; 2] = [FieldSpec::new(0, 4), FieldSpec::new(4, 2)];
+///
+/// let mut totals = Vec::new();
+/// for record in data.records(6).with_fields(&FIELDS) {
+///     let name = record.field(0).trim_ascii_end();
+///     let qty = record.field_reader(1).read_u16_be().unwrap();
+///     totals.push((name, qty));
+/// }
+/// assert_eq!(totals, [(&b"nut"[..], 7), (&b"bolt"[..], 256)]);
+/// ```
+///
+/// [`BytesExt::records`]: trait.BytesExt.html#method.records
+/// [`remainder`]: struct.Records.html#method.remainder
+#[derive(Debug, Clone)]
+pub struct Records<'a> {
+    chunks: ChunksExact<'a, u8>,
+    record_len: usize,
+}
+
+impl<'a> Records<'a> {
+    /// # Panics
+    /// Panics if `record_len` is 0.
+    #[inline]
+    pub(crate) fn new(src: &'a [u8], record_len: usize) -> Self {
+        assert!(record_len > 0, "record_len must be greater than 0");
+        Self {
+            chunks: src.chunks_exact(record_len),
+            record_len,
+        }
+    }
+
+    /// Returns the length of the records.
+    #[inline]
+    pub const fn record_len(&self) -> usize {
+        self.record_len
+    }
+
+    /// Returns the trailing bytes which do not make up a whole record.
+    #[inline]
+    pub fn remainder(&self) -> &'a [u8] {
+        self.chunks.remainder()
+    }
+
+    /// Yields the records as [`Record`]s, whose fields are located by `fields`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a field does not fit in the record length.
+    ///
+    /// [`Record`]: struct.Record.html
+    pub fn with_fields<'s>(self, fields: &'s [FieldSpec]) -> FieldRecords<'a, 's> {
+        for (i, f) in fields.iter().enumerate() {
+            assert!(
+                f.offset
+                    .checked_add(f.len)
+                    .is_some_and(|end| end <= self.record_len),
+                "field {} ({:?}) does not fit in records of {} bytes",
+                i,
+                f,
+                self.record_len
+            );
+        }
+        FieldRecords {
+            records: self,
+            fields,
+        }
+    }
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = &'a [u8];
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.chunks.nth(n)
+    }
+}
+
+impl DoubleEndedIterator for Records<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.chunks.next_back()
+    }
+}
+
+impl ExactSizeIterator for Records<'_> {}
+
+impl FusedIterator for Records<'_> {}
+
+/// A fixed-width record with named positions of its fields, yielded by [`FieldRecords`].
+///
+/// [`FieldRecords`]: struct.FieldRecords.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Record<'a, 's> {
+    bytes: &'a [u8],
+    fields: &'s [FieldSpec],
+}
+
+impl<'a, 's> Record<'a, 's> {
+    /// Returns the bytes of the whole record.
+    #[inline]
+    pub const fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Returns the number of fields.
+    #[inline]
+    pub const fn num_fields(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Returns the bytes of the field `i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no field `i`.
+    #[inline]
+    pub fn field(&self, i: usize) -> &'a [u8] {
+        let f = self.fields[i];
+        &self.bytes[f.offset..f.end()]
+    }
+
+    /// Returns the bytes of the field `i`, or `None` if there is no such field.
+    #[inline]
+    pub fn get_field(&self, i: usize) -> Option<&'a [u8]> {
+        self.fields.get(i).map(|f| &self.bytes[f.offset..f.end()])
+    }
+
+    /// Returns a [`SliceReader`] over the field `i`, for typed reads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no field `i`.
+    ///
+    /// [`SliceReader`]: struct.SliceReader.html
+    #[inline]
+    pub fn field_reader(&self, i: usize) -> SliceReader<'a> {
+        SliceReader::new(self.field(i))
+    }
+
+    /// Returns an iterator over the bytes of all the fields, in the order of the specs.
+    #[inline]
+    pub fn fields(&self) -> impl Iterator<Item = &'a [u8]> + 's
+    where
+        'a: 's,
+    {
+        let bytes = self.bytes;
+        self.fields.iter().map(move |f| &bytes[f.offset..f.end()])
+    }
+}
+
+/// An iterator over the fixed-width records of a byte slice as [`Record`]s, returned by
+/// [`Records::with_fields`].
+///
+/// [`Record`]: struct.Record.html
+/// [`Records::with_fields`]: struct.Records.html#method.with_fields
+#[derive(Debug, Clone)]
+pub struct FieldRecords<'a, 's> {
+    records: Records<'a>,
+    fields: &'s [FieldSpec],
+}
+
+impl<'a> FieldRecords<'a, '_> {
+    /// Returns the trailing bytes which do not make up a whole record.
+    #[inline]
+    pub fn remainder(&self) -> &'a [u8] {
+        self.records.remainder()
+    }
+}
+
+impl<'a, 's> Iterator for FieldRecords<'a, 's> {
+    type Item = Record<'a, 's>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let fields = self.fields;
+        self.records.next().map(|bytes| Record { bytes, fields })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.records.size_hint()
+    }
+}
+
+impl ExactSizeIterator for FieldRecords<'_, '_> {}
+
+impl FusedIterator for FieldRecords<'_, '_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records() {
+        let mut records = Records::new(b"aabbccd", 2);
+        assert_eq!(records.len(), 3);
+        assert_eq!(records.next_back(), Some(&b"cc"[..]));
+        assert_eq!(records.collect::<Vec<_>>(), [b"aa", b"bb"]);
+        assert_eq!(Records::new(b"aabbccd", 2).remainder(), b"d");
+        assert_eq!(Records::new(b"", 3).next(), None);
+    }
+
+    #[test]
+    fn test_record_fields() {
+        let fields = [FieldSpec::new(2, 2), FieldSpec::new(0, 1)];
+        let mut records = Records::new(b"abcdefghi", 4).with_fields(&fields);
+        let r = records.next().unwrap();
+        assert_eq!(r.as_bytes(), b"abcd");
+        assert_eq!(r.num_fields(), 2);
+        assert_eq!(r.field(0), b"cd");
+        assert_eq!(r.get_field(2), None);
+        assert_eq!(r.fields().collect::<Vec<_>>(), [&b"cd"[..], b"a"]);
+        assert_eq!(records.next().unwrap().field(1), b"e");
+        assert!(records.next().is_none());
+        assert_eq!(records.remainder(), b"i");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_field_out_of_record() {
+        Records::new(b"abcd", 4).with_fields(&[FieldSpec::new(3, 2)]);
+    }
+}
@@ -0,0 +1,180 @@
+//! A polynomial rolling hash for sliding windows.
+
+const BASE: u64 = 0x100_0000_01b3;
+
+/// The inverse of `BASE` modulo 2^64 (`BASE` is odd), computed with Newton's iteration.
+const BASE_INV: u64 = {
+    let mut inv = BASE;
+    let mut i = 0;
+    while i < 6 {
+        inv = inv.wrapping_mul(2u64.wrapping_sub(BASE.wrapping_mul(inv)));
+        i += 1;
+    }
+    inv
+};
+
+/// A polynomial (Rabin-Karp) rolling hash over a sliding window of bytes.
+///
+/// Bytes enter the window with [`push`] and leave it, oldest first, with [`pop`], so sliding-window
+/// dedup and search can be built on top of it. Two windows with the same content always have the
+/// same [`hash`], whatever the order of pushes and pops which built them.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::RollingHash;
+///
+/// let mut window = RollingHash::new();
+/// for b in b"xabc" {
+///     window.push(*b);
+/// }
+/// window.pop(b'x');
+///
+/// let mut other = RollingHash::new();
+/// for b in b"abc" {
+///     other.push(*b);
+/// }
+/// assert_eq!(window.hash(), other.hash());
+/// ```
+///
+/// [`push`]: struct.RollingHash.html#method.push
+/// [`pop`]: struct.RollingHash.html#method.pop
+/// [`hash`]: struct.RollingHash.html#method.hash
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RollingHash {
+    hash: u64,
+    /// `BASE` to the power of `len`.
+    pow: u64,
+    len: usize,
+}
+
+impl Default for RollingHash {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RollingHash {
+    /// Creates the hash of an empty window.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            hash: 0,
+            pow: 1,
+            len: 0,
+        }
+    }
+
+    /// Creates the hash of a window holding `src`.
+    pub fn from_bytes(src: &[u8]) -> Self {
+        let mut h = Self::new();
+        for b in src {
+            h.push(*b);
+        }
+        h
+    }
+
+    /// Appends a byte to the window.
+    #[inline]
+    pub fn push(&mut self, byte: u8) {
+        self.hash = self.hash.wrapping_mul(BASE).wrapping_add(byte as u64 + 1);
+        self.pow = self.pow.wrapping_mul(BASE);
+        self.len += 1;
+    }
+
+    /// Removes the oldest byte of the window, which must be `byte`.
+    ///
+    /// # Panics
+    /// Panics if the window is empty.
+    #[inline]
+    pub fn pop(&mut self, byte: u8) {
+        assert!(self.len > 0, "pop from an empty RollingHash");
+        self.pow = self.pow.wrapping_mul(BASE_INV);
+        self.hash = self
+            .hash
+            .wrapping_sub((byte as u64 + 1).wrapping_mul(self.pow));
+        self.len -= 1;
+    }
+
+    /// Pushes `incoming` and pops `outgoing`, keeping the length of the window.
+    #[inline]
+    pub fn roll(&mut self, outgoing: u8, incoming: u8) {
+        self.push(incoming);
+        self.pop(outgoing);
+    }
+
+    /// Returns the hash of the window.
+    #[inline]
+    pub const fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Returns the number of bytes in the window.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the window is empty.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Rabin-Karp search, returns the index of the first occurrence of `needle` in `src`.
+pub(crate) fn find_rabin_karp(src: &[u8], needle: &[u8]) -> Option<usize> {
+    let n = needle.len();
+    if n > src.len() {
+        return None;
+    }
+
+    let target = RollingHash::from_bytes(needle).hash();
+    let mut window = RollingHash::from_bytes(&src[..n]);
+    let mut i = 0;
+    loop {
+        if window.hash() == target && &src[i..i + n] == needle {
+            return Some(i);
+        }
+        if i + n == src.len() {
+            return None;
+        }
+        window.roll(src[i], src[i + n]);
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BytesExt;
+
+    #[test]
+    fn test_base_inv() {
+        assert_eq!(BASE.wrapping_mul(BASE_INV), 1);
+    }
+
+    #[test]
+    fn test_rolling_window() {
+        let src = b"the quick brown fox jumps over the lazy dog";
+        let mut window = RollingHash::from_bytes(&src[..8]);
+        for i in 8..src.len() {
+            window.roll(src[i - 8], src[i]);
+            assert_eq!(window, RollingHash::from_bytes(&src[i - 7..=i]));
+        }
+        for b in &src[src.len() - 8..] {
+            window.pop(*b);
+        }
+        assert_eq!(window, RollingHash::new());
+    }
+
+    #[test]
+    fn test_find_subslice_rabin_karp() {
+        let src = b"abracadabra";
+        assert_eq!(src.find_subslice_rabin_karp("cad"), Some(4));
+        assert_eq!(src.find_subslice_rabin_karp("abra"), Some(0));
+        assert_eq!(src.find_subslice_rabin_karp("bra!"), None);
+        assert_eq!(src.find_subslice_rabin_karp(""), Some(0));
+        assert_eq!(b"ab".find_subslice_rabin_karp("abc"), None);
+    }
+}
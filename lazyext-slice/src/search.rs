@@ -0,0 +1,66 @@
+/// Returns the start of the last occurrence of `needle` lying entirely within `src[..end]`,
+/// searching backwards with the Boyer-Moore-Horspool algorithm.
+pub(crate) fn rfind_horspool(src: &[u8], needle: &[u8], end: usize) -> Option<usize> {
+    let src = &src[..end];
+    let m = needle.len();
+    if m == 0 {
+        return Some(src.len());
+    }
+    if m > src.len() {
+        return None;
+    }
+
+    // Scanning backwards, the window is shifted by the distance from the start of the needle to the
+    // first later occurrence of the byte under the start of the window.
+    let mut shifts = [m; 256];
+    for (i, &b) in needle.iter().enumerate().skip(1).rev() {
+        shifts[b as usize] = i;
+    }
+
+    let mut pos = src.len() - m;
+    loop {
+        if &src[pos..pos + m] == needle {
+            return Some(pos);
+        }
+        pos = pos.checked_sub(shifts[src[pos] as usize])?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive(src: &[u8], needle: &[u8]) -> Option<usize> {
+        if needle.len() > src.len() {
+            return None;
+        }
+        (0..=src.len() - needle.len())
+            .rev()
+            .find(|&i| src[i..].starts_with(needle))
+    }
+
+    #[test]
+    fn test_rfind_horspool() {
+        let src = b"abcabcabdabcab";
+        for needle in [
+            &b"abc"[..],
+            b"ab",
+            b"cab",
+            b"d",
+            b"abd",
+            b"x",
+            b"abcabcabdabcab",
+            b"bcabd",
+        ] {
+            assert_eq!(
+                rfind_horspool(src, needle, src.len()),
+                naive(src, needle),
+                "{:?}",
+                needle
+            );
+        }
+        assert_eq!(rfind_horspool(src, b"abc", 11), Some(3));
+        assert_eq!(rfind_horspool(b"ab", b"abc", 2), None);
+        assert_eq!(rfind_horspool(b"ab", b"", 2), Some(2));
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{AsBytesMutRef, AsBytesRef, BytesExt, BytesMutExt};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+use zeroize::Zeroize;
+
+/// An owned byte buffer for key material, which zeroes its memory when dropped.
+///
+/// It implements [`BytesExt`] and [`BytesMutExt`], so secrets can go through the same buffer
+/// utilities as everything else. `Debug` never prints the content.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::{BytesExt, SecretBytes};
+///
+/// let key = SecretBytes::from(vec![0xde, 0xad, 0xbe, 0xef]);
+/// assert!(key.has_prefix([0xde]));
+/// assert_eq!(format!("{:?}", key), "SecretBytes([REDACTED; 4])");
+/// ```
+///
+/// [`BytesExt`]: trait.BytesExt.html
+/// [`BytesMutExt`]: trait.BytesMutExt.html
+pub struct SecretBytes(Box<[u8]>);
+
+impl SecretBytes {
+    /// Copies the secret from a slice. The caller is responsible for zeroing the source.
+    pub fn from_slice(src: &[u8]) -> Self {
+        Self(Box::from(src))
+    }
+
+    /// Returns the number of bytes of the secret.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether the secret is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Takes the vec without leaving copies behind: if shrinking it to fit would reallocate,
+/// the bytes are copied and the whole capacity of the vec is zeroed instead.
+impl From<Vec<u8>> for SecretBytes {
+    fn from(mut src: Vec<u8>) -> Self {
+        if src.len() == src.capacity() {
+            return Self(src.into_boxed_slice());
+        }
+
+        let secret = Self::from_slice(&src);
+        Zeroize::zeroize(&mut src);
+        secret
+    }
+}
+
+impl From<Box<[u8]>> for SecretBytes {
+    fn from(src: Box<[u8]>) -> Self {
+        Self(src)
+    }
+}
+
+impl AsBytesRef for SecretBytes {
+    #[inline]
+    fn as_bytes_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsBytesMutRef for SecretBytes {
+    #[inline]
+    fn as_bytes_mut_ref(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+impl BytesExt for SecretBytes {}
+
+impl BytesMutExt for SecretBytes {}
+
+impl Drop for SecretBytes {
+    fn drop(&mut self) {
+        Zeroize::zeroize(&mut self.0);
+    }
+}
+
+impl fmt::Debug for SecretBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretBytes([REDACTED; {}])", self.0.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zeroize() {
+        let mut key = SecretBytes::from_slice(&[1, 2, 3, 4]);
+        key.as_bytes_mut_ref()[0] = 5;
+        assert_eq!(key.as_bytes_ref(), &[5, 2, 3, 4]);
+        BytesMutExt::zeroize(&mut key);
+        assert_eq!(key.as_bytes_ref(), &[0; 4]);
+
+        let mut buf = [7u8; 8];
+        BytesMutExt::zeroize(&mut buf);
+        assert_eq!(buf, [0; 8]);
+    }
+
+    #[test]
+    fn test_from_vec() {
+        let mut v = Vec::with_capacity(16);
+        v.extend_from_slice(b"secret");
+        let key = SecretBytes::from(v);
+        assert_eq!(key.len(), 6);
+        assert!(key.bytes_eq("secret"));
+    }
+}
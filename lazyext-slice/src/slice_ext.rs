@@ -1,7 +1,12 @@
+#[cfg(feature = "alloc")]
 use crate::BytesExt;
+use crate::pod::{cast_slice, cast_slice_mut};
+use crate::{GroupBy, Pod, Runs};
 #[cfg(feature = "alloc")]
 use alloc::borrow::Cow;
 use core::mem;
+#[cfg(feature = "half")]
+use half::{bf16, f16};
 use core::slice::{from_raw_parts, from_raw_parts_mut};
 
 #[cfg(feature = "alloc")]
@@ -32,51 +37,44 @@ macro_rules! impl_to_x_vec_suite {
             #[doc = concat!("Copy ", $from_literal, " slice to ", $to_literal, " vec in big-endian")]
             #[inline]
             fn [<to_be_ $ty _vec>](&self) -> Vec<$ty> {
-                self.to_u8_slice().[<to_be_ $ty _vec>]()
+                self.cast_bytes().[<to_be_ $ty _vec>]()
             }
 
             #[doc = concat!("Copy ", $from_literal, " slice to ", $to_literal, " vec in little-endian")]
             #[inline]
             fn [<to_le_ $ty _vec>](&self) -> Vec<$ty> {
-                self.to_u8_slice().[<to_le_ $ty _vec>]()
+                self.cast_bytes().[<to_le_ $ty _vec>]()
             }
 
             #[doc = concat!("Copy ", $from_literal, " slice to ", $to_literal, " vec in native-endian")]
             #[inline]
             fn [<to_ne_ $ty _vec>](&self) -> Vec<$ty> {
-                self.to_u8_slice().[<to_ne_ $ty _vec>]()
+                self.cast_bytes().[<to_ne_ $ty _vec>]()
             }
         }
         )*
     };
 }
 
-macro_rules! impl_to_x_slice_suite_in {
-    ($this: ident, $builder:ident, $trait:tt::$fn:tt::$ptr:tt, $raw_ptr: ident, $ty: ty) => {{
-        let src = $trait::$fn($this);
-        let src_ptr = src.$ptr();
-        let len = src.len() * mem::size_of::<$ty>();
-        unsafe { $builder(src_ptr as *$raw_ptr $ty, len) }
-    }};
-}
-
 macro_rules! impl_to_x_slice_suite {
-    ($from_literal: literal, $builder:ident, $trait:tt::$fn:tt::$ptr:tt, $raw_ptr: ident, $([$ty: ty, $ty_literal: literal]), +$(,)?) => {
+    ($from_literal: literal, $([$ty: ty, $ty_literal: literal]), +$(,)?) => {
         $(
         paste! {
             #[doc = concat!("Convert ", $from_literal, " slice to ", $ty_literal, " slice in native-endian(zero-copy)")]
+            #[deprecated(since = "0.0.3", note = "renamed to `cast_to`, to tell it apart from the value conversions")]
             fn [<to_ $ty _slice>](&self) -> &[$ty] {
-                impl_to_x_slice_suite_in!(self, $builder, $trait::$fn::$ptr, $raw_ptr, $ty)
+                self.cast_to()
             }
         }
         )*
     };
-    (mut $from_literal: literal, $builder:ident, $trait:tt::$fn:tt::$ptr:tt, $raw_ptr: ident, $([$ty: ty, $ty_literal: literal]), +$(,)?) => {
+    (mut $from_literal: literal, $([$ty: ty, $ty_literal: literal]), +$(,)?) => {
         $(
         paste! {
             #[doc = concat!("Convert mutable ", $from_literal, " slice to mutable", $ty_literal, " slice in native-endian(zero-copy)")]
+            #[deprecated(since = "0.0.3", note = "renamed to `cast_to_mut`, to tell it apart from the value conversions")]
             fn [<to_ $ty _slice_mut>](&mut self) -> &[$ty] {
-                impl_to_x_slice_suite_in!(self, $builder, $trait::$fn::$ptr, $raw_ptr, $ty)
+                self.cast_to_mut()
             }
         }
         )*
@@ -88,9 +86,9 @@ macro_rules! impl_to_x_slice_lossy_suite {
     ($from_literal: literal, $trait:ident, $([$ty:ty, $ty_literal: literal]), +$(,)?) => {
         $(
         paste! {
-            #[doc = concat!("Convert ", $from_literal, " slice to Cow<'_, [", $ty_literal, "]> slice in native-endian(zero-copy)")]
+            #[doc = concat!("Convert ", $from_literal, " slice to `Cow<'_, [", $ty_literal, "]>` slice in native-endian(zero-copy)")]
             fn [<to_ $ty _slice_lossy>](&self) -> Cow<'_, [$ty]> {
-                Cow::Borrowed($trait::[<to_ $ty _slice>](self))
+                Cow::Borrowed($trait::cast_to(self))
             }
         }
         )*
@@ -100,7 +98,7 @@ macro_rules! impl_to_x_slice_lossy_suite {
 macro_rules! impl_traits_for_slice_type {
     ($ext_trait: ident, $as_trait: ident, $ty: tt) => {
         paste! {
-            impl<'a> $as_trait for &'a [$ty] {
+            impl $as_trait for &[$ty] {
                 fn [<as_ $ty _slice>](&self) -> &[$ty] {
                     self
                 }
@@ -113,7 +111,7 @@ macro_rules! impl_traits_for_slice_type {
             }
         }
 
-        impl<'a> $ext_trait for &'a [$ty] {}
+        impl $ext_trait for &[$ty] {}
 
         impl<const N: usize> $ext_trait for [$ty; N] {}
     };
@@ -122,13 +120,13 @@ macro_rules! impl_traits_for_slice_type {
 macro_rules! impl_traits_for_slice_mut_type {
     ($ext_trait: ident, $mut_ext_trait: ident, $as_trait: ident, $as_mut_trait: ident, $ty: tt) => {
         paste! {
-            impl<'a> $as_trait for &'a mut [$ty] {
+            impl $as_trait for &mut [$ty] {
                 fn [<as_ $ty _slice>](&self) -> &[$ty] {
                     self
                 }
             }
 
-            impl<'a> $as_mut_trait for &'a mut [$ty] {
+            impl $as_mut_trait for &mut [$ty] {
                 fn [<as_ $ty _slice_mut>](&mut self) -> &mut [$ty] {
                     self
                 }
@@ -141,9 +139,9 @@ macro_rules! impl_traits_for_slice_mut_type {
             }
         }
 
-        impl<'a> $ext_trait for &'a mut [$ty] {}
+        impl $ext_trait for &mut [$ty] {}
 
-        impl<'a> $mut_ext_trait for &'a mut [$ty] {}
+        impl $mut_ext_trait for &mut [$ty] {}
 
         impl<const N: usize> $mut_ext_trait for [$ty; N] {}
     };
@@ -153,7 +151,7 @@ macro_rules! impl_traits_for_slice_mut_type {
 macro_rules! impl_traits_for_vec_type {
     ($ext_trait: ident, $as_trait: ident, $ty: tt) => {
         paste! {
-            impl<'a> $as_trait for &'a Vec<$ty> {
+            impl $as_trait for &Vec<$ty> {
                 fn [<as_ $ty _slice>](&self) -> &[$ty] {
                     self.as_slice()
                 }
@@ -166,7 +164,7 @@ macro_rules! impl_traits_for_vec_type {
             }
         }
 
-        impl<'a> $ext_trait for &'a Vec<$ty> {}
+        impl $ext_trait for &Vec<$ty> {}
 
         impl $ext_trait for Vec<$ty> {}
     };
@@ -176,13 +174,13 @@ macro_rules! impl_traits_for_vec_type {
 macro_rules! impl_traits_for_vec_mut_type {
     ($ext_trait: ident, $mut_ext_trait: ident, $as_trait: ident, $as_mut_trait: ident, $ty: tt) => {
         paste! {
-            impl<'a> $as_trait for &'a mut Vec<$ty> {
+            impl $as_trait for &mut Vec<$ty> {
                 fn [<as_ $ty _slice>](&self) -> &[$ty] {
                     self.as_slice()
                 }
             }
 
-            impl<'a> $as_mut_trait for &'a mut Vec<$ty> {
+            impl $as_mut_trait for &mut Vec<$ty> {
                 fn [<as_ $ty _slice_mut>](&mut self) -> &mut [$ty] {
                     self.as_mut_slice()
                 }
@@ -195,9 +193,9 @@ macro_rules! impl_traits_for_vec_mut_type {
             }
         }
 
-        impl<'a> $ext_trait for &'a mut Vec<$ty> {}
+        impl $ext_trait for &mut Vec<$ty> {}
 
-        impl<'a> $mut_ext_trait for &'a mut Vec<$ty> {}
+        impl $mut_ext_trait for &mut Vec<$ty> {}
 
         impl $mut_ext_trait for Vec<$ty> {}
     };
@@ -207,7 +205,7 @@ macro_rules! impl_traits_for_vec_mut_type {
 macro_rules! impl_traits_for_box_type {
     ($ext_trait: ident, $as_trait: ident, $ty: tt) => {
         paste! {
-            impl<'a> $as_trait for &'a Box<[$ty]> {
+            impl $as_trait for &Box<[$ty]> {
                 fn [<as_ $ty _slice>](&self) -> &[$ty] {
                     self.as_ref()
                 }
@@ -220,7 +218,7 @@ macro_rules! impl_traits_for_box_type {
             }
         }
 
-        impl<'a> $ext_trait for &'a Box<[$ty]> {}
+        impl $ext_trait for &Box<[$ty]> {}
         impl $ext_trait for Box<[$ty]> {}
     };
 }
@@ -229,13 +227,13 @@ macro_rules! impl_traits_for_box_type {
 macro_rules! impl_traits_for_box_mut_type {
     ($ext_trait: ident, $mut_ext_trait: ident, $as_trait: ident, $as_mut_trait: ident, $ty: tt) => {
         paste! {
-            impl<'a> $as_trait for &'a mut Box<[$ty]> {
+            impl $as_trait for &mut Box<[$ty]> {
                 fn [<as_ $ty _slice>](&self) -> &[$ty] {
                     self.as_ref()
                 }
             }
 
-            impl<'a> $as_mut_trait for &'a mut Box<[$ty]> {
+            impl $as_mut_trait for &mut Box<[$ty]> {
                 fn [<as_ $ty _slice_mut>](&mut self) -> &mut [$ty] {
                     self.as_mut()
                 }
@@ -248,16 +246,251 @@ macro_rules! impl_traits_for_box_mut_type {
             }
         }
 
-        impl<'a> $ext_trait for &'a mut Box<[$ty]> {}
+        impl $ext_trait for &mut Box<[$ty]> {}
 
-        impl<'a> $mut_ext_trait for &'a mut Box<[$ty]> {}
+        impl $mut_ext_trait for &mut Box<[$ty]> {}
         impl $mut_ext_trait for Box<[$ty]> {}
     };
 }
 
+/// Float types which fixed-point integers can be decoded into, see `fixed_to_float` on the
+/// signed integer slice traits.
+pub trait FromFixed: Copy {
+    /// Converts the fixed-point value `v` with `frac_bits` fractional bits.
+    fn from_fixed(v: i64, frac_bits: u32) -> Self;
+}
+
+#[inline]
+fn fixed_scale(frac_bits: u32) -> f64 {
+    assert!(frac_bits < 64, "too many fractional bits: {}", frac_bits);
+    1.0 / (1u64 << frac_bits) as f64
+}
+
+impl FromFixed for f32 {
+    #[inline]
+    fn from_fixed(v: i64, frac_bits: u32) -> Self {
+        (v as f64 * fixed_scale(frac_bits)) as f32
+    }
+}
+
+impl FromFixed for f64 {
+    #[inline]
+    fn from_fixed(v: i64, frac_bits: u32) -> Self {
+        v as f64 * fixed_scale(frac_bits)
+    }
+}
+
+#[allow(unused_macros)]
+macro_rules! impl_fixed_to_float {
+    ($trait:tt::$fn:tt) => {
+        /// Decodes the values as fixed-point numbers with `frac_bits` fractional bits,
+        /// e.g. `frac_bits = 8` maps `256` to `1.0`.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `frac_bits` is 64 or more.
+        #[cfg(feature = "alloc")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+        fn fixed_to_float<F: FromFixed>(&self, frac_bits: u32) -> Vec<F> {
+            assert!(frac_bits < 64, "too many fractional bits: {}", frac_bits);
+            $trait::$fn(self)
+                .iter().map(|v| F::from_fixed(*v as i64, frac_bits)).collect()
+        }
+    };
+}
+
+// Only the signed integer slices get the fixed-point decoders.
+macro_rules! impl_fixed_suites {
+    ($trait:tt::$fn:tt, i16) => {
+        impl_fixed_to_float!($trait::$fn);
+
+        /// Decodes Q15 samples (Q1.15, as used by 16-bit audio and DSP) into `[-1.0, 1.0)`.
+        #[cfg(feature = "alloc")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+        #[inline]
+        fn to_q15_f32_vec(&self) -> Vec<f32> {
+            self.fixed_to_float(15)
+        }
+    };
+    ($trait:tt::$fn:tt, i32) => {
+        impl_fixed_to_float!($trait::$fn);
+
+        /// Decodes Q31 samples (Q1.31) into `[-1.0, 1.0)`.
+        #[cfg(feature = "alloc")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+        #[inline]
+        fn to_q31_f64_vec(&self) -> Vec<f64> {
+            self.fixed_to_float(31)
+        }
+    };
+    ($trait:tt::$fn:tt, i8) => {
+        impl_fixed_to_float!($trait::$fn);
+    };
+    ($trait:tt::$fn:tt, i64) => {
+        impl_fixed_to_float!($trait::$fn);
+    };
+    ($trait:tt::$fn:tt, $typ:tt) => {};
+}
+
+/// `as` conversion behind the `convert_to_<ty>_vec` methods.
+#[cfg(feature = "alloc")]
+trait ConvertTo<T> {
+    fn convert_to(self) -> T;
+}
+
+#[cfg(feature = "alloc")]
+macro_rules! impl_convert_to {
+    (@from $src:ty => $($dst:ty), +) => {
+        $(
+        impl ConvertTo<$dst> for $src {
+            #[inline]
+            fn convert_to(self) -> $dst {
+                self as $dst
+            }
+        }
+        )*
+    };
+    ($($src:ty), +$(,)?) => {
+        $(
+        impl_convert_to!(@from $src => u8, u16, u32, usize, u64, u128, i8, i16, i32, isize, i64, i128, f32, f64);
+        )*
+    };
+}
+
+#[cfg(feature = "alloc")]
+impl_convert_to!(u16, u32, usize, u64, u128, i8, i16, i32, isize, i64, i128, f32, f64);
+
+#[cfg(all(feature = "alloc", feature = "half"))]
+macro_rules! impl_convert_to_half {
+    ($($src:ty), +$(,)?) => {
+        $(
+        impl<T> ConvertTo<T> for $src
+        where
+            f32: ConvertTo<T>,
+        {
+            #[inline]
+            fn convert_to(self) -> T {
+                self.to_f32().convert_to()
+            }
+        }
+        )*
+    };
+}
+
+#[cfg(all(feature = "alloc", feature = "half"))]
+impl_convert_to_half!(f16, bf16);
+
+#[cfg(feature = "alloc")]
+macro_rules! impl_convert_to_x_vec_suite {
+    ($trait:tt::$fn:tt, $from_literal: literal, $([$ty: ty, $to_literal: literal]), +$(,)?) => {
+        $(
+        paste! {
+            #[doc = concat!("Converts the ", $from_literal, " values to ", $to_literal, "s, like an `as` cast.")]
+            ///
+            /// Integers are truncated or sign-extended, floats are truncated toward zero and
+            /// saturate (NaN becomes 0). See the `to_*_clamped_vec` methods for saturating
+            /// integer conversions, and `cast_to` for reinterpreting the memory instead.
+            fn [<convert_to_ $ty _vec>](&self) -> Vec<$ty> {
+                $trait::$fn(self).iter().map(|v| ConvertTo::<$ty>::convert_to(*v)).collect()
+            }
+        }
+        )*
+    };
+}
+
+#[cfg(feature = "alloc")]
+/// Saturating value conversion behind the `to_<ty>_clamped_vec` methods.
+trait ClampTo<T> {
+    fn clamp_to(self) -> T;
+}
+
+#[cfg(feature = "alloc")]
+macro_rules! impl_clamp_to {
+    (@int $src:ty => $($dst:ty), +) => {
+        $(
+        impl ClampTo<$dst> for $src {
+            #[inline]
+            fn clamp_to(self) -> $dst {
+                match <$dst>::try_from(self) {
+                    Ok(v) => v,
+                    Err(_) if self > 0 => <$dst>::MAX,
+                    Err(_) => <$dst>::MIN,
+                }
+            }
+        }
+        )*
+    };
+    (@float $src:ty => $($dst:ty), +) => {
+        $(
+        impl ClampTo<$dst> for $src {
+            #[inline]
+            fn clamp_to(self) -> $dst {
+                // `as` saturates and maps NaN to 0, it only needs the rounding.
+                if self >= 0.0 {
+                    (self + 0.5) as $dst
+                } else {
+                    (self - 0.5) as $dst
+                }
+            }
+        }
+        )*
+    };
+    ($($src:ty), +$(,)?) => {
+        $(
+        impl_clamp_to!(@int $src => u8, i8, u16, i16, u32, i32);
+        )*
+    };
+}
+
+#[cfg(feature = "alloc")]
+impl_clamp_to!(u16, u32, usize, u64, u128, i8, i16, i32, isize, i64, i128);
+#[cfg(feature = "alloc")]
+impl_clamp_to!(@float f32 => u8, i8, u16, i16, u32, i32);
+#[cfg(feature = "alloc")]
+impl_clamp_to!(@float f64 => u8, i8, u16, i16, u32, i32);
+
+#[cfg(all(feature = "alloc", feature = "half"))]
+macro_rules! impl_clamp_to_half {
+    ($($src:ty), +$(,)?) => {
+        $(
+        impl<T> ClampTo<T> for $src
+        where
+            f32: ClampTo<T>,
+        {
+            #[inline]
+            fn clamp_to(self) -> T {
+                self.to_f32().clamp_to()
+            }
+        }
+        )*
+    };
+}
+
+#[cfg(all(feature = "alloc", feature = "half"))]
+impl_clamp_to_half!(f16, bf16);
+
+#[cfg(feature = "alloc")]
+macro_rules! impl_to_x_clamped_vec_suite {
+    ($trait:tt::$fn:tt, $from_literal: literal, $([$ty: ty, $to_literal: literal]), +$(,)?) => {
+        $(
+        paste! {
+            #[doc = concat!("Converts the ", $from_literal, " values to ", $to_literal, "s, saturating at the bounds of ", $to_literal, ".")]
+            ///
+            /// Unlike the `to_*_slice` and `to_*_vec` methods, which reinterpret the bytes, this
+            /// converts each value. Floats are rounded to the nearest integer (ties away from zero)
+            /// and NaN becomes 0.
+            fn [<to_ $ty _clamped_vec>](&self) -> Vec<$ty> {
+                $trait::$fn(self).iter().map(|v| ClampTo::<$ty>::clamp_to(*v)).collect()
+            }
+        }
+        )*
+    };
+}
+
 macro_rules! declare_as_x_slice_trait {
-    ($([$ext_trait_name: ident, $as_trait_name: ident, $fn_name: ident, $typ: tt, $typ_literal: literal, $([$convert_typ: ty, $convert_typ_literal: literal]), +$(,)?]), +$(,)?) => {
+    ($([$ext_trait_name: ident, $as_trait_name: ident, $fn_name: ident, $typ: tt, $typ_literal: literal, $feature: literal, $([$convert_typ: ty, $convert_typ_literal: literal]), +$(,)?]), +$(,)?) => {
         $(
+        cfg_feature!($feature, {
         #[doc = concat!("Converts to `&'a [", $typ_literal, "]`")]
         pub trait $as_trait_name {
             #[doc = concat!("Converts to ", $typ_literal, " slice")]
@@ -269,6 +502,33 @@ macro_rules! declare_as_x_slice_trait {
 
             impl_psfix_suites!($as_trait_name::$fn_name, $typ, $typ_literal);
 
+            impl_group_suites!($as_trait_name::$fn_name, $typ);
+
+            impl_split_suites!($as_trait_name::$fn_name, $typ);
+
+            impl_get_suites!($as_trait_name::$fn_name, $typ);
+
+            impl_gather_suites!($as_trait_name::$fn_name, $typ);
+
+            impl_map_suites!($as_trait_name::$fn_name, $typ);
+
+            impl_rand_suites!($as_trait_name::$fn_name, $typ);
+
+            impl_fixed_suites!($as_trait_name::$fn_name, $typ);
+
+            cfg_feature!("alloc", {
+                impl_to_x_clamped_vec_suite!(
+                    $as_trait_name::$fn_name,
+                    $typ_literal,
+                    [u8, "u8"],
+                    [i8, "i8"],
+                    [u16, "u16"],
+                    [i16, "i16"],
+                    [u32, "u32"],
+                    [i32, "i32"],
+                );
+            });
+
             #[doc = concat!("Copy ", $typ_literal, " slice to u8 vec in big-endian")]
             #[cfg(feature = "alloc")]
             fn to_be_u8_vec(&self) -> Vec<u8> {
@@ -287,28 +547,55 @@ macro_rules! declare_as_x_slice_trait {
                 impl_x_to_u8_vec!(self, $as_trait_name::$fn_name, $typ::to_ne_bytes)
             }
 
-            cfg_alloc!(impl_to_x_vec_suite!($typ_literal, $([$convert_typ, $convert_typ_literal],)*););
+            cfg_feature!("alloc", { impl_to_x_vec_suite!($typ_literal, $([$convert_typ, $convert_typ_literal],)*); });
 
-            #[doc = concat!("convert u16 slice to u8 slice")]
-            fn to_u8_slice(&self) -> &[u8] {
+            #[doc = concat!("Reinterprets the ", $typ_literal, " slice as its bytes in native-endian (zero-copy).")]
+            fn cast_bytes(&self) -> &[u8] {
                 impl_x_to_u8_slice!(self, $typ, $as_trait_name::$fn_name::as_ptr, from_raw_parts, const)
             }
 
-            cfg_alloc!(impl_to_x_slice_lossy_suite!($typ_literal, $ext_trait_name, [u8, "u8"], $([$convert_typ, $convert_typ_literal],)*););
+            #[doc = concat!("Reinterprets the memory of the ", $typ_literal, " slice as a slice of `T` in native-endian (zero-copy).")]
+            ///
+            /// This does not convert the values, see the `convert_to_*_vec` methods for that.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the byte length is not a multiple of the size of `T`, or if the slice is
+            /// not aligned for `T`.
+            #[inline]
+            fn cast_to<T: Pod>(&self) -> &[T] {
+                cast_slice(self.cast_bytes())
+            }
+
+            #[doc = concat!("convert ", $typ_literal, " slice to u8 slice")]
+            #[deprecated(since = "0.0.3", note = "renamed to `cast_bytes`, to tell it apart from the value conversions")]
+            fn to_u8_slice(&self) -> &[u8] {
+                self.cast_bytes()
+            }
+
+            cfg_feature!("alloc", { impl_to_x_slice_lossy_suite!($typ_literal, $ext_trait_name, [u8, "u8"], $([$convert_typ, $convert_typ_literal],)*); });
+
+            cfg_feature!("alloc", {
+                impl_convert_to_x_vec_suite!($as_trait_name::$fn_name, $typ_literal, [u8, "u8"], $([$convert_typ, $convert_typ_literal],)*);
+            });
 
-            impl_to_x_slice_suite!($typ_literal, from_raw_parts, $ext_trait_name::to_u8_slice::as_ptr, const, $([$convert_typ, $convert_typ_literal],)*);
+            impl_to_x_slice_suite!($typ_literal, $([$convert_typ, $convert_typ_literal],)*);
         }
 
         impl_traits_for_slice_type!($ext_trait_name, $as_trait_name, $typ);
-        impl_traits_for_vec_type!($ext_trait_name, $as_trait_name, $typ);
-        impl_traits_for_box_type!($ext_trait_name, $as_trait_name, $typ);
+        cfg_feature!("alloc", {
+            impl_traits_for_vec_type!($ext_trait_name, $as_trait_name, $typ);
+            impl_traits_for_box_type!($ext_trait_name, $as_trait_name, $typ);
+        });
+        });
         )*
     };
 }
 
 macro_rules! declare_as_x_slice_mut_trait {
-    ($([$ext_trait_name: ident, $mut_ext_trait_name: ident, $as_trait_name: ident, $as_mut_trait_name: ident, $fn_mut_name: ident, $typ: ty, $typ_literal: literal, $([$convert_typ: ty, $convert_typ_literal: literal]), +$(,)?]), +$(,)?) => {
+    ($([$ext_trait_name: ident, $mut_ext_trait_name: ident, $as_trait_name: ident, $as_mut_trait_name: ident, $fn_mut_name: ident, $typ: ty, $typ_literal: literal, $feature: literal, $([$convert_typ: ty, $convert_typ_literal: literal]), +$(,)?]), +$(,)?) => {
         $(
+        cfg_feature!($feature, {
         #[doc = concat!("Converts to `&'a mut [", $typ_literal, "]`")]
         pub trait $as_mut_trait_name: $as_trait_name  {
             #[doc = concat!("Converts to mutable ", $typ_literal, " slice")]
@@ -317,91 +604,319 @@ macro_rules! declare_as_x_slice_mut_trait {
 
         #[doc = concat!("Extensions for mutable ", $typ_literal, " slice")]
         pub trait $mut_ext_trait_name: $as_mut_trait_name + $ext_trait_name {
+            impl_partition_suites!($as_mut_trait_name::$fn_mut_name, $typ);
+
+            impl_permutation_suites!($as_mut_trait_name::$fn_mut_name);
+
+            impl_reorder_suites!($as_mut_trait_name::$fn_mut_name);
+
+            impl_gather_suites!(mut $as_mut_trait_name::$fn_mut_name, $typ);
+
+            impl_rand_suites!(mut $as_mut_trait_name::$fn_mut_name);
+
+            #[doc = concat!("Reinterprets the mutable ", $typ_literal, " slice as its mutable bytes in native-endian (zero-copy).")]
+            fn cast_bytes_mut(&mut self) -> &mut [u8] {
+                impl_x_to_u8_slice!(self, $typ, $as_mut_trait_name::$fn_mut_name::as_mut_ptr, from_raw_parts_mut, mut)
+            }
+
+            #[doc = concat!("Reinterprets the memory of the mutable ", $typ_literal, " slice as a mutable slice of `T` in native-endian (zero-copy).")]
+            ///
+            /// # Panics
+            ///
+            /// Panics if the byte length is not a multiple of the size of `T`, or if the slice is
+            /// not aligned for `T`.
+            #[inline]
+            fn cast_to_mut<T: Pod>(&mut self) -> &mut [T] {
+                cast_slice_mut(self.cast_bytes_mut())
+            }
+
             #[doc = concat!("convert ", $typ_literal, " slice to mutable u8 slice")]
+            #[deprecated(since = "0.0.3", note = "renamed to `cast_bytes_mut`, to tell it apart from the value conversions")]
             fn to_u8_slice_mut(&mut self) -> &mut [u8] {
-                impl_x_to_u8_slice!(self, $typ, $as_mut_trait_name::$fn_mut_name::as_mut_ptr, from_raw_parts_mut, mut)
+                self.cast_bytes_mut()
             }
 
-            impl_to_x_slice_suite!(mut $typ_literal, from_raw_parts_mut, $mut_ext_trait_name::to_u8_slice_mut::as_mut_ptr, mut, [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]);
+            impl_to_x_slice_suite!(mut $typ_literal, [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]);
         }
 
         impl_traits_for_slice_mut_type!($ext_trait_name, $mut_ext_trait_name, $as_trait_name, $as_mut_trait_name, $typ);
-        impl_traits_for_vec_mut_type!($ext_trait_name, $mut_ext_trait_name, $as_trait_name, $as_mut_trait_name, $typ);
-        impl_traits_for_box_mut_type!($ext_trait_name, $mut_ext_trait_name, $as_trait_name, $as_mut_trait_name, $typ);
+        cfg_feature!("alloc", {
+            impl_traits_for_vec_mut_type!($ext_trait_name, $mut_ext_trait_name, $as_trait_name, $as_mut_trait_name, $typ);
+            impl_traits_for_box_mut_type!($ext_trait_name, $mut_ext_trait_name, $as_trait_name, $as_mut_trait_name, $typ);
+        });
+        });
         )*
     };
 }
 
 declare_as_x_slice_trait! {
-    [U16SliceExt, AsU16Slice, as_u16_slice, u16, "u16", [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+    [U16SliceExt, AsU16Slice, as_u16_slice, u16, "u16", "slice-u16", [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+
+    [U32SliceExt, AsU32Slice, as_u32_slice, u32, "u32", "slice-u32", [u16, "u16"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
 
-    [U32SliceExt, AsU32Slice, as_u32_slice, u32, "u32", [u16, "u16"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+    [USizeSliceExt, AsUSizeSlice, as_usize_slice, usize, "usize", "slice-usize", [u16, "u16"], [u32, "u32"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
 
-    [USizeSliceExt, AsUSizeSlice, as_usize_slice, usize, "usize", [u16, "u16"], [u32, "u32"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+    [U64SliceExt, AsU64Slice, as_u64_slice, u64, "u64", "slice-u64", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
 
-    [U64SliceExt, AsU64Slice, as_u64_slice, u64, "u64", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+    [U128SliceExt, AsU128Slice, as_u128_slice, u128, "u128", "slice-u128", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
 
-    [U128SliceExt, AsU128Slice, as_u128_slice, u128, "u128", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+    [I8SliceExt, AsI8Slice, as_i8_slice, i8, "i8", "slice-i8", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
 
-    [I8SliceExt, AsI8Slice, as_i8_slice, i8, "i8", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+    [I16SliceExt, AsI16Slice, as_i16_slice, i16, "i16", "slice-i16", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
 
-    [I16SliceExt, AsI16Slice, as_i16_slice, i16, "i16", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+    [I32SliceExt, AsI32Slice, as_i32_slice, i32, "i32", "slice-i32", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
 
-    [I32SliceExt, AsI32Slice, as_i32_slice, i32, "i32", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+    [ISizeSliceExt, AsISizeSlice, as_isize_slice, isize, "isize", "slice-isize", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
 
-    [ISizeSliceExt, AsISizeSlice, as_isize_slice, isize, "isize", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+    [I64SliceExt, AsI64Slice, as_i64_slice, i64, "i64", "slice-i64", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
 
-    [I64SliceExt, AsI64Slice, as_i64_slice, i64, "i64", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+    [I128SliceExt, AsI128Slice, as_i128_slice, i128, "i128", "slice-i128", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [f32, "f32"], [f64, "f64"]],
 
-    [I128SliceExt, AsI128Slice, as_i128_slice, i128, "i128", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [f32, "f32"], [f64, "f64"]],
+    [F32SliceExt, AsF32Slice, as_f32_slice, f32, "f32", "slice-f32", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f64, "f64"]],
 
-    [F32SliceExt, AsF32Slice, as_f32_slice, f32, "f32", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f64, "f64"]],
+    [F64SliceExt, AsF64Slice, as_f64_slice, f64, "f64", "slice-f64", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"]],
 
-    [F64SliceExt, AsF64Slice, as_f64_slice, f64, "f64", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"]],
+    [F16SliceExt, AsF16Slice, as_f16_slice, f16, "f16", "half", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+
+    [BF16SliceExt, AsBF16Slice, as_bf16_slice, bf16, "bf16", "half", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
 }
 
 declare_as_x_slice_mut_trait! {
-    [U16SliceExt, U16SliceMutExt, AsU16Slice, AsU16SliceMut, as_u16_slice_mut, u16, "u16", [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+    [U16SliceExt, U16SliceMutExt, AsU16Slice, AsU16SliceMut, as_u16_slice_mut, u16, "u16", "slice-u16", [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+
+    [U32SliceExt, U32SliceMutExt, AsU32Slice, AsU32SliceMut, as_u32_slice_mut, u32, "u32", "slice-u32", [u16, "u16"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+
+    [USizeSliceExt, USizeSliceMutExt, AsUSizeSlice, AsUSizeSliceMut, as_usize_slice_mut, usize, "usize", "slice-usize", [u16, "u16"], [u32, "u32"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
 
-    [U32SliceExt, U32SliceMutExt, AsU32Slice, AsU32SliceMut, as_u32_slice_mut, u32, "u32", [u16, "u16"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+    [U64SliceExt, U64SliceMutExt, AsU64Slice, AsU64SliceMut, as_u64_slice_mut, u64, "u64", "slice-u64", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
 
-    [USizeSliceExt, USizeSliceMutExt, AsUSizeSlice, AsUSizeSliceMut, as_usize_slice_mut, usize, "usize", [u16, "u16"], [u32, "u32"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+    [U128SliceExt, U128SliceMutExt, AsU128Slice, AsU128SliceMut, as_u128_slice_mut, u128, "u128", "slice-u128", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
 
-    [U64SliceExt, U64SliceMutExt, AsU64Slice, AsU64SliceMut, as_u64_slice_mut, u64, "u64", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+    [I8SliceExt, I8SliceMutExt, AsI8Slice, AsI8SliceMut, as_i8_slice_mut, i8, "i8", "slice-i8", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
 
-    [U128SliceExt, U128SliceMutExt, AsU128Slice, AsU128SliceMut, as_u128_slice_mut, u128, "u128", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+    [I16SliceExt, I16SliceMutExt, AsI16Slice, AsI16SliceMut, as_i16_slice_mut, i16, "i16", "slice-i16", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
 
-    [I8SliceExt, I8SliceMutExt, AsI8Slice, AsI8SliceMut, as_i8_slice_mut, i8, "i8", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+    [I32SliceExt, I32SliceMutExt, AsI32Slice, AsI32SliceMut, as_i32_slice_mut, i32, "i32", "slice-i32", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
 
-    [I16SliceExt, I16SliceMutExt, AsI16Slice, AsI16SliceMut, as_i16_slice_mut, i16, "i16", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+    [ISizeSliceExt, ISizeSliceMutExt, AsISizeSlice, AsISizeSliceMut, as_isize_slice_mut, isize, "isize", "slice-isize", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
 
-    [I32SliceExt, I32SliceMutExt, AsI32Slice, AsI32SliceMut, as_i32_slice_mut, i32, "i32", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+    [I64SliceExt, I64SliceMutExt, AsI64Slice, AsI64SliceMut, as_i64_slice_mut, i64, "i64", "slice-i64", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
 
-    [ISizeSliceExt, ISizeSliceMutExt, AsISizeSlice, AsISizeSliceMut, as_isize_slice_mut, isize, "isize", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+    [I128SliceExt, I128SliceMutExt, AsI128Slice, AsI128SliceMut, as_i128_slice_mut, i128, "i128", "slice-i128", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [f32, "f32"], [f64, "f64"]],
 
-    [I64SliceExt, I64SliceMutExt, AsI64Slice, AsI64SliceMut, as_i64_slice_mut, i64, "i64", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
+    [F32SliceExt, F32SliceMutExt, AsF32Slice, AsF32SliceMut, as_f32_slice_mut, f32, "f32", "slice-f32", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"],  [f64, "f64"]],
 
-    [I128SliceExt, I128SliceMutExt, AsI128Slice, AsI128SliceMut, as_i128_slice_mut, i128, "i128", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [f32, "f32"], [f64, "f64"]],
+    [F64SliceExt, F64SliceMutExt, AsF64Slice, AsF64SliceMut, as_f64_slice_mut, f64, "f64", "slice-f64", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"]],
 
-    [F32SliceExt, F32SliceMutExt, AsF32Slice, AsF32SliceMut, as_f32_slice_mut, f32, "f32", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"],  [f64, "f64"]],
+    [F16SliceExt, F16SliceMutExt, AsF16Slice, AsF16SliceMut, as_f16_slice_mut, f16, "f16", "half", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
 
-    [F64SliceExt, F64SliceMutExt, AsF64Slice, AsF64SliceMut, as_f64_slice_mut, f64, "f64", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"]],
+    [BF16SliceExt, BF16SliceMutExt, AsBF16Slice, AsBF16SliceMut, as_bf16_slice_mut, bf16, "bf16", "half", [u16, "u16"], [u32, "u32"], [usize, "usize"], [u64, "u64"], [u128, "u128"], [i8, "i8"], [i16, "i16"], [i32, "i32"], [i64, "i64"], [isize, "isize"], [i128, "i128"], [f32, "f32"], [f64, "f64"]],
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "slice-u16"))]
 mod tests {
     use crate::slice_ext::U16SliceExt;
+    use crate::{BytesExt, LayoutError};
+
+    /// Aligns the data for the casts to wider types, which panic on misaligned slices.
+    #[repr(align(8))]
+    struct Aligned<T>(T);
 
     #[test]
-    fn test_slice() {
-        let u16s = [1u16; 12];
-        let u8v = u16s.as_slice();
-        eprintln!(
-            "{:?} {:?} {:?}",
-            u8v.to_be_u32_vec(),
-            u8v.to_le_u32_vec(),
-            u8v.to_ne_u32_vec()
+    fn test_to_x_slice_len() {
+        let u16s = Aligned([1u16; 12]);
+        assert_eq!(u16s.0.cast_to::<u32>().len(), 6);
+        assert_eq!(u16s.0.cast_to::<u64>().len(), 3);
+        assert_eq!(u16s.0.cast_bytes().len(), 24);
+        assert_eq!(
+            (&u16s.0.cast_bytes()[2..22]).try_cast_to::<u32>(),
+            Err(LayoutError::Misaligned {
+                align: 4,
+                offset: 2
+            })
         );
-        eprintln!("{:?}", u8v.to_u32_slice());
+        assert_eq!([1u16, 300].to_u8_clamped_vec(), [1, 255]);
+    }
+
+    #[test]
+    fn test_cast_and_convert() {
+        use crate::slice_ext::U16SliceMutExt;
+
+        let mut u16s = Aligned([1u16, 300, 0, 0]);
+        let u16s = &mut u16s.0;
+        let mut first = [0u8; 4];
+        first[..2].copy_from_slice(&1u16.to_ne_bytes());
+        first[2..].copy_from_slice(&300u16.to_ne_bytes());
+        assert_eq!(u16s.cast_to::<u32>(), [u32::from_ne_bytes(first), 0]);
+        assert_eq!(u16s.convert_to_u32_vec(), [1, 300, 0, 0]);
+        assert_eq!(u16s.convert_to_u8_vec(), [1, 44, 0, 0]);
+        assert_eq!(u16s.convert_to_f32_vec(), [1.0, 300.0, 0.0, 0.0]);
+
+        u16s.cast_to_mut::<u32>()[1] = u32::MAX;
+        assert_eq!(*u16s, [1, 300, u16::MAX, u16::MAX]);
+
+        #[allow(deprecated)]
+        let bytes = u16s.to_u8_slice();
+        assert_eq!(bytes, u16s.cast_bytes());
+    }
+
+    #[test]
+    fn test_psfix() {
+        let u16s = [1u16, 2, 3, 4];
+        assert!(u16s.has_suffix([3u16, 4]));
+        assert!(!u16s.has_suffix([2u16, 4]));
+        assert_eq!(u16s.longest_prefix([1u16, 2, 3, 4, 5]), &[1, 2, 3, 4]);
+        assert!(u16s.longest_prefix([0u16; 0]).is_empty());
+    }
+
+    #[test]
+    fn test_split_and_partition() {
+        use crate::slice_ext::U16SliceMutExt;
+
+        let vals = vec![1u16, 0, 2, 0, 3];
+        assert_eq!(vals.split_once_on([0u16]), Some((&[1u16][..], &[2u16, 0, 3][..])));
+        assert_eq!(vals.rsplit_once_on([0u16]), Some((&[1u16, 0, 2][..], &[3u16][..])));
+        assert_eq!(vals.take_front(2), Some((&[1u16, 0][..], &[2u16, 0, 3][..])));
+        assert_eq!(vals.get_range(3..9), None);
+
+        let mut vals = vals;
+        assert_eq!(vals.partition_in_place(|v| *v == 0), 2);
+        assert_eq!(&vals[..2], &[0, 0]);
+    }
+
+    #[test]
+    fn test_permutation_in_place() {
+        use crate::slice_ext::U16SliceMutExt;
+
+        let mut vals = vec![1u16, 2, 3, 4];
+        let mut count = 1;
+        while vals.next_permutation_in_place() {
+            count += 1;
+        }
+        assert_eq!(count, 24);
+        assert_eq!(vals, [1, 2, 3, 4]);
+
+        let mut vals = vec![1u16, 3, 2];
+        assert!(vals.prev_permutation_in_place());
+        assert_eq!(vals, [1, 2, 3]);
+        assert!(vals.next_permutation_in_place());
+        assert_eq!(vals, [1, 3, 2]);
+    }
+
+    #[test]
+    fn test_reorder() {
+        use crate::slice_ext::U16SliceMutExt;
+
+        // Two interleaved stereo frames: L0 R0 L1 R1.
+        let mut frames = vec![1u16, 2, 3, 4];
+        frames.reverse_chunks(2);
+        assert_eq!(frames, [3, 4, 1, 2]);
+        frames.rotate_left_by(5);
+        assert_eq!(frames, [4, 1, 2, 3]);
+        frames.rotate_right_by(1);
+        assert_eq!(frames, [3, 4, 1, 2]);
+        frames.swap_ranges(2..4, 0..1);
+        assert_eq!(frames, [1, 2, 4, 3]);
+    }
+
+    #[test]
+    fn test_gather_scatter() {
+        use crate::slice_ext::U16SliceMutExt;
+        use crate::OobAt;
+
+        let cols = [10u16, 20, 30];
+        assert_eq!(cols.gather(&[2, 0, 0]), [30, 10, 10]);
+        assert_eq!(
+            cols.try_gather(&[1, 3]),
+            Err(OobAt {
+                position: 1,
+                index: 3,
+                len: 3
+            })
+        );
+
+        let order = [2, 0, 1];
+        let mut restored = [0u16; 3];
+        restored.scatter_from(&order, &cols.gather(&order));
+        assert_eq!(restored, cols);
+    }
+
+    #[test]
+    fn test_map_into() {
+        use crate::slice_ext::U16SliceExt;
+
+        let vals = [1u16, 2, 3];
+        let mut out = [0u32; 3];
+        vals.map_into(&mut out, |v| v as u32 * 100_000);
+        assert_eq!(out, [100_000, 200_000, 300_000]);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_par_map_into() {
+        use crate::slice_ext::U16SliceExt;
+
+        let vals: Vec<u16> = (0..10_000).collect();
+        let mut out = vec![0u64; vals.len()];
+        vals.par_map_into(&mut out, |v| v as u64 * 2);
+        assert!(out.iter().enumerate().all(|(i, v)| *v == i as u64 * 2));
+    }
+
+    #[test]
+    #[cfg(all(feature = "slice-i16", feature = "slice-i32"))]
+    fn test_fixed_to_float() {
+        use crate::slice_ext::{I16SliceExt, I32SliceExt};
+
+        let q15 = [0i16, 16384, -32768, i16::MAX];
+        assert_eq!(q15.to_q15_f32_vec(), [0.0, 0.5, -1.0, 32767.0 / 32768.0]);
+        let q31 = [1i32 << 30, i32::MIN];
+        assert_eq!(q31.to_q31_f64_vec(), [0.5, -1.0]);
+        assert_eq!(q31.fixed_to_float::<f32>(0), [(1u32 << 30) as f32, i32::MIN as f32]);
+        assert_eq!([-384i16].fixed_to_float::<f64>(8), [-1.5]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "slice-i32", feature = "slice-f32"))]
+    fn test_clamped_vec() {
+        use crate::slice_ext::{F32SliceExt, I32SliceExt};
+
+        let samples = [-70000i32, -1, 0, 300, 70000];
+        assert_eq!(samples.to_u8_clamped_vec(), [0, 0, 0, 255, 255]);
+        assert_eq!(samples.to_i16_clamped_vec(), [-32768, -1, 0, 300, 32767]);
+
+        let pixels = [-0.4f32, 0.5, 1.49, 254.6, 1e9, f32::NAN];
+        assert_eq!(pixels.to_u8_clamped_vec(), [0, 1, 1, 255, 255, 0]);
+        assert_eq!([-2.5f32].to_i8_clamped_vec(), [-3]);
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(feature = "slice-i16")]
+    fn test_fixed_to_float_too_many_bits() {
+        use crate::slice_ext::I16SliceExt;
+
+        [1i16].fixed_to_float::<f64>(64);
+    }
+
+    #[test]
+    fn test_runs() {
+        let deltas = [0u16, 0, 0, 5, 5, 1];
+        let runs = deltas.runs().map(|(v, n)| (*v, n)).collect::<Vec<_>>();
+        assert_eq!(runs, vec![(0, 3), (5, 2), (1, 1)]);
+        assert_eq!(deltas.group_by(|a, b| a <= b).count(), 2);
+    }
+
+    #[test]
+    fn test_slice() {
+        let u16s = Aligned([1u16; 12]);
+        let mut word = [0u8; 4];
+        word[..2].copy_from_slice(&1u16.to_ne_bytes());
+        word[2..].copy_from_slice(&1u16.to_ne_bytes());
+
+        let u16s = u16s.0.as_slice();
+        assert_eq!(u16s.to_be_u32_vec(), [u32::from_be_bytes(word); 6]);
+        assert_eq!(u16s.to_le_u32_vec(), [u32::from_le_bytes(word); 6]);
+        assert_eq!(u16s.to_ne_u32_vec(), [u32::from_ne_bytes(word); 6]);
+        assert_eq!(u16s.cast_to::<u32>(), [u32::from_ne_bytes(word); 6]);
     }
 }
@@ -0,0 +1,388 @@
+//! A byte string stored inline up to a fixed length, and on the heap beyond it.
+
+use crate::{AsBytesMutRef, AsBytesRef, BytesExt, BytesMutExt};
+use alloc::vec::Vec;
+use core::borrow::{Borrow, BorrowMut};
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::{Deref, DerefMut};
+
+#[derive(Clone)]
+enum Repr<const N: usize> {
+    Inline { len: u8, buf: [u8; N] },
+    Heap(Vec<u8>),
+}
+
+/// A byte string which keeps up to `N` bytes inline and moves to the heap beyond, to save the
+/// allocations of the short keys which dominate most key-value workloads.
+///
+/// The default `N` of 23 makes it as large as a `Vec<u8>` plus a word. It compares, orders and
+/// hashes like `[u8]`, so it can be looked up by `&[u8]` in maps.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::{BytesExt, SmallBytes};
+///
+/// let mut key = SmallBytes::<23>::from("user:42");
+/// assert!(key.is_inline());
+/// assert!(key.has_prefix("user:"));
+///
+/// key.extend_from_slice(&[b'!'; 20]);
+/// assert!(!key.is_inline());
+/// assert_eq!(key.len(), 27);
+/// assert!(SmallBytes::<23>::from("a") < SmallBytes::from("b"));
+/// ```
+#[derive(Clone)]
+pub struct SmallBytes<const N: usize = 23> {
+    repr: Repr<N>,
+}
+
+impl<const N: usize> SmallBytes<N> {
+    /// The inline length must fit in the `u8` length.
+    const VALID: () = assert!(
+        N <= u8::MAX as usize,
+        "SmallBytes holds at most 255 bytes inline"
+    );
+
+    /// Creates an empty `SmallBytes`.
+    #[inline]
+    pub const fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID;
+        Self {
+            repr: Repr::Inline {
+                len: 0,
+                buf: [0; N],
+            },
+        }
+    }
+
+    /// Copies `src`, inline if it is at most `N` bytes long.
+    pub fn from_slice(src: &[u8]) -> Self {
+        let mut this = Self::new();
+        this.extend_from_slice(src);
+        this
+    }
+
+    /// Returns whether the bytes are stored inline.
+    #[inline]
+    pub const fn is_inline(&self) -> bool {
+        matches!(self.repr, Repr::Inline { .. })
+    }
+
+    /// Returns the bytes.
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        match &self.repr {
+            Repr::Inline { len, buf } => &buf[..*len as usize],
+            Repr::Heap(vec) => vec,
+        }
+    }
+
+    /// Returns the bytes, mutably.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        match &mut self.repr {
+            Repr::Inline { len, buf } => &mut buf[..*len as usize],
+            Repr::Heap(vec) => vec,
+        }
+    }
+
+    /// Appends `src`, moving the bytes to the heap if they no longer fit inline.
+    pub fn extend_from_slice(&mut self, src: &[u8]) {
+        match &mut self.repr {
+            Repr::Inline { len, buf } if *len as usize + src.len() <= N => {
+                let start = *len as usize;
+                buf[start..start + src.len()].copy_from_slice(src);
+                *len += src.len() as u8;
+            }
+            Repr::Inline { len, buf } => {
+                let mut vec = Vec::with_capacity(*len as usize + src.len());
+                vec.extend_from_slice(&buf[..*len as usize]);
+                vec.extend_from_slice(src);
+                self.repr = Repr::Heap(vec);
+            }
+            Repr::Heap(vec) => vec.extend_from_slice(src),
+        }
+    }
+
+    /// Appends a byte.
+    #[inline]
+    pub fn push(&mut self, b: u8) {
+        self.extend_from_slice(&[b]);
+    }
+
+    /// Shortens to `len` bytes, the bytes stay on the heap if they are there.
+    #[inline]
+    pub fn truncate(&mut self, new_len: usize) {
+        match &mut self.repr {
+            Repr::Inline { len, .. } => *len = (*len).min(new_len.min(N) as u8),
+            Repr::Heap(vec) => vec.truncate(new_len),
+        }
+    }
+
+    /// Converts into a `Vec<u8>`, without copying if the bytes are on the heap.
+    #[inline]
+    pub fn into_vec(self) -> Vec<u8> {
+        match self.repr {
+            Repr::Inline { len, buf } => buf[..len as usize].to_vec(),
+            Repr::Heap(vec) => vec,
+        }
+    }
+}
+
+impl<const N: usize> Default for SmallBytes<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Deref for SmallBytes<N> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl<const N: usize> DerefMut for SmallBytes<N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [u8] {
+        self.as_mut_slice()
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for SmallBytes<N> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl<const N: usize> AsMut<[u8]> for SmallBytes<N> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [u8] {
+        self.as_mut_slice()
+    }
+}
+
+impl<const N: usize> Borrow<[u8]> for SmallBytes<N> {
+    #[inline]
+    fn borrow(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl<const N: usize> BorrowMut<[u8]> for SmallBytes<N> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut [u8] {
+        self.as_mut_slice()
+    }
+}
+
+impl<const N: usize> From<&[u8]> for SmallBytes<N> {
+    #[inline]
+    fn from(src: &[u8]) -> Self {
+        Self::from_slice(src)
+    }
+}
+
+impl<const N: usize> From<&str> for SmallBytes<N> {
+    #[inline]
+    fn from(src: &str) -> Self {
+        Self::from_slice(src.as_bytes())
+    }
+}
+
+impl<const N: usize> From<Vec<u8>> for SmallBytes<N> {
+    /// Keeps the allocation of `src` unless it fits inline.
+    fn from(src: Vec<u8>) -> Self {
+        if src.len() <= N {
+            Self::from_slice(&src)
+        } else {
+            Self {
+                repr: Repr::Heap(src),
+            }
+        }
+    }
+}
+
+impl<const N: usize> From<SmallBytes<N>> for Vec<u8> {
+    #[inline]
+    fn from(src: SmallBytes<N>) -> Self {
+        src.into_vec()
+    }
+}
+
+impl<const N: usize> Extend<u8> for SmallBytes<N> {
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|b| self.push(b))
+    }
+}
+
+impl<const N: usize> FromIterator<u8> for SmallBytes<N> {
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+        let mut this = Self::new();
+        this.extend(iter);
+        this
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+#[cfg_attr(docsrs, doc(cfg(feature = "arbitrary")))]
+impl<'a, const N: usize> arbitrary::Arbitrary<'a> for SmallBytes<N> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        <&[u8]>::arbitrary(u).map(Self::from_slice)
+    }
+
+    #[inline]
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <&[u8]>::size_hint(depth)
+    }
+}
+
+impl<const N: usize> fmt::Debug for SmallBytes<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+impl<const N: usize> PartialEq for SmallBytes<N> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<const N: usize> Eq for SmallBytes<N> {}
+
+impl<const N: usize> PartialEq<[u8]> for SmallBytes<N> {
+    #[inline]
+    fn eq(&self, other: &[u8]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&[u8]> for SmallBytes<N> {
+    #[inline]
+    fn eq(&self, other: &&[u8]) -> bool {
+        self.as_slice() == *other
+    }
+}
+
+impl<const N: usize> PartialOrd for SmallBytes<N> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize> Ord for SmallBytes<N> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+impl<const N: usize> Hash for SmallBytes<N> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl<const N: usize> AsBytesRef for SmallBytes<N> {
+    fn as_bytes_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl<const N: usize> AsBytesMutRef for SmallBytes<N> {
+    fn as_bytes_mut_ref(&mut self) -> &mut [u8] {
+        self.as_mut_slice()
+    }
+}
+
+impl<const N: usize> BytesExt for SmallBytes<N> {}
+
+impl<const N: usize> BytesMutExt for SmallBytes<N> {}
+
+impl<const N: usize> AsBytesRef for &SmallBytes<N> {
+    fn as_bytes_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl<const N: usize> BytesExt for &SmallBytes<N> {}
+
+impl<const N: usize> AsBytesRef for &mut SmallBytes<N> {
+    fn as_bytes_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl<const N: usize> AsBytesMutRef for &mut SmallBytes<N> {
+    fn as_bytes_mut_ref(&mut self) -> &mut [u8] {
+        self.as_mut_slice()
+    }
+}
+
+impl<const N: usize> BytesExt for &mut SmallBytes<N> {}
+
+impl<const N: usize> BytesMutExt for &mut SmallBytes<N> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::hash::BuildHasher;
+
+    #[test]
+    fn test_small_bytes_spill() {
+        let mut b = SmallBytes::<4>::new();
+        assert!(b.is_empty() && b.is_inline());
+        b.extend_from_slice(b"abc");
+        b.push(b'd');
+        assert!(b.is_inline());
+        b.push(b'e');
+        assert!(!b.is_inline());
+        assert_eq!(b, &b"abcde"[..]);
+        b.truncate(2);
+        assert_eq!(b.as_slice(), b"ab");
+        b.as_mut_slice()[0] = b'x';
+        assert_eq!(b.into_vec(), b"xb");
+
+        assert!(SmallBytes::<4>::from(b"abcd".to_vec()).is_inline());
+        let heap = SmallBytes::<4>::from(b"abcde".to_vec());
+        assert!(!heap.is_inline());
+        assert_eq!(heap.as_slice(), SmallBytes::<8>::from("abcde").as_slice());
+        assert_eq!(
+            b"xyz".iter().copied().collect::<SmallBytes<2>>(),
+            &b"xyz"[..]
+        );
+    }
+
+    #[test]
+    fn test_small_bytes_ord_hash() {
+        let inline = SmallBytes::<23>::from("key");
+        let heap = SmallBytes::<23>::from(b"key".to_vec());
+        let mut long = SmallBytes::<2>::from("ke");
+        long.push(b'y');
+        assert!(!long.is_inline());
+        assert_eq!(inline.as_slice(), long.as_slice());
+        assert_eq!(inline.cmp(&heap), Ordering::Equal);
+        assert!(SmallBytes::<23>::from("ab") < SmallBytes::<23>::from("b"));
+
+        let state = std::collections::hash_map::RandomState::new();
+        assert_eq!(state.hash_one(&inline), state.hash_one(&b"key"[..]));
+        assert_eq!(state.hash_one(&inline), state.hash_one(&long));
+
+        let mut set = std::collections::HashSet::new();
+        set.insert(inline);
+        assert!(set.contains(&b"key"[..]));
+        assert!(long.bytes_eq("key"));
+    }
+}
@@ -0,0 +1,396 @@
+//! A sorted-string-table data block in the layout of LevelDB: prefix-compressed entries,
+//! a restart array and its length.
+//!
+//! Each entry is `shared (varint) | unshared (varint) | value_len (varint) | key_delta | value`,
+//! where `shared` is the length of the prefix the key shares with the previous one. Every
+//! `restart_interval` entries the full key is stored, and the offset of that entry is recorded
+//! in the restart array, so lookups can binary search the restarts. The block ends with the
+//! restart offsets and their count, as u32s in little-endian.
+
+use crate::{AsBytesRef, BytesExt, ReadError, SliceReader, VecByteWriterExt};
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+
+/// The default number of entries between restart points.
+pub const SST_RESTART_INTERVAL: usize = 16;
+
+/// The error returned when an SST block is malformed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SstBlockError {
+    /// The block is too short for its restart array.
+    TooShort {
+        /// The length of the block.
+        len: usize,
+    },
+    /// A restart offset points past the entries.
+    InvalidRestart {
+        /// The index of the restart.
+        index: usize,
+        /// The offset it points to.
+        offset: usize,
+    },
+    /// The entry at the offset is truncated or shares more bytes than the previous key has.
+    Corrupted {
+        /// The offset of the entry.
+        offset: usize,
+    },
+}
+
+impl fmt::Display for SstBlockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort { len } => write!(f, "sst block of {} bytes is too short", len),
+            Self::InvalidRestart { index, offset } => write!(
+                f,
+                "sst restart {} points to {}, past the entries",
+                index, offset
+            ),
+            Self::Corrupted { offset } => write!(f, "corrupted sst entry at offset {}", offset),
+        }
+    }
+}
+
+cfg_feature!("std", {
+    impl std::error::Error for SstBlockError {}
+});
+
+/// Builds an SST block from entries added in strictly increasing key order.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::{SstBlockBuilder, SstBlockIter};
+///
+/// let mut builder = SstBlockBuilder::new(2);
+/// builder.add("apple", "1");
+/// builder.add("apricot", "2");
+/// builder.add("banana", "3");
+/// let block = builder.finish();
+///
+/// let mut iter = SstBlockIter::new(&block).unwrap();
+/// iter.seek("apz").unwrap();
+/// assert_eq!(iter.next(), Some(Ok((b"banana".to_vec(), &b"3"[..]))));
+/// assert_eq!(iter.next(), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SstBlockBuilder {
+    buf: Vec<u8>,
+    restarts: Vec<u32>,
+    restart_interval: usize,
+    counter: usize,
+    last_key: Vec<u8>,
+}
+
+impl Default for SstBlockBuilder {
+    fn default() -> Self {
+        Self::new(SST_RESTART_INTERVAL)
+    }
+}
+
+impl SstBlockBuilder {
+    /// Creates a builder storing a full key every `restart_interval` entries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `restart_interval` is 0.
+    pub fn new(restart_interval: usize) -> Self {
+        assert!(restart_interval > 0, "restart interval must not be 0");
+        Self {
+            buf: Vec::new(),
+            restarts: alloc::vec![0],
+            restart_interval,
+            counter: 0,
+            last_key: Vec::new(),
+        }
+    }
+
+    /// Returns whether no entry has been added.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Returns the size the block would have if it was finished now.
+    #[inline]
+    pub fn estimated_len(&self) -> usize {
+        self.buf.len() + 4 * self.restarts.len() + 4
+    }
+
+    /// Adds an entry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not greater than the key of the previous entry, or if the block grows
+    /// past `u32::MAX` bytes.
+    pub fn add(&mut self, key: impl AsBytesRef, value: impl AsBytesRef) {
+        let key = key.as_bytes_ref();
+        let value = value.as_bytes_ref();
+        assert!(
+            self.is_empty() || key > self.last_key.as_slice(),
+            "sst keys must be added in strictly increasing order"
+        );
+
+        let shared = if self.counter < self.restart_interval {
+            self.last_key.longest_prefix(key).len()
+        } else {
+            let offset = u32::try_from(self.buf.len()).expect("sst block larger than u32::MAX");
+            self.restarts.push(offset);
+            self.counter = 0;
+            0
+        };
+        self.buf.push_varint(shared as u64);
+        self.buf.push_varint((key.len() - shared) as u64);
+        self.buf.push_varint(value.len() as u64);
+        self.buf.push_slice(&key[shared..]);
+        self.buf.push_slice(value);
+
+        self.last_key.truncate(shared);
+        self.last_key.extend_from_slice(&key[shared..]);
+        self.counter += 1;
+    }
+
+    /// Appends the restart array and returns the block, leaving the builder empty for the next
+    /// block.
+    pub fn finish(&mut self) -> Vec<u8> {
+        let mut block = core::mem::take(&mut self.buf);
+        for &restart in &self.restarts {
+            block.push_u32_le(restart);
+        }
+        block.push_u32_le(self.restarts.len() as u32);
+
+        self.restarts.clear();
+        self.restarts.push(0);
+        self.counter = 0;
+        self.last_key.clear();
+        block
+    }
+}
+
+/// An iterator over the entries of an SST block, yielding the keys and the borrowed values.
+///
+/// The iterator stops after the first error.
+#[derive(Debug, Clone)]
+pub struct SstBlockIter<'a> {
+    block: &'a [u8],
+    /// The start of the restart array, i.e. the end of the entries.
+    end: usize,
+    offset: usize,
+    key: Vec<u8>,
+    /// The value of an entry decoded by `seek`, to be yielded first.
+    pending: Option<&'a [u8]>,
+    done: bool,
+}
+
+impl<'a> SstBlockIter<'a> {
+    /// Creates an iterator at the first entry of `block`, after validating its restart array.
+    pub fn new(block: &'a [u8]) -> Result<Self, SstBlockError> {
+        let too_short = SstBlockError::TooShort { len: block.len() };
+        let count = match block.len().checked_sub(4) {
+            Some(at) => u32::from_le_bytes(block[at..].try_into().unwrap()) as usize,
+            None => return Err(too_short),
+        };
+        let end = count
+            .checked_mul(4)
+            .and_then(|n| block.len().checked_sub(4 + n))
+            .ok_or(too_short)?;
+
+        let iter = Self {
+            block,
+            end,
+            offset: 0,
+            key: Vec::new(),
+            pending: None,
+            done: false,
+        };
+        for index in 0..count {
+            let offset = iter.restart(index);
+            if offset > end {
+                return Err(SstBlockError::InvalidRestart { index, offset });
+            }
+        }
+        Ok(iter)
+    }
+
+    /// Returns the number of restart points.
+    #[inline]
+    pub fn restarts(&self) -> usize {
+        (self.block.len() - 4 - self.end) / 4
+    }
+
+    fn restart(&self, index: usize) -> usize {
+        let at = self.end + 4 * index;
+        u32::from_le_bytes(self.block[at..at + 4].try_into().unwrap()) as usize
+    }
+
+    /// Positions the iterator so the next entry is the first one with a key greater than or
+    /// equal to `target`.
+    pub fn seek(&mut self, target: impl AsBytesRef) -> Result<(), SstBlockError> {
+        let target = target.as_bytes_ref();
+        self.pending = None;
+        self.done = false;
+        self.key.clear();
+        self.offset = self.end;
+        if self.end == 0 || self.restarts() == 0 {
+            return Ok(());
+        }
+
+        // Find the last restart whose key is less than the target.
+        let (mut left, mut right) = (0, self.restarts() - 1);
+        while left < right {
+            let mid = (left + right).div_ceil(2);
+            self.offset = self.restart(mid);
+            self.key.clear();
+            self.decode()?;
+            if self.key.as_slice() < target {
+                left = mid;
+            } else {
+                right = mid - 1;
+            }
+        }
+
+        self.offset = self.restart(left);
+        self.key.clear();
+        while self.offset < self.end {
+            let value = self.decode()?;
+            if self.key.as_slice() >= target {
+                self.pending = Some(value);
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Decodes the entry at the offset into the key buffer and advances past it.
+    fn decode(&mut self) -> Result<&'a [u8], SstBlockError> {
+        let offset = self.offset;
+        let corrupted = |_: ReadError| SstBlockError::Corrupted { offset };
+        let mut r = SliceReader::new(&self.block[offset..self.end]);
+        let shared = r.read_varint().map_err(corrupted)? as usize;
+        let unshared = r.read_varint().map_err(corrupted)? as usize;
+        let value_len = r.read_varint().map_err(corrupted)? as usize;
+        if shared > self.key.len() {
+            return Err(SstBlockError::Corrupted { offset });
+        }
+        let delta = r.take(unshared).map_err(corrupted)?;
+        let value = r.take(value_len).map_err(corrupted)?;
+
+        self.key.truncate(shared);
+        self.key.extend_from_slice(delta);
+        self.offset += r.offset();
+        Ok(value)
+    }
+}
+
+impl<'a> Iterator for SstBlockIter<'a> {
+    type Item = Result<(Vec<u8>, &'a [u8]), SstBlockError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let value = match self.pending.take() {
+            Some(value) => Ok(value),
+            None if self.offset >= self.end => {
+                self.done = true;
+                return None;
+            }
+            None => self.decode(),
+        };
+        match value {
+            Ok(value) => Some(Ok((self.key.clone(), value))),
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl FusedIterator for SstBlockIter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+
+    fn build(n: usize, interval: usize) -> Vec<u8> {
+        let mut builder = SstBlockBuilder::new(interval);
+        for i in 0..n {
+            builder.add(format!("key{:03}", i * 2), format!("v{}", i));
+        }
+        let len = builder.estimated_len();
+        let block = builder.finish();
+        assert_eq!(block.len(), len);
+        assert!(builder.is_empty());
+        block
+    }
+
+    #[test]
+    fn test_sst_block_roundtrip() {
+        let block = build(40, 4);
+        let iter = SstBlockIter::new(&block).unwrap();
+        assert_eq!(iter.restarts(), 10);
+        let entries = iter.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(entries.len(), 40);
+        assert_eq!(entries[17].0, b"key034");
+        assert_eq!(entries[17].1, b"v17");
+        // The second entry only stores "2" after the shared "key00".
+        assert_eq!(&block[..9], b"\x00\x06\x02key000");
+        assert_eq!(&block[11..15], b"\x05\x01\x02\x32");
+
+        let empty = SstBlockBuilder::default().finish();
+        assert_eq!(empty, [0, 0, 0, 0, 1, 0, 0, 0]);
+        assert_eq!(SstBlockIter::new(&empty).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_sst_block_seek() {
+        let block = build(40, 4);
+        let mut iter = SstBlockIter::new(&block).unwrap();
+        for (target, expected) in [("", "key000"), ("key034", "key034"), ("key035", "key036")] {
+            iter.seek(target).unwrap();
+            assert_eq!(iter.next().unwrap().unwrap().0, expected.as_bytes());
+        }
+        iter.seek("key079").unwrap();
+        assert_eq!(iter.next(), None);
+
+        let mut iter = SstBlockIter::new(&[0, 0, 0, 0, 1, 0, 0, 0]).unwrap();
+        iter.seek("a").unwrap();
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_sst_block_errors() {
+        assert_eq!(
+            SstBlockIter::new(&[1, 0]).unwrap_err(),
+            SstBlockError::TooShort { len: 2 }
+        );
+        assert_eq!(
+            SstBlockIter::new(&[2, 0, 0, 0, 1, 0, 0, 0]).unwrap_err(),
+            SstBlockError::InvalidRestart {
+                index: 0,
+                offset: 2
+            }
+        );
+
+        let mut block = build(3, 4);
+        // Make the second entry share more bytes than the first key has.
+        block[11] = 9;
+        let mut iter = SstBlockIter::new(&block).unwrap();
+        assert!(iter.next().unwrap().is_ok());
+        assert_eq!(
+            iter.next(),
+            Some(Err(SstBlockError::Corrupted { offset: 11 }))
+        );
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sst_block_unordered() {
+        let mut builder = SstBlockBuilder::default();
+        builder.add("b", "");
+        builder.add("a", "");
+    }
+}
@@ -0,0 +1,138 @@
+//! [`proptest`](https://docs.rs/proptest) strategies for the inputs of the conversion APIs.
+
+use crate::{AsBytesMutRef, AsBytesRef, BytesExt, BytesMutExt, Pod};
+use core::fmt;
+use core::mem;
+use core::ops::{Deref, DerefMut};
+use core::slice::{from_raw_parts, from_raw_parts_mut};
+use proptest::collection::{vec, SizeRange};
+use proptest::prelude::*;
+
+/// Bytes stored in a `Vec<T>`, so they are aligned for `T` and can be cast to `&[T]` with
+/// `cast_to` (or viewed with the `as_*_slice` conversions) without a misalignment panic.
+#[derive(Clone, Default)]
+pub struct AlignedBytes<T: Pod> {
+    elems: Vec<T>,
+}
+
+impl<T: Pod> AlignedBytes<T> {
+    /// Returns the bytes.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        // SAFETY: `Pod` types have no padding.
+        unsafe {
+            from_raw_parts(
+                self.elems.as_ptr() as *const u8,
+                mem::size_of_val(&*self.elems),
+            )
+        }
+    }
+
+    /// Returns the bytes, mutably.
+    #[inline]
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        let len = mem::size_of_val(&*self.elems);
+        // SAFETY: `Pod` types have no padding and accept any bit pattern.
+        unsafe { from_raw_parts_mut(self.elems.as_mut_ptr() as *mut u8, len) }
+    }
+
+    /// Returns the bytes as `T`s.
+    #[inline]
+    pub fn as_elems(&self) -> &[T] {
+        &self.elems
+    }
+
+    /// Returns the underlying `Vec<T>`.
+    #[inline]
+    pub fn into_inner(self) -> Vec<T> {
+        self.elems
+    }
+}
+
+impl<T: Pod> fmt::Debug for AlignedBytes<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AlignedBytes")
+            .field(&self.as_bytes())
+            .finish()
+    }
+}
+
+impl<T: Pod> Deref for AlignedBytes<T> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<T: Pod> DerefMut for AlignedBytes<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [u8] {
+        self.as_bytes_mut()
+    }
+}
+
+impl<T: Pod> AsBytesRef for AlignedBytes<T> {
+    fn as_bytes_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<T: Pod> AsBytesMutRef for AlignedBytes<T> {
+    fn as_bytes_mut_ref(&mut self) -> &mut [u8] {
+        self.as_bytes_mut()
+    }
+}
+
+impl<T: Pod> BytesExt for AlignedBytes<T> {}
+
+impl<T: Pod> BytesMutExt for AlignedBytes<T> {}
+
+/// Generates bytes aligned for `T`, holding a number of `T`s in `len`, with arbitrary bit
+/// patterns.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::strategy::aligned_bytes;
+/// use lazyext_slice::BytesExt;
+/// use proptest::prelude::*;
+///
+/// proptest!(|(bytes in aligned_bytes::<u64>(0..16))| {
+///     prop_assert_eq!(bytes.cast_to::<u64>(), bytes.as_elems());
+///     prop_assert_eq!(bytes.len() % 8, 0);
+/// });
+/// ```
+pub fn aligned_bytes<T: Pod>(len: impl Into<SizeRange>) -> impl Strategy<Value = AlignedBytes<T>> {
+    let size = mem::size_of::<T>();
+    vec(vec(any::<u8>(), size), len).prop_map(move |elems| {
+        // SAFETY: `Pod` types accept any bit pattern, including zeros.
+        let mut aligned = AlignedBytes {
+            elems: vec![unsafe { mem::zeroed::<T>() }; elems.len()],
+        };
+        if size > 0 {
+            for (dst, src) in aligned.as_bytes_mut().chunks_exact_mut(size).zip(&elems) {
+                dst.copy_from_slice(src);
+            }
+        }
+        aligned
+    })
+}
+
+/// Generates bytes aligned for `T`, holding up to 64 `T`s, see [`aligned_bytes`].
+///
+/// [`aligned_bytes`]: fn.aligned_bytes.html
+#[inline]
+pub fn any_aligned_bytes<T: Pod>() -> impl Strategy<Value = AlignedBytes<T>> {
+    aligned_bytes(0..=64)
+}
+
+/// Generates bytes whose length is a multiple of `elem_size`, holding a number of elements in
+/// `len`, for the typed conversions (`to_*_vec`, the endian views) which reject other lengths.
+pub fn bytes_of_elems(
+    elem_size: usize,
+    len: impl Into<SizeRange>,
+) -> impl Strategy<Value = Vec<u8>> {
+    vec(vec(any::<u8>(), elem_size), len).prop_map(|elems| elems.concat())
+}
@@ -0,0 +1,209 @@
+use crate::{Endian, ReadError, SliceReader};
+
+/// The layout of the entries of a type-length-value encoding, see [`TlvIter`].
+///
+/// [`TlvIter`]: struct.TlvIter.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TlvFormat {
+    type_width: usize,
+    len_width: usize,
+    endian: Endian,
+}
+
+impl TlvFormat {
+    /// Creates a format whose entries start with a `type_width`-byte type and a `len_width`-byte
+    /// length of the value, both unsigned integers in the `endian` byte order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a width is not between 1 and 8.
+    pub const fn new(type_width: usize, len_width: usize, endian: Endian) -> Self {
+        assert!(
+            type_width >= 1 && type_width <= 8,
+            "type width must be between 1 and 8"
+        );
+        assert!(
+            len_width >= 1 && len_width <= 8,
+            "length width must be between 1 and 8"
+        );
+        Self {
+            type_width,
+            len_width,
+            endian,
+        }
+    }
+
+    /// Returns the width of the type in bytes.
+    #[inline]
+    pub const fn type_width(&self) -> usize {
+        self.type_width
+    }
+
+    /// Returns the width of the length in bytes.
+    #[inline]
+    pub const fn len_width(&self) -> usize {
+        self.len_width
+    }
+
+    /// Returns the byte order of the type and the length.
+    #[inline]
+    pub const fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    /// Returns the length of the header of an entry.
+    #[inline]
+    pub const fn header_len(&self) -> usize {
+        self.type_width + self.len_width
+    }
+}
+
+/// An iterator over the `(type, value)` entries of a type-length-value encoding.
+///
+/// It is returned by [`BytesExt::tlv_iter`]. The iterator yields an error, and then stops, if
+/// the header or the value of an entry is truncated; it never reads past the end of the bytes.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::{BytesExt, Endian, TlvFormat};
+///
+/// // Two entries with a one-byte type and a big-endian two-byte length.
+/// let src = b"\x01\x00\x02hi\x07\x00\x00";
+/// let mut entries = src.tlv_iter(TlvFormat::new(1, 2, Endian::Big));
+/// assert_eq!(entries.next(), Some(Ok((1, &b"hi"[..]))));
+/// assert_eq!(entries.next(), Some(Ok((7, &b""[..]))));
+/// assert_eq!(entries.next(), None);
+///
+/// let mut truncated = b"\x01\x00\x05hi".tlv_iter(TlvFormat::new(1, 2, Endian::Big));
+/// assert!(truncated.next().unwrap().is_err());
+/// assert_eq!(truncated.next(), None);
+/// ```
+///
+/// [`BytesExt::tlv_iter`]: trait.BytesExt.html#method.tlv_iter
+#[derive(Debug, Clone)]
+pub struct TlvIter<'a> {
+    reader: SliceReader<'a>,
+    format: TlvFormat,
+    failed: bool,
+}
+
+impl<'a> TlvIter<'a> {
+    /// Creates an iterator over the entries of `src`.
+    #[inline]
+    pub const fn new(src: &'a [u8], format: TlvFormat) -> Self {
+        Self {
+            reader: SliceReader::new(src),
+            format,
+            failed: false,
+        }
+    }
+
+    /// Returns the offset of the next entry.
+    #[inline]
+    pub const fn offset(&self) -> usize {
+        self.reader.offset()
+    }
+
+    /// Returns the bytes of the entries which have not been read.
+    #[inline]
+    pub fn remaining(&self) -> &'a [u8] {
+        self.reader.remaining()
+    }
+
+    fn read_uint(&self, r: &mut SliceReader<'a>, width: usize) -> Result<u64, ReadError> {
+        let bytes = r.take(width)?;
+        let fold = |acc: u64, b: &u8| (acc << 8) | *b as u64;
+        Ok(match self.format.endian {
+            Endian::Big => bytes.iter().fold(0, fold),
+            Endian::Little => bytes.iter().rev().fold(0, fold),
+        })
+    }
+
+    fn read_entry(&self, r: &mut SliceReader<'a>) -> Result<(u64, &'a [u8]), ReadError> {
+        let ty = self.read_uint(r, self.format.type_width)?;
+        let len = self.read_uint(r, self.format.len_width)?;
+        // A length which does not fit `usize` cannot fit the bytes either.
+        let value = r.take(usize::try_from(len).unwrap_or(usize::MAX))?;
+        Ok((ty, value))
+    }
+}
+
+impl<'a> Iterator for TlvIter<'a> {
+    type Item = Result<(u64, &'a [u8]), ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.reader.is_empty() {
+            return None;
+        }
+
+        let mut r = self.reader;
+        let entry = self.read_entry(&mut r);
+        match entry {
+            Ok(_) => self.reader = r,
+            Err(_) => self.failed = true,
+        }
+        Some(entry)
+    }
+}
+
+impl core::iter::FusedIterator for TlvIter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tlv_widths() {
+        // Two-byte little-endian type, three-byte little-endian length.
+        let format = TlvFormat::new(2, 3, Endian::Little);
+        let src = b"\x34\x12\x01\x00\x00x\xff\xff\x00\x00\x00";
+        let entries = TlvIter::new(src, format).collect::<Result<Vec<_>, _>>();
+        assert_eq!(entries, Ok(vec![(0x1234, &b"x"[..]), (0xffff, &b""[..])]));
+        assert_eq!(format.header_len(), 5);
+
+        let format = TlvFormat::new(8, 8, Endian::Big);
+        let mut src = [0u8; 17];
+        src[7] = 9;
+        src[15] = 1;
+        src[16] = b'z';
+        let mut iter = TlvIter::new(&src, format);
+        assert_eq!(iter.next(), Some(Ok((9, &b"z"[..]))));
+        assert_eq!(iter.offset(), 17);
+    }
+
+    #[test]
+    fn test_tlv_truncated() {
+        let format = TlvFormat::new(1, 1, Endian::Big);
+        let mut iter = TlvIter::new(b"\x01\x01a\x02", format);
+        assert_eq!(iter.next(), Some(Ok((1, &b"a"[..]))));
+        assert_eq!(
+            iter.next(),
+            Some(Err(ReadError::UnexpectedEof {
+                offset: 4,
+                needed: 1,
+                remaining: 0
+            }))
+        );
+        assert_eq!(iter.next(), None);
+        // The failed entry is not consumed.
+        assert_eq!(iter.offset(), 3);
+        assert_eq!(iter.remaining(), b"\x02");
+
+        // A huge length fails instead of overflowing.
+        let mut iter = TlvIter::new(
+            &[1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+            TlvFormat::new(1, 8, Endian::Big),
+        );
+        assert!(matches!(
+            iter.next(),
+            Some(Err(ReadError::UnexpectedEof { .. }))
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_tlv_invalid_width() {
+        TlvFormat::new(0, 2, Endian::Big);
+    }
+}
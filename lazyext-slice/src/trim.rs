@@ -0,0 +1,68 @@
+/// Returns the length of the UTF-8 sequence starting with `first`, or `None` if it is not a leading byte.
+#[inline]
+fn utf8_width(first: u8) -> Option<usize> {
+    match first {
+        0x00..=0x7f => Some(1),
+        0xc2..=0xdf => Some(2),
+        0xe0..=0xef => Some(3),
+        0xf0..=0xf4 => Some(4),
+        _ => None,
+    }
+}
+
+/// Decodes `src` as exactly one char.
+#[inline]
+fn decode_char(src: &[u8]) -> Option<char> {
+    let mut chars = core::str::from_utf8(src).ok()?.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+/// Trims the leading and trailing whitespace of `src`: any Unicode whitespace char which is valid
+/// UTF-8, and ASCII whitespace otherwise.
+pub(crate) fn trim_utf8_whitespace(mut src: &[u8]) -> &[u8] {
+    while let Some(&first) = src.first() {
+        let w = match utf8_width(first) {
+            Some(w) if w <= src.len() => w,
+            _ => break,
+        };
+        match decode_char(&src[..w]) {
+            Some(c) if c.is_whitespace() => src = &src[w..],
+            _ => break,
+        }
+    }
+
+    while !src.is_empty() {
+        // a char ends the slice if its leading byte is among the last 4 bytes
+        let start = (src.len().saturating_sub(4)..src.len())
+            .rev()
+            .find(|&i| src[i] & 0xc0 != 0x80)
+            .unwrap_or(src.len() - 1);
+        match decode_char(&src[start..]) {
+            Some(c) if c.is_whitespace() => src = &src[..start],
+            _ => break,
+        }
+    }
+    src
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trim_utf8_whitespace() {
+        assert_eq!(trim_utf8_whitespace(b" \t\r\nabc \x0c"), b"abc");
+        // no-break space, ideographic space and line separator
+        let src = "\u{a0}\u{3000}héllo wörld\u{2028}".as_bytes();
+        assert_eq!(trim_utf8_whitespace(src), "héllo wörld".as_bytes());
+        // invalid UTF-8 at the edges stops the trimming
+        assert_eq!(trim_utf8_whitespace(b" \xff abc \x80 "), b"\xff abc \x80");
+        assert_eq!(trim_utf8_whitespace(b"\xe3\x80 x"), b"\xe3\x80 x");
+        assert_eq!(trim_utf8_whitespace(b" \xe3\x80"), b"\xe3\x80");
+        assert_eq!(trim_utf8_whitespace(b" \t "), b"");
+        assert_eq!(trim_utf8_whitespace(b""), b"");
+    }
+}
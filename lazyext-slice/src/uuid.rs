@@ -0,0 +1,170 @@
+use core::fmt;
+
+/// The variant of a UUID, encoded in the most significant bits of its 9th byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UuidVariant {
+    /// Reserved, NCS backward compatibility.
+    Ncs,
+    /// The variant of RFC 4122 (and RFC 9562), used by almost all UUIDs.
+    Rfc4122,
+    /// Reserved, Microsoft Corporation backward compatibility.
+    Microsoft,
+    /// Reserved for future definition.
+    Future,
+}
+
+impl UuidVariant {
+    pub(crate) fn from_byte(b: u8) -> Self {
+        match b {
+            0x00..=0x7f => Self::Ncs,
+            0x80..=0xbf => Self::Rfc4122,
+            0xc0..=0xdf => Self::Microsoft,
+            _ => Self::Future,
+        }
+    }
+}
+
+/// The error returned by [`parse_uuid`].
+///
+/// [`parse_uuid`]: fn.parse_uuid.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseUuidError {
+    /// The input has an unexpected length (without braces).
+    InvalidLength(usize),
+    /// The input contains a non-hex character at the given index.
+    InvalidCharacter {
+        /// The index of the character in the input.
+        index: usize,
+        /// The character.
+        byte: u8,
+    },
+    /// The hyphens of a hyphenated UUID are not at the expected positions.
+    InvalidGroups,
+}
+
+impl fmt::Display for ParseUuidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength(len) => {
+                write!(f, "invalid uuid length {}, expected 32 or 36", len)
+            }
+            Self::InvalidCharacter { index, byte } => {
+                write!(f, "invalid uuid character {:?} at {}", *byte as char, index)
+            }
+            Self::InvalidGroups => f.write_str("invalid uuid groups, expected 8-4-4-4-12"),
+        }
+    }
+}
+
+cfg_feature!("std", {
+    impl std::error::Error for ParseUuidError {}
+});
+
+/// Positions of the hyphens in the hyphenated form.
+const HYPHENS: [usize; 4] = [8, 13, 18, 23];
+
+/// Parses a UUID in the simple (`32` hex digits) or hyphenated (`8-4-4-4-12`) form,
+/// optionally wrapped in braces. Hex digits are case-insensitive.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::{parse_uuid, BytesExt};
+///
+/// let id = parse_uuid("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+/// assert_eq!(id[0], 0x67);
+/// assert_eq!(id.uuid_version(), 4);
+/// assert_eq!(parse_uuid("{67E5504410B1426F9247BB680E5FE0C8}").unwrap(), id);
+/// assert!(parse_uuid("67e55044").is_err());
+/// ```
+pub fn parse_uuid(src: impl crate::AsBytesRef) -> Result<[u8; 16], ParseUuidError> {
+    let mut src = src.as_bytes_ref();
+    let mut offset = 0;
+    if let [b'{', inner @ .., b'}'] = src {
+        src = inner;
+        offset = 1;
+    }
+
+    let hyphenated = match src.len() {
+        32 => false,
+        36 => true,
+        len => return Err(ParseUuidError::InvalidLength(len)),
+    };
+
+    let mut out = [0u8; 16];
+    let mut digits = 0;
+    for (i, &b) in src.iter().enumerate() {
+        if hyphenated && HYPHENS.contains(&i) {
+            if b != b'-' {
+                return Err(ParseUuidError::InvalidGroups);
+            }
+            continue;
+        }
+
+        let v = match b {
+            b'0'..=b'9' => b - b'0',
+            b'a'..=b'f' => b - b'a' + 10,
+            b'A'..=b'F' => b - b'A' + 10,
+            b'-' if hyphenated => return Err(ParseUuidError::InvalidGroups),
+            _ => {
+                return Err(ParseUuidError::InvalidCharacter {
+                    index: i + offset,
+                    byte: b,
+                })
+            }
+        };
+        out[digits / 2] |= v << (4 * (1 - digits % 2));
+        digits += 1;
+    }
+    Ok(out)
+}
+
+/// Writes the 16 bytes as a lowercase hyphenated UUID.
+#[cfg(feature = "alloc")]
+pub(crate) fn write_uuid(src: &[u8], f: &mut impl fmt::Write) -> fmt::Result {
+    for (i, b) in src.iter().enumerate() {
+        if matches!(i, 4 | 6 | 8 | 10) {
+            f.write_char('-')?;
+        }
+        write!(f, "{:02x}", b)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BytesExt;
+
+    #[test]
+    fn test_uuid_roundtrip() {
+        let s = "a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8";
+        let id = parse_uuid(s).unwrap();
+        assert_eq!(
+            id,
+            [
+                0xa1, 0xa2, 0xa3, 0xa4, 0xb1, 0xb2, 0xc1, 0xc2, 0xd1, 0xd2, 0xd3, 0xd4, 0xd5, 0xd6,
+                0xd7, 0xd8
+            ]
+        );
+        assert_eq!(id.as_uuid_string(), s);
+        assert_eq!(id.uuid_version(), 0xc);
+        assert_eq!(id.uuid_variant(), UuidVariant::Microsoft);
+    }
+
+    #[test]
+    fn test_parse_uuid_errors() {
+        assert_eq!(parse_uuid(""), Err(ParseUuidError::InvalidLength(0)));
+        assert_eq!(
+            parse_uuid("a1a2a3a4-b1b2-c1c2-d1d2d-3d4d5d6d7d8"),
+            Err(ParseUuidError::InvalidGroups)
+        );
+        assert_eq!(
+            parse_uuid("{a1a2a3a4b1b2c1c2d1d2d3d4d5d6d7dx}"),
+            Err(ParseUuidError::InvalidCharacter {
+                index: 32,
+                byte: b'x'
+            })
+        );
+    }
+}
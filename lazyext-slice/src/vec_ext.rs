@@ -0,0 +1,256 @@
+use alloc::vec::Vec;
+use core::mem::{self, MaybeUninit};
+
+/// The memory usage of a `Vec`, returned by [`VecExt::memory_footprint`].
+///
+/// [`VecExt::memory_footprint`]: trait.VecExt.html#method.memory_footprint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MemoryFootprint {
+    /// The number of elements.
+    pub len: usize,
+    /// The number of elements the vec can hold without reallocating.
+    pub capacity: usize,
+    /// The size of the heap allocation in bytes (`capacity` times the size of the elements).
+    pub bytes: usize,
+}
+
+/// Extensions for `Vec`, e.g. maintaining small sorted vectors of keys/offsets without a `BTreeMap`.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::VecExt;
+///
+/// let mut offsets = vec![10, 30];
+/// offsets.insert_sorted(20);
+/// assert!(!offsets.binary_insert_dedup(30));
+/// assert_eq!(offsets, [10, 20, 30]);
+/// ```
+pub trait VecExt<T> {
+    /// Inserts `item` after all the elements less than or equal to it, keeping a sorted vec sorted.
+    /// Returns the index of the inserted element.
+    fn insert_sorted(&mut self, item: T) -> usize
+    where
+        T: Ord;
+
+    /// Inserts `item` after all the elements whose key is less than or equal to its key,
+    /// keeping a vec sorted by `f` sorted. Returns the index of the inserted element.
+    fn insert_sorted_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, item: T, f: F) -> usize;
+
+    /// Inserts `item` into a sorted vec unless an equal element is already there.
+    /// Returns whether `item` was inserted.
+    fn binary_insert_dedup(&mut self, item: T) -> bool
+    where
+        T: Ord;
+
+    /// Returns whether inserting `item` at `index` keeps a sorted vec sorted.
+    fn is_sorted_insertion_point(&self, index: usize, item: &T) -> bool
+    where
+        T: Ord;
+
+    /// Removes and returns the first element matching `pred`, preserving the order of the others.
+    fn remove_first_where<F: FnMut(&T) -> bool>(&mut self, pred: F) -> Option<T>;
+
+    /// Removes and returns the first element matching `pred`, replacing it with the last element.
+    ///
+    /// This does not preserve ordering, but is O(1) once the element is found.
+    fn swap_remove_where<F: FnMut(&T) -> bool>(&mut self, pred: F) -> Option<T>;
+
+    /// Removes all the elements matching `pred` and returns them.
+    ///
+    /// Both the removed and the remaining elements keep their relative order
+    /// (like the unstable `Vec::extract_if`), and the vec keeps its capacity.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::VecExt;
+    ///
+    /// let mut sessions = vec![1, 2, 3, 4, 5, 6];
+    /// let expired = sessions.drain_where(|id| id % 2 == 0);
+    /// assert_eq!(expired, [2, 4, 6]);
+    /// assert_eq!(sessions, [1, 3, 5]);
+    /// ```
+    fn drain_where<F: FnMut(&T) -> bool>(&mut self, pred: F) -> Vec<T>;
+
+    /// Shrinks the capacity to fit the length when more than `ratio` of the capacity is unused,
+    /// e.g. `0.5` shrinks a buffer once less than half of it is used. Returns whether it shrank.
+    ///
+    /// This is a policy hook for long-lived buffers which balloon after transient spikes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::VecExt;
+    ///
+    /// let mut buf = Vec::<u8>::with_capacity(1024);
+    /// buf.extend_from_slice(b"small");
+    /// assert!(buf.shrink_if_slack_over(0.5));
+    /// assert!(buf.capacity() < 1024);
+    /// assert!(!buf.shrink_if_slack_over(0.5));
+    /// ```
+    fn shrink_if_slack_over(&mut self, ratio: f64) -> bool;
+
+    /// Reserves capacity so at least `n` more elements fit without reallocating,
+    /// and returns the spare capacity to be filled directly.
+    fn with_spare_capacity_at_least(&mut self, n: usize) -> &mut [MaybeUninit<T>];
+
+    /// Reports the length, capacity and allocated bytes of the vec.
+    fn memory_footprint(&self) -> MemoryFootprint;
+}
+
+impl<T> VecExt<T> for Vec<T> {
+    fn insert_sorted(&mut self, item: T) -> usize
+    where
+        T: Ord,
+    {
+        let idx = self.partition_point(|v| v <= &item);
+        self.insert(idx, item);
+        idx
+    }
+
+    fn insert_sorted_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, item: T, mut f: F) -> usize {
+        let key = f(&item);
+        let idx = self.partition_point(|v| f(v) <= key);
+        self.insert(idx, item);
+        idx
+    }
+
+    fn binary_insert_dedup(&mut self, item: T) -> bool
+    where
+        T: Ord,
+    {
+        match self.binary_search(&item) {
+            Ok(_) => false,
+            Err(idx) => {
+                self.insert(idx, item);
+                true
+            }
+        }
+    }
+
+    fn is_sorted_insertion_point(&self, index: usize, item: &T) -> bool
+    where
+        T: Ord,
+    {
+        index <= self.len()
+            && (index == 0 || &self[index - 1] <= item)
+            && (index == self.len() || item <= &self[index])
+    }
+
+    fn remove_first_where<F: FnMut(&T) -> bool>(&mut self, pred: F) -> Option<T> {
+        self.iter().position(pred).map(|idx| self.remove(idx))
+    }
+
+    fn swap_remove_where<F: FnMut(&T) -> bool>(&mut self, pred: F) -> Option<T> {
+        self.iter().position(pred).map(|idx| self.swap_remove(idx))
+    }
+
+    fn drain_where<F: FnMut(&T) -> bool>(&mut self, mut pred: F) -> Vec<T> {
+        let cap = self.capacity();
+        let mut removed = Vec::new();
+        for item in core::mem::replace(self, Vec::with_capacity(cap)) {
+            if pred(&item) {
+                removed.push(item);
+            } else {
+                self.push(item);
+            }
+        }
+        removed
+    }
+
+    fn shrink_if_slack_over(&mut self, ratio: f64) -> bool {
+        let slack = self.capacity() - self.len();
+        if slack == 0 || (slack as f64) <= self.capacity() as f64 * ratio {
+            return false;
+        }
+        self.shrink_to_fit();
+        true
+    }
+
+    #[inline]
+    fn with_spare_capacity_at_least(&mut self, n: usize) -> &mut [MaybeUninit<T>] {
+        self.reserve(n);
+        self.spare_capacity_mut()
+    }
+
+    #[inline]
+    fn memory_footprint(&self) -> MemoryFootprint {
+        MemoryFootprint {
+            len: self.len(),
+            capacity: self.capacity(),
+            bytes: self.capacity() * mem::size_of::<T>(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_sorted() {
+        let mut v = Vec::new();
+        for x in [5, 1, 4, 1, 3] {
+            let idx = v.insert_sorted(x);
+            assert_eq!(v[idx], x);
+        }
+        assert_eq!(v, [1, 1, 3, 4, 5]);
+
+        let mut pairs = vec![(1, "a"), (3, "c")];
+        assert_eq!(pairs.insert_sorted_by_key((1, "b"), |p| p.0), 1);
+        assert_eq!(pairs, [(1, "a"), (1, "b"), (3, "c")]);
+    }
+
+    #[test]
+    fn test_binary_insert_dedup() {
+        let mut v = vec![1, 3];
+        assert!(v.binary_insert_dedup(2));
+        assert!(!v.binary_insert_dedup(3));
+        assert_eq!(v, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_remove_where() {
+        let mut v = vec![1, 2, 3, 2, 5];
+        assert_eq!(v.remove_first_where(|x| *x == 2), Some(2));
+        assert_eq!(v, [1, 3, 2, 5]);
+        assert_eq!(v.swap_remove_where(|x| *x == 1), Some(1));
+        assert_eq!(v, [5, 3, 2]);
+        assert_eq!(v.remove_first_where(|x| *x == 9), None);
+        assert_eq!(v.swap_remove_where(|x| *x == 9), None);
+
+        let mut v = (0..10).collect::<Vec<_>>();
+        let cap = v.capacity();
+        assert_eq!(v.drain_where(|x| x % 3 == 0), [0, 3, 6, 9]);
+        assert_eq!(v, [1, 2, 4, 5, 7, 8]);
+        assert_eq!(v.capacity(), cap);
+    }
+
+    #[test]
+    fn test_capacity_helpers() {
+        let mut v = Vec::<u32>::with_capacity(100);
+        v.extend([1, 2, 3]);
+        assert!(!v.shrink_if_slack_over(0.99));
+        assert!(v.shrink_if_slack_over(0.9));
+        assert_eq!(v.memory_footprint().len, 3);
+        assert_eq!(v.memory_footprint().bytes, v.capacity() * 4);
+
+        let spare = v.with_spare_capacity_at_least(10);
+        assert!(spare.len() >= 10);
+        spare[0].write(4);
+        unsafe { v.set_len(4) };
+        assert_eq!(v, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_is_sorted_insertion_point() {
+        let v = vec![1, 3, 3, 5];
+        assert!(v.is_sorted_insertion_point(0, &0));
+        assert!(v.is_sorted_insertion_point(1, &3));
+        assert!(v.is_sorted_insertion_point(3, &3));
+        assert!(v.is_sorted_insertion_point(4, &5));
+        assert!(!v.is_sorted_insertion_point(4, &4));
+        assert!(!v.is_sorted_insertion_point(5, &9));
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{AsBytesRef, BytesExt};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// The source of the generations of all the [`VersionedBytes`], so a [`SnapshotToken`] is never
+/// valid for another buffer than the one it was taken from.
+///
+/// [`VersionedBytes`]: struct.VersionedBytes.html
+/// [`SnapshotToken`]: struct.SnapshotToken.html
+static NEXT_GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+#[inline]
+fn next_generation() -> usize {
+    NEXT_GENERATION.fetch_add(1, Ordering::Relaxed)
+}
+
+/// A buffer paired with a generation which changes whenever the buffer may be recycled or
+/// modified, so long-lived readers can detect that the bytes they refer to are gone.
+///
+/// Borrowed [`Snapshot`]s are checked by the compiler. Readers which must not borrow the buffer,
+/// e.g. iterators stored next to it, keep a [`SnapshotToken`] (and their own offsets) instead and
+/// [`resolve`] it before each access: a stale token resolves to `None` instead of silently
+/// reading the bytes of the next user of the buffer.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::VersionedBytes;
+///
+/// let mut buf = VersionedBytes::new(b"first".to_vec());
+/// let token = buf.snapshot().token();
+/// assert_eq!(buf.resolve(token).unwrap().as_bytes(), b"first");
+///
+/// let old = buf.recycle(b"second".to_vec());
+/// assert_eq!(old, b"first");
+/// assert!(buf.resolve(token).is_none());
+/// ```
+///
+/// [`Snapshot`]: struct.Snapshot.html
+/// [`SnapshotToken`]: struct.SnapshotToken.html
+/// [`resolve`]: struct.VersionedBytes.html#method.resolve
+#[derive(Debug)]
+pub struct VersionedBytes<B> {
+    buf: B,
+    generation: usize,
+}
+
+impl<B: AsBytesRef> VersionedBytes<B> {
+    /// Wraps `buf` in a new generation.
+    #[inline]
+    pub fn new(buf: B) -> Self {
+        Self {
+            buf,
+            generation: next_generation(),
+        }
+    }
+
+    /// Returns the current generation.
+    #[inline]
+    pub const fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Returns a view of the bytes tagged with the current generation.
+    #[inline]
+    pub fn snapshot(&self) -> Snapshot<'_> {
+        Snapshot {
+            bytes: self.buf.as_bytes_ref(),
+            generation: self.generation,
+        }
+    }
+
+    /// Returns whether `token` was taken in the current generation.
+    #[inline]
+    pub fn is_current(&self, token: SnapshotToken) -> bool {
+        token.generation == self.generation
+    }
+
+    /// Returns a view of the bytes if `token` was taken in the current generation, or `None`
+    /// if the buffer has been recycled or modified since.
+    #[inline]
+    pub fn resolve(&self, token: SnapshotToken) -> Option<Snapshot<'_>> {
+        self.is_current(token).then(|| self.snapshot())
+    }
+
+    /// Returns the buffer for modification, starting a new generation so the tokens taken so
+    /// far become stale.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut B {
+        self.generation = next_generation();
+        &mut self.buf
+    }
+
+    /// Replaces the buffer with `buf` in a new generation, and returns the old one, e.g. to
+    /// give it back to a pool.
+    #[inline]
+    pub fn recycle(&mut self, buf: B) -> B {
+        self.generation = next_generation();
+        core::mem::replace(&mut self.buf, buf)
+    }
+
+    /// Returns the buffer.
+    #[inline]
+    pub fn into_inner(self) -> B {
+        self.buf
+    }
+}
+
+/// A view of the bytes of a [`VersionedBytes`] in one generation.
+///
+/// [`VersionedBytes`]: struct.VersionedBytes.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snapshot<'a> {
+    bytes: &'a [u8],
+    generation: usize,
+}
+
+impl<'a> Snapshot<'a> {
+    /// Returns the bytes.
+    #[inline]
+    pub const fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Returns the generation the view was taken in.
+    #[inline]
+    pub const fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Returns a token which does not borrow the buffer, to be resolved again with
+    /// [`VersionedBytes::resolve`].
+    ///
+    /// [`VersionedBytes::resolve`]: struct.VersionedBytes.html#method.resolve
+    #[inline]
+    pub const fn token(&self) -> SnapshotToken {
+        SnapshotToken {
+            generation: self.generation,
+        }
+    }
+}
+
+impl AsBytesRef for Snapshot<'_> {
+    #[inline]
+    fn as_bytes_ref(&self) -> &[u8] {
+        self.bytes
+    }
+}
+
+impl BytesExt for Snapshot<'_> {}
+
+/// The generation of a [`Snapshot`], kept instead of the snapshot by readers which must not
+/// borrow the buffer.
+///
+/// [`Snapshot`]: struct.Snapshot.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SnapshotToken {
+    generation: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generations() {
+        let mut buf = VersionedBytes::new([1u8, 2, 3]);
+        let snap = buf.snapshot();
+        assert_eq!(snap.as_bytes(), [1, 2, 3]);
+        assert!(snap.has_prefix([1u8]));
+        let token = snap.token();
+        assert!(buf.is_current(token));
+
+        buf.get_mut()[0] = 9;
+        assert!(!buf.is_current(token));
+        assert_eq!(
+            buf.resolve(buf.snapshot().token()).unwrap().as_bytes(),
+            [9, 2, 3]
+        );
+        assert_eq!(buf.into_inner(), [9, 2, 3]);
+    }
+
+    #[test]
+    fn test_tokens_are_per_buffer() {
+        let a = VersionedBytes::new(&b"a"[..]);
+        let b = VersionedBytes::new(&b"b"[..]);
+        assert_ne!(a.generation(), b.generation());
+        assert!(b.resolve(a.snapshot().token()).is_none());
+    }
+}
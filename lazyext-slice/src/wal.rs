@@ -0,0 +1,453 @@
+//! A write-ahead-log record codec in the log format of LevelDB and RocksDB: the log is a
+//! sequence of fixed-size blocks, and records are split into fragments which never cross a
+//! block boundary.
+//!
+//! Each fragment is `crc32 (4) | len (2) | type (1) | payload`, little-endian, with the CRC-32
+//! covering the type and the payload. Block tails too short for a header are zero-padded.
+//! The checksum is the plain CRC-32 of [`Crc32`], so logs are not byte-compatible with
+//! LevelDB's masked CRC-32C.
+//!
+//! [`Crc32`]: struct.Crc32.html
+
+use crate::{AsBytesRef, Checksum, Crc32, SliceReader, VecByteWriterExt};
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+
+/// The default block size of the log, 32 KiB.
+pub const WAL_BLOCK_SIZE: usize = 32 * 1024;
+
+/// The size of the header of a fragment.
+pub const WAL_HEADER_LEN: usize = 7;
+
+/// The type of a fragment, telling how it relates to the record it belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum WalRecordType {
+    /// A whole record.
+    Full = 1,
+    /// The first fragment of a record.
+    First = 2,
+    /// A fragment in the middle of a record.
+    Middle = 3,
+    /// The last fragment of a record.
+    Last = 4,
+}
+
+impl WalRecordType {
+    fn from_u8(b: u8) -> Option<Self> {
+        match b {
+            1 => Some(Self::Full),
+            2 => Some(Self::First),
+            3 => Some(Self::Middle),
+            4 => Some(Self::Last),
+            _ => None,
+        }
+    }
+}
+
+/// The error yielded by [`WalReader`] when the log is truncated or corrupted. The offsets are
+/// the ones of the fragments in the log.
+///
+/// [`WalReader`]: struct.WalReader.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalError {
+    /// The log ends in the middle of a fragment or of a fragmented record, e.g. after a crash
+    /// during a write.
+    Truncated {
+        /// The offset of the incomplete fragment or record.
+        offset: usize,
+    },
+    /// The checksum of the fragment does not match.
+    ChecksumMismatch {
+        /// The offset of the fragment.
+        offset: usize,
+    },
+    /// The fragment has an unknown type, or its length crosses the end of its block.
+    Corrupted {
+        /// The offset of the fragment.
+        offset: usize,
+    },
+    /// A `Middle` or `Last` fragment without a `First`, or a `First` or `Full` fragment inside
+    /// a fragmented record.
+    UnexpectedFragment {
+        /// The offset of the fragment.
+        offset: usize,
+        /// The type of the fragment.
+        ty: WalRecordType,
+    },
+}
+
+impl fmt::Display for WalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated { offset } => write!(f, "wal truncated at offset {}", offset),
+            Self::ChecksumMismatch { offset } => {
+                write!(f, "wal checksum mismatch at offset {}", offset)
+            }
+            Self::Corrupted { offset } => write!(f, "corrupted wal fragment at offset {}", offset),
+            Self::UnexpectedFragment { offset, ty } => {
+                write!(f, "unexpected {:?} wal fragment at offset {}", ty, offset)
+            }
+        }
+    }
+}
+
+cfg_feature!("std", {
+    impl std::error::Error for WalError {}
+});
+
+/// A fragment read from the log: its offset, type and payload.
+type Fragment<'a> = (usize, WalRecordType, &'a [u8]);
+
+#[inline]
+fn fragment_crc(ty: u8, payload: &[u8]) -> u32 {
+    let mut crc = Crc32::default();
+    crc.update(&[ty]);
+    crc.update(payload);
+    crc.finalize()
+}
+
+#[inline]
+fn check_block_size(block_size: usize) {
+    assert!(
+        block_size > WAL_HEADER_LEN && block_size <= WAL_HEADER_LEN + u16::MAX as usize,
+        "invalid wal block size {}",
+        block_size
+    );
+}
+
+/// Encodes records into the log, splitting them across blocks.
+///
+/// The writer only tracks the position in the log, the fragments are appended to the `Vec<u8>`
+/// passed to [`append`], which can be flushed and reused between calls.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::{WalReader, WalWriter};
+///
+/// let mut w = WalWriter::with_block_size(64);
+/// let mut log = Vec::new();
+/// w.append(&mut log, "put a 1");
+/// w.append(&mut log, [0xab; 100]);
+///
+/// let records = WalReader::with_block_size(&log, 64)
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+/// assert_eq!(records[0], &b"put a 1"[..]);
+/// assert_eq!(records[1], &[0xab; 100][..]);
+/// ```
+///
+/// [`append`]: struct.WalWriter.html#method.append
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WalWriter {
+    block_size: usize,
+    offset: u64,
+}
+
+impl Default for WalWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WalWriter {
+    /// Creates a writer at the start of a log with blocks of [`WAL_BLOCK_SIZE`].
+    ///
+    /// [`WAL_BLOCK_SIZE`]: constant.WAL_BLOCK_SIZE.html
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            block_size: WAL_BLOCK_SIZE,
+            offset: 0,
+        }
+    }
+
+    /// Creates a writer at the start of a log with blocks of `block_size` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_size` is not greater than the header size, or leaves more than
+    /// `u16::MAX` bytes for the payload of a fragment.
+    #[inline]
+    pub fn with_block_size(block_size: usize) -> Self {
+        Self::resume(block_size, 0)
+    }
+
+    /// Creates a writer continuing a log which is `offset` bytes long.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_size` is invalid, see [`with_block_size`].
+    ///
+    /// [`with_block_size`]: struct.WalWriter.html#method.with_block_size
+    pub fn resume(block_size: usize, offset: u64) -> Self {
+        check_block_size(block_size);
+        Self { block_size, offset }
+    }
+
+    /// Returns the length of the log written so far.
+    #[inline]
+    pub const fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Appends the fragments of `payload` (and the padding of any block tail it skips) to `dst`,
+    /// and returns the number of bytes appended.
+    pub fn append(&mut self, dst: &mut Vec<u8>, payload: impl AsBytesRef) -> usize {
+        let start = dst.len();
+        let mut left = payload.as_bytes_ref();
+        let mut begin = true;
+        loop {
+            let leftover = self.block_size - (self.offset % self.block_size as u64) as usize;
+            if leftover < WAL_HEADER_LEN {
+                dst.resize(dst.len() + leftover, 0);
+                self.offset += leftover as u64;
+                continue;
+            }
+
+            let avail = leftover - WAL_HEADER_LEN;
+            let (fragment, rest) = left.split_at(left.len().min(avail));
+            let ty = match (begin, rest.is_empty()) {
+                (true, true) => WalRecordType::Full,
+                (true, false) => WalRecordType::First,
+                (false, true) => WalRecordType::Last,
+                (false, false) => WalRecordType::Middle,
+            } as u8;
+            dst.push_u32_le(fragment_crc(ty, fragment));
+            dst.push_u16_le(fragment.len() as u16);
+            dst.push(ty);
+            dst.push_slice(fragment);
+            self.offset += (WAL_HEADER_LEN + fragment.len()) as u64;
+
+            if rest.is_empty() {
+                return dst.len() - start;
+            }
+            left = rest;
+            begin = false;
+        }
+    }
+}
+
+/// An iterator over the records of a log written by [`WalWriter`], reassembling fragmented
+/// records.
+///
+/// Whole records are borrowed from the log, fragmented ones are copied. The iterator stops
+/// after the first error.
+///
+/// [`WalWriter`]: struct.WalWriter.html
+#[derive(Debug, Clone)]
+pub struct WalReader<'a> {
+    src: &'a [u8],
+    block_size: usize,
+    offset: usize,
+    done: bool,
+}
+
+impl<'a> WalReader<'a> {
+    /// Creates a reader over a log with blocks of [`WAL_BLOCK_SIZE`].
+    ///
+    /// [`WAL_BLOCK_SIZE`]: constant.WAL_BLOCK_SIZE.html
+    #[inline]
+    pub fn new(src: &'a [u8]) -> Self {
+        Self::with_block_size(src, WAL_BLOCK_SIZE)
+    }
+
+    /// Creates a reader over a log with blocks of `block_size` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_size` is invalid, see [`WalWriter::with_block_size`].
+    ///
+    /// [`WalWriter::with_block_size`]: struct.WalWriter.html#method.with_block_size
+    pub fn with_block_size(src: &'a [u8], block_size: usize) -> Self {
+        check_block_size(block_size);
+        Self {
+            src,
+            block_size,
+            offset: 0,
+            done: false,
+        }
+    }
+
+    /// Returns the offset of the next fragment to read.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Reads the next fragment, `None` at the end of the log.
+    fn next_fragment(&mut self) -> Option<Result<Fragment<'a>, WalError>> {
+        loop {
+            let leftover = self.block_size - self.offset % self.block_size;
+            if leftover < WAL_HEADER_LEN {
+                self.offset += leftover;
+                continue;
+            }
+            if self.offset >= self.src.len() {
+                return None;
+            }
+
+            let offset = self.offset;
+            let mut r = SliceReader::new(&self.src[offset..]);
+            let header = (r.read_u32_le(), r.read_u16_le(), r.read_u8());
+            let (crc, len, ty) = match header {
+                (Ok(crc), Ok(len), Ok(ty)) => (crc, len as usize, ty),
+                _ => return Some(Err(WalError::Truncated { offset })),
+            };
+            // A zeroed header is the padding of a preallocated log.
+            if ty == 0 && len == 0 {
+                self.offset += leftover;
+                continue;
+            }
+            if WAL_HEADER_LEN + len > leftover {
+                return Some(Err(WalError::Corrupted { offset }));
+            }
+            let payload = match r.take(len) {
+                Ok(payload) => payload,
+                Err(_) => return Some(Err(WalError::Truncated { offset })),
+            };
+            if fragment_crc(ty, payload) != crc {
+                return Some(Err(WalError::ChecksumMismatch { offset }));
+            }
+            let ty = match WalRecordType::from_u8(ty) {
+                Some(ty) => ty,
+                None => return Some(Err(WalError::Corrupted { offset })),
+            };
+            self.offset += WAL_HEADER_LEN + len;
+            return Some(Ok((offset, ty, payload)));
+        }
+    }
+
+    fn next_record(&mut self) -> Option<Result<Cow<'a, [u8]>, WalError>> {
+        let mut record: Option<(usize, Vec<u8>)> = None;
+        loop {
+            let (offset, ty, payload) = match self.next_fragment() {
+                Some(Ok(fragment)) => fragment,
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    return record.map(|(offset, _)| Err(WalError::Truncated { offset }));
+                }
+            };
+            match (ty, record.as_mut()) {
+                (WalRecordType::Full, None) => return Some(Ok(Cow::Borrowed(payload))),
+                (WalRecordType::First, None) => record = Some((offset, payload.to_vec())),
+                (WalRecordType::Middle, Some((_, buf))) => buf.extend_from_slice(payload),
+                (WalRecordType::Last, Some((_, buf))) => {
+                    buf.extend_from_slice(payload);
+                    return record.map(|(_, buf)| Ok(Cow::Owned(buf)));
+                }
+                (ty, _) => return Some(Err(WalError::UnexpectedFragment { offset, ty })),
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for WalReader<'a> {
+    type Item = Result<Cow<'a, [u8]>, WalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.next_record();
+        if !matches!(item, Some(Ok(_))) {
+            self.done = true;
+        }
+        item
+    }
+}
+
+impl FusedIterator for WalReader<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(block_size: usize, records: &[&[u8]]) -> Vec<u8> {
+        let mut w = WalWriter::with_block_size(block_size);
+        let mut log = Vec::new();
+        for r in records {
+            w.append(&mut log, *r);
+        }
+        assert_eq!(w.offset(), log.len() as u64);
+        log
+    }
+
+    #[test]
+    fn test_wal_fragments() {
+        // 16-byte blocks leave 9 bytes of payload per fragment.
+        let log = write(16, &[b"abc", &[1; 20], b""]);
+        // "abc" takes 10 bytes, the 6-byte tail is too short for a header and is padded.
+        assert_eq!(&log[10..16], &[0; 6]);
+        assert_eq!(log[16 + 6], WalRecordType::First as u8);
+        assert_eq!(log[32 + 6], WalRecordType::Middle as u8);
+        assert_eq!(log[48 + 6], WalRecordType::Last as u8);
+
+        let records = WalReader::with_block_size(&log, 16)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(records.len(), 3);
+        assert!(matches!(records[0], Cow::Borrowed(b"abc")));
+        assert_eq!(records[1], &[1; 20][..]);
+        assert!(records[2].is_empty());
+    }
+
+    #[test]
+    fn test_wal_resume() {
+        let mut w = WalWriter::with_block_size(16);
+        let mut log = Vec::new();
+        w.append(&mut log, [2; 12]);
+        let mut tail = Vec::new();
+        let mut w = WalWriter::resume(16, w.offset());
+        w.append(&mut tail, [3; 4]);
+        log.extend_from_slice(&tail);
+        let records = WalReader::with_block_size(&log, 16).collect::<Vec<_>>();
+        assert_eq!(
+            records,
+            [Ok(Cow::from(&[2; 12][..])), Ok(Cow::from(&[3; 4][..]))]
+        );
+    }
+
+    #[test]
+    fn test_wal_errors() {
+        let log = write(16, &[b"abc", &[1; 20]]);
+
+        let mut r = WalReader::with_block_size(&log[..40], 16);
+        assert!(r.next().unwrap().is_ok());
+        assert_eq!(r.next(), Some(Err(WalError::Truncated { offset: 32 })));
+        assert_eq!(r.next(), None);
+
+        let mut r = WalReader::with_block_size(&log[..48], 16);
+        r.next();
+        assert_eq!(r.next(), Some(Err(WalError::Truncated { offset: 16 })));
+
+        let mut corrupted = log.clone();
+        corrupted[20] ^= 1;
+        let mut r = WalReader::with_block_size(&corrupted, 16);
+        r.next();
+        assert_eq!(
+            r.next(),
+            Some(Err(WalError::ChecksumMismatch { offset: 16 }))
+        );
+
+        let mut r = WalReader::with_block_size(&log[32..], 16);
+        assert_eq!(
+            r.next(),
+            Some(Err(WalError::UnexpectedFragment {
+                offset: 0,
+                ty: WalRecordType::Middle
+            }))
+        );
+    }
+
+    #[test]
+    fn test_wal_zero_padding() {
+        let mut log = write(16, &[b"abc"]);
+        log.resize(48, 0);
+        let records = WalReader::with_block_size(&log, 16).collect::<Vec<_>>();
+        assert_eq!(records, [Ok(Cow::from(&b"abc"[..]))]);
+    }
+}
@@ -0,0 +1,500 @@
+use crate::{AsBytesRef, BLOCK_HEADER_LEN};
+use alloc::vec::Vec;
+use core::mem::{self, MaybeUninit};
+#[cfg(feature = "std")]
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4};
+
+macro_rules! push_x_impl_suite {
+    ($([$ty:ty, $ty_literal: literal]), +$(,)?) => {
+        $(
+        paste! {
+            #[doc = concat!("Appends a ", $ty_literal, " in big-endian")]
+            fn [<push_ $ty _be>](&mut self, v: $ty);
+
+            #[doc = concat!("Appends a ", $ty_literal, " in little-endian")]
+            fn [<push_ $ty _le>](&mut self, v: $ty);
+
+            #[doc = concat!("Appends a ", $ty_literal, " in native-endian")]
+            fn [<push_ $ty _ne>](&mut self, v: $ty);
+        }
+        )*
+    };
+}
+
+macro_rules! impl_push_x_suite {
+    ($([$ty:ty, $ty_literal: literal]), +$(,)?) => {
+        $(
+        paste! {
+            #[inline]
+            fn [<push_ $ty _be>](&mut self, v: $ty) {
+                self.extend_from_slice(&v.to_be_bytes());
+            }
+
+            #[inline]
+            fn [<push_ $ty _le>](&mut self, v: $ty) {
+                self.extend_from_slice(&v.to_le_bytes());
+            }
+
+            #[inline]
+            fn [<push_ $ty _ne>](&mut self, v: $ty) {
+                self.extend_from_slice(&v.to_ne_bytes());
+            }
+        }
+        )*
+    };
+}
+
+/// Extensions for using `Vec<u8>` as the encoding target of the decoding APIs of this crate.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_slice::{BytesExt, VecByteWriterExt};
+///
+/// let mut buf = Vec::new();
+/// buf.reserve_exact_for::<u32>(2);
+/// buf.push_u32_be(1);
+/// buf.push_u32_be(2);
+/// assert_eq!(buf.to_be_u32_vec(), [1, 2]);
+///
+/// buf.clear();
+/// assert_eq!(buf.push_varint(300), 2);
+/// buf.push_slice("abc");
+/// assert_eq!(buf, [0xac, 0x02, b'a', b'b', b'c']);
+/// ```
+pub trait VecByteWriterExt {
+    push_x_impl_suite!(
+        [u16, "u16"],
+        [u32, "u32"],
+        [usize, "usize"],
+        [u64, "u64"],
+        [u128, "u128"],
+        [i8, "i8"],
+        [i16, "i16"],
+        [i32, "i32"],
+        [i64, "i64"],
+        [isize, "isize"],
+        [i128, "i128"],
+        [f32, "f32"],
+        [f64, "f64"],
+    );
+
+    packed_push_suite!([u24, u32, 3], [u48, u64, 6]);
+
+    /// Appends `v` as an unsigned LEB128 varint (7 bits per byte, least significant group first).
+    /// Returns the number of bytes written.
+    fn push_varint(&mut self, v: u64) -> usize;
+
+    /// Appends an IPv4 address in network order.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    fn push_ipv4_addr(&mut self, addr: Ipv4Addr);
+
+    /// Appends an IPv6 address in network order.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    fn push_ipv6_addr(&mut self, addr: Ipv6Addr);
+
+    /// Appends a socket address as its IPv4 address followed by the port in big-endian,
+    /// the layout read by `BytesExt::to_socket_addr_v4`.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    fn push_socket_addr_v4(&mut self, addr: SocketAddrV4);
+
+    /// Appends `payload` as a checksummed block (see [`write_block_with_crc`]), and returns the
+    /// number of bytes appended.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the payload is larger than `u32::MAX` bytes.
+    ///
+    /// [`write_block_with_crc`]: fn.write_block_with_crc.html
+    fn push_block_with_crc(&mut self, payload: impl AsBytesRef) -> usize;
+
+    /// Appends the bytes of `src`.
+    fn push_slice(&mut self, src: impl AsBytesRef);
+
+    /// Reserves the exact capacity for `n` more values of `T`.
+    fn reserve_exact_for<T>(&mut self, n: usize);
+
+    /// Resizes the vec to `len` bytes, zero-filling any new bytes.
+    ///
+    /// An unallocated vec gets a zeroed allocation (`calloc`-style), which the allocator can often
+    /// hand out without writing the memory.
+    fn resize_zeroed(&mut self, len: usize);
+
+    /// Returns the spare capacity of the vec, to be filled directly before [`commit_spare`].
+    ///
+    /// [`commit_spare`]: trait.VecByteWriterExt.html#method.commit_spare
+    fn spare_capacity_bytes_mut(&mut self) -> &mut [MaybeUninit<u8>];
+
+    /// Extends the length by `n` bytes, taking them from the spare capacity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the spare capacity.
+    ///
+    /// # Safety
+    ///
+    /// The first `n` bytes of [`spare_capacity_bytes_mut`] must have been initialized.
+    ///
+    /// [`spare_capacity_bytes_mut`]: trait.VecByteWriterExt.html#method.spare_capacity_bytes_mut
+    unsafe fn commit_spare(&mut self, n: usize);
+
+    /// Reserves `n` bytes, lets `f` fill them and appends the first bytes `f` reports as written,
+    /// e.g. for handing the buffer to `Read::read`. The bytes are zeroed first, so this is safe.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` returns more than `n`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::VecByteWriterExt;
+    ///
+    /// let mut buf = b"ab".to_vec();
+    /// let read = buf.fill_spare(8, |dst| {
+    ///     dst[..3].copy_from_slice(b"cde");
+    ///     3
+    /// });
+    /// assert_eq!(read, 3);
+    /// assert_eq!(buf, b"abcde");
+    /// ```
+    fn fill_spare<F: FnOnce(&mut [u8]) -> usize>(&mut self, n: usize, f: F) -> usize;
+
+    /// Reads once from `reader` into the spare capacity, reserving some if there is none, and
+    /// appends the bytes read. Returns the number of bytes read, 0 at the end of the reader.
+    ///
+    /// The spare capacity is zeroed before it is handed to `Read::read`, so a reader never sees
+    /// uninitialized memory, and the length only grows by the count `read` reports. Reads
+    /// interrupted by `ErrorKind::Interrupted` are retried.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `reader` reports more bytes than the buffer it was given.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::VecByteWriterExt;
+    ///
+    /// let mut src = &b"hello"[..];
+    /// let mut buf = Vec::with_capacity(3);
+    /// let mut total = 0;
+    /// while let n @ 1.. = buf.read_into_spare(&mut src).unwrap() {
+    ///     total += n;
+    /// }
+    /// assert_eq!(total, 5);
+    /// assert_eq!(buf, b"hello");
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    fn read_into_spare<R: std::io::Read + ?Sized>(
+        &mut self,
+        reader: &mut R,
+    ) -> std::io::Result<usize>;
+
+    /// Reads exactly `n` bytes from `reader` and appends them.
+    ///
+    /// On error, e.g. `ErrorKind::UnexpectedEof` if the reader ends early, the vec is left as it
+    /// was: the bytes read so far are discarded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_slice::VecByteWriterExt;
+    ///
+    /// let mut src = &b"abcdef"[..];
+    /// let mut buf = b"x".to_vec();
+    /// buf.extend_from_reader_exact(&mut src, 4).unwrap();
+    /// assert_eq!(buf, b"xabcd");
+    ///
+    /// assert!(buf.extend_from_reader_exact(&mut src, 4).is_err());
+    /// assert_eq!(buf, b"xabcd");
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    fn extend_from_reader_exact<R: std::io::Read + ?Sized>(
+        &mut self,
+        reader: &mut R,
+        n: usize,
+    ) -> std::io::Result<()>;
+}
+
+impl VecByteWriterExt for Vec<u8> {
+    impl_push_x_suite!(
+        [u16, "u16"],
+        [u32, "u32"],
+        [usize, "usize"],
+        [u64, "u64"],
+        [u128, "u128"],
+        [i8, "i8"],
+        [i16, "i16"],
+        [i32, "i32"],
+        [i64, "i64"],
+        [isize, "isize"],
+        [i128, "i128"],
+        [f32, "f32"],
+        [f64, "f64"],
+    );
+
+    impl_packed_push_suite!([u24, u32, 3], [u48, u64, 6]);
+
+    fn push_varint(&mut self, mut v: u64) -> usize {
+        let start = self.len();
+        while v >= 0x80 {
+            self.push(v as u8 | 0x80);
+            v >>= 7;
+        }
+        self.push(v as u8);
+        self.len() - start
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn push_ipv4_addr(&mut self, addr: Ipv4Addr) {
+        self.extend_from_slice(&addr.octets());
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn push_ipv6_addr(&mut self, addr: Ipv6Addr) {
+        self.extend_from_slice(&addr.octets());
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn push_socket_addr_v4(&mut self, addr: SocketAddrV4) {
+        self.push_ipv4_addr(*addr.ip());
+        self.push_u16_be(addr.port());
+    }
+
+    fn push_block_with_crc(&mut self, payload: impl AsBytesRef) -> usize {
+        let payload = payload.as_bytes_ref();
+        self.extend_from_slice(&crate::block::block_header(payload));
+        self.extend_from_slice(payload);
+        BLOCK_HEADER_LEN + payload.len()
+    }
+
+    #[inline]
+    fn push_slice(&mut self, src: impl AsBytesRef) {
+        self.extend_from_slice(src.as_bytes_ref());
+    }
+
+    #[inline]
+    fn reserve_exact_for<T>(&mut self, n: usize) {
+        self.reserve_exact(n * mem::size_of::<T>());
+    }
+
+    fn resize_zeroed(&mut self, len: usize) {
+        if self.capacity() == 0 {
+            *self = alloc::vec![0; len];
+        } else {
+            self.resize(len, 0);
+        }
+    }
+
+    #[inline]
+    fn spare_capacity_bytes_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        self.spare_capacity_mut()
+    }
+
+    #[inline]
+    unsafe fn commit_spare(&mut self, n: usize) {
+        assert!(
+            n <= self.capacity() - self.len(),
+            "cannot commit {} bytes with a spare capacity of {}",
+            n,
+            self.capacity() - self.len()
+        );
+        self.set_len(self.len() + n);
+    }
+
+    fn fill_spare<F: FnOnce(&mut [u8]) -> usize>(&mut self, n: usize, f: F) -> usize {
+        let start = self.len();
+        self.resize(start + n, 0);
+        let written = f(&mut self[start..]);
+        assert!(
+            written <= n,
+            "fill_spare callback reported {} bytes written into a buffer of {}",
+            written,
+            n
+        );
+        self.truncate(start + written);
+        written
+    }
+
+    #[cfg(feature = "std")]
+    fn read_into_spare<R: std::io::Read + ?Sized>(
+        &mut self,
+        reader: &mut R,
+    ) -> std::io::Result<usize> {
+        /// The spare capacity reserved when the vec is full.
+        const MIN_READ: usize = 32;
+
+        if self.capacity() == self.len() {
+            self.reserve(MIN_READ);
+        }
+        let spare = self.capacity() - self.len();
+        loop {
+            let mut res = Ok(0);
+            self.fill_spare(spare, |dst| {
+                res = reader.read(dst);
+                *res.as_ref().unwrap_or(&0)
+            });
+            match res {
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                res => return res,
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn extend_from_reader_exact<R: std::io::Read + ?Sized>(
+        &mut self,
+        reader: &mut R,
+        n: usize,
+    ) -> std::io::Result<()> {
+        let start = self.len();
+        self.resize(start + n, 0);
+        let res = reader.read_exact(&mut self[start..]);
+        if res.is_err() {
+            self.truncate(start);
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BytesExt;
+
+    #[test]
+    fn test_push_roundtrip() {
+        let mut buf = Vec::new();
+        buf.push_u16_be(0x0102);
+        buf.push_i64_le(-2);
+        buf.push_f64_ne(1.5);
+        assert_eq!(&buf[..2], &[1, 2]);
+        let (le, ne) = (&buf[2..10], &buf[10..]);
+        assert_eq!(le.to_le_i64_vec(), [-2]);
+        assert_eq!(ne.to_ne_f64_vec(), [1.5]);
+    }
+
+    #[test]
+    fn test_push_packed() {
+        let mut buf = Vec::new();
+        buf.push_u24_be(0xff01_0203);
+        buf.push_u48_le(0x0405_0607_0809);
+        assert_eq!(buf, [1, 2, 3, 9, 8, 7, 6, 5, 4]);
+        assert_eq!(buf.read_u24_be_at(0), Some(0x01_0203));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_push_net_addrs() {
+        use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4};
+
+        let mut buf = Vec::new();
+        buf.push_socket_addr_v4(SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 1), 443));
+        assert_eq!(buf, [10, 0, 0, 1, 1, 0xbb]);
+        assert_eq!(buf.to_socket_addr_v4().unwrap().port(), 443);
+
+        buf.clear();
+        buf.push_ipv6_addr(Ipv6Addr::LOCALHOST);
+        assert_eq!(buf.to_ipv6_addr(), Some(Ipv6Addr::LOCALHOST));
+    }
+
+    #[test]
+    fn test_push_block_with_crc() {
+        let mut buf = Vec::new();
+        assert_eq!(buf.push_block_with_crc("a"), 9);
+        assert_eq!(buf.push_block_with_crc("bc"), 10);
+        assert_eq!(crate::read_block_with_crc(&buf), Ok(&b"a"[..]));
+        assert_eq!(crate::read_block_with_crc(&buf[9..]), Ok(&b"bc"[..]));
+    }
+
+    #[test]
+    fn test_push_varint() {
+        let mut buf = Vec::new();
+        assert_eq!(buf.push_varint(0), 1);
+        assert_eq!(buf.push_varint(127), 1);
+        assert_eq!(buf.push_varint(128), 2);
+        assert_eq!(buf.push_varint(u64::MAX), 10);
+        assert_eq!(&buf[..4], &[0x00, 0x7f, 0x80, 0x01]);
+        assert_eq!(buf[4..].last(), Some(&0x01));
+    }
+
+    #[test]
+    fn test_spare_capacity() {
+        let mut buf = Vec::new();
+        buf.resize_zeroed(4);
+        assert_eq!(buf, [0; 4]);
+        buf.resize_zeroed(6);
+        assert_eq!(buf, [0; 6]);
+
+        buf.clear();
+        buf.reserve(4);
+        for (i, b) in buf.spare_capacity_bytes_mut()[..2].iter_mut().enumerate() {
+            b.write(i as u8);
+        }
+        unsafe { buf.commit_spare(2) };
+        assert_eq!(buf, [0, 1]);
+
+        assert_eq!(buf.fill_spare(4, |dst| dst.len()), 4);
+        assert_eq!(buf, [0, 1, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_commit_spare_overflow() {
+        let mut buf = Vec::<u8>::new();
+        unsafe { buf.commit_spare(1) };
+    }
+
+    #[test]
+    fn test_reserve_exact_for() {
+        let mut buf = Vec::new();
+        buf.reserve_exact_for::<u64>(4);
+        assert!(buf.capacity() >= 32);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_read_into_spare() {
+        use std::io::{Error, ErrorKind, Read};
+
+        /// Fails once with `Interrupted`, then yields its bytes two at a time.
+        struct Flaky<'a>(&'a [u8], bool);
+
+        impl Read for Flaky<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                if !core::mem::replace(&mut self.1, true) {
+                    return Err(Error::from(ErrorKind::Interrupted));
+                }
+                assert!(buf.iter().all(|b| *b == 0));
+                let n = self.0.len().min(buf.len()).min(2);
+                buf[..n].copy_from_slice(&self.0[..n]);
+                self.0 = &self.0[n..];
+                Ok(n)
+            }
+        }
+
+        let mut src = Flaky(b"abc", false);
+        let mut buf = b"x".to_vec();
+        assert_eq!(buf.read_into_spare(&mut src).unwrap(), 2);
+        assert_eq!(buf.read_into_spare(&mut src).unwrap(), 1);
+        assert_eq!(buf.read_into_spare(&mut src).unwrap(), 0);
+        assert_eq!(buf, b"xabc");
+
+        let mut src = std::io::Cursor::new(b"0123456789");
+        buf.extend_from_reader_exact(&mut src, 0).unwrap();
+        buf.extend_from_reader_exact(&mut src, 6).unwrap();
+        assert_eq!(buf, b"xabc012345");
+        let err = buf.extend_from_reader_exact(&mut src, 5).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(buf, b"xabc012345");
+    }
+}
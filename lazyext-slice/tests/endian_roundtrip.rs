@@ -0,0 +1,140 @@
+//! Round-trip property tests for every pair of endian conversions: the `push_*` writers, the
+//! `read_*` reader methods, the `to_*_vec` decoders, the packed integers and the endian views.
+
+use lazyext_slice::{Be, BeSlice, BytesExt, Le, LeSlice, SliceReader, VecByteWriterExt};
+use paste::paste;
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+macro_rules! roundtrip {
+    ($($ty:ident),+ $(,)?) => {
+        paste! {
+            proptest! {
+                $(
+                #[test]
+                fn [<roundtrip_ $ty>](vals in vec(any::<$ty>(), 0..32)) {
+                    roundtrip!(@endian $ty, vals, be);
+                    roundtrip!(@endian $ty, vals, le);
+                    roundtrip!(@endian $ty, vals, ne);
+                }
+                )+
+            }
+        }
+    };
+    (@endian $ty:ident, $vals:ident, $e:ident) => {
+        paste! {{
+            let mut buf = Vec::new();
+            for v in &$vals {
+                buf.[<push_ $ty _ $e>](*v);
+            }
+            prop_assert_eq!(buf.len(), $vals.len() * core::mem::size_of::<$ty>());
+            prop_assert_eq!(&buf.[<to_ $e _ $ty _vec>](), &$vals);
+
+            let mut r = SliceReader::new(&buf);
+            for v in &$vals {
+                prop_assert_eq!(r.[<read_ $ty _ $e>]().unwrap(), *v);
+            }
+            prop_assert!(r.is_empty());
+        }}
+    };
+}
+
+roundtrip!(u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+macro_rules! roundtrip_float {
+    ($($ty:ident: $bits:ident),+ $(,)?) => {
+        paste! {
+            proptest! {
+                $(
+                #[test]
+                fn [<roundtrip_ $ty>](bits in vec(any::<$bits>(), 0..32)) {
+                    // Compare the bits, so every NaN payload must survive.
+                    let vals = bits.iter().map(|b| $ty::from_bits(*b)).collect::<Vec<_>>();
+                    roundtrip_float!(@endian $ty, vals, bits, be);
+                    roundtrip_float!(@endian $ty, vals, bits, le);
+                    roundtrip_float!(@endian $ty, vals, bits, ne);
+                }
+                )+
+            }
+        }
+    };
+    (@endian $ty:ident, $vals:ident, $bits:ident, $e:ident) => {
+        paste! {{
+            let mut buf = Vec::new();
+            for v in &$vals {
+                buf.[<push_ $ty _ $e>](*v);
+            }
+            let decoded = buf.[<to_ $e _ $ty _vec>]();
+            prop_assert_eq!(decoded.iter().map(|v| v.to_bits()).collect::<Vec<_>>(), $bits.clone());
+
+            let mut r = SliceReader::new(&buf);
+            for b in &$bits {
+                prop_assert_eq!(r.[<read_ $ty _ $e>]().unwrap().to_bits(), *b);
+            }
+        }}
+    };
+}
+
+roundtrip_float!(f32: u32, f64: u64);
+
+macro_rules! roundtrip_views {
+    ($($ty:ident),+ $(,)?) => {
+        paste! {
+            proptest! {
+                $(
+                #[test]
+                fn [<roundtrip_views_ $ty>](vals in vec(any::<$ty>(), 0..32)) {
+                    let mut be = Vec::new();
+                    let mut le = Vec::new();
+                    for v in &vals {
+                        be.[<push_ $ty _be>](*v);
+                        le.[<push_ $ty _le>](*v);
+                        prop_assert_eq!(Be::new(*v).get(), *v);
+                        prop_assert_eq!(Le::new(*v).get(), *v);
+                        let encoded = Be::new(*v);
+                        prop_assert_eq!(encoded.as_bytes(), &v.to_be_bytes()[..]);
+                    }
+                    prop_assert_eq!(BeSlice::<$ty>::new(&be).unwrap().to_vec(), vals.clone());
+                    prop_assert_eq!(LeSlice::<$ty>::new(&le).unwrap().to_vec(), vals.clone());
+                    prop_assert_eq!(be.[<as_be_ $ty s>]().to_vec(), vals.clone());
+                    prop_assert_eq!(le.[<as_le_ $ty s>]().to_vec(), vals);
+                }
+                )+
+            }
+        }
+    };
+}
+
+roundtrip_views!(u16, u32, u64, u128, i16, i32, i64, i128);
+
+proptest! {
+    #[test]
+    fn roundtrip_packed(vals in vec(any::<u64>(), 0..32)) {
+        let mut buf = Vec::new();
+        for v in &vals {
+            buf.push_u24_be(*v as u32 & 0xff_ffff);
+            buf.push_u24_le(*v as u32 & 0xff_ffff);
+            buf.push_u48_be(*v & 0xffff_ffff_ffff);
+            buf.push_u48_le(*v & 0xffff_ffff_ffff);
+        }
+        for (i, v) in vals.iter().enumerate() {
+            let at = i * 18;
+            prop_assert_eq!(buf.read_u24_be_at(at), Some(*v as u32 & 0xff_ffff));
+            prop_assert_eq!(buf.read_u24_le_at(at + 3), Some(*v as u32 & 0xff_ffff));
+            prop_assert_eq!(buf.read_u48_be_at(at + 6), Some(*v & 0xffff_ffff_ffff));
+            prop_assert_eq!(buf.read_u48_le_at(at + 12), Some(*v & 0xffff_ffff_ffff));
+        }
+    }
+}
+
+#[cfg(feature = "proptest")]
+proptest! {
+    #[test]
+    fn roundtrip_aligned_casts(bytes in lazyext_slice::strategy::any_aligned_bytes::<u32>()) {
+        prop_assert_eq!(bytes.cast_to::<u32>(), bytes.as_elems());
+        let words = bytes.cast_to::<u16>();
+        prop_assert_eq!(words.len(), bytes.len() / 2);
+        let ne = bytes.to_ne_u32_vec();
+        prop_assert_eq!(&ne[..], bytes.as_elems());
+    }
+}
@@ -0,0 +1,76 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Splits `src` into the words of an identifier or phrase.
+///
+/// Any non-alphanumeric character separates words, and so do case changes:
+/// `fooBar` splits as `foo|Bar` and `HTTPServer` as `HTTP|Server`. Digits stay with the word before them.
+pub(crate) fn split_words(src: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    for part in src.split(|c: char| !c.is_alphanumeric()) {
+        let mut start = 0;
+        let mut chars = part.char_indices().peekable();
+        let mut prev: Option<char> = None;
+        while let Some((i, c)) = chars.next() {
+            if let Some(p) = prev {
+                let next_is_lower = chars.peek().is_some_and(|&(_, n)| n.is_lowercase());
+                let boundary = c.is_uppercase()
+                    && (p.is_lowercase() || p.is_numeric() || (p.is_uppercase() && next_is_lower));
+                if boundary {
+                    words.push(&part[start..i]);
+                    start = i;
+                }
+            }
+            prev = Some(c);
+        }
+        if start < part.len() {
+            words.push(&part[start..]);
+        }
+    }
+    words
+}
+
+/// Joins the words of `src` with `sep`, rendering the `i`-th word with `f(i, word, &mut dst)`.
+pub(crate) fn join_words<F: FnMut(usize, &str, &mut String)>(
+    src: &str,
+    sep: &str,
+    mut f: F,
+) -> String {
+    let mut dst = String::with_capacity(src.len());
+    for (i, word) in split_words(src).into_iter().enumerate() {
+        if i > 0 {
+            dst.push_str(sep);
+        }
+        f(i, word, &mut dst);
+    }
+    dst
+}
+
+/// Appends `word` lowercased.
+pub(crate) fn push_lower(word: &str, dst: &mut String) {
+    dst.extend(word.chars().flat_map(char::to_lowercase));
+}
+
+/// Appends `word` with the first char uppercased and the rest lowercased.
+pub(crate) fn push_capitalized(word: &str, dst: &mut String) {
+    let mut chars = word.chars();
+    if let Some(first) = chars.next() {
+        dst.extend(first.to_uppercase());
+        dst.extend(chars.flat_map(char::to_lowercase));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(split_words("fooBar"), ["foo", "Bar"]);
+        assert_eq!(split_words("HTTPServerError"), ["HTTP", "Server", "Error"]);
+        assert_eq!(split_words("__foo--bar baz__"), ["foo", "bar", "baz"]);
+        assert_eq!(split_words("version2Update"), ["version2", "Update"]);
+        assert_eq!(split_words("ÄpfelÜber"), ["Äpfel", "Über"]);
+        assert!(split_words("  --  ").is_empty());
+    }
+}
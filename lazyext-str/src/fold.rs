@@ -0,0 +1,147 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+
+/// The ASCII base letter of each char from U+00C0 to U+017F which canonically decomposes into
+/// an ASCII letter and combining marks, or `.` for the chars which do not.
+const BASE_LETTERS: &[u8; 192] = b"\
+AAAAAA.CEEEEIIII.NOOOOO..UUUUY..aaaaaa.ceeeeiiii.nooooo..uuuuy.y\
+AaAaAaCcCcCcCcDd..EeEeEeEeEeGgGgGgGgHh..IiIiIiIiI...JjKk.LlLlLl.\
+...NnNnNn...OoOoOo..RrRrRrSsSsSsSsTtTt..UuUuUuUuUuUuWwYyYZzZzZz.";
+
+/// Returns whether `c` is a combining diacritical mark (U+0300 to U+036F).
+#[inline]
+fn is_combining_mark(c: char) -> bool {
+    ('\u{300}'..='\u{36f}').contains(&c)
+}
+
+/// Returns the ASCII base letter of `c` if it is a Latin letter with diacritics.
+#[inline]
+fn base_letter(c: char) -> Option<char> {
+    let i = (c as usize).checked_sub(0xc0)?;
+    match BASE_LETTERS.get(i) {
+        Some(b'.') | None => None,
+        Some(&b) => Some(b as char),
+    }
+}
+
+/// Returns the ASCII replacement of the letters and punctuation without a canonical decomposition.
+fn ascii_replacement(c: char) -> Option<&'static str> {
+    Some(match c {
+        'Æ' => "AE",
+        'æ' => "ae",
+        'Ð' | 'Đ' => "D",
+        'ð' | 'đ' => "d",
+        'Ø' => "O",
+        'ø' => "o",
+        'Þ' => "TH",
+        'þ' => "th",
+        'ß' => "ss",
+        'Ħ' => "H",
+        'ħ' => "h",
+        'ı' => "i",
+        'Ĳ' => "IJ",
+        'ĳ' => "ij",
+        'Ŀ' | 'Ł' => "L",
+        'ŀ' | 'ł' => "l",
+        'Ŋ' => "N",
+        'ŋ' => "n",
+        'Œ' => "OE",
+        'œ' => "oe",
+        'Ŧ' => "T",
+        'ŧ' => "t",
+        'ſ' => "s",
+        '\u{a0}' => " ",
+        '\u{2018}' | '\u{2019}' | '\u{201a}' => "'",
+        '\u{201c}' | '\u{201d}' | '\u{201e}' | '«' | '»' => "\"",
+        '\u{2013}' | '\u{2014}' => "-",
+        '\u{2026}' => "...",
+        _ => return None,
+    })
+}
+
+/// Rewrites the chars of `src` with `f`, which returns `false` for the chars to keep as they are,
+/// borrowing `src` when no char is rewritten.
+fn rewrite<F: FnMut(char, &mut String) -> bool>(src: &str, mut f: F) -> Cow<'_, str> {
+    let mut scratch = String::new();
+    let first = match src.char_indices().find(|&(_, c)| f(c, &mut scratch)) {
+        Some((i, _)) => i,
+        None => return Cow::Borrowed(src),
+    };
+
+    let mut dst = String::with_capacity(src.len());
+    dst.push_str(&src[..first]);
+    for c in src[first..].chars() {
+        if !f(c, &mut dst) {
+            dst.push(c);
+        }
+    }
+    Cow::Owned(dst)
+}
+
+pub(crate) fn strip_diacritics(src: &str) -> Cow<'_, str> {
+    rewrite(src, |c, dst| {
+        if is_combining_mark(c) {
+            return true;
+        }
+        match base_letter(c) {
+            Some(b) => {
+                dst.push(b);
+                true
+            }
+            None => false,
+        }
+    })
+}
+
+pub(crate) fn ascii_fold(src: &str) -> Cow<'_, str> {
+    if src.is_ascii() {
+        return Cow::Borrowed(src);
+    }
+    rewrite(src, |c, dst| {
+        if is_combining_mark(c) {
+            return true;
+        }
+        if let Some(b) = base_letter(c) {
+            dst.push(b);
+            return true;
+        }
+        match ascii_replacement(c) {
+            Some(r) => {
+                dst.push_str(r);
+                true
+            }
+            None => false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_diacritics() {
+        assert_eq!(strip_diacritics("Crème Brûlée"), "Creme Brulee");
+        assert_eq!(
+            strip_diacritics("Ångström, Dvořák, İstanbul"),
+            "Angstrom, Dvorak, Istanbul"
+        );
+        // decomposed input
+        assert_eq!(strip_diacritics("e\u{301}te\u{301}"), "ete");
+        // letters without a decomposition are kept
+        assert_eq!(strip_diacritics("Straße, Łódź"), "Straße, Łodz");
+        assert!(matches!(strip_diacritics("plain"), Cow::Borrowed(_)));
+        assert!(matches!(strip_diacritics("日本"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_ascii_fold() {
+        assert_eq!(ascii_fold("Straße, Łódź, Œuvre"), "Strasse, Lodz, OEuvre");
+        assert_eq!(
+            ascii_fold("\u{201c}Hi\u{201d} \u{2014} it\u{2019}s\u{2026}"),
+            "\"Hi\" - it's..."
+        );
+        assert_eq!(ascii_fold("日本 café"), "日本 cafe");
+        assert!(matches!(ascii_fold("ascii"), Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,70 @@
+/// Returns whether `src` begins with `prefix`, ignoring case.
+pub(crate) fn starts_with_ignore_case(src: &str, prefix: &str) -> bool {
+    if src.is_ascii() && prefix.is_ascii() {
+        return src.len() >= prefix.len()
+            && src.as_bytes()[..prefix.len()].eq_ignore_ascii_case(prefix.as_bytes());
+    }
+    starts_with_folded(src, prefix)
+}
+
+/// Returns the byte index of the first match of `needle` in `src`, ignoring case.
+pub(crate) fn find_ignore_case(src: &str, needle: &str) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+    if src.is_ascii() && needle.is_ascii() {
+        return src
+            .as_bytes()
+            .windows(needle.len())
+            .position(|w| w.eq_ignore_ascii_case(needle.as_bytes()));
+    }
+    src.char_indices()
+        .map(|(i, _)| i)
+        .find(|&i| starts_with_folded(&src[i..], needle))
+}
+
+/// Compares chars by their lowercase mapping.
+#[cfg(feature = "unicode-case")]
+fn starts_with_folded(src: &str, prefix: &str) -> bool {
+    let mut src = src.chars().flat_map(char::to_lowercase);
+    prefix
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|c| src.next() == Some(c))
+}
+
+/// Compares ASCII letters case-insensitively and every other char exactly.
+#[cfg(not(feature = "unicode-case"))]
+fn starts_with_folded(src: &str, prefix: &str) -> bool {
+    let mut src = src.chars();
+    prefix
+        .chars()
+        .all(|c| src.next().is_some_and(|s| s.eq_ignore_ascii_case(&c)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ascii() {
+        assert!(starts_with_ignore_case("Content-Type", "content-"));
+        assert!(!starts_with_ignore_case("Con", "content"));
+        assert_eq!(find_ignore_case("Hello World", "WORLD"), Some(6));
+        assert_eq!(find_ignore_case("Hello", "xyz"), None);
+        assert_eq!(find_ignore_case("Hello", ""), Some(0));
+    }
+
+    #[test]
+    fn test_non_ascii() {
+        assert_eq!(find_ignore_case("café CAFÉ", "CAF"), Some(0));
+        assert!(starts_with_ignore_case("Ünïcode", "Ünï"));
+        #[cfg(feature = "unicode-case")]
+        {
+            assert_eq!(find_ignore_case("café CAFÉ", "é c"), Some(3));
+            assert!(starts_with_ignore_case("ÜBER", "über"));
+        }
+        #[cfg(not(feature = "unicode-case"))]
+        assert!(!starts_with_ignore_case("ÜBER", "über"));
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, PoisonError, RwLock};
+use std::vec::Vec;
+
+/// A handle to a value interned by an [`Interner`] or a [`SyncInterner`].
+///
+/// Symbols are only meaningful for the interner which returned them.
+///
+/// [`Interner`]: struct.Interner.html
+/// [`SyncInterner`]: struct.SyncInterner.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// Returns the index of the symbol, which counts up from 0 in the order the values were interned.
+    #[inline]
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Deduplicates strings (or byte slices, with `Interner<[u8]>`) and hands out small [`Symbol`]s,
+/// which are cheap to copy, hash and compare, and resolve back to the value in O(1).
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_str::Interner;
+///
+/// let mut interner = Interner::new();
+/// let a = interner.intern("GET");
+/// let b = interner.intern("POST");
+/// assert_eq!(interner.intern("GET"), a);
+/// assert_ne!(a, b);
+/// assert_eq!(interner.resolve(b), Some("POST"));
+/// assert_eq!(interner.len(), 2);
+///
+/// let mut keys = Interner::<[u8]>::new();
+/// let k = keys.intern(b"key");
+/// assert_eq!(keys.resolve(k), Some(&b"key"[..]));
+/// ```
+///
+/// [`Symbol`]: struct.Symbol.html
+#[derive(Debug, Clone)]
+pub struct Interner<T: ?Sized = str> {
+    symbols: HashMap<Arc<T>, Symbol>,
+    values: Vec<Arc<T>>,
+}
+
+impl<T: ?Sized> Default for Interner<T> {
+    fn default() -> Self {
+        Self {
+            symbols: HashMap::new(),
+            values: Vec::new(),
+        }
+    }
+}
+
+impl<T: ?Sized + Eq + Hash> Interner<T>
+where
+    for<'a> Arc<T>: From<&'a T>,
+{
+    /// Creates an empty interner.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty interner with space for `cap` values.
+    #[inline]
+    pub fn with_capacity(cap: usize) -> Self {
+        Self {
+            symbols: HashMap::with_capacity(cap),
+            values: Vec::with_capacity(cap),
+        }
+    }
+
+    /// Returns the symbol of `value`, interning it first if it is new.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than `u32::MAX` values are interned.
+    pub fn intern(&mut self, value: &T) -> Symbol {
+        if let Some(&sym) = self.symbols.get(value) {
+            return sym;
+        }
+        let sym = Symbol(u32::try_from(self.values.len()).expect("interner is full"));
+        let value = Arc::<T>::from(value);
+        self.values.push(value.clone());
+        self.symbols.insert(value, sym);
+        sym
+    }
+
+    /// Returns the symbol of `value` if it was interned, without interning it.
+    #[inline]
+    pub fn get(&self, value: &T) -> Option<Symbol> {
+        self.symbols.get(value).copied()
+    }
+
+    /// Returns the value of `sym`, or `None` if it does not belong to this interner.
+    #[inline]
+    pub fn resolve(&self, sym: Symbol) -> Option<&T> {
+        self.values.get(sym.index()).map(|v| &**v)
+    }
+
+    /// Returns the number of interned values.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns whether no value was interned.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// A thread-safe [`Interner`] behind a `RwLock`, so it can be shared by reference.
+///
+/// Lookups of values which are already interned only take the read lock.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_str::SyncInterner;
+/// use std::sync::Arc;
+///
+/// let interner = Arc::new(SyncInterner::<str>::new());
+/// let handles = (0..4)
+///     .map(|_| {
+///         let interner = interner.clone();
+///         std::thread::spawn(move || interner.intern("level=info"))
+///     })
+///     .collect::<Vec<_>>();
+/// let syms = handles.into_iter().map(|h| h.join().unwrap()).collect::<Vec<_>>();
+/// assert!(syms.iter().all(|s| *s == syms[0]));
+/// assert_eq!(interner.resolve(syms[0]).as_deref(), Some("level=info"));
+/// ```
+///
+/// [`Interner`]: struct.Interner.html
+#[derive(Debug, Default)]
+pub struct SyncInterner<T: ?Sized = str> {
+    inner: RwLock<Interner<T>>,
+}
+
+impl<T: ?Sized + Eq + Hash> SyncInterner<T>
+where
+    for<'a> Arc<T>: From<&'a T>,
+{
+    /// Creates an empty interner.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            inner: RwLock::new(Interner::new()),
+        }
+    }
+
+    /// Returns the symbol of `value`, interning it first if it is new.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than `u32::MAX` values are interned.
+    pub fn intern(&self, value: &T) -> Symbol {
+        if let Some(sym) = self.get(value) {
+            return sym;
+        }
+        self.inner
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .intern(value)
+    }
+
+    /// Returns the symbol of `value` if it was interned, without interning it.
+    #[inline]
+    pub fn get(&self, value: &T) -> Option<Symbol> {
+        self.inner
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(value)
+    }
+
+    /// Returns the value of `sym`, or `None` if it does not belong to this interner.
+    #[inline]
+    pub fn resolve(&self, sym: Symbol) -> Option<Arc<T>> {
+        self.inner
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values
+            .get(sym.index())
+            .cloned()
+    }
+
+    /// Returns the number of interned values.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Returns whether no value was interned.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interner() {
+        let mut interner = Interner::<str>::with_capacity(4);
+        assert!(interner.is_empty());
+        let syms = ["a", "b", "a", "c", "b"].map(|s| interner.intern(s));
+        assert_eq!(syms.map(Symbol::index), [0, 1, 0, 2, 1]);
+        assert_eq!(interner.get("c"), Some(syms[3]));
+        assert_eq!(interner.get("d"), None);
+        assert_eq!(interner.resolve(Symbol(3)), None);
+        assert_eq!(interner.len(), 3);
+    }
+
+    #[test]
+    fn test_sync_interner() {
+        let interner = SyncInterner::<[u8]>::new();
+        let a = interner.intern(b"a");
+        assert_eq!(interner.intern(b"a"), a);
+        assert_eq!(interner.resolve(a).as_deref(), Some(&b"a"[..]));
+        assert_eq!(interner.len(), 1);
+    }
+}
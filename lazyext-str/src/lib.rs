@@ -0,0 +1,38 @@
+//! Utility functions for strings
+//!
+//! The text-level counterpart of `lazyext-slice`: where that crate works on bytes,
+//! [`StrExt`] works on `str` and keeps every result valid UTF-8.
+#![doc(html_root_url = "https://docs.rs/lazyext-str/0.0.1")]
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(missing_docs, warnings)]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+#![cfg_attr(docsrs, allow(unused_attributes))]
+
+extern crate alloc;
+
+#[macro_use]
+extern crate lazyext_macros;
+
+mod case;
+#[cfg(feature = "fold")]
+mod fold;
+mod ignore_case;
+cfg_feature!("std", {
+    mod interner;
+    pub use interner::{Interner, Symbol, SyncInterner};
+});
+mod natural;
+pub use natural::NaturalSortExt;
+mod sanitize;
+mod similarity;
+mod str_ext;
+mod tokens;
+
+pub use str_ext::StrExt;
+pub use tokens::{SplitKeepDelims, Tokens, Words};
+
+/// Re-exports all the extension traits, so they can be imported in one line.
+pub mod prelude {
+    pub use crate::natural::NaturalSortExt;
+    pub use crate::str_ext::*;
+}
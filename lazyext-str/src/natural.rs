@@ -0,0 +1,121 @@
+use core::cmp::Ordering;
+
+/// Compares `a` and `b` in natural order: runs of ASCII digits are compared by their numeric value.
+///
+/// Ties are broken by the number of leading zeros and then, when ignoring case, by the case,
+/// so distinct strings never compare equal.
+pub(crate) fn natural_cmp(mut a: &str, mut b: &str, ignore_case: bool) -> Ordering {
+    let mut tiebreak = Ordering::Equal;
+    loop {
+        let (x, y) = match (a.chars().next(), b.chars().next()) {
+            (None, None) => return tiebreak,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => (x, y),
+        };
+
+        if x.is_ascii_digit() && y.is_ascii_digit() {
+            let da = a.find(|c: char| !c.is_ascii_digit()).unwrap_or(a.len());
+            let db = b.find(|c: char| !c.is_ascii_digit()).unwrap_or(b.len());
+            let na = a[..da].trim_start_matches('0');
+            let nb = b[..db].trim_start_matches('0');
+            let ord = na.len().cmp(&nb.len()).then_with(|| na.cmp(nb));
+            if ord != Ordering::Equal {
+                return ord;
+            }
+            tiebreak = tiebreak.then(da.cmp(&db));
+            a = &a[da..];
+            b = &b[db..];
+            continue;
+        }
+
+        let ord = if ignore_case {
+            tiebreak = tiebreak.then(x.cmp(&y));
+            x.to_lowercase().cmp(y.to_lowercase())
+        } else {
+            x.cmp(&y)
+        };
+        if ord != Ordering::Equal {
+            return ord;
+        }
+        a = &a[x.len_utf8()..];
+        b = &b[y.len_utf8()..];
+    }
+}
+
+/// Sorts slices of strings in natural order, e.g. `file2` before `file10`.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_str::NaturalSortExt;
+///
+/// let mut files = vec!["file10.txt", "File2.txt", "file1.txt"];
+/// files.natural_sort();
+/// assert_eq!(files, ["File2.txt", "file1.txt", "file10.txt"]);
+/// files.natural_sort_ignore_case();
+/// assert_eq!(files, ["file1.txt", "File2.txt", "file10.txt"]);
+/// ```
+pub trait NaturalSortExt {
+    /// Sorts in natural order, see [`StrExt::natural_cmp`]. The sort is stable.
+    ///
+    /// [`StrExt::natural_cmp`]: trait.StrExt.html#method.natural_cmp
+    fn natural_sort(&mut self);
+
+    /// Sorts in natural order ignoring case, see [`StrExt::natural_cmp_ignore_case`]. The sort is stable.
+    ///
+    /// [`StrExt::natural_cmp_ignore_case`]: trait.StrExt.html#method.natural_cmp_ignore_case
+    fn natural_sort_ignore_case(&mut self);
+}
+
+impl<S: AsRef<str>> NaturalSortExt for [S] {
+    #[inline]
+    fn natural_sort(&mut self) {
+        self.sort_by(|a, b| natural_cmp(a.as_ref(), b.as_ref(), false));
+    }
+
+    #[inline]
+    fn natural_sort_ignore_case(&mut self) {
+        self.sort_by(|a, b| natural_cmp(a.as_ref(), b.as_ref(), true));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_natural_cmp() {
+        assert_eq!(natural_cmp("a2", "a10", false), Ordering::Less);
+        assert_eq!(natural_cmp("a10b", "a10a", false), Ordering::Greater);
+        assert_eq!(natural_cmp("v1.10", "v1.9", false), Ordering::Greater);
+        assert_eq!(natural_cmp("a1", "a01", false), Ordering::Less);
+        assert_eq!(natural_cmp("a", "a1", false), Ordering::Less);
+        assert_eq!(natural_cmp("B", "a", false), Ordering::Less);
+        assert_eq!(natural_cmp("B", "a", true), Ordering::Greater);
+        assert_eq!(natural_cmp("A", "a", true), Ordering::Less);
+        assert_eq!(
+            natural_cmp(
+                "x99999999999999999999999",
+                "x100000000000000000000000",
+                false
+            ),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_natural_sort() {
+        let mut v = ["img12.png", "img10.png", "IMG2.png", "img1.png"]
+            .map(String::from)
+            .to_vec();
+        v.natural_sort_ignore_case();
+        assert_eq!(v, ["img1.png", "IMG2.png", "img10.png", "img12.png"]);
+
+        let mut v: Vec<&str> = Vec::new();
+        v.natural_sort();
+        assert!(v.is_empty());
+    }
+}
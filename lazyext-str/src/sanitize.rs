@@ -0,0 +1,92 @@
+use alloc::string::String;
+
+/// The device names Windows reserves regardless of extension.
+const WINDOWS_RESERVED: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+pub(crate) fn slugify(src: &str, sep: char) -> String {
+    let mut dst = String::with_capacity(src.len());
+    let mut pending_sep = false;
+    for c in src.chars() {
+        if c.is_alphanumeric() {
+            if pending_sep && !dst.is_empty() {
+                dst.push(sep);
+            }
+            pending_sep = false;
+            dst.extend(c.to_lowercase());
+        } else {
+            pending_sep = true;
+        }
+    }
+    dst
+}
+
+pub(crate) fn to_valid_ident(src: &str) -> String {
+    let mut dst = String::with_capacity(src.len() + 1);
+    if !src.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        dst.push('_');
+    }
+    dst.extend(src.chars().map(|c| {
+        if c.is_alphanumeric() || c == '_' {
+            c
+        } else {
+            '_'
+        }
+    }));
+    dst
+}
+
+pub(crate) fn sanitize_filename(src: &str, replacement: char) -> String {
+    let mut dst: String = src
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => replacement,
+            c if c.is_control() => replacement,
+            c => c,
+        })
+        .collect();
+
+    // Windows drops trailing dots and spaces.
+    dst.truncate(dst.trim_end_matches(['.', ' ']).len());
+
+    let stem = dst.split('.').next().unwrap_or("");
+    if dst.is_empty()
+        || WINDOWS_RESERVED
+            .iter()
+            .any(|r| r.eq_ignore_ascii_case(stem))
+    {
+        dst.insert(0, replacement);
+    }
+    dst
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("  Hello, World! ", '-'), "hello-world");
+        assert_eq!(slugify("Rust 2021 -- Édition", '_'), "rust_2021_édition");
+        assert_eq!(slugify("!!!", '-'), "");
+    }
+
+    #[test]
+    fn test_to_valid_ident() {
+        assert_eq!(to_valid_ident("user name"), "user_name");
+        assert_eq!(to_valid_ident("2fa-code"), "_2fa_code");
+        assert_eq!(to_valid_ident("_ok"), "_ok");
+        assert_eq!(to_valid_ident(""), "_");
+    }
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(sanitize_filename("a/b:c?.txt", '_'), "a_b_c_.txt");
+        assert_eq!(sanitize_filename("notes. . ", '_'), "notes");
+        assert_eq!(sanitize_filename("con.txt", '_'), "_con.txt");
+        assert_eq!(sanitize_filename("..", '_'), "_");
+        assert_eq!(sanitize_filename("tab\there", '-'), "tab-here");
+    }
+}
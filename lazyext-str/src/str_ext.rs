@@ -0,0 +1,666 @@
+use crate::case::{join_words, push_capitalized, push_lower};
+use crate::ignore_case;
+use crate::natural::natural_cmp;
+use crate::sanitize;
+use crate::similarity::{edit_distance, jaro_winkler};
+use crate::{SplitKeepDelims, Tokens, Words};
+#[cfg(feature = "fold")]
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::ops::{Bound, RangeBounds};
+use lazyext_slice::BytesExt;
+
+/// Returns the byte offset of the `n`-th char of `src`, or `src.len()` if it has fewer chars.
+#[inline]
+fn char_offset(src: &str, n: usize) -> usize {
+    src.char_indices().nth(n).map_or(src.len(), |(i, _)| i)
+}
+
+/// Extensions for `str`, also available on `String` through deref.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_str::StrExt;
+///
+/// assert_eq!("HTTPServer error".to_snake_case(), "http_server_error");
+/// assert_eq!("http_server_error".to_pascal_case(), "HttpServerError");
+/// ```
+pub trait StrExt {
+    /// Converts to `snake_case`.
+    ///
+    /// Words are split on non-alphanumeric characters and on case changes, see [`to_camel_case`].
+    ///
+    /// [`to_camel_case`]: trait.StrExt.html#method.to_camel_case
+    fn to_snake_case(&self) -> String;
+
+    /// Converts to `camelCase`.
+    ///
+    /// Any non-alphanumeric character separates words, and so do case changes:
+    /// `fooBar` splits as `foo|Bar` and `HTTPServer` as `HTTP|Server`. Digits stay with the word before them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_str::StrExt;
+    ///
+    /// assert_eq!("user-id".to_camel_case(), "userId");
+    /// assert_eq!("XMLHttpRequest".to_camel_case(), "xmlHttpRequest");
+    /// ```
+    fn to_camel_case(&self) -> String;
+
+    /// Converts to `PascalCase`.
+    fn to_pascal_case(&self) -> String;
+
+    /// Converts to `kebab-case`.
+    fn to_kebab_case(&self) -> String;
+
+    /// Converts to `Title Case`.
+    fn to_title_case(&self) -> String;
+
+    /// Returns the first `n` chars, or the whole string if it is shorter.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_str::StrExt;
+    ///
+    /// assert_eq!("héllo".truncate_chars(2), "hé");
+    /// assert_eq!("héllo".truncate_bytes_lossy(2), "h");
+    /// assert_eq!("héllo".substring_chars(1..3), "él");
+    /// ```
+    fn truncate_chars(&self, n: usize) -> &str;
+
+    /// Returns the longest prefix of at most `n` bytes which ends on a char boundary,
+    /// so a multi-byte char straddling the limit is dropped instead of panicking.
+    fn truncate_bytes_lossy(&self, n: usize) -> &str;
+
+    /// Returns the chars in `range`, counted in chars rather than bytes.
+    ///
+    /// The range is clamped to the string, so out of range bounds yield a shorter (or empty) string.
+    fn substring_chars(&self, range: impl RangeBounds<usize>) -> &str;
+
+    /// Pads the start with `ch` up to `width` chars. Strings already at least `width` chars are unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_str::StrExt;
+    ///
+    /// assert_eq!("42".pad_left(5, '0'), "00042");
+    /// assert_eq!("ab".pad_right(4, '.'), "ab..");
+    /// assert_eq!("ab".center(5, '*'), "*ab**");
+    /// ```
+    fn pad_left(&self, width: usize, ch: char) -> String;
+
+    /// Pads the end with `ch` up to `width` chars.
+    fn pad_right(&self, width: usize, ch: char) -> String;
+
+    /// Pads both ends with `ch` up to `width` chars, putting the extra char on the right when
+    /// the padding is odd.
+    fn center(&self, width: usize, ch: char) -> String;
+
+    /// Prepends `prefix` to every non-blank line.
+    fn indent(&self, prefix: &str) -> String;
+
+    /// Removes the leading spaces and tabs shared by all non-blank lines.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_str::StrExt;
+    ///
+    /// assert_eq!("    a\n      b\n".dedent(), "a\n  b\n");
+    /// assert_eq!("a\n  b".indent("> "), "> a\n>   b");
+    /// ```
+    fn dedent(&self) -> String;
+
+    /// Greedily wraps the words into lines of at most `width` chars, returned as slices of `self`.
+    ///
+    /// Any whitespace, including newlines, separates words, and the whitespace between the words of a line
+    /// is kept as is. A word longer than `width` gets a line of its own.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_str::StrExt;
+    ///
+    /// assert_eq!("the quick brown fox".wrap(10), ["the quick", "brown fox"]);
+    /// ```
+    fn wrap(&self, width: usize) -> Vec<&str>;
+
+    /// Returns whether `needle` is a substring, ignoring case.
+    ///
+    /// ASCII strings take a byte-wise fast path. Otherwise only ASCII letters are folded, unless the
+    /// `unicode-case` feature is enabled, which compares every char by its lowercase mapping.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_str::StrExt;
+    ///
+    /// assert!("Content-Length".contains_ignore_case("LENGTH"));
+    /// assert_eq!("Content-Length".find_ignore_case("length"), Some(8));
+    /// assert!("Content-Length".starts_with_ignore_case("content"));
+    /// ```
+    fn contains_ignore_case(&self, needle: &str) -> bool;
+
+    /// Returns the byte index of the first match of `needle`, ignoring case,
+    /// see [`contains_ignore_case`].
+    ///
+    /// [`contains_ignore_case`]: trait.StrExt.html#method.contains_ignore_case
+    fn find_ignore_case(&self, needle: &str) -> Option<usize>;
+
+    /// Returns whether the string begins with `prefix`, ignoring case, see [`contains_ignore_case`].
+    ///
+    /// [`contains_ignore_case`]: trait.StrExt.html#method.contains_ignore_case
+    fn starts_with_ignore_case(&self, prefix: &str) -> bool;
+
+    /// Finds the longest shared prefix, backed off to a char boundary so it is always valid UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_str::StrExt;
+    ///
+    /// // "é" and "è" share their first UTF-8 byte
+    /// assert_eq!("café".longest_prefix_str("cafè"), "caf");
+    /// assert_eq!("résumé".longest_suffix_str("consommé"), "mé");
+    /// ```
+    fn longest_prefix_str(&self, other: &str) -> &str;
+
+    /// Finds the longest shared suffix, backed off to a char boundary so it is always valid UTF-8.
+    fn longest_suffix_str(&self, other: &str) -> &str;
+
+    /// Converts to a lowercase URL slug, joining the runs of alphanumeric chars with `-`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_str::StrExt;
+    ///
+    /// assert_eq!("Hello, World!".slugify(), "hello-world");
+    /// assert_eq!("Hello, World!".slugify_with('_'), "hello_world");
+    /// assert_eq!("2nd item".to_valid_ident(), "_2nd_item");
+    /// assert_eq!("a/b: c?".sanitize_filename(), "a_b_ c_");
+    /// ```
+    fn slugify(&self) -> String;
+
+    /// Converts to a lowercase slug, joining the runs of alphanumeric chars with `sep`.
+    fn slugify_with(&self, sep: char) -> String;
+
+    /// Converts to a valid identifier: every char other than alphanumerics and `_` becomes `_`,
+    /// and a `_` is prepended when the string does not start with a letter or `_`.
+    fn to_valid_ident(&self) -> String;
+
+    /// Converts to a file name valid on both Unix and Windows, replacing the invalid chars with `_`,
+    /// see [`sanitize_filename_with`].
+    ///
+    /// [`sanitize_filename_with`]: trait.StrExt.html#method.sanitize_filename_with
+    fn sanitize_filename(&self) -> String;
+
+    /// Converts to a file name valid on both Unix and Windows.
+    ///
+    /// Path separators, `: * ? " < > |` and control chars become `replacement`, trailing dots and spaces are
+    /// trimmed, and `replacement` is prepended to empty names and Windows device names such as `CON`.
+    /// `replacement` should itself be valid in file names.
+    fn sanitize_filename_with(&self, replacement: char) -> String;
+
+    /// Splits on any of `delims`, also yielding every delimiter as an item of its own,
+    /// see [`SplitKeepDelims`].
+    ///
+    /// [`SplitKeepDelims`]: struct.SplitKeepDelims.html
+    fn split_keep_delims<'a>(&'a self, delims: &'a [char]) -> SplitKeepDelims<'a>;
+
+    /// Returns an iterator over the words and punctuation, skipping whitespace, see [`Words`].
+    ///
+    /// [`Words`]: struct.Words.html
+    fn words(&self) -> Words<'_>;
+
+    /// Splits on the chars for which `is_sep` holds, also yielding every separator as an item of its own,
+    /// see [`Tokens`].
+    ///
+    /// [`Tokens`]: struct.Tokens.html
+    fn tokens<F: FnMut(char) -> bool>(&self, is_sep: F) -> Tokens<'_, F>;
+
+    /// Compares in natural order: runs of ASCII digits are compared by their numeric value,
+    /// so `file2` sorts before `file10`. Ties are broken by the number of leading zeros.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use core::cmp::Ordering;
+    /// use lazyext_str::StrExt;
+    ///
+    /// assert_eq!("file2".natural_cmp("file10"), Ordering::Less);
+    /// assert_eq!("File2".natural_cmp("file10"), Ordering::Less);
+    /// assert_eq!("file2".natural_cmp_ignore_case("File10"), Ordering::Less);
+    /// ```
+    fn natural_cmp(&self, other: &str) -> Ordering;
+
+    /// Compares in natural order ignoring case, see [`natural_cmp`]. Strings differing only in case
+    /// are still ordered by case, so distinct strings never compare equal.
+    ///
+    /// [`natural_cmp`]: trait.StrExt.html#method.natural_cmp
+    fn natural_cmp_ignore_case(&self, other: &str) -> Ordering;
+
+    /// Returns the Levenshtein distance to `other`: the number of char insertions, deletions
+    /// and substitutions turning one into the other.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_str::StrExt;
+    ///
+    /// assert_eq!("kitten".edit_distance("sitting"), 3);
+    /// assert!("martha".similarity("marhta") > 0.95);
+    /// assert_eq!("stauts".closest_match(["start", "status", "stash"]).map(|(i, _)| i), Some(1));
+    /// ```
+    fn edit_distance(&self, other: &str) -> usize;
+
+    /// Returns the Jaro-Winkler similarity to `other`, from `0.0` (nothing in common) to `1.0` (equal).
+    ///
+    /// It favors strings sharing a prefix, which suits typos in names and commands.
+    fn similarity(&self, other: &str) -> f64;
+
+    /// Returns the index and similarity of the most similar candidate (the first one on ties),
+    /// or `None` if there are no candidates, for "did you mean" suggestions.
+    ///
+    /// Callers usually discard matches below a threshold such as `0.8`.
+    fn closest_match<I>(&self, candidates: I) -> Option<(usize, f64)>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>;
+
+    /// Removes the diacritics of Latin letters, e.g. `é` becomes `e`, borrowing `self` when there are none.
+    ///
+    /// Precomposed letters from the Latin-1 and Latin Extended-A blocks are replaced by their base letter,
+    /// and combining marks (U+0300 to U+036F) are removed. Letters which do not decompose, such as `ß` or `ł`,
+    /// are kept, see [`ascii_fold`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_str::StrExt;
+    ///
+    /// assert_eq!("Crème Brûlée".strip_diacritics(), "Creme Brulee");
+    /// assert_eq!("Łódź Straße".ascii_fold(), "Lodz Strasse");
+    /// ```
+    ///
+    /// [`ascii_fold`]: trait.StrExt.html#method.ascii_fold
+    #[cfg(feature = "fold")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "fold")))]
+    fn strip_diacritics(&self) -> Cow<'_, str>;
+
+    /// Like [`strip_diacritics`], but also spells out the Latin letters which do not decompose
+    /// (`ß` becomes `ss`, `ł` becomes `l`, `æ` becomes `ae`, ...) and replaces typographic quotes,
+    /// dashes, ellipses and non-breaking spaces with ASCII. Other chars are kept.
+    ///
+    /// [`strip_diacritics`]: trait.StrExt.html#method.strip_diacritics
+    #[cfg(feature = "fold")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "fold")))]
+    fn ascii_fold(&self) -> Cow<'_, str>;
+}
+
+impl StrExt for str {
+    fn to_snake_case(&self) -> String {
+        join_words(self, "_", |_, w, dst| push_lower(w, dst))
+    }
+
+    fn to_camel_case(&self) -> String {
+        join_words(self, "", |i, w, dst| {
+            if i == 0 {
+                push_lower(w, dst)
+            } else {
+                push_capitalized(w, dst)
+            }
+        })
+    }
+
+    fn to_pascal_case(&self) -> String {
+        join_words(self, "", |_, w, dst| push_capitalized(w, dst))
+    }
+
+    fn to_kebab_case(&self) -> String {
+        join_words(self, "-", |_, w, dst| push_lower(w, dst))
+    }
+
+    fn to_title_case(&self) -> String {
+        join_words(self, " ", |_, w, dst| push_capitalized(w, dst))
+    }
+
+    #[inline]
+    fn truncate_chars(&self, n: usize) -> &str {
+        &self[..char_offset(self, n)]
+    }
+
+    fn truncate_bytes_lossy(&self, n: usize) -> &str {
+        if n >= self.len() {
+            return self;
+        }
+        let mut end = n;
+        while !self.is_char_boundary(end) {
+            end -= 1;
+        }
+        &self[..end]
+    }
+
+    fn substring_chars(&self, range: impl RangeBounds<usize>) -> &str {
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let rest = &self[char_offset(self, start)..];
+        match range.end_bound() {
+            Bound::Included(&n) => rest.truncate_chars(n.saturating_add(1).saturating_sub(start)),
+            Bound::Excluded(&n) => rest.truncate_chars(n.saturating_sub(start)),
+            Bound::Unbounded => rest,
+        }
+    }
+
+    fn pad_left(&self, width: usize, ch: char) -> String {
+        let pad = width.saturating_sub(self.chars().count());
+        let mut dst = String::with_capacity(self.len() + pad * ch.len_utf8());
+        dst.extend(core::iter::repeat_n(ch, pad));
+        dst.push_str(self);
+        dst
+    }
+
+    fn pad_right(&self, width: usize, ch: char) -> String {
+        let pad = width.saturating_sub(self.chars().count());
+        let mut dst = String::with_capacity(self.len() + pad * ch.len_utf8());
+        dst.push_str(self);
+        dst.extend(core::iter::repeat_n(ch, pad));
+        dst
+    }
+
+    fn center(&self, width: usize, ch: char) -> String {
+        let pad = width.saturating_sub(self.chars().count());
+        let mut dst = String::with_capacity(self.len() + pad * ch.len_utf8());
+        dst.extend(core::iter::repeat_n(ch, pad / 2));
+        dst.push_str(self);
+        dst.extend(core::iter::repeat_n(ch, pad - pad / 2));
+        dst
+    }
+
+    fn indent(&self, prefix: &str) -> String {
+        let mut dst = String::with_capacity(self.len());
+        for line in self.split_inclusive('\n') {
+            if !line.trim().is_empty() {
+                dst.push_str(prefix);
+            }
+            dst.push_str(line);
+        }
+        dst
+    }
+
+    fn dedent(&self) -> String {
+        let indent_of = |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+        let common = self
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(indent_of)
+            .min()
+            .unwrap_or(0);
+        let mut dst = String::with_capacity(self.len());
+        for line in self.split_inclusive('\n') {
+            dst.push_str(&line[indent_of(line).min(common)..]);
+        }
+        dst
+    }
+
+    fn wrap(&self, width: usize) -> Vec<&str> {
+        let mut lines = Vec::new();
+        // (start, end, chars) of the line being built
+        let mut line: Option<(usize, usize, usize)> = None;
+        for word in self.split_whitespace() {
+            let start = word.as_ptr() as usize - self.as_ptr() as usize;
+            let end = start + word.len();
+            let chars = word.chars().count();
+            line = match line {
+                Some((ls, _, lc)) if lc + 1 + chars <= width => Some((ls, end, lc + 1 + chars)),
+                Some((ls, le, _)) => {
+                    lines.push(&self[ls..le]);
+                    Some((start, end, chars))
+                }
+                None => Some((start, end, chars)),
+            };
+        }
+        if let Some((ls, le, _)) = line {
+            lines.push(&self[ls..le]);
+        }
+        lines
+    }
+
+    #[inline]
+    fn contains_ignore_case(&self, needle: &str) -> bool {
+        ignore_case::find_ignore_case(self, needle).is_some()
+    }
+
+    #[inline]
+    fn find_ignore_case(&self, needle: &str) -> Option<usize> {
+        ignore_case::find_ignore_case(self, needle)
+    }
+
+    #[inline]
+    fn starts_with_ignore_case(&self, prefix: &str) -> bool {
+        ignore_case::starts_with_ignore_case(self, prefix)
+    }
+
+    fn longest_prefix_str(&self, other: &str) -> &str {
+        let mut end = BytesExt::longest_prefix(&self, other).len();
+        while !self.is_char_boundary(end) {
+            end -= 1;
+        }
+        &self[..end]
+    }
+
+    fn longest_suffix_str(&self, other: &str) -> &str {
+        let mut start = self.len() - BytesExt::longest_suffix(&self, other).len();
+        while !self.is_char_boundary(start) {
+            start += 1;
+        }
+        &self[start..]
+    }
+
+    #[inline]
+    fn slugify(&self) -> String {
+        sanitize::slugify(self, '-')
+    }
+
+    #[inline]
+    fn slugify_with(&self, sep: char) -> String {
+        sanitize::slugify(self, sep)
+    }
+
+    #[inline]
+    fn to_valid_ident(&self) -> String {
+        sanitize::to_valid_ident(self)
+    }
+
+    #[inline]
+    fn sanitize_filename(&self) -> String {
+        sanitize::sanitize_filename(self, '_')
+    }
+
+    #[inline]
+    fn sanitize_filename_with(&self, replacement: char) -> String {
+        sanitize::sanitize_filename(self, replacement)
+    }
+
+    #[inline]
+    fn split_keep_delims<'a>(&'a self, delims: &'a [char]) -> SplitKeepDelims<'a> {
+        SplitKeepDelims::new(self, delims)
+    }
+
+    #[inline]
+    fn words(&self) -> Words<'_> {
+        Words::new(self)
+    }
+
+    #[inline]
+    fn tokens<F: FnMut(char) -> bool>(&self, is_sep: F) -> Tokens<'_, F> {
+        Tokens::new(self, is_sep)
+    }
+
+    #[inline]
+    fn natural_cmp(&self, other: &str) -> Ordering {
+        natural_cmp(self, other, false)
+    }
+
+    #[inline]
+    fn natural_cmp_ignore_case(&self, other: &str) -> Ordering {
+        natural_cmp(self, other, true)
+    }
+
+    #[inline]
+    fn edit_distance(&self, other: &str) -> usize {
+        edit_distance(self, other)
+    }
+
+    #[inline]
+    fn similarity(&self, other: &str) -> f64 {
+        jaro_winkler(self, other)
+    }
+
+    fn closest_match<I>(&self, candidates: I) -> Option<(usize, f64)>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        candidates
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| (i, jaro_winkler(self, c.as_ref())))
+            .fold(None, |best, (i, score)| match best {
+                Some((_, best_score)) if best_score >= score => best,
+                _ => Some((i, score)),
+            })
+    }
+
+    #[cfg(feature = "fold")]
+    #[inline]
+    fn strip_diacritics(&self) -> Cow<'_, str> {
+        crate::fold::strip_diacritics(self)
+    }
+
+    #[cfg(feature = "fold")]
+    #[inline]
+    fn ascii_fold(&self) -> Cow<'_, str> {
+        crate::fold::ascii_fold(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_conversion() {
+        let cases = [
+            (
+                "fooBarBaz",
+                "foo_bar_baz",
+                "fooBarBaz",
+                "FooBarBaz",
+                "foo-bar-baz",
+                "Foo Bar Baz",
+            ),
+            (
+                "HTTP_SERVER",
+                "http_server",
+                "httpServer",
+                "HttpServer",
+                "http-server",
+                "Http Server",
+            ),
+            (
+                "the quick  fox",
+                "the_quick_fox",
+                "theQuickFox",
+                "TheQuickFox",
+                "the-quick-fox",
+                "The Quick Fox",
+            ),
+            ("", "", "", "", "", ""),
+        ];
+        for (src, snake, camel, pascal, kebab, title) in cases {
+            assert_eq!(src.to_snake_case(), snake);
+            assert_eq!(src.to_camel_case(), camel);
+            assert_eq!(src.to_pascal_case(), pascal);
+            assert_eq!(src.to_kebab_case(), kebab);
+            assert_eq!(src.to_title_case(), title);
+        }
+    }
+
+    #[test]
+    fn test_truncate() {
+        let s = "añ😀b";
+        assert_eq!(s.truncate_chars(0), "");
+        assert_eq!(s.truncate_chars(3), "añ😀");
+        assert_eq!(s.truncate_chars(10), s);
+        for n in 0..=s.len() + 1 {
+            let t = s.truncate_bytes_lossy(n);
+            assert!(t.len() <= n && s.starts_with(t));
+        }
+        assert_eq!(s.truncate_bytes_lossy(4), "añ");
+        assert_eq!(s.truncate_bytes_lossy(7), "añ😀");
+    }
+
+    #[test]
+    fn test_substring_chars() {
+        let s = "añ😀b";
+        assert_eq!(s.substring_chars(1..3), "ñ😀");
+        assert_eq!(s.substring_chars(1..=3), "ñ😀b");
+        assert_eq!(s.substring_chars(2..), "😀b");
+        assert_eq!(s.substring_chars(..1), "a");
+        assert_eq!(s.substring_chars(3..3), "");
+        assert_eq!(s.substring_chars(5..9), "");
+    }
+
+    #[test]
+    fn test_padding() {
+        assert_eq!("é".pad_left(3, ' '), "  é");
+        assert_eq!("é".pad_right(3, '-'), "é--");
+        assert_eq!("abc".center(4, ' '), "abc ");
+        assert_eq!("abcdef".pad_left(3, ' '), "abcdef");
+        assert_eq!("".center(2, '·'), "··");
+    }
+
+    #[test]
+    fn test_indent_dedent() {
+        let src = "  fn f() {\n\n      body\n  }";
+        assert_eq!(src.dedent(), "fn f() {\n\n    body\n}");
+        assert_eq!(src.dedent().indent("\t"), "\tfn f() {\n\n\t    body\n\t}");
+        assert_eq!("".dedent(), "");
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!("a bb ccc dddd".wrap(4), ["a bb", "ccc", "dddd"]);
+        assert_eq!("  tiny  ".wrap(10), ["tiny"]);
+        assert_eq!("overlong word".wrap(3), ["overlong", "word"]);
+        assert_eq!("x\ny z".wrap(3), ["x\ny", "z"]);
+        assert!("   ".wrap(5).is_empty());
+    }
+
+    #[test]
+    fn test_longest_psfix_str() {
+        assert_eq!("abc".longest_prefix_str("abd"), "ab");
+        assert_eq!("abc".longest_prefix_str(""), "");
+        assert_eq!("😀x".longest_prefix_str("😁x"), "");
+        assert_eq!("x😀".longest_suffix_str("x😀"), "x😀");
+        // U+0101 and U+0201 share their last UTF-8 byte
+        assert_eq!("a\u{101}".longest_suffix_str("b\u{201}"), "");
+        assert_eq!("same".longest_suffix_str("name"), "ame");
+    }
+
+    #[test]
+    fn test_string_deref() {
+        let s = String::from("some_value");
+        assert_eq!(s.to_pascal_case(), "SomeValue");
+    }
+}
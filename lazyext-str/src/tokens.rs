@@ -0,0 +1,155 @@
+/// Pops the next token off `rest`: a single separator char, or the run of chars up to the next separator.
+fn next_token<'a, F: FnMut(char) -> bool>(rest: &mut &'a str, mut is_sep: F) -> Option<&'a str> {
+    let src = *rest;
+    let first = src.chars().next()?;
+    let end = if is_sep(first) {
+        first.len_utf8()
+    } else {
+        src.find(&mut is_sep).unwrap_or(src.len())
+    };
+    let (token, tail) = src.split_at(end);
+    *rest = tail;
+    Some(token)
+}
+
+/// An iterator over the runs between separators and the separators themselves, each separator char
+/// being an item of its own.
+///
+/// It is returned by [`StrExt::tokens`].
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_str::StrExt;
+///
+/// let tokens = "f(a,b)".tokens(|c| "(),".contains(c)).collect::<Vec<_>>();
+/// assert_eq!(tokens, ["f", "(", "a", ",", "b", ")"]);
+/// ```
+///
+/// [`StrExt::tokens`]: trait.StrExt.html#method.tokens
+pub struct Tokens<'a, F> {
+    rest: &'a str,
+    is_sep: F,
+}
+
+impl<'a, F> Tokens<'a, F> {
+    pub(crate) fn new(src: &'a str, is_sep: F) -> Self {
+        Self { rest: src, is_sep }
+    }
+}
+
+impl<'a, F: FnMut(char) -> bool> Iterator for Tokens<'a, F> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_token(&mut self.rest, &mut self.is_sep)
+    }
+}
+
+impl<F: FnMut(char) -> bool> core::iter::FusedIterator for Tokens<'_, F> {}
+
+/// An iterator like `str::split`, but which also yields every delimiter as an item of its own.
+///
+/// It is returned by [`StrExt::split_keep_delims`].
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_str::StrExt;
+///
+/// let parts = "a+b-c".split_keep_delims(&['+', '-']).collect::<Vec<_>>();
+/// assert_eq!(parts, ["a", "+", "b", "-", "c"]);
+/// ```
+///
+/// [`StrExt::split_keep_delims`]: trait.StrExt.html#method.split_keep_delims
+#[derive(Debug, Clone)]
+pub struct SplitKeepDelims<'a> {
+    rest: &'a str,
+    delims: &'a [char],
+}
+
+impl<'a> SplitKeepDelims<'a> {
+    pub(crate) fn new(src: &'a str, delims: &'a [char]) -> Self {
+        Self { rest: src, delims }
+    }
+}
+
+impl<'a> Iterator for SplitKeepDelims<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let delims = self.delims;
+        next_token(&mut self.rest, |c| delims.contains(&c))
+    }
+}
+
+impl core::iter::FusedIterator for SplitKeepDelims<'_> {}
+
+/// An iterator over the words and punctuation of a string: runs of alphanumeric chars (and `_`)
+/// are yielded as words, any other char except whitespace as an item of its own, and whitespace is skipped.
+///
+/// It is returned by [`StrExt::words`].
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_str::StrExt;
+///
+/// let words = "Hello, world!".words().collect::<Vec<_>>();
+/// assert_eq!(words, ["Hello", ",", "world", "!"]);
+/// ```
+///
+/// [`StrExt::words`]: trait.StrExt.html#method.words
+#[derive(Debug, Clone)]
+pub struct Words<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Words<'a> {
+    pub(crate) fn new(src: &'a str) -> Self {
+        Self { rest: src }
+    }
+}
+
+impl<'a> Iterator for Words<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rest = self.rest.trim_start();
+        next_token(&mut self.rest, |c| !c.is_alphanumeric() && c != '_')
+    }
+}
+
+impl core::iter::FusedIterator for Words<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::StrExt;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_tokens() {
+        let tokens = "a  b".tokens(char::is_whitespace).collect::<Vec<_>>();
+        assert_eq!(tokens, ["a", " ", " ", "b"]);
+        assert_eq!("".tokens(char::is_whitespace).count(), 0);
+        // the tokens always cover the whole string
+        let src = "x=1; y = 2;";
+        let tokens = src.tokens(|c| c == ';' || c == '=');
+        assert_eq!(tokens.collect::<Vec<_>>().concat(), src);
+    }
+
+    #[test]
+    fn test_split_keep_delims() {
+        let parts = "/usr//bin/".split_keep_delims(&['/']).collect::<Vec<_>>();
+        assert_eq!(parts, ["/", "usr", "/", "/", "bin", "/"]);
+        let parts = "é→ü".split_keep_delims(&['→']).collect::<Vec<_>>();
+        assert_eq!(parts, ["é", "→", "ü"]);
+    }
+
+    #[test]
+    fn test_words() {
+        let words = "  let x_1 = foo(2);\n".words().collect::<Vec<_>>();
+        assert_eq!(words, ["let", "x_1", "=", "foo", "(", "2", ")", ";"]);
+        assert_eq!(" \t ".words().count(), 0);
+    }
+}
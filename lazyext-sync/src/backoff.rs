@@ -0,0 +1,390 @@
+//! Backoff for spin loops and retries with exponential delays, for sync/async Rust.
+//!
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::BuildHasher;
+use std::hint::spin_loop;
+use std::iter::FusedIterator;
+use std::thread;
+use std::time::Duration;
+
+/// The last step which spins, spinning `2^step` times.
+const SPIN_LIMIT: u32 = 6;
+/// The last step which yields the time slice, before parking.
+const YIELD_LIMIT: u32 = 10;
+/// The longest a thread parks in [`Backoff::snooze`].
+///
+/// [`Backoff::snooze`]: struct.Backoff.html#method.snooze
+const MAX_PARK: Duration = Duration::from_millis(1);
+
+/// A Backoff escalates the waiting of a thread which retries an operation in a loop, e.g. a
+/// compare-and-swap: it first spins, then yields its time slice, then parks for a growing
+/// while, so short waits stay fast and long ones do not burn a core.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_sync::Backoff;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// fn fetch_double(a: &AtomicUsize) -> usize {
+///     let mut backoff = Backoff::new();
+///     let mut cur = a.load(Ordering::Relaxed);
+///     loop {
+///         match a.compare_exchange_weak(cur, cur * 2, Ordering::AcqRel, Ordering::Relaxed) {
+///             Ok(prev) => return prev,
+///             Err(actual) => {
+///                 cur = actual;
+///                 backoff.snooze();
+///             }
+///         }
+///     }
+/// }
+///
+/// let a = AtomicUsize::new(3);
+/// assert_eq!(fetch_double(&a), 3);
+/// assert_eq!(a.load(Ordering::Relaxed), 6);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Backoff {
+    step: u32,
+}
+
+impl Backoff {
+    /// Creates a new `Backoff`.
+    pub const fn new() -> Self {
+        Self { step: 0 }
+    }
+
+    /// Starts over from spinning, e.g. after the operation made progress.
+    pub fn reset(&mut self) {
+        self.step = 0;
+    }
+
+    /// Spins for a short while, only ever spinning. Suited to lock-free loops which fail
+    /// because of contention, where another thread is making progress.
+    pub fn spin(&mut self) {
+        for _ in 0..1u32 << self.step.min(SPIN_LIMIT) {
+            spin_loop();
+        }
+        if self.step <= SPIN_LIMIT {
+            self.step += 1;
+        }
+    }
+
+    /// Waits for a growing while: spins first, then yields the time slice, then parks the
+    /// thread for up to a millisecond. Suited to loops waiting for another thread to act.
+    pub fn snooze(&mut self) {
+        match self.step {
+            step @ 0..=SPIN_LIMIT => {
+                for _ in 0..1u32 << step {
+                    spin_loop();
+                }
+            }
+            step if step <= YIELD_LIMIT => thread::yield_now(),
+            step => {
+                let park = Duration::from_micros(1 << (step - YIELD_LIMIT).min(10));
+                thread::park_timeout(park.min(MAX_PARK));
+            }
+        }
+        self.step = self.step.saturating_add(1);
+    }
+
+    /// Returns whether the backoff has escalated to parking, a hint that the caller should
+    /// switch to a blocking primitive instead of looping.
+    pub fn is_completed(&self) -> bool {
+        self.step > YIELD_LIMIT
+    }
+}
+
+/// How [`retry`] and [`retry_async`] space out the attempts of an operation: exponentially
+/// growing delays, capped, optionally jittered and limited in number.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_sync::RetryPolicy;
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy::new(Duration::from_millis(10), Duration::from_millis(50))
+///     .with_max_retries(4)
+///     .with_jitter(false);
+/// let delays = policy.delays().map(|d| d.as_millis()).collect::<Vec<_>>();
+/// assert_eq!(delays, [10, 20, 40, 50]);
+/// ```
+///
+/// [`retry`]: fn.retry.html
+/// [`retry_async`]: fn.retry_async.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    initial: Duration,
+    max_delay: Duration,
+    multiplier: u32,
+    max_retries: Option<usize>,
+    jitter: bool,
+}
+
+impl RetryPolicy {
+    /// Creates a policy whose first delay is `initial`, doubled after each retry up to
+    /// `max_delay`, with jitter and without a limit on the number of retries.
+    pub const fn new(initial: Duration, max_delay: Duration) -> Self {
+        Self {
+            initial,
+            max_delay,
+            multiplier: 2,
+            max_retries: None,
+            jitter: true,
+        }
+    }
+
+    /// Gives up after `max_retries` retries, i.e. `max_retries + 1` attempts.
+    pub const fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    /// Multiplies the delay by `multiplier` after each retry, instead of doubling it.
+    pub const fn with_multiplier(mut self, multiplier: u32) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Sets whether the delays are jittered: each one is then picked at random between half
+    /// and all of its exponential value, so clients which failed together do not all retry in
+    /// lockstep.
+    pub const fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Returns the maximum number of retries, if any.
+    pub const fn max_retries(&self) -> Option<usize> {
+        self.max_retries
+    }
+
+    /// Returns the delay before the retry `retry` (starting from 0), without jitter.
+    pub fn delay(&self, retry: usize) -> Duration {
+        let factor = u32::try_from(retry)
+            .ok()
+            .and_then(|retry| self.multiplier.checked_pow(retry));
+        factor
+            .and_then(|factor| self.initial.checked_mul(factor))
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay))
+    }
+
+    /// Returns an iterator over the delays before each retry.
+    pub fn delays(&self) -> Delays {
+        Delays {
+            policy: *self,
+            retry: 0,
+            // `RandomState` is seeded differently for every instance.
+            rng: RandomState::new().hash_one(0u8) | 1,
+        }
+    }
+}
+
+/// An iterator over the delays of a [`RetryPolicy`], returned by [`RetryPolicy::delays`].
+///
+/// [`RetryPolicy`]: struct.RetryPolicy.html
+/// [`RetryPolicy::delays`]: struct.RetryPolicy.html#method.delays
+#[derive(Debug, Clone)]
+pub struct Delays {
+    policy: RetryPolicy,
+    retry: usize,
+    rng: u64,
+}
+
+impl Delays {
+    /// Returns the next pseudo-random number, with xorshift64*.
+    fn next_random(&mut self) -> u64 {
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+        self.rng.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+}
+
+impl Iterator for Delays {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        if self.policy.max_retries.is_some_and(|max| self.retry >= max) {
+            return None;
+        }
+
+        let delay = self.policy.delay(self.retry);
+        self.retry += 1;
+        if !self.policy.jitter {
+            return Some(delay);
+        }
+        let half = delay / 2;
+        let range = (delay - half).as_nanos() as u64;
+        let jitter = match range {
+            0 => 0,
+            range => self.next_random() % (range + 1),
+        };
+        Some(half + Duration::from_nanos(jitter))
+    }
+}
+
+impl FusedIterator for Delays {}
+
+/// Calls `op` until it succeeds, sleeping between the attempts as `policy` says, and returns
+/// its result, or its last error once the retries are exhausted.
+///
+/// `op` is given the number of the attempt, starting from 0.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_sync::{retry, RetryPolicy};
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy::new(Duration::from_millis(1), Duration::from_millis(10))
+///     .with_max_retries(5);
+/// let res = retry(&policy, |attempt| match attempt {
+///     0 | 1 => Err("connection refused"),
+///     n => Ok(n),
+/// });
+/// assert_eq!(res, Ok(2));
+///
+/// let res: Result<(), _> = retry(&policy.with_max_retries(1), |_| Err("down"));
+/// assert_eq!(res, Err("down"));
+/// ```
+pub fn retry<T, E, F>(policy: &RetryPolicy, mut op: F) -> Result<T, E>
+where
+    F: FnMut(usize) -> Result<T, E>,
+{
+    let mut delays = policy.delays();
+    let mut attempt = 0;
+    loop {
+        let err = match op(attempt) {
+            Ok(v) => return Ok(v),
+            Err(e) => e,
+        };
+        match delays.next() {
+            Some(delay) => thread::sleep(delay),
+            None => return Err(err),
+        }
+        attempt += 1;
+    }
+}
+
+/// Like [`retry`], for an asynchronous `op`. The crate depends on no runtime, so the delays
+/// are awaited with the futures returned by `sleep`, e.g. `tokio::time::sleep`.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_sync::{retry_async, RetryPolicy};
+/// use std::time::Duration;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let policy = RetryPolicy::new(Duration::from_millis(1), Duration::from_millis(10));
+///     let res: Result<_, &str> = retry_async(
+///         &policy,
+///         |attempt| async move { if attempt < 3 { Err("busy") } else { Ok(attempt) } },
+///         tokio::time::sleep,
+///     )
+///     .await;
+///     assert_eq!(res, Ok(3));
+/// }
+/// ```
+///
+/// [`retry`]: fn.retry.html
+pub async fn retry_async<T, E, F, Fut, S, SFut>(
+    policy: &RetryPolicy,
+    mut op: F,
+    mut sleep: S,
+) -> Result<T, E>
+where
+    F: FnMut(usize) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    S: FnMut(Duration) -> SFut,
+    SFut: Future<Output = ()>,
+{
+    let mut delays = policy.delays();
+    let mut attempt = 0;
+    loop {
+        let err = match op(attempt).await {
+            Ok(v) => return Ok(v),
+            Err(e) => e,
+        };
+        match delays.next() {
+            Some(delay) => sleep(delay).await,
+            None => return Err(err),
+        }
+        attempt += 1;
+    }
+}
+
+#[cfg(all(test, not(loom), not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let mut backoff = Backoff::new();
+        while !backoff.is_completed() {
+            backoff.snooze();
+        }
+        assert_eq!(backoff.step, YIELD_LIMIT + 1);
+        backoff.snooze();
+        backoff.reset();
+        assert!(!backoff.is_completed());
+
+        for _ in 0..100 {
+            backoff.spin();
+        }
+        assert!(!backoff.is_completed());
+    }
+
+    #[test]
+    fn test_delays() {
+        let policy = RetryPolicy::new(Duration::from_secs(1), Duration::from_secs(30))
+            .with_multiplier(3)
+            .with_jitter(false);
+        assert_eq!(policy.max_retries(), None);
+        let delays = policy
+            .delays()
+            .take(5)
+            .map(|d| d.as_secs())
+            .collect::<Vec<_>>();
+        assert_eq!(delays, [1, 3, 9, 27, 30]);
+        // No overflow far down the line.
+        assert_eq!(policy.delay(usize::MAX), Duration::from_secs(30));
+
+        let policy = policy.with_jitter(true).with_max_retries(20);
+        let mut count = 0;
+        for (retry, delay) in policy.delays().enumerate() {
+            let max = policy.delay(retry);
+            assert!(delay >= max / 2 && delay <= max, "{:?} vs {:?}", delay, max);
+            count += 1;
+        }
+        assert_eq!(count, 20);
+    }
+
+    #[tokio::test]
+    async fn test_retry_async() {
+        let policy = RetryPolicy::new(Duration::from_millis(2), Duration::from_millis(3))
+            .with_max_retries(3)
+            .with_jitter(false);
+        let mut slept = Vec::new();
+        let res: Result<(), usize> = retry_async(
+            &policy,
+            |attempt| async move { Err(attempt) },
+            |d| {
+                slept.push(d);
+                std::future::ready(())
+            },
+        )
+        .await;
+        assert_eq!(res, Err(3));
+        assert_eq!(
+            slept,
+            [2, 3, 3].map(Duration::from_millis),
+            "the last failure is not followed by a delay"
+        );
+    }
+}
@@ -0,0 +1,225 @@
+//! A cell for small `Copy` values shared between threads.
+//!
+use crate::Backoff;
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// An AtomicCell holds a `Copy` value which threads load, store and update atomically, e.g.
+/// the state of a small state machine, without wrapping it in a `Mutex`.
+///
+/// Every access copies the value under a tiny per-cell spin lock, so the critical sections
+/// are as short as a copy of `T` and never block on user code, except the `PartialEq` of `T`
+/// in [`compare_exchange`]. Updates which depend on the current value are
+/// compare-and-swap loops, see [`fetch_update`].
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_sync::AtomicCell;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Phase {
+///     Idle,
+///     Running { jobs: u32 },
+///     Stopped,
+/// }
+///
+/// let phase = AtomicCell::new(Phase::Idle);
+/// let start = |p| match p {
+///     Phase::Idle => Some(Phase::Running { jobs: 0 }),
+///     _ => None,
+/// };
+/// assert_eq!(phase.fetch_update(start), Ok(Phase::Idle));
+/// assert_eq!(phase.fetch_update(start), Err(Phase::Running { jobs: 0 }));
+///
+/// phase.store(Phase::Stopped);
+/// assert_eq!(phase.load(), Phase::Stopped);
+/// ```
+///
+/// [`compare_exchange`]: struct.AtomicCell.html#method.compare_exchange
+/// [`fetch_update`]: struct.AtomicCell.html#method.fetch_update
+pub struct AtomicCell<T> {
+    locked: AtomicBool,
+    value: UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Send for AtomicCell<T> {}
+unsafe impl<T: Send> Sync for AtomicCell<T> {}
+
+/// Releases the lock of a cell when dropped, even if the critical section panics.
+struct Unlock<'a>(&'a AtomicBool);
+
+impl Drop for Unlock<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+impl<T> AtomicCell<T> {
+    /// Creates a new `AtomicCell` holding `value`.
+    pub const fn new(value: T) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Returns a mutable reference to the value, no locking needed as the cell is borrowed
+    /// mutably.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    /// Returns the value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    fn with_lock<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        let mut backoff = Backoff::new();
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            backoff.snooze();
+        }
+        let _unlock = Unlock(&self.locked);
+        // SAFETY: the lock gives exclusive access to the value.
+        f(unsafe { &mut *self.value.get() })
+    }
+}
+
+impl<T: Copy> AtomicCell<T> {
+    /// Returns a copy of the value.
+    pub fn load(&self) -> T {
+        self.with_lock(|v| *v)
+    }
+
+    /// Stores `value`.
+    pub fn store(&self, value: T) {
+        self.with_lock(|v| *v = value);
+    }
+
+    /// Stores `value` and returns the previous value.
+    pub fn swap(&self, value: T) -> T {
+        self.with_lock(|v| std::mem::replace(v, value))
+    }
+
+    /// Stores `new` if the value is equal to `current`, compared with `==`. Returns the
+    /// previous value, in `Ok` if `new` was stored, in `Err` otherwise.
+    pub fn compare_exchange(&self, current: T, new: T) -> Result<T, T>
+    where
+        T: PartialEq,
+    {
+        self.with_lock(|v| {
+            if *v == current {
+                Ok(std::mem::replace(v, new))
+            } else {
+                Err(*v)
+            }
+        })
+    }
+
+    /// Updates the value with `f` in a compare-and-swap loop until `f` returns `None` or the
+    /// value it returns is stored. Returns the previous value, in `Ok` if it was updated, in
+    /// `Err` otherwise.
+    ///
+    /// `f` runs without the lock held, so it may be called several times if other threads
+    /// update the value concurrently, like `AtomicUsize::fetch_update`.
+    pub fn fetch_update<F>(&self, mut f: F) -> Result<T, T>
+    where
+        T: PartialEq,
+        F: FnMut(T) -> Option<T>,
+    {
+        let mut backoff = Backoff::new();
+        let mut cur = self.load();
+        while let Some(new) = f(cur) {
+            match self.compare_exchange(cur, new) {
+                Ok(prev) => return Ok(prev),
+                Err(actual) => {
+                    cur = actual;
+                    backoff.spin();
+                }
+            }
+        }
+        Err(cur)
+    }
+}
+
+impl<T: Default> Default for AtomicCell<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> From<T> for AtomicCell<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: Copy + std::fmt::Debug> std::fmt::Debug for AtomicCell<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AtomicCell")
+            .field("value", &self.load())
+            .finish()
+    }
+}
+
+#[cfg(all(test, not(loom), not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_atomic_cell() {
+        let cell = AtomicCell::new((1u8, 2u64));
+        assert_eq!(cell.swap((3, 4)), (1, 2));
+        assert_eq!(cell.compare_exchange((1, 2), (5, 6)), Err((3, 4)));
+        assert_eq!(cell.compare_exchange((3, 4), (5, 6)), Ok((3, 4)));
+        assert_eq!(format!("{:?}", cell), "AtomicCell { value: (5, 6) }");
+
+        let mut cell = AtomicCell::<[u32; 3]>::default();
+        cell.get_mut()[1] = 7;
+        assert_eq!(cell.into_inner(), [0, 7, 0]);
+    }
+
+    #[test]
+    fn test_concurrent_fetch_update() {
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        struct Stats {
+            count: u64,
+            sum: u64,
+        }
+
+        let cell = Arc::new(AtomicCell::new(Stats { count: 0, sum: 0 }));
+        let handles = (0..4)
+            .map(|i| {
+                let cell = cell.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..1000 {
+                        cell.fetch_update(|s| {
+                            Some(Stats {
+                                count: s.count + 1,
+                                sum: s.sum + i,
+                            })
+                        })
+                        .unwrap();
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for h in handles {
+            h.join().unwrap();
+        }
+        // Both fields are always updated together.
+        assert_eq!(
+            cell.load(),
+            Stats {
+                count: 4000,
+                sum: 6000
+            }
+        );
+    }
+}
@@ -0,0 +1,382 @@
+//! Wait groups which collect a result from each job, for sync/async Rust.
+//!
+use crate::wg::Wakers;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+struct State<T> {
+    pending: usize,
+    results: Vec<T>,
+    wakers: Wakers,
+}
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    cvar: Condvar,
+}
+
+impl<T> Shared<T> {
+    fn new(capacity: usize) -> Arc<Self> {
+        Arc::new(Self {
+            state: Mutex::new(State {
+                pending: 0,
+                results: Vec::with_capacity(capacity),
+                wakers: Wakers::default(),
+            }),
+            cvar: Condvar::new(),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn add(self: &Arc<Self>) -> CompletionHandle<T> {
+        self.lock().pending += 1;
+        CompletionHandle {
+            shared: Some(self.clone()),
+        }
+    }
+
+    fn finish(&self, value: Option<T>) {
+        let mut state = self.lock();
+        state.results.extend(value);
+        state.pending -= 1;
+        if state.pending == 0 {
+            self.cvar.notify_all();
+            let wakers = state.wakers.take();
+            drop(state);
+            wakers.for_each(Waker::wake);
+        }
+    }
+}
+
+/// A handle to one job of a [`CompletionGroup`] or an [`AsyncCompletionGroup`], which reports
+/// its result with [`complete`].
+///
+/// Dropping the handle without completing it, e.g. when the job panics, finishes the job
+/// without a result, so the group never waits for it forever.
+///
+/// [`CompletionGroup`]: struct.CompletionGroup.html
+/// [`AsyncCompletionGroup`]: struct.AsyncCompletionGroup.html
+/// [`complete`]: struct.CompletionHandle.html#method.complete
+pub struct CompletionHandle<T> {
+    shared: Option<Arc<Shared<T>>>,
+}
+
+impl<T> CompletionHandle<T> {
+    /// Finishes the job with `value`, which is returned by the `wait` of the group.
+    pub fn complete(mut self, value: T) {
+        if let Some(shared) = self.shared.take() {
+            shared.finish(Some(value));
+        }
+    }
+}
+
+impl<T> Drop for CompletionHandle<T> {
+    fn drop(&mut self) {
+        if let Some(shared) = self.shared.take() {
+            shared.finish(None);
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for CompletionHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompletionHandle").finish()
+    }
+}
+
+/// A CompletionGroup is a [`WaitGroup`] which also collects a result from each job, instead of
+/// pairing the group with a results channel.
+///
+/// Every call to [`add`] returns a [`CompletionHandle`] for one job, and [`wait`] blocks until
+/// all the handles are completed, then returns their results in completion order.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_sync::CompletionGroup;
+///
+/// let group = CompletionGroup::with_capacity(4);
+/// for i in 0..4u64 {
+///     let handle = group.add();
+///     std::thread::spawn(move || handle.complete(i * i));
+/// }
+///
+/// let mut squares = group.wait();
+/// squares.sort_unstable();
+/// assert_eq!(squares, [0, 1, 4, 9]);
+/// ```
+///
+/// [`WaitGroup`]: struct.WaitGroup.html
+/// [`CompletionHandle`]: struct.CompletionHandle.html
+/// [`add`]: struct.CompletionGroup.html#method.add
+/// [`wait`]: struct.CompletionGroup.html#method.wait
+pub struct CompletionGroup<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Default for CompletionGroup<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for CompletionGroup<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for CompletionGroup<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompletionGroup")
+            .field("pending", &self.pending())
+            .finish()
+    }
+}
+
+impl<T> CompletionGroup<T> {
+    /// Creates a new `CompletionGroup`.
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates a new `CompletionGroup` with room for `capacity` results, so collecting them
+    /// does not reallocate.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            shared: Shared::new(capacity),
+        }
+    }
+
+    /// Adds a job to the group and returns its handle.
+    pub fn add(&self) -> CompletionHandle<T> {
+        self.shared.add()
+    }
+
+    /// Returns the number of jobs which are not finished yet.
+    pub fn pending(&self) -> usize {
+        self.shared.lock().pending
+    }
+
+    /// Blocks until all the jobs are finished and returns their results, in completion order.
+    ///
+    /// The results are taken out of the group, so it can be reused for another set of jobs
+    /// once `wait` has returned. If several threads wait on clones of the group at the same
+    /// time, they all return, but only the first one to return gets the results: the others
+    /// get an empty `Vec`.
+    pub fn wait(&self) -> Vec<T> {
+        let mut state = self.shared.lock();
+        while state.pending > 0 {
+            state = self
+                .shared
+                .cvar
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        std::mem::take(&mut state.results)
+    }
+}
+
+/// An AsyncCompletionGroup is an [`AsyncWaitGroup`] which also collects a result from each
+/// job, see [`CompletionGroup`].
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_sync::AsyncCompletionGroup;
+///
+/// #[tokio::main(flavor = "multi_thread", worker_threads = 4)]
+/// async fn main() {
+///     let group = AsyncCompletionGroup::new();
+///     for name in ["a", "b", "c"] {
+///         let handle = group.add();
+///         tokio::spawn(async move { handle.complete(name.len()) });
+///     }
+///
+///     assert_eq!(group.wait().await, [1, 1, 1]);
+/// }
+/// ```
+///
+/// [`AsyncWaitGroup`]: struct.AsyncWaitGroup.html
+/// [`CompletionGroup`]: struct.CompletionGroup.html
+pub struct AsyncCompletionGroup<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Default for AsyncCompletionGroup<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for AsyncCompletionGroup<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for AsyncCompletionGroup<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncCompletionGroup")
+            .field("pending", &self.pending())
+            .finish()
+    }
+}
+
+impl<T> AsyncCompletionGroup<T> {
+    /// Creates a new `AsyncCompletionGroup`.
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates a new `AsyncCompletionGroup` with room for `capacity` results, so collecting
+    /// them does not reallocate.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            shared: Shared::new(capacity),
+        }
+    }
+
+    /// Adds a job to the group and returns its handle.
+    pub fn add(&self) -> CompletionHandle<T> {
+        self.shared.add()
+    }
+
+    /// Returns the number of jobs which are not finished yet.
+    pub fn pending(&self) -> usize {
+        self.shared.lock().pending
+    }
+
+    /// Waits until all the jobs are finished and returns their results, in completion order.
+    ///
+    /// The results are taken out of the group, so it can be reused for another set of jobs
+    /// once `wait` has returned. If several tasks wait on clones of the group at the same
+    /// time, they are all woken, but only the first one to return gets the results: the
+    /// others get an empty `Vec`.
+    pub async fn wait(&self) -> Vec<T> {
+        CompletionFuture {
+            shared: &self.shared,
+            id: None,
+        }
+        .await
+    }
+}
+
+struct CompletionFuture<'a, T> {
+    shared: &'a Shared<T>,
+    /// The id of the waker of this future in the group, once it has been registered.
+    id: Option<usize>,
+}
+
+impl<T> Future for CompletionFuture<'_, T> {
+    type Output = Vec<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let mut state = this.shared.lock();
+        match state.pending {
+            0 => {
+                if let Some(id) = this.id.take() {
+                    state.wakers.remove(id);
+                }
+                Poll::Ready(std::mem::take(&mut state.results))
+            }
+            _ => {
+                state.wakers.register(&mut this.id, cx.waker());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> Drop for CompletionFuture<'_, T> {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            self.shared.lock().wakers.remove(id);
+        }
+    }
+}
+
+#[cfg(all(test, not(loom), not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_completion_group() {
+        let group = CompletionGroup::new();
+        assert!(group.wait().is_empty());
+
+        let handles = (0..5).map(|_| group.add()).collect::<Vec<_>>();
+        assert_eq!(group.pending(), 5);
+        for (i, handle) in handles.into_iter().enumerate() {
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(5));
+                match i {
+                    // A job which gives up does not block the group.
+                    3 => drop(handle),
+                    i => handle.complete(i),
+                }
+            });
+        }
+
+        let mut results = group.wait();
+        results.sort_unstable();
+        assert_eq!(results, [0, 1, 2, 4]);
+
+        // Reuse.
+        group.add().complete(7);
+        assert_eq!(group.wait(), [7]);
+    }
+
+    #[tokio::test]
+    async fn test_async_completion_group() {
+        let group = AsyncCompletionGroup::with_capacity(3);
+        for i in 0..3u64 {
+            let handle = group.add();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(20 * (3 - i))).await;
+                handle.complete(i);
+            });
+        }
+
+        assert_eq!(group.wait().await, [2, 1, 0]);
+        assert_eq!(group.pending(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_async_concurrent_waiters() {
+        let group = AsyncCompletionGroup::new();
+        let handle = group.add();
+        let waiters = (0..2)
+            .map(|_| {
+                let group = group.clone();
+                tokio::spawn(async move { group.wait().await })
+            })
+            .collect::<Vec<_>>();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(group.shared.lock().wakers.len(), 2);
+
+        handle.complete(1);
+        let mut results = Vec::new();
+        for waiter in waiters {
+            let res = tokio::time::timeout(Duration::from_secs(5), waiter)
+                .await
+                .expect("every waiter is woken")
+                .unwrap();
+            results.push(res);
+        }
+        // Only one of the waiters gets the results.
+        results.sort_unstable();
+        assert_eq!(results, [vec![], vec![1]]);
+    }
+}
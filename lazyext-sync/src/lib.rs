@@ -1,7 +1,59 @@
-//!
+//! Synchronization primitives and extensions for sync/async Rust.
 #![deny(missing_docs)]
 
+/// `#[cfg]` for the items which use `parking_lot`, which is never used on `wasm32`.
+macro_rules! cfg_parking_lot {
+    ($($item:item)*) => {
+        $(
+            #[cfg(all(feature = "parking_lot", not(target_arch = "wasm32")))]
+            $item
+        )*
+    }
+}
+
+/// `#[cfg]` for the items which replace those of `cfg_parking_lot`.
+#[cfg_attr(loom, allow(unused_macros))]
+macro_rules! cfg_not_parking_lot {
+    ($($item:item)*) => {
+        $(
+            #[cfg(any(not(feature = "parking_lot"), target_arch = "wasm32"))]
+            $item
+        )*
+    }
+}
+
+mod sync;
+
 mod wg;
-pub use wg::{AsyncWaitGroup, WaitGroup};
+pub use wg::{AsyncWaitGroup, WaitGroup, WaitGroupMetrics, WaitGroupMisuse};
+
+mod completion;
+pub use completion::{AsyncCompletionGroup, CompletionGroup, CompletionHandle};
+
+mod ticket;
+pub use ticket::{TicketLock, TicketLockGuard};
+
+mod mutex;
+pub use mutex::MutexExt;
+
+mod semaphore;
+pub use semaphore::{AcquireError, Semaphore, SemaphorePermit};
+
+mod lockmap;
+pub use lockmap::{AsyncLockMap, AsyncLockMapGuard, LockMap, LockMapGuard};
+
+mod backoff;
+pub use backoff::{retry, retry_async, Backoff, Delays, RetryPolicy};
+
+mod cell;
+pub use cell::AtomicCell;
+
+mod oneshot;
+pub use oneshot::{
+    async_oneshot, oneshot, AsyncOneshotReceiver, Oneshot, OneshotReceiver, RecvError, TryRecvError,
+};
 
 mod arcmut;
+
+mod skipmap;
+pub use skipmap::{Iter as SkipMapIter, SkipMap};
@@ -0,0 +1,338 @@
+//! Per-key locks striped over a fixed set of shards, for sync/async Rust.
+//!
+use crate::{Semaphore, SemaphorePermit};
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+use std::sync::{Mutex, MutexGuard, PoisonError, TryLockError};
+
+/// The number of shards of the maps created with `new`.
+const DEFAULT_SHARDS: usize = 64;
+
+/// Maps the hash of keys to one of `shards` shards, a power of two.
+#[derive(Clone)]
+struct Striping<S> {
+    hasher: S,
+    mask: usize,
+}
+
+impl<S: BuildHasher> Striping<S> {
+    /// # Panics
+    /// Panics if `shards` is 0.
+    fn new(shards: usize, hasher: S) -> Self {
+        assert!(shards > 0, "a lock map needs at least one shard");
+        Self {
+            hasher,
+            mask: shards.next_power_of_two() - 1,
+        }
+    }
+
+    fn shards(&self) -> usize {
+        self.mask + 1
+    }
+
+    fn index<Q: Hash + ?Sized>(&self, key: &Q) -> usize {
+        self.hasher.hash_one(key) as usize & self.mask
+    }
+}
+
+/// A LockMap provides a lock per key, without storing the keys: each key is hashed to one of a
+/// fixed number of shards, which holds the lock.
+///
+/// Critical sections for the same key are mutually exclusive, while those for different keys
+/// usually run in parallel, e.g. the compaction of different files. Two keys may share a
+/// shard, so a thread must not lock a key while it holds the lock of another one, or it may
+/// deadlock on itself.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_sync::LockMap;
+/// use std::sync::Arc;
+///
+/// let locks = Arc::new(LockMap::<str>::new());
+/// let handles = ["a.sst", "b.sst", "a.sst"].into_iter().map(|file| {
+///     let locks = locks.clone();
+///     std::thread::spawn(move || {
+///         let _guard = locks.lock(file);
+///         // compact `file`, nobody else is compacting it
+///     })
+/// }).collect::<Vec<_>>();
+///
+/// for h in handles {
+///     h.join().unwrap();
+/// }
+/// ```
+pub struct LockMap<K: ?Sized, S = RandomState> {
+    shards: Box<[Mutex<()>]>,
+    striping: Striping<S>,
+    _key: PhantomData<fn(&K)>,
+}
+
+impl<K: ?Sized> Default for LockMap<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: ?Sized, S> std::fmt::Debug for LockMap<K, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LockMap")
+            .field("shards", &self.shards.len())
+            .finish()
+    }
+}
+
+impl<K: ?Sized> LockMap<K> {
+    /// Creates a new `LockMap` with the default number of shards.
+    pub fn new() -> Self {
+        Self::with_shards(DEFAULT_SHARDS)
+    }
+
+    /// Creates a new `LockMap` with `shards` shards, rounded up to a power of two. More shards
+    /// make it less likely that different keys contend for the same lock.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is 0.
+    pub fn with_shards(shards: usize) -> Self {
+        Self::with_shards_and_hasher(shards, RandomState::new())
+    }
+}
+
+impl<K: ?Sized, S: BuildHasher> LockMap<K, S> {
+    /// Creates a new `LockMap` with `shards` shards, which hashes the keys with `hasher`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is 0.
+    pub fn with_shards_and_hasher(shards: usize, hasher: S) -> Self {
+        let striping = Striping::new(shards, hasher);
+        Self {
+            shards: (0..striping.shards()).map(|_| Mutex::new(())).collect(),
+            striping,
+            _key: PhantomData,
+        }
+    }
+
+    /// Returns the number of shards.
+    pub fn shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Blocks until the lock of `key` is acquired, and returns a guard which releases it when
+    /// dropped.
+    pub fn lock<Q>(&self, key: &Q) -> LockMapGuard<'_>
+    where
+        K: Borrow<Q>,
+        Q: Hash + ?Sized,
+    {
+        let shard = &self.shards[self.striping.index(key)];
+        LockMapGuard {
+            _guard: shard.lock().unwrap_or_else(PoisonError::into_inner),
+        }
+    }
+
+    /// Acquires the lock of `key` if it is free, without blocking.
+    pub fn try_lock<Q>(&self, key: &Q) -> Option<LockMapGuard<'_>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + ?Sized,
+    {
+        let guard = match self.shards[self.striping.index(key)].try_lock() {
+            Ok(guard) => guard,
+            Err(TryLockError::Poisoned(e)) => e.into_inner(),
+            Err(TryLockError::WouldBlock) => return None,
+        };
+        Some(LockMapGuard { _guard: guard })
+    }
+}
+
+/// The lock of a key of a [`LockMap`], released when dropped.
+///
+/// [`LockMap`]: struct.LockMap.html
+#[derive(Debug)]
+pub struct LockMapGuard<'a> {
+    _guard: MutexGuard<'a, ()>,
+}
+
+/// An AsyncLockMap is a [`LockMap`] whose locks are acquired asynchronously, in FIFO order.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_sync::AsyncLockMap;
+/// use std::sync::Arc;
+///
+/// #[tokio::main(flavor = "multi_thread", worker_threads = 4)]
+/// async fn main() {
+///     let locks = Arc::new(AsyncLockMap::<u64>::new());
+///     let mut handles = Vec::new();
+///     for user in [1, 2, 1] {
+///         let locks = locks.clone();
+///         handles.push(tokio::spawn(async move {
+///             let _guard = locks.lock(&user).await;
+///             // update the account of `user`
+///         }));
+///     }
+///     for h in handles {
+///         h.await.unwrap();
+///     }
+/// }
+/// ```
+///
+/// [`LockMap`]: struct.LockMap.html
+pub struct AsyncLockMap<K: ?Sized, S = RandomState> {
+    shards: Box<[Semaphore]>,
+    striping: Striping<S>,
+    _key: PhantomData<fn(&K)>,
+}
+
+impl<K: ?Sized> Default for AsyncLockMap<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: ?Sized, S> std::fmt::Debug for AsyncLockMap<K, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncLockMap")
+            .field("shards", &self.shards.len())
+            .finish()
+    }
+}
+
+impl<K: ?Sized> AsyncLockMap<K> {
+    /// Creates a new `AsyncLockMap` with the default number of shards.
+    pub fn new() -> Self {
+        Self::with_shards(DEFAULT_SHARDS)
+    }
+
+    /// Creates a new `AsyncLockMap` with `shards` shards, rounded up to a power of two.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is 0.
+    pub fn with_shards(shards: usize) -> Self {
+        Self::with_shards_and_hasher(shards, RandomState::new())
+    }
+}
+
+impl<K: ?Sized, S: BuildHasher> AsyncLockMap<K, S> {
+    /// Creates a new `AsyncLockMap` with `shards` shards, which hashes the keys with `hasher`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is 0.
+    pub fn with_shards_and_hasher(shards: usize, hasher: S) -> Self {
+        let striping = Striping::new(shards, hasher);
+        Self {
+            shards: (0..striping.shards()).map(|_| Semaphore::new(1)).collect(),
+            striping,
+            _key: PhantomData,
+        }
+    }
+
+    /// Returns the number of shards.
+    pub fn shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Waits until the lock of `key` is acquired, and returns a guard which releases it when
+    /// dropped. The guard does not borrow the map, so it can be moved into another task.
+    pub async fn lock<Q>(&self, key: &Q) -> AsyncLockMapGuard
+    where
+        K: Borrow<Q>,
+        Q: Hash + ?Sized,
+    {
+        let shard = &self.shards[self.striping.index(key)];
+        match shard.acquire().await {
+            Ok(permit) => AsyncLockMapGuard { _permit: permit },
+            Err(e) => unreachable!("the shards are never closed: {}", e),
+        }
+    }
+
+    /// Acquires the lock of `key` if it is free and nobody is waiting for it, without waiting.
+    pub fn try_lock<Q>(&self, key: &Q) -> Option<AsyncLockMapGuard>
+    where
+        K: Borrow<Q>,
+        Q: Hash + ?Sized,
+    {
+        let shard = &self.shards[self.striping.index(key)];
+        shard
+            .try_acquire_many(1)
+            .ok()
+            .map(|permit| AsyncLockMapGuard { _permit: permit })
+    }
+}
+
+/// The lock of a key of an [`AsyncLockMap`], released when dropped.
+///
+/// [`AsyncLockMap`]: struct.AsyncLockMap.html
+#[derive(Debug)]
+pub struct AsyncLockMapGuard {
+    _permit: SemaphorePermit,
+}
+
+#[cfg(all(test, not(loom), not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_lock_map() {
+        let locks = LockMap::<String>::with_shards(5);
+        assert_eq!(locks.shards(), 8);
+
+        let guard = locks.lock("a");
+        assert!(locks.try_lock("a").is_none());
+        drop(guard);
+        assert!(locks.try_lock("a").is_some());
+
+        // A single shard serializes all the keys.
+        let locks = LockMap::<u32>::with_shards(1);
+        let _guard = locks.lock(&1);
+        assert!(locks.try_lock(&2).is_none());
+    }
+
+    #[test]
+    fn test_lock_map_exclusive() {
+        let locks = Arc::new(LockMap::<usize>::new());
+        let inside = Arc::new([AtomicUsize::new(0), AtomicUsize::new(0)]);
+        let handles = (0..8)
+            .map(|i| {
+                let locks = locks.clone();
+                let inside = inside.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..100 {
+                        let key = i % 2;
+                        let _guard = locks.lock(&key);
+                        assert_eq!(inside[key].fetch_add(1, Ordering::SeqCst), 0);
+                        std::thread::yield_now();
+                        inside[key].fetch_sub(1, Ordering::SeqCst);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for h in handles {
+            h.join().unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_lock_map() {
+        let locks = AsyncLockMap::<str>::with_shards(16);
+        let guard = locks.lock("a").await;
+        assert!(locks.try_lock("a").is_none());
+
+        let waiter = tokio::spawn(async move {
+            let _guard = locks.lock("a").await;
+        });
+        tokio::task::yield_now().await;
+        assert!(!waiter.is_finished());
+        drop(guard);
+        waiter.await.unwrap();
+    }
+}
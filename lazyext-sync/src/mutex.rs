@@ -0,0 +1,175 @@
+//! Extensions for mutexes.
+//!
+use crate::ticket::spin_or_yield;
+use crate::{TicketLock, TicketLockGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Extensions for mutexes.
+///
+/// The trait is implemented for [`std::sync::Mutex`], for [`TicketLock`] and,
+/// when the `parking_lot` feature is enabled (except on `wasm32`), for [`parking_lot::Mutex`],
+/// so the same code works across the configurations supported by this crate.
+///
+/// A poisoned [`std::sync::Mutex`] is still usable, so poisoning is not reported:
+/// the guard is recovered with [`std::sync::PoisonError::into_inner`], just like a normal acquisition.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_sync::MutexExt;
+/// use std::sync::Mutex;
+/// use std::time::Duration;
+///
+/// let m = Mutex::new(1);
+/// let guard = m.lock_timeout(Duration::from_millis(10)).unwrap();
+/// assert!(m.lock_timeout(Duration::from_millis(10)).is_none());
+/// drop(guard);
+/// assert_eq!(*m.lock_timeout(Duration::from_millis(10)).unwrap(), 1);
+/// ```
+///
+/// [`TicketLock`]: struct.TicketLock.html
+pub trait MutexExt<'a> {
+    /// The RAII guard returned when the lock is acquired.
+    type Guard: 'a;
+
+    /// Attempts to acquire the lock without blocking.
+    fn try_lock_ext(&'a self) -> Option<Self::Guard>;
+
+    /// Attempts to acquire the lock, giving up after `timeout` has elapsed.
+    ///
+    /// Returns `None` if the lock could not be acquired in time.
+    fn lock_timeout(&'a self, timeout: Duration) -> Option<Self::Guard> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.lock_deadline(deadline),
+            None => loop {
+                if let Some(guard) = self.try_lock_ext() {
+                    return Some(guard);
+                }
+                thread::yield_now();
+            },
+        }
+    }
+
+    /// Attempts to acquire the lock, giving up once `deadline` is reached.
+    ///
+    /// Returns `None` if the lock could not be acquired in time.
+    fn lock_deadline(&'a self, deadline: Instant) -> Option<Self::Guard> {
+        let mut spins = 0;
+        loop {
+            if let Some(guard) = self.try_lock_ext() {
+                return Some(guard);
+            }
+
+            if Instant::now() >= deadline {
+                return None;
+            }
+
+            spin_or_yield(&mut spins);
+        }
+    }
+}
+
+impl<'a, T: ?Sized + 'a> MutexExt<'a> for std::sync::Mutex<T> {
+    type Guard = std::sync::MutexGuard<'a, T>;
+
+    fn try_lock_ext(&'a self) -> Option<Self::Guard> {
+        match self.try_lock() {
+            Ok(guard) => Some(guard),
+            Err(std::sync::TryLockError::WouldBlock) => None,
+            Err(std::sync::TryLockError::Poisoned(e)) => Some(e.into_inner()),
+        }
+    }
+}
+
+cfg_parking_lot! {
+    impl<'a, T: ?Sized + 'a> MutexExt<'a> for parking_lot::Mutex<T> {
+        type Guard = parking_lot::MutexGuard<'a, T>;
+
+        fn try_lock_ext(&'a self) -> Option<Self::Guard> {
+            self.try_lock()
+        }
+
+        fn lock_timeout(&'a self, timeout: Duration) -> Option<Self::Guard> {
+            self.try_lock_for(timeout)
+        }
+
+        fn lock_deadline(&'a self, deadline: Instant) -> Option<Self::Guard> {
+            self.try_lock_until(deadline)
+        }
+    }
+}
+
+/// The timed variants take a real ticket (see [`TicketLock::lock_deadline`]) instead of
+/// polling `try_lock`, which would never succeed while other threads keep the lock queued.
+///
+/// [`TicketLock::lock_deadline`]: struct.TicketLock.html#method.lock_deadline
+impl<'a, T: ?Sized + 'a> MutexExt<'a> for TicketLock<T> {
+    type Guard = TicketLockGuard<'a, T>;
+
+    fn try_lock_ext(&'a self) -> Option<Self::Guard> {
+        self.try_lock()
+    }
+
+    fn lock_timeout(&'a self, timeout: Duration) -> Option<Self::Guard> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.lock_deadline(deadline),
+            None => Some(self.lock()),
+        }
+    }
+
+    fn lock_deadline(&'a self, deadline: Instant) -> Option<Self::Guard> {
+        TicketLock::lock_deadline(self, deadline)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    fn lock_timeout_suite<M>(m: Arc<M>)
+    where
+        M: for<'a> MutexExt<'a> + Send + Sync + 'static,
+    {
+        let guard = m.lock_timeout(Duration::from_millis(10)).unwrap();
+        let mx = m.clone();
+        let handle = thread::spawn(move || mx.lock_timeout(Duration::from_millis(10)).is_none());
+        assert!(handle.join().unwrap());
+        drop(guard);
+
+        let mx = m.clone();
+        let handle = thread::spawn(move || mx.lock_timeout(Duration::from_secs(5)).is_some());
+        assert!(handle.join().unwrap());
+    }
+
+    #[test]
+    fn test_std_lock_timeout() {
+        lock_timeout_suite(Arc::new(Mutex::new(0)));
+    }
+
+    cfg_parking_lot! {
+        #[test]
+        fn test_parking_lot_lock_timeout() {
+            lock_timeout_suite(Arc::new(parking_lot::Mutex::new(0)));
+        }
+    }
+
+    #[test]
+    fn test_std_lock_timeout_poisoned() {
+        let m = Arc::new(Mutex::new(0));
+        let mx = m.clone();
+        let _ = thread::spawn(move || {
+            let _g = mx.lock().unwrap();
+            panic!("poison the mutex");
+        })
+        .join();
+        assert!(m.is_poisoned());
+        assert_eq!(*m.lock_timeout(Duration::from_millis(10)).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_ticket_lock_timeout() {
+        lock_timeout_suite(Arc::new(TicketLock::new(0)));
+    }
+}
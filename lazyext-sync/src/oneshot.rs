@@ -0,0 +1,349 @@
+//! One-shot channels which deliver a single value, for sync/async Rust.
+//!
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+/// The error of `recv`, when the [`Oneshot`] was dropped without sending a value.
+///
+/// [`Oneshot`]: struct.Oneshot.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecvError;
+
+impl std::fmt::Display for RecvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the oneshot was dropped without sending a value")
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+/// The error of `try_recv`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// No value was sent yet.
+    Empty,
+    /// The [`Oneshot`] was dropped without sending a value, or the value was already received.
+    ///
+    /// [`Oneshot`]: struct.Oneshot.html
+    Closed,
+}
+
+impl std::fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TryRecvError::Empty => write!(f, "no value was sent on the oneshot yet"),
+            TryRecvError::Closed => write!(f, "the oneshot was dropped without sending a value"),
+        }
+    }
+}
+
+impl std::error::Error for TryRecvError {}
+
+struct State<T> {
+    value: Option<T>,
+    /// Whether the sender was consumed or dropped.
+    sent: bool,
+    receiver_alive: bool,
+    waker: Option<Waker>,
+}
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    cvar: Condvar,
+}
+
+impl<T> Shared<T> {
+    fn new() -> Arc<Self> {
+        Arc::new(Self {
+            state: Mutex::new(State {
+                value: None,
+                sent: false,
+                receiver_alive: true,
+                waker: None,
+            }),
+            cvar: Condvar::new(),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Closes the sending side, storing `value` if the receiver is still alive, and wakes the
+    /// receiver. Gives `value` back otherwise.
+    fn finish(&self, value: Option<T>) -> Result<(), Option<T>> {
+        let mut state = self.lock();
+        state.sent = true;
+        if !state.receiver_alive {
+            return Err(value);
+        }
+        state.value = value;
+        self.cvar.notify_one();
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    fn try_recv(&self) -> Result<T, TryRecvError> {
+        let mut state = self.lock();
+        match state.value.take() {
+            Some(value) => Ok(value),
+            None if state.sent => Err(TryRecvError::Closed),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    fn close_receiver(&self) {
+        let mut state = self.lock();
+        state.receiver_alive = false;
+        state.waker = None;
+    }
+}
+
+/// Creates a oneshot channel whose value is received by blocking the thread.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_sync::oneshot;
+///
+/// let (tx, rx) = oneshot();
+/// std::thread::spawn(move || {
+///     tx.send(42u64).unwrap();
+/// });
+/// assert_eq!(rx.recv(), Ok(42));
+/// ```
+pub fn oneshot<T>() -> (Oneshot<T>, OneshotReceiver<T>) {
+    let shared = Shared::new();
+    (
+        Oneshot {
+            shared: Some(shared.clone()),
+        },
+        OneshotReceiver { shared },
+    )
+}
+
+/// Creates a oneshot channel whose value is received asynchronously, with any runtime.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_sync::async_oneshot;
+///
+/// #[tokio::main(flavor = "multi_thread", worker_threads = 2)]
+/// async fn main() {
+///     let (tx, rx) = async_oneshot();
+///     tokio::spawn(async move {
+///         tx.send("done").unwrap();
+///     });
+///     assert_eq!(rx.recv().await, Ok("done"));
+/// }
+/// ```
+pub fn async_oneshot<T>() -> (Oneshot<T>, AsyncOneshotReceiver<T>) {
+    let shared = Shared::new();
+    (
+        Oneshot {
+            shared: Some(shared.clone()),
+        },
+        AsyncOneshotReceiver { shared },
+    )
+}
+
+/// The sending half of a oneshot channel, created by [`oneshot`] or [`async_oneshot`].
+///
+/// A Oneshot completes exactly once: either [`send`] delivers the value, or dropping the
+/// Oneshot makes the receiver return [`RecvError`], so the receiver never waits forever.
+///
+/// [`oneshot`]: fn.oneshot.html
+/// [`async_oneshot`]: fn.async_oneshot.html
+/// [`send`]: struct.Oneshot.html#method.send
+/// [`RecvError`]: struct.RecvError.html
+pub struct Oneshot<T> {
+    shared: Option<Arc<Shared<T>>>,
+}
+
+impl<T> Oneshot<T> {
+    /// Sends `value` to the receiver, or gives it back if the receiver was dropped.
+    pub fn send(mut self, value: T) -> Result<(), T> {
+        match self.shared.take() {
+            Some(shared) => shared
+                .finish(Some(value))
+                .map_err(|value| value.expect("the value is given back")),
+            None => Err(value),
+        }
+    }
+
+    /// Returns whether the receiver was dropped, in which case sending is pointless.
+    pub fn is_closed(&self) -> bool {
+        self.shared
+            .as_ref()
+            .is_none_or(|shared| !shared.lock().receiver_alive)
+    }
+}
+
+impl<T> Drop for Oneshot<T> {
+    fn drop(&mut self) {
+        if let Some(shared) = self.shared.take() {
+            let _ = shared.finish(None);
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Oneshot<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Oneshot")
+            .field("closed", &self.is_closed())
+            .finish()
+    }
+}
+
+/// The receiving half of a channel created by [`oneshot`].
+///
+/// [`oneshot`]: fn.oneshot.html
+pub struct OneshotReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> OneshotReceiver<T> {
+    /// Blocks until the value is sent, or returns an error if the [`Oneshot`] is dropped
+    /// without sending it.
+    ///
+    /// [`Oneshot`]: struct.Oneshot.html
+    pub fn recv(self) -> Result<T, RecvError> {
+        let mut state = self.shared.lock();
+        loop {
+            if let Some(value) = state.value.take() {
+                return Ok(value);
+            }
+            if state.sent {
+                return Err(RecvError);
+            }
+            state = self
+                .shared
+                .cvar
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Returns the value if it was sent, without blocking.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        self.shared.try_recv()
+    }
+}
+
+impl<T> Drop for OneshotReceiver<T> {
+    fn drop(&mut self) {
+        self.shared.close_receiver();
+    }
+}
+
+impl<T> std::fmt::Debug for OneshotReceiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OneshotReceiver").finish()
+    }
+}
+
+/// The receiving half of a channel created by [`async_oneshot`].
+///
+/// [`async_oneshot`]: fn.async_oneshot.html
+pub struct AsyncOneshotReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> AsyncOneshotReceiver<T> {
+    /// Waits until the value is sent, or returns an error if the [`Oneshot`] is dropped
+    /// without sending it.
+    ///
+    /// [`Oneshot`]: struct.Oneshot.html
+    pub async fn recv(self) -> Result<T, RecvError> {
+        RecvFuture {
+            shared: &self.shared,
+        }
+        .await
+    }
+
+    /// Returns the value if it was sent, without waiting.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        self.shared.try_recv()
+    }
+}
+
+impl<T> Drop for AsyncOneshotReceiver<T> {
+    fn drop(&mut self) {
+        self.shared.close_receiver();
+    }
+}
+
+impl<T> std::fmt::Debug for AsyncOneshotReceiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncOneshotReceiver").finish()
+    }
+}
+
+struct RecvFuture<'a, T> {
+    shared: &'a Shared<T>,
+}
+
+impl<T> Future for RecvFuture<'_, T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.shared.lock();
+        if let Some(value) = state.value.take() {
+            return Poll::Ready(Ok(value));
+        }
+        if state.sent {
+            return Poll::Ready(Err(RecvError));
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+#[cfg(all(test, not(loom), not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_oneshot() {
+        let (tx, mut rx) = oneshot();
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+        assert!(!tx.is_closed());
+        tx.send(vec![1, 2]).unwrap();
+        assert_eq!(rx.try_recv(), Ok(vec![1, 2]));
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Closed));
+
+        let (tx, rx) = oneshot::<u8>();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            drop(tx);
+        });
+        assert_eq!(rx.recv(), Err(RecvError));
+
+        let (tx, rx) = oneshot();
+        drop(rx);
+        assert!(tx.is_closed());
+        assert_eq!(tx.send(7), Err(7));
+    }
+
+    #[tokio::test]
+    async fn test_async_oneshot() {
+        let (tx, rx) = async_oneshot();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            tx.send(String::from("done")).unwrap();
+        });
+        assert_eq!(rx.recv().await.unwrap(), "done");
+
+        let (tx, rx) = async_oneshot::<()>();
+        let waiter = tokio::spawn(rx.recv());
+        tokio::task::yield_now().await;
+        drop(tx);
+        assert_eq!(waiter.await.unwrap(), Err(RecvError));
+    }
+}
@@ -0,0 +1,533 @@
+//! A weighted semaphore with FIFO fairness, for sync and async Rust.
+//!
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+/// The error returned when permits cannot be acquired from a [`Semaphore`].
+///
+/// [`Semaphore`]: struct.Semaphore.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AcquireError {
+    /// The semaphore has been closed, see [`Semaphore::close`].
+    ///
+    /// [`Semaphore::close`]: struct.Semaphore.html#method.close
+    Closed,
+    /// Not enough permits are available right now, only returned by the `try_acquire` methods.
+    NoPermits,
+    /// More permits were requested than the semaphore will ever have.
+    ExceedsCapacity {
+        /// The number of permits requested.
+        requested: usize,
+        /// The capacity of the semaphore.
+        capacity: usize,
+    },
+}
+
+impl std::fmt::Display for AcquireError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Closed => write!(f, "semaphore closed"),
+            Self::NoPermits => write!(f, "no permits available"),
+            Self::ExceedsCapacity {
+                requested,
+                capacity,
+            } => write!(
+                f,
+                "requested {} permits from a semaphore with a capacity of {}",
+                requested, capacity
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AcquireError {}
+
+struct Waiter {
+    id: u64,
+    /// `None` for threads blocked on the condvar.
+    waker: Option<Waker>,
+}
+
+struct State {
+    permits: usize,
+    closed: bool,
+    /// The waiters in arrival order. Only the front one may take permits, so a large request
+    /// is not starved by a stream of small ones.
+    queue: VecDeque<Waiter>,
+    next_id: u64,
+}
+
+impl State {
+    fn remove(&mut self, id: u64) -> bool {
+        match self.queue.iter().position(|w| w.id == id) {
+            Some(pos) => {
+                self.queue.remove(pos);
+                pos == 0
+            }
+            None => false,
+        }
+    }
+}
+
+struct Inner {
+    state: Mutex<State>,
+    cvar: Condvar,
+    capacity: usize,
+}
+
+impl Inner {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Wakes the front waiter so it checks whether the permits it needs are available.
+    fn wake_front(&self, state: &State) {
+        if let Some(waker) = state.queue.front().and_then(|w| w.waker.as_ref()) {
+            waker.wake_by_ref();
+        }
+        self.cvar.notify_all();
+    }
+
+    /// Takes `n` permits if the waiter `id` (or a new waiter, if `None`) is first in line and
+    /// they are available, otherwise queues the waiter.
+    fn poll_acquire(
+        &self,
+        state: &mut State,
+        n: usize,
+        id: &mut Option<u64>,
+        waker: Option<&Waker>,
+    ) -> Poll<Result<(), AcquireError>> {
+        if state.closed {
+            if let Some(id) = id.take() {
+                state.remove(id);
+            }
+            return Poll::Ready(Err(AcquireError::Closed));
+        }
+
+        let first = match *id {
+            Some(id) => state.queue.front().map(|w| w.id) == Some(id),
+            None => state.queue.is_empty(),
+        };
+        if first && state.permits >= n {
+            state.permits -= n;
+            if id.take().is_some() {
+                state.queue.pop_front();
+            }
+            // The next waiter may fit in the permits which are left.
+            self.wake_front(state);
+            return Poll::Ready(Ok(()));
+        }
+
+        match *id {
+            Some(id) => {
+                if let Some(w) = state.queue.iter_mut().find(|w| w.id == id) {
+                    w.waker = waker.cloned();
+                }
+            }
+            None => {
+                let new_id = state.next_id;
+                state.next_id += 1;
+                state.queue.push_back(Waiter {
+                    id: new_id,
+                    waker: waker.cloned(),
+                });
+                *id = Some(new_id);
+            }
+        }
+        Poll::Pending
+    }
+
+    fn release(&self, n: usize) {
+        let mut state = self.lock();
+        state.permits += n;
+        self.wake_front(&state);
+    }
+}
+
+/// A Semaphore hands out up to a fixed number of permits, acquired in any weight and in FIFO
+/// order, like Go's `semaphore.Weighted`.
+///
+/// Permits are released when the returned [`SemaphorePermit`] is dropped. A permit owns a
+/// handle to its semaphore, so it can be moved into a spawned thread or task, which then
+/// releases it when done. [`close`] fails all the pending and future acquisitions.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_sync::Semaphore;
+///
+/// #[tokio::main(flavor = "multi_thread", worker_threads = 4)]
+/// async fn main() {
+///     // At most 10 units of memory in flight.
+///     let sem = Semaphore::new(10);
+///     let mut handles = Vec::new();
+///     for cost in [4, 6, 3, 7] {
+///         let permit = sem.acquire_many(cost).await.unwrap();
+///         handles.push(tokio::spawn(async move {
+///             // do some work of `cost` units, then release them
+///             drop(permit);
+///         }));
+///     }
+///     for h in handles {
+///         h.await.unwrap();
+///     }
+///     assert_eq!(sem.available_permits(), 10);
+/// }
+/// ```
+///
+/// [`SemaphorePermit`]: struct.SemaphorePermit.html
+/// [`close`]: struct.Semaphore.html#method.close
+#[derive(Clone)]
+pub struct Semaphore {
+    inner: Arc<Inner>,
+}
+
+impl std::fmt::Debug for Semaphore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.inner.lock();
+        f.debug_struct("Semaphore")
+            .field("permits", &state.permits)
+            .field("capacity", &self.inner.capacity)
+            .field("waiters", &state.queue.len())
+            .field("closed", &state.closed)
+            .finish()
+    }
+}
+
+impl Semaphore {
+    /// Creates a new semaphore with `permits` permits, which is also its capacity.
+    pub fn new(permits: usize) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                state: Mutex::new(State {
+                    permits,
+                    closed: false,
+                    queue: VecDeque::new(),
+                    next_id: 0,
+                }),
+                cvar: Condvar::new(),
+                capacity: permits,
+            }),
+        }
+    }
+
+    /// Returns the number of permits of the semaphore when none is acquired.
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity
+    }
+
+    /// Returns the number of permits which are not acquired.
+    pub fn available_permits(&self) -> usize {
+        self.inner.lock().permits
+    }
+
+    /// Closes the semaphore: the pending and future acquisitions fail with
+    /// [`AcquireError::Closed`]. The permits already acquired stay valid.
+    ///
+    /// [`AcquireError::Closed`]: enum.AcquireError.html#variant.Closed
+    pub fn close(&self) {
+        let mut state = self.inner.lock();
+        state.closed = true;
+        for w in state.queue.drain(..) {
+            if let Some(waker) = w.waker {
+                waker.wake();
+            }
+        }
+        self.inner.cvar.notify_all();
+    }
+
+    /// Returns whether the semaphore has been closed.
+    pub fn is_closed(&self) -> bool {
+        self.inner.lock().closed
+    }
+
+    fn check(&self, n: usize) -> Result<(), AcquireError> {
+        if n > self.inner.capacity {
+            return Err(AcquireError::ExceedsCapacity {
+                requested: n,
+                capacity: self.inner.capacity,
+            });
+        }
+        Ok(())
+    }
+
+    fn permit(&self, n: usize) -> SemaphorePermit {
+        SemaphorePermit {
+            sem: self.clone(),
+            n,
+        }
+    }
+
+    /// Acquires one permit, see [`acquire_many`].
+    ///
+    /// [`acquire_many`]: struct.Semaphore.html#method.acquire_many
+    pub async fn acquire(&self) -> Result<SemaphorePermit, AcquireError> {
+        self.acquire_many(1).await
+    }
+
+    /// Waits until `n` permits are available to this caller and acquires them.
+    ///
+    /// Callers are served in FIFO order: a request which does not fit yet blocks the requests
+    /// made after it, even if they would fit. Dropping the future gives up the place in line.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the semaphore is closed, or if `n` is greater than its capacity.
+    pub async fn acquire_many(&self, n: usize) -> Result<SemaphorePermit, AcquireError> {
+        self.check(n)?;
+        Acquire {
+            inner: &self.inner,
+            n,
+            id: None,
+        }
+        .await?;
+        Ok(self.permit(n))
+    }
+
+    /// Blocks the current thread until `n` permits are available to it and acquires them, see
+    /// [`acquire_many`].
+    ///
+    /// [`acquire_many`]: struct.Semaphore.html#method.acquire_many
+    pub fn acquire_many_blocking(&self, n: usize) -> Result<SemaphorePermit, AcquireError> {
+        self.check(n)?;
+        let mut state = self.inner.lock();
+        let mut id = None;
+        loop {
+            if let Poll::Ready(res) = self.inner.poll_acquire(&mut state, n, &mut id, None) {
+                return res.map(|()| self.permit(n));
+            }
+            state = self
+                .inner
+                .cvar
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Acquires `n` permits if they are available and nobody is waiting, without blocking.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_sync::{AcquireError, Semaphore};
+    ///
+    /// let sem = Semaphore::new(3);
+    /// let mut permit = sem.try_acquire_many(3).unwrap();
+    /// assert_eq!(sem.try_acquire_many(1).unwrap_err(), AcquireError::NoPermits);
+    ///
+    /// // Hand one of the permits back.
+    /// drop(permit.split(1));
+    /// assert_eq!(permit.num_permits(), 2);
+    /// assert!(sem.try_acquire_many(1).is_ok());
+    /// ```
+    pub fn try_acquire_many(&self, n: usize) -> Result<SemaphorePermit, AcquireError> {
+        self.check(n)?;
+        let mut state = self.inner.lock();
+        if state.closed {
+            return Err(AcquireError::Closed);
+        }
+        if !state.queue.is_empty() || state.permits < n {
+            return Err(AcquireError::NoPermits);
+        }
+        state.permits -= n;
+        Ok(self.permit(n))
+    }
+}
+
+struct Acquire<'a> {
+    inner: &'a Inner,
+    n: usize,
+    /// The place in line, once the future has been queued.
+    id: Option<u64>,
+}
+
+impl Future for Acquire<'_> {
+    type Output = Result<(), AcquireError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let mut state = this.inner.lock();
+        this.inner
+            .poll_acquire(&mut state, this.n, &mut this.id, Some(cx.waker()))
+    }
+}
+
+impl Drop for Acquire<'_> {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            let mut state = self.inner.lock();
+            // The next waiter may fit in the permits this one was waiting for.
+            if state.remove(id) {
+                self.inner.wake_front(&state);
+            }
+        }
+    }
+}
+
+/// Permits acquired from a [`Semaphore`], released when dropped.
+///
+/// [`Semaphore`]: struct.Semaphore.html
+#[derive(Debug)]
+pub struct SemaphorePermit {
+    sem: Semaphore,
+    n: usize,
+}
+
+impl SemaphorePermit {
+    /// Returns the number of permits held.
+    pub fn num_permits(&self) -> usize {
+        self.n
+    }
+
+    /// Splits `n` permits off into a new permit, e.g. to forward them to another task.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the number of permits held.
+    pub fn split(&mut self, n: usize) -> SemaphorePermit {
+        assert!(
+            n <= self.n,
+            "cannot split {} permits off a permit of {}",
+            n,
+            self.n
+        );
+        self.n -= n;
+        self.sem.permit(n)
+    }
+
+    /// Merges the permits of `other`, which must come from the same semaphore, into this one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the permits come from different semaphores.
+    pub fn merge(&mut self, mut other: SemaphorePermit) {
+        assert!(
+            Arc::ptr_eq(&self.sem.inner, &other.sem.inner),
+            "cannot merge permits of different semaphores"
+        );
+        self.n += std::mem::take(&mut other.n);
+    }
+
+    /// Keeps the permits acquired forever, reducing the permits of the semaphore.
+    pub fn forget(mut self) {
+        self.n = 0;
+    }
+}
+
+impl Drop for SemaphorePermit {
+    fn drop(&mut self) {
+        if self.n > 0 {
+            self.sem.inner.release(self.n);
+        }
+    }
+}
+
+#[cfg(all(test, not(loom), not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_weighted_fifo() {
+        let sem = Semaphore::new(5);
+        let held = sem.acquire_many(3).await.unwrap();
+
+        // The large request is first in line, so the small one must wait behind it.
+        let large = tokio::spawn({
+            let sem = sem.clone();
+            async move { sem.acquire_many(4).await.map(|p| p.num_permits()) }
+        });
+        while sem.inner.lock().queue.is_empty() {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(
+            sem.try_acquire_many(1).unwrap_err(),
+            AcquireError::NoPermits
+        );
+
+        drop(held);
+        assert_eq!(large.await.unwrap(), Ok(4));
+        assert_eq!(sem.available_permits(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_waiter_passes_turn() {
+        let sem = Semaphore::new(2);
+        let held = sem.acquire().await.unwrap();
+
+        let large = tokio::spawn({
+            let sem = sem.clone();
+            async move { sem.acquire_many(2).await.map(drop) }
+        });
+        while sem.inner.lock().queue.is_empty() {
+            tokio::task::yield_now().await;
+        }
+        let small = tokio::spawn({
+            let sem = sem.clone();
+            async move { sem.acquire().await.map(|p| p.num_permits()) }
+        });
+        while sem.inner.lock().queue.len() < 2 {
+            tokio::task::yield_now().await;
+        }
+
+        large.abort();
+        assert_eq!(small.await.unwrap(), Ok(1));
+        drop(held);
+    }
+
+    #[tokio::test]
+    async fn test_close() {
+        let sem = Semaphore::new(1);
+        let held = sem.acquire().await.unwrap();
+        let waiter = tokio::spawn({
+            let sem = sem.clone();
+            async move { sem.acquire().await.map(drop) }
+        });
+        let blocked = std::thread::spawn({
+            let sem = sem.clone();
+            move || sem.acquire_many_blocking(1).map(drop)
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        sem.close();
+        assert_eq!(waiter.await.unwrap(), Err(AcquireError::Closed));
+        assert_eq!(blocked.join().unwrap(), Err(AcquireError::Closed));
+        assert!(sem.is_closed());
+        assert_eq!(sem.try_acquire_many(0).unwrap_err(), AcquireError::Closed);
+        drop(held);
+        assert_eq!(sem.available_permits(), 1);
+    }
+
+    #[test]
+    fn test_forward_permits() {
+        let sem = Semaphore::new(4);
+        let mut permit = sem.acquire_many_blocking(4).unwrap();
+        let handles = (0..3)
+            .map(|_| {
+                let part = permit.split(1);
+                std::thread::spawn(move || drop(part))
+            })
+            .collect::<Vec<_>>();
+        for h in handles {
+            h.join().unwrap();
+        }
+        assert_eq!(permit.num_permits(), 1);
+        assert_eq!(sem.acquire_many_blocking(3).unwrap().num_permits(), 3);
+
+        let other = sem.try_acquire_many(2).unwrap();
+        permit.merge(other);
+        assert_eq!(permit.num_permits(), 3);
+        permit.forget();
+        assert_eq!(sem.available_permits(), 1);
+        assert_eq!(
+            sem.try_acquire_many(5).unwrap_err(),
+            AcquireError::ExceedsCapacity {
+                requested: 5,
+                capacity: 4
+            }
+        );
+    }
+}
@@ -0,0 +1,347 @@
+//! A concurrent, insert-only skip list keyed by byte strings.
+//!
+use lazyext_slice::AsBytesRef;
+use std::cmp::Ordering as CmpOrdering;
+use std::fmt;
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, AtomicU64, AtomicUsize, Ordering};
+
+/// The maximum height of a tower, enough for about 4^12 (16M) entries.
+const MAX_HEIGHT: usize = 12;
+
+type Tower<V> = [AtomicPtr<Node<V>>];
+
+struct Node<V> {
+    key: Box<[u8]>,
+    value: V,
+    tower: Box<Tower<V>>,
+}
+
+/// A sorted map from byte strings to values, which can be read and inserted into
+/// concurrently without locks, e.g. as the memtable of a LSM-tree.
+///
+/// Keys are ordered bytewise (like `memcmp`), so the order-preserving encodings of
+/// `lazyext-slice` can be used to build composite keys.
+///
+/// Entries are never removed or replaced while the map is alive, which is what lets readers
+/// hold references to them without any reclamation scheme: [`insert`] gives the value back if
+/// the key is already present. Memtables usually make keys unique by appending a sequence
+/// number.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_sync::SkipMap;
+///
+/// let map = SkipMap::new();
+/// std::thread::scope(|s| {
+///     for t in 0..4u32 {
+///         let map = &map;
+///         s.spawn(move || {
+///             for i in 0..100u32 {
+///                 map.insert((i * 4 + t).to_be_bytes(), i).unwrap();
+///             }
+///         });
+///     }
+/// });
+///
+/// assert_eq!(map.len(), 400);
+/// assert_eq!(map.get(7u32.to_be_bytes()), Some(&1));
+/// assert_eq!(map.insert(7u32.to_be_bytes(), 0), Err(0));
+/// let keys = map.iter().map(|(k, _)| k.to_vec()).collect::<Vec<_>>();
+/// assert!(keys.windows(2).all(|w| w[0] < w[1]));
+/// ```
+///
+/// [`insert`]: struct.SkipMap.html#method.insert
+pub struct SkipMap<V> {
+    head: Box<Tower<V>>,
+    len: AtomicUsize,
+    /// The state of the generator of tower heights.
+    seed: AtomicU64,
+}
+
+// The map owns its nodes, which are shared with every thread reading it.
+unsafe impl<V: Send> Send for SkipMap<V> {}
+unsafe impl<V: Send + Sync> Sync for SkipMap<V> {}
+
+impl<V> Default for SkipMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> SkipMap<V> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self {
+            head: new_tower(MAX_HEIGHT),
+            len: AtomicUsize::new(0),
+            seed: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the number of entries.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Acquire)
+    }
+
+    /// Returns whether the map has no entry.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the value of `key`.
+    pub fn get(&self, key: impl AsBytesRef) -> Option<&V> {
+        let key = key.as_bytes_ref();
+        let mut pred: &Tower<V> = &self.head;
+        for level in (0..MAX_HEIGHT).rev() {
+            // SAFETY: nodes are only freed when the map is dropped.
+            while let Some(node) = unsafe { pred[level].load(Ordering::Acquire).as_ref() } {
+                match (*node.key).cmp(key) {
+                    CmpOrdering::Less => pred = &node.tower,
+                    CmpOrdering::Equal => return Some(&node.value),
+                    CmpOrdering::Greater => break,
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns whether the map has an entry for `key`.
+    #[inline]
+    pub fn contains_key(&self, key: impl AsBytesRef) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Inserts an entry, or gives `value` back if `key` is already present.
+    pub fn insert(&self, key: impl AsBytesRef, value: V) -> Result<(), V> {
+        let key = key.as_bytes_ref();
+        let mut preds = [ptr::null::<AtomicPtr<Node<V>>>(); MAX_HEIGHT];
+        let mut succs = [ptr::null_mut(); MAX_HEIGHT];
+        if self.find_splice(key, &mut preds, &mut succs) {
+            return Err(value);
+        }
+
+        let height = self.random_height();
+        let node = Box::into_raw(Box::new(Node {
+            key: key.into(),
+            value,
+            tower: new_tower(height),
+        }));
+        for level in 0..height {
+            loop {
+                // SAFETY: `node` is not shared until it is linked at level 0, then only its
+                // atomic tower is written, and `preds` point into towers which outlive `self`.
+                unsafe {
+                    (*node).tower[level].store(succs[level], Ordering::Relaxed);
+                    let pred = &*preds[level].add(level);
+                    if pred
+                        .compare_exchange(succs[level], node, Ordering::AcqRel, Ordering::Acquire)
+                        .is_ok()
+                    {
+                        break;
+                    }
+                }
+
+                // Another thread changed the splice, search it again.
+                if self.find_splice(key, &mut preds, &mut succs) && level == 0 {
+                    // SAFETY: the node was never linked, so this is its only reference.
+                    let node = unsafe { Box::from_raw(node) };
+                    return Err(node.value);
+                }
+            }
+        }
+        self.len.fetch_add(1, Ordering::AcqRel);
+        Ok(())
+    }
+
+    /// Returns an iterator over the entries, in key order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, V> {
+        Iter {
+            next: self.head[0].load(Ordering::Acquire),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over the entries whose key is greater than or equal to `key`.
+    pub fn range_from(&self, key: impl AsBytesRef) -> Iter<'_, V> {
+        let mut preds = [ptr::null(); MAX_HEIGHT];
+        let mut succs = [ptr::null_mut(); MAX_HEIGHT];
+        self.find_splice(key.as_bytes_ref(), &mut preds, &mut succs);
+        Iter {
+            next: succs[0],
+            _marker: PhantomData,
+        }
+    }
+
+    /// Finds, at every level, the tower of the last node before `key` and the node after it
+    /// (or the node of `key` itself), and returns whether `key` is present.
+    fn find_splice(
+        &self,
+        key: &[u8],
+        preds: &mut [*const AtomicPtr<Node<V>>; MAX_HEIGHT],
+        succs: &mut [*mut Node<V>; MAX_HEIGHT],
+    ) -> bool {
+        let mut found = false;
+        let mut pred: &Tower<V> = &self.head;
+        for level in (0..MAX_HEIGHT).rev() {
+            let mut next = pred[level].load(Ordering::Acquire);
+            // SAFETY: nodes are only freed when the map is dropped.
+            while let Some(node) = unsafe { next.as_ref() } {
+                match (*node.key).cmp(key) {
+                    CmpOrdering::Less => {
+                        pred = &node.tower;
+                        next = pred[level].load(Ordering::Acquire);
+                    }
+                    CmpOrdering::Equal => {
+                        found = true;
+                        break;
+                    }
+                    CmpOrdering::Greater => break,
+                }
+            }
+            preds[level] = pred.as_ptr();
+            succs[level] = next;
+        }
+        found
+    }
+
+    /// Draws a height with a branching factor of 4, from a SplitMix64 sequence.
+    fn random_height(&self) -> usize {
+        const GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+        let mut z = self
+            .seed
+            .fetch_add(GAMMA, Ordering::Relaxed)
+            .wrapping_add(GAMMA);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        (1 + z.trailing_zeros() as usize / 2).min(MAX_HEIGHT)
+    }
+}
+
+fn new_tower<V>(height: usize) -> Box<Tower<V>> {
+    (0..height)
+        .map(|_| AtomicPtr::new(ptr::null_mut()))
+        .collect()
+}
+
+impl<V> Drop for SkipMap<V> {
+    fn drop(&mut self) {
+        let mut next = *self.head[0].get_mut();
+        while !next.is_null() {
+            // SAFETY: every node is linked exactly once at level 0, and `&mut self` means no
+            // reader is left.
+            let mut node = unsafe { Box::from_raw(next) };
+            next = *node.tower[0].get_mut();
+        }
+    }
+}
+
+impl<V: fmt::Debug> fmt::Debug for SkipMap<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, V> IntoIterator for &'a SkipMap<V> {
+    type Item = (&'a [u8], &'a V);
+    type IntoIter = Iter<'a, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the entries of a [`SkipMap`], in key order.
+///
+/// Entries inserted concurrently after the current position may or may not be yielded.
+///
+/// [`SkipMap`]: struct.SkipMap.html
+pub struct Iter<'a, V> {
+    next: *mut Node<V>,
+    _marker: PhantomData<&'a SkipMap<V>>,
+}
+
+unsafe impl<V: Send + Sync> Send for Iter<'_, V> {}
+unsafe impl<V: Send + Sync> Sync for Iter<'_, V> {}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = (&'a [u8], &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // SAFETY: the map, which owns the nodes, outlives `'a`.
+        let node = unsafe { self.next.as_ref::<'a>()? };
+        self.next = node.tower[0].load(Ordering::Acquire);
+        Some((&node.key, &node.value))
+    }
+}
+
+impl<V> FusedIterator for Iter<'_, V> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_skipmap_order() {
+        let map = SkipMap::new();
+        for k in ["b", "a", "ab", "", "c"] {
+            map.insert(k, k.len()).unwrap();
+        }
+        assert_eq!(map.insert("ab", 0), Err(0));
+        assert_eq!(map.len(), 5);
+        assert_eq!(map.get("ab"), Some(&2));
+        assert!(!map.contains_key("abc"));
+
+        let keys = map.iter().map(|(k, _)| k).collect::<Vec<_>>();
+        assert_eq!(keys, [&b""[..], b"a", b"ab", b"b", b"c"]);
+        let keys = map.range_from("aa").map(|(k, _)| k).collect::<Vec<_>>();
+        assert_eq!(keys, [&b"ab"[..], b"b", b"c"]);
+        assert_eq!(map.range_from("d").next(), None);
+        assert_eq!(format!("{:?}", SkipMap::<()>::new()), "{}");
+    }
+
+    #[test]
+    fn test_skipmap_concurrent_insert() {
+        let map = Arc::new(SkipMap::new());
+        let handles = (0..8u32)
+            .map(|t| {
+                let map = map.clone();
+                // Every thread inserts the same keys, so each key is contended.
+                thread::spawn(move || {
+                    (0..1000u32)
+                        .filter(|i| map.insert(i.to_be_bytes(), t).is_ok())
+                        .count()
+                })
+            })
+            .collect::<Vec<_>>();
+        let inserted: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+
+        assert_eq!(inserted, 1000);
+        assert_eq!(map.len(), 1000);
+        let keys = map.iter().map(|(k, _)| k.to_vec()).collect::<Vec<_>>();
+        assert_eq!(keys.len(), 1000);
+        assert!(keys.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_skipmap_drop() {
+        let value = Arc::new(());
+        let map = SkipMap::new();
+        for i in 0..100u8 {
+            map.insert([i], value.clone()).unwrap();
+        }
+        assert!(map.insert([0], value.clone()).is_err());
+        assert_eq!(Arc::strong_count(&value), 101);
+        drop(map);
+        assert_eq!(Arc::strong_count(&value), 1);
+    }
+}
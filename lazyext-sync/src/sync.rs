@@ -0,0 +1,56 @@
+//! Synchronization primitives used by the wait groups, they are replaced by
+//! [loom](https://docs.rs/loom)'s when compiled with `--cfg loom`.
+//!
+//! On `wasm32` the std primitives are always used, even with the `parking_lot` feature: without
+//! threads a lock is never contended, so they cost nothing.
+//!
+use lazyext_macros::{cfg_loom, cfg_not_loom};
+
+cfg_loom! {
+    pub(crate) use loom::sync::atomic::{AtomicUsize, Ordering};
+    pub(crate) use loom::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+    #[inline]
+    pub(crate) fn lock<T>(m: &Mutex<T>) -> MutexGuard<'_, T> {
+        m.lock().unwrap()
+    }
+
+    #[inline]
+    pub(crate) fn wait<'a, T>(cvar: &Condvar, guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+        cvar.wait(guard).unwrap()
+    }
+}
+
+cfg_not_loom! {
+    pub(crate) use std::sync::atomic::{AtomicUsize, Ordering};
+    pub(crate) use std::sync::Arc;
+
+    cfg_parking_lot! {
+        pub(crate) use parking_lot::{Condvar, Mutex, MutexGuard};
+
+        #[inline]
+        pub(crate) fn lock<T>(m: &Mutex<T>) -> MutexGuard<'_, T> {
+            m.lock()
+        }
+
+        #[inline]
+        pub(crate) fn wait<'a, T>(cvar: &Condvar, mut guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+            cvar.wait(&mut guard);
+            guard
+        }
+    }
+
+    cfg_not_parking_lot! {
+        pub(crate) use std::sync::{Condvar, Mutex, MutexGuard};
+
+        #[inline]
+        pub(crate) fn lock<T>(m: &Mutex<T>) -> MutexGuard<'_, T> {
+            m.lock().unwrap()
+        }
+
+        #[inline]
+        pub(crate) fn wait<'a, T>(cvar: &Condvar, guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+            cvar.wait(guard).unwrap()
+        }
+    }
+}
@@ -0,0 +1,343 @@
+//! A fair (FIFO) spin lock based on tickets.
+//!
+use std::cell::UnsafeCell;
+use std::hint::spin_loop;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+use std::time::Instant;
+
+/// How many times a waiter spins before it starts yielding its time slice.
+const SPIN_LIMIT: usize = 64;
+
+/// Spins for the first [`SPIN_LIMIT`] calls, then yields the time slice of the current thread.
+#[inline]
+pub(crate) fn spin_or_yield(spins: &mut usize) {
+    if *spins < SPIN_LIMIT {
+        *spins += 1;
+        spin_loop();
+    } else {
+        thread::yield_now();
+    }
+}
+
+/// A TicketLock is a mutual exclusion primitive which grants the lock in FIFO order.
+///
+/// Every call to [`lock`] takes a ticket, and the lock is handed to the tickets
+/// one by one in the order they were taken, so no thread can be starved by
+/// a stream of newer lock requests (which can happen with unfair locks).
+///
+/// Waiters spin for a short while and then yield the time slice, so the lock is best
+/// suited for short critical sections on latency-sensitive paths.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext_sync::TicketLock;
+/// use std::sync::Arc;
+///
+/// let lock = Arc::new(TicketLock::new(0));
+///
+/// let handles = (0..4).map(|_| {
+///     let lock = lock.clone();
+///     std::thread::spawn(move || {
+///         for _ in 0..100 {
+///             *lock.lock() += 1;
+///         }
+///     })
+/// }).collect::<Vec<_>>();
+///
+/// for h in handles {
+///     h.join().unwrap();
+/// }
+///
+/// assert_eq!(*lock.lock(), 400);
+/// ```
+///
+/// [`lock`]: struct.TicketLock.html#method.lock
+pub struct TicketLock<T: ?Sized> {
+    next_ticket: AtomicUsize,
+    now_serving: AtomicUsize,
+    /// Number of tickets in `abandoned`, lets `unlock` skip the mutex in the common case.
+    abandoned_len: AtomicUsize,
+    /// Tickets whose holders gave up waiting (see [`lock_deadline`]), `unlock` skips over them.
+    ///
+    /// [`lock_deadline`]: struct.TicketLock.html#method.lock_deadline
+    abandoned: Mutex<Vec<usize>>,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Send for TicketLock<T> {}
+unsafe impl<T: ?Sized + Send> Sync for TicketLock<T> {}
+
+impl<T> TicketLock<T> {
+    /// Creates a new `TicketLock` in an unlocked state.
+    pub const fn new(data: T) -> Self {
+        Self {
+            next_ticket: AtomicUsize::new(0),
+            now_serving: AtomicUsize::new(0),
+            abandoned_len: AtomicUsize::new(0),
+            abandoned: Mutex::new(Vec::new()),
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    /// Consumes this lock, returning the underlying data.
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T: ?Sized> TicketLock<T> {
+    /// Acquires the lock, blocking the current thread until it is its turn.
+    pub fn lock(&self) -> TicketLockGuard<'_, T> {
+        let ticket = self.next_ticket.fetch_add(1, Ordering::Relaxed);
+        let mut spins = 0;
+        while self.now_serving.load(Ordering::Acquire) != ticket {
+            spin_or_yield(&mut spins);
+        }
+        TicketLockGuard { lock: self }
+    }
+
+    /// Acquires the lock in FIFO order, giving up once `deadline` is reached.
+    ///
+    /// Unlike polling [`try_lock`], the caller takes a real ticket, so it keeps its place
+    /// in the queue even when other threads hold the lock back to back. If the deadline passes
+    /// before its turn comes, the ticket is abandoned and later unlocks skip over it.
+    ///
+    /// [`try_lock`]: struct.TicketLock.html#method.try_lock
+    pub fn lock_deadline(&self, deadline: Instant) -> Option<TicketLockGuard<'_, T>> {
+        let ticket = self.next_ticket.fetch_add(1, Ordering::Relaxed);
+        let mut spins = 0;
+        loop {
+            if self.now_serving.load(Ordering::SeqCst) == ticket {
+                return Some(TicketLockGuard { lock: self });
+            }
+
+            if Instant::now() >= deadline {
+                return self.abandon(ticket);
+            }
+
+            spin_or_yield(&mut spins);
+        }
+    }
+
+    /// Registers `ticket` as abandoned, unless the lock was handed to it in the meantime.
+    fn abandon(&self, ticket: usize) -> Option<TicketLockGuard<'_, T>> {
+        let mut abandoned = self
+            .abandoned
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        self.abandoned_len.fetch_add(1, Ordering::SeqCst);
+        abandoned.push(ticket);
+
+        // An unlocker which did not see the registration may already have handed us the lock,
+        // in that case take it instead of leaving the lock held by nobody.
+        if self.now_serving.load(Ordering::SeqCst) == ticket {
+            abandoned.pop();
+            self.abandoned_len.fetch_sub(1, Ordering::SeqCst);
+            return Some(TicketLockGuard { lock: self });
+        }
+        None
+    }
+
+    /// Attempts to acquire the lock without waiting.
+    ///
+    /// Returns `None` if the lock is held or other threads are already queued for it.
+    pub fn try_lock(&self) -> Option<TicketLockGuard<'_, T>> {
+        let serving = self.now_serving.load(Ordering::Acquire);
+        self.next_ticket
+            .compare_exchange(
+                serving,
+                serving.wrapping_add(1),
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            )
+            .ok()
+            .map(|_| TicketLockGuard { lock: self })
+    }
+
+    /// Returns whether the lock is currently held.
+    pub fn is_locked(&self) -> bool {
+        self.next_ticket.load(Ordering::Relaxed) != self.now_serving.load(Ordering::Relaxed)
+    }
+
+    /// Returns a mutable reference to the underlying data.
+    ///
+    /// Since this call borrows the lock mutably, no actual locking needs to take place.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+
+    fn unlock(&self) {
+        let mut next = self.now_serving.load(Ordering::Relaxed).wrapping_add(1);
+        self.now_serving.store(next, Ordering::SeqCst);
+        if self.abandoned_len.load(Ordering::SeqCst) == 0 {
+            return;
+        }
+
+        // Some waiters gave up, skip their tickets so the lock is not handed to nobody.
+        // Abandoned tickets are only registered while holding this mutex, and a ticket found here
+        // was never served, so `now_serving` cannot move under us.
+        let mut abandoned = self
+            .abandoned
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        while let Some(pos) = abandoned.iter().position(|t| *t == next) {
+            abandoned.swap_remove(pos);
+            self.abandoned_len.fetch_sub(1, Ordering::SeqCst);
+            next = next.wrapping_add(1);
+            self.now_serving.store(next, Ordering::SeqCst);
+        }
+    }
+}
+
+impl<T: Default> Default for TicketLock<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: ?Sized + std::fmt::Debug> std::fmt::Debug for TicketLock<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.try_lock() {
+            Some(guard) => f
+                .debug_struct("TicketLock")
+                .field("data", &&*guard)
+                .finish(),
+            None => f
+                .debug_struct("TicketLock")
+                .field("data", &"<locked>")
+                .finish(),
+        }
+    }
+}
+
+/// An RAII guard of [`TicketLock`], the lock is released when the guard is dropped.
+///
+/// [`TicketLock`]: struct.TicketLock.html
+pub struct TicketLockGuard<'a, T: ?Sized> {
+    lock: &'a TicketLock<T>,
+}
+
+unsafe impl<T: ?Sized + Sync> Sync for TicketLockGuard<'_, T> {}
+
+impl<T: ?Sized> Deref for TicketLockGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for TicketLockGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for TicketLockGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.unlock();
+    }
+}
+
+impl<T: ?Sized + std::fmt::Debug> std::fmt::Debug for TicketLockGuard<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_ticket_lock() {
+        let lock = Arc::new(TicketLock::new(0usize));
+        let handles = (0..8)
+            .map(|_| {
+                let lock = lock.clone();
+                thread::spawn(move || {
+                    for _ in 0..1000 {
+                        *lock.lock() += 1;
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for h in handles {
+            h.join().unwrap();
+        }
+        assert_eq!(*lock.lock(), 8000);
+        assert!(!lock.is_locked());
+    }
+
+    #[test]
+    fn test_lock_deadline_under_contention() {
+        let lock = Arc::new(TicketLock::new(0usize));
+        let stop = Arc::new(AtomicUsize::new(0));
+        let handles = (0..4)
+            .map(|_| {
+                let lock = lock.clone();
+                let stop = stop.clone();
+                thread::spawn(move || {
+                    while stop.load(Ordering::Relaxed) == 0 {
+                        let mut g = lock.lock();
+                        *g += 1;
+                        thread::sleep(Duration::from_micros(200));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // make sure the lock is queued before the timed waiter shows up
+        while *lock.lock() < 16 {}
+        for _ in 0..8 {
+            let g = lock.lock_deadline(Instant::now() + Duration::from_secs(10));
+            assert!(g.is_some());
+        }
+
+        stop.store(1, Ordering::Relaxed);
+        for h in handles {
+            h.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_lock_deadline_abandon() {
+        let lock = Arc::new(TicketLock::new(0usize));
+        let guard = lock.lock();
+
+        let lockx = lock.clone();
+        let timed = thread::spawn(move || {
+            lockx
+                .lock_deadline(Instant::now() + Duration::from_millis(20))
+                .is_none()
+        });
+        assert!(timed.join().unwrap());
+
+        let lockx = lock.clone();
+        let waiter = thread::spawn(move || *lockx.lock() += 1);
+        thread::sleep(Duration::from_millis(10));
+        drop(guard);
+        waiter.join().unwrap();
+        assert_eq!(*lock.lock(), 1);
+        assert!(!lock.is_locked());
+    }
+
+    #[test]
+    fn test_try_lock() {
+        let lock = TicketLock::new(1);
+        let guard = lock.try_lock().unwrap();
+        assert!(lock.is_locked());
+        assert!(lock.try_lock().is_none());
+        assert_eq!(format!("{:?}", lock), "TicketLock { data: \"<locked>\" }");
+        drop(guard);
+        assert_eq!(format!("{:?}", lock), "TicketLock { data: 1 }");
+        assert_eq!(lock.into_inner(), 1);
+    }
+}
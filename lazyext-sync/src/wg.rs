@@ -19,40 +19,76 @@
  */
 //! Golang like WaitGroup implementation for sync/async Rust.
 //!
-macro_rules! cfg_not_parking_lot_expr {
-    ($($item: expr;)*) => {
-        $(
-        #[cfg(not(feature = "parking_lot"))]
-        $item;
-        )*
-    };
+use crate::sync::{lock, wait, Arc, AtomicUsize, Condvar, Mutex, Ordering};
+use std::future::Future;
+use std::ops::Deref;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+/// A misuse of a wait group counter, reported by the `try_add` and `try_done` methods of
+/// [`WaitGroup`] and [`AsyncWaitGroup`].
+///
+/// `add` and `done` panic with this error in debug builds and for groups created with `strict`;
+/// otherwise they clamp the counter, to `usize::MAX` or 0.
+///
+/// [`WaitGroup`]: struct.WaitGroup.html
+/// [`AsyncWaitGroup`]: struct.AsyncWaitGroup.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitGroupMisuse {
+    /// Adding `delta` to the counter would overflow it.
+    Overflow {
+        /// The counter before the addition.
+        count: usize,
+        /// The delta which was added.
+        delta: usize,
+    },
+    /// `done` was called while the counter was already zero.
+    DoneOnZero,
 }
 
-macro_rules! cfg_parking_lot_expr {
-    ($($item: expr;)*) => {
-        $(
-        #[cfg(feature = "parking_lot")]
-        $item;
-        )*
-    };
+impl std::fmt::Display for WaitGroupMisuse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Overflow { count, delta } => write!(
+                f,
+                "adding {} to the wait group counter of {} overflows it",
+                delta, count
+            ),
+            Self::DoneOnZero => write!(f, "done called on a wait group whose counter is zero"),
+        }
+    }
 }
 
-#[cfg(not(feature = "parking_lot"))]
-use std::sync::{Condvar, Mutex, MutexGuard};
+impl std::error::Error for WaitGroupMisuse {}
 
-#[cfg(feature = "parking_lot")]
-use parking_lot::{Condvar, Mutex, MutexGuard};
+/// Panics with `err` if misuses are fatal for the group: in debug builds, or if it is strict.
+#[inline]
+fn misuse(strict: bool, err: WaitGroupMisuse) {
+    if strict || cfg!(debug_assertions) {
+        panic!("{}", err);
+    }
+}
 
-use std::future::Future;
-use std::ops::Sub;
-use std::pin::Pin;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
-use std::task::{Context, Poll, Waker};
+/// A snapshot of the state of a wait group, returned by the `metrics` method of [`WaitGroup`]
+/// and [`AsyncWaitGroup`].
+///
+/// [`WaitGroup`]: struct.WaitGroup.html
+/// [`AsyncWaitGroup`]: struct.AsyncWaitGroup.html
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct WaitGroupMetrics {
+    /// The number of jobs which have been added but are not done yet.
+    pub pending_jobs: usize,
+    /// The number of threads or tasks blocked in `wait`.
+    pub waiting_tasks: usize,
+}
 
 struct Inner {
     cvar: Condvar,
     count: Mutex<usize>,
+    waiters: AtomicUsize,
+    strict: bool,
+    /// The group in which this one counts as a single job while its counter is not zero.
+    parent: Option<WaitGroup>,
 }
 
 /// A WaitGroup waits for a collection of threads to finish.
@@ -66,7 +102,7 @@ struct Inner {
 /// # Example
 ///
 /// ```rust
-/// use wg::WaitGroup;
+/// use lazyext_sync::WaitGroup;
 /// use std::sync::Arc;
 /// use std::sync::atomic::{AtomicUsize, Ordering};
 /// use std::time::Duration;
@@ -100,12 +136,7 @@ pub struct WaitGroup {
 
 impl Default for WaitGroup {
     fn default() -> Self {
-        Self {
-            inner: Arc::new(Inner {
-                cvar: Condvar::new(),
-                count: Mutex::new(0),
-            }),
-        }
+        Self::with_strict(false)
     }
 }
 
@@ -119,13 +150,7 @@ impl Clone for WaitGroup {
 
 impl std::fmt::Debug for WaitGroup {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let count;
-        cfg_not_parking_lot_expr!(
-            count = self.inner.count.lock().unwrap();
-        );
-        cfg_parking_lot_expr!(
-            count = self.inner.count.lock();
-        );
+        let count = lock(&self.inner.count);
         f.debug_struct("WaitGroup").field("count", &*count).finish()
     }
 }
@@ -136,7 +161,7 @@ impl WaitGroup {
     /// # Examples
     ///
     /// ```
-    /// use wg::WaitGroup;
+    /// use lazyext_sync::WaitGroup;
     ///
     /// let wg = WaitGroup::new();
     /// ```
@@ -144,6 +169,72 @@ impl WaitGroup {
         Self::default()
     }
 
+    /// Creates a new wait group whose [`add`] and [`done`] panic on misuse, i.e. an overflow of
+    /// the counter or `done` on a zero counter, in release builds as well.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// use lazyext_sync::WaitGroup;
+    ///
+    /// let wg = WaitGroup::strict();
+    /// wg.done();
+    /// ```
+    ///
+    /// [`add`]: struct.WaitGroup.html#method.add
+    /// [`done`]: struct.WaitGroup.html#method.done
+    pub fn strict() -> Self {
+        Self::with_strict(true)
+    }
+
+    fn with_strict(strict: bool) -> Self {
+        Self::with_parent(strict, None)
+    }
+
+    fn with_parent(strict: bool, parent: Option<Self>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                cvar: Condvar::new(),
+                count: Mutex::new(0),
+                waiters: AtomicUsize::new(0),
+                strict,
+                parent,
+            }),
+        }
+    }
+
+    /// Creates a child group, which counts as a single job of this group while it has jobs of
+    /// its own. [`wait`] on this group thus also waits for all the jobs of its descendants,
+    /// while [`wait`] on the child only waits for the child's subtree.
+    ///
+    /// The child is strict if this group is.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_sync::WaitGroup;
+    ///
+    /// let root = WaitGroup::new();
+    /// let workers = root.child();
+    /// for _ in 0..3 {
+    ///     let t_wg = workers.add(1);
+    ///     std::thread::spawn(move || {
+    ///         // do some time consuming work
+    ///         t_wg.done();
+    ///     });
+    /// }
+    /// assert_eq!(root.waitings(), 1);
+    ///
+    /// // Shuts down once every worker of every child is done.
+    /// root.wait();
+    /// assert_eq!(workers.waitings(), 0);
+    /// ```
+    ///
+    /// [`wait`]: struct.WaitGroup.html#method.wait
+    pub fn child(&self) -> Self {
+        Self::with_parent(self.inner.strict, Some(self.clone()))
+    }
+
     /// Adds delta to the WaitGroup counter.
     /// If the counter becomes zero, all threads blocked on [`wait`] are released.
     ///
@@ -156,7 +247,7 @@ impl WaitGroup {
     ///
     /// # Example
     /// ```rust
-    /// use wg::WaitGroup;
+    /// use lazyext_sync::WaitGroup;
     ///
     /// let wg = WaitGroup::new();
     ///
@@ -172,20 +263,52 @@ impl WaitGroup {
     /// wg.wait();
     /// ```
     ///
+    /// # Panics
+    ///
+    /// Panics if the counter overflows, in debug builds or if the group is [`strict`]. The
+    /// counter saturates otherwise.
+    ///
     /// [`wait`]: struct.AsyncWaitGroup.html#method.wait
+    /// [`strict`]: struct.WaitGroup.html#method.strict
     pub fn add(&self, num: usize) -> Self {
-        let mut ctr;
-        cfg_not_parking_lot_expr!(
-            ctr = self.inner.count.lock().unwrap();
-        );
-        cfg_parking_lot_expr!(
-            ctr = self.inner.count.lock();
-        );
+        if let Err(e) = self.try_add(num) {
+            misuse(self.inner.strict, e);
+            *lock(&self.inner.count) = usize::MAX;
+        }
+        self.clone()
+    }
 
-        *ctr += num;
-        Self {
-            inner: self.inner.clone(),
+    /// Adds delta to the WaitGroup counter like [`add`], or fails without changing it if the
+    /// counter would overflow.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_sync::{WaitGroup, WaitGroupMisuse};
+    ///
+    /// let wg = WaitGroup::new();
+    /// wg.try_add(usize::MAX).unwrap();
+    /// assert_eq!(
+    ///     wg.try_add(1).unwrap_err(),
+    ///     WaitGroupMisuse::Overflow { count: usize::MAX, delta: 1 }
+    /// );
+    /// assert_eq!(wg.waitings(), usize::MAX);
+    /// ```
+    ///
+    /// [`add`]: struct.WaitGroup.html#method.add
+    pub fn try_add(&self, num: usize) -> Result<Self, WaitGroupMisuse> {
+        let mut count = lock(&self.inner.count);
+        let new = count.checked_add(num).ok_or(WaitGroupMisuse::Overflow {
+            count: *count,
+            delta: num,
+        })?;
+        // Parents are always locked after their children, so this cannot deadlock.
+        if let (0, 1.., Some(parent)) = (*count, new, &self.inner.parent) {
+            parent.add(1);
         }
+        *count = new;
+        drop(count);
+        Ok(self.clone())
     }
 
     /// done decrements the WaitGroup counter by one.
@@ -193,7 +316,7 @@ impl WaitGroup {
     /// # Example
     ///
     /// ```rust
-    /// use wg::WaitGroup;
+    /// use lazyext_sync::WaitGroup;
     /// use std::thread;
     ///
     /// let wg = WaitGroup::new();
@@ -205,36 +328,88 @@ impl WaitGroup {
     /// });
     ///
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the counter is already zero, in debug builds or if the group is [`strict`].
+    /// The call is ignored otherwise.
+    ///
+    /// [`strict`]: struct.WaitGroup.html#method.strict
     pub fn done(&self) {
-        let mut val;
-        cfg_not_parking_lot_expr!(
-            val = self.inner.count.lock().unwrap();
-        );
-        cfg_parking_lot_expr!(
-            val = self.inner.count.lock();
-        );
+        if let Err(e) = self.try_done() {
+            misuse(self.inner.strict, e);
+        }
+    }
 
-        *val = if val.eq(&1) {
-            self.inner.cvar.notify_all();
-            0
-        } else if val.eq(&0) {
-            0
-        } else {
-            val.sub(1)
-        };
+    /// Decrements the WaitGroup counter by one like [`done`], or fails if it is already zero.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_sync::{WaitGroup, WaitGroupMisuse};
+    ///
+    /// let wg = WaitGroup::new();
+    /// wg.add(1);
+    /// assert_eq!(wg.try_done(), Ok(()));
+    /// assert_eq!(wg.try_done(), Err(WaitGroupMisuse::DoneOnZero));
+    /// ```
+    ///
+    /// [`done`]: struct.WaitGroup.html#method.done
+    pub fn try_done(&self) -> Result<(), WaitGroupMisuse> {
+        let mut val = lock(&self.inner.count);
+        match *val {
+            0 => return Err(WaitGroupMisuse::DoneOnZero),
+            1 => {
+                self.inner.cvar.notify_all();
+                if let Some(parent) = &self.inner.parent {
+                    parent.done();
+                }
+            }
+            _ => {}
+        }
+        *val -= 1;
+        Ok(())
     }
 
-    /// waitings return how many jobs are waiting.
+    /// waitings return how many jobs are waiting, same as [`pending_jobs`].
+    ///
+    /// [`pending_jobs`]: struct.WaitGroup.html#method.pending_jobs
     pub fn waitings(&self) -> usize {
-        let num;
-        cfg_not_parking_lot_expr!(
-            num = self.inner.count.lock().unwrap().clone();
-        );
+        self.pending_jobs()
+    }
 
-        cfg_parking_lot_expr!(
-            num = self.inner.count.lock().clone();
-        );
-        num
+    /// Returns the number of jobs which have been added but are not done yet.
+    pub fn pending_jobs(&self) -> usize {
+        *lock(&self.inner.count)
+    }
+
+    /// Returns the number of threads blocked in [`wait`].
+    ///
+    /// [`wait`]: struct.WaitGroup.html#method.wait
+    pub fn waiting_tasks(&self) -> usize {
+        self.inner.waiters.load(Ordering::SeqCst)
+    }
+
+    /// Returns the pending jobs and the waiting threads at once.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_sync::{WaitGroup, WaitGroupMetrics};
+    ///
+    /// let wg = WaitGroup::new();
+    /// wg.add(2);
+    /// assert_eq!(
+    ///     wg.metrics(),
+    ///     WaitGroupMetrics { pending_jobs: 2, waiting_tasks: 0 }
+    /// );
+    /// ```
+    pub fn metrics(&self) -> WaitGroupMetrics {
+        let count = lock(&self.inner.count);
+        WaitGroupMetrics {
+            pending_jobs: *count,
+            waiting_tasks: self.inner.waiters.load(Ordering::SeqCst),
+        }
     }
 
     /// wait blocks until the WaitGroup counter is zero.
@@ -242,7 +417,7 @@ impl WaitGroup {
     /// # Example
     ///
     /// ```rust
-    /// use wg::WaitGroup;
+    /// use lazyext_sync::WaitGroup;
     /// use std::thread;
     ///
     /// let wg = WaitGroup::new();
@@ -257,34 +432,68 @@ impl WaitGroup {
     /// wg.wait();
     /// ```
     pub fn wait(&self) {
-        let mut ctr;
-        cfg_not_parking_lot_expr!(
-            ctr = self.inner.count.lock().unwrap();
-        );
-
-        cfg_parking_lot_expr!(
-            ctr = self.inner.count.lock();
-        );
+        let mut ctr = lock(&self.inner.count);
 
         if ctr.eq(&0) {
             return;
         }
 
+        // Counted under the lock, so `metrics` sees the waiter and the jobs consistently.
+        self.inner.waiters.fetch_add(1, Ordering::SeqCst);
         while *ctr > 0 {
-            cfg_not_parking_lot_expr!(
-                ctr = self.inner.cvar.wait(ctr).unwrap();
-            );
+            ctr = wait(&self.inner.cvar, ctr);
+        }
+        self.inner.waiters.fetch_sub(1, Ordering::SeqCst);
+    }
+}
 
-            cfg_parking_lot_expr!(
-                self.inner.cvar.wait(&mut ctr);
-            );
+/// The wakers of the futures waiting on an [`AsyncWaitGroup`], one per future.
+#[derive(Default)]
+pub(crate) struct Wakers {
+    next_id: usize,
+    slots: Vec<(usize, Waker)>,
+}
+
+impl Wakers {
+    /// Stores the waker of the future `id`, allocating an id on its first registration.
+    /// A future keeps its id after being woken, and registers again if it has to keep waiting.
+    pub(crate) fn register(&mut self, id: &mut Option<usize>, waker: &Waker) {
+        let id = *id.get_or_insert_with(|| {
+            self.next_id = self.next_id.wrapping_add(1);
+            self.next_id
+        });
+        match self.slots.iter_mut().find(|(slot, _)| *slot == id) {
+            Some((_, w)) if w.will_wake(waker) => {}
+            Some((_, w)) => *w = waker.clone(),
+            None => self.slots.push((id, waker.clone())),
         }
     }
+
+    pub(crate) fn remove(&mut self, id: usize) {
+        self.slots.retain(|(slot, _)| *slot != id);
+    }
+
+    #[cfg(all(test, not(loom), not(target_arch = "wasm32")))]
+    pub(crate) fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Takes the wakers of all the futures, to wake them once the lock is released. The ids
+    /// keep increasing, so woken futures which register again do not collide with new ones.
+    pub(crate) fn take(&mut self) -> impl Iterator<Item = Waker> {
+        std::mem::take(&mut self.slots)
+            .into_iter()
+            .map(|(_, waker)| waker)
+    }
 }
 
 struct AsyncInner {
-    waker: Mutex<Option<Waker>>,
+    wakers: Mutex<Wakers>,
     count: AtomicUsize,
+    waiters: AtomicUsize,
+    strict: bool,
+    /// The group in which this one counts as a single job while its counter is not zero.
+    parent: Option<AsyncWaitGroup>,
 }
 
 /// An AsyncWaitGroup waits for a collection of threads to finish.
@@ -298,7 +507,7 @@ struct AsyncInner {
 /// # Example
 ///
 /// ```rust
-/// use wg::AsyncWaitGroup;
+/// use lazyext_sync::AsyncWaitGroup;
 /// use std::sync::Arc;
 /// use std::sync::atomic::{AtomicUsize, Ordering};
 /// use tokio::{spawn, time::{sleep, Duration}};
@@ -334,12 +543,7 @@ pub struct AsyncWaitGroup {
 
 impl Default for AsyncWaitGroup {
     fn default() -> Self {
-        Self {
-            inner: Arc::new(AsyncInner {
-                count: AtomicUsize::new(0),
-                waker: Mutex::new(None),
-            }),
-        }
+        Self::with_strict(false)
     }
 }
 
@@ -367,6 +571,63 @@ impl AsyncWaitGroup {
         Self::default()
     }
 
+    /// Creates a new `AsyncWaitGroup` whose [`add`] and [`done`] panic on misuse, i.e. an
+    /// overflow of the counter or `done` on a zero counter, in release builds as well.
+    ///
+    /// [`add`]: struct.AsyncWaitGroup.html#method.add
+    /// [`done`]: struct.AsyncWaitGroup.html#method.done
+    pub fn strict() -> Self {
+        Self::with_strict(true)
+    }
+
+    fn with_strict(strict: bool) -> Self {
+        Self::with_parent(strict, None)
+    }
+
+    fn with_parent(strict: bool, parent: Option<Self>) -> Self {
+        Self {
+            inner: Arc::new(AsyncInner {
+                count: AtomicUsize::new(0),
+                wakers: Mutex::new(Wakers::default()),
+                waiters: AtomicUsize::new(0),
+                strict,
+                parent,
+            }),
+        }
+    }
+
+    /// Creates a child group, which counts as a single job of this group while it has jobs of
+    /// its own, see [`WaitGroup::child`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_sync::AsyncWaitGroup;
+    ///
+    /// #[tokio::main(flavor = "multi_thread", worker_threads = 4)]
+    /// async fn main() {
+    ///     let root = AsyncWaitGroup::new();
+    ///     for _ in 0..2 {
+    ///         let conns = root.child();
+    ///         for _ in 0..3 {
+    ///             let t_wg = conns.add(1);
+    ///             tokio::spawn(async move {
+    ///                 // serve a connection
+    ///                 t_wg.done();
+    ///             });
+    ///         }
+    ///     }
+    ///
+    ///     // Shuts down once every connection of every child is done.
+    ///     root.wait().await;
+    /// }
+    /// ```
+    ///
+    /// [`WaitGroup::child`]: struct.WaitGroup.html#method.child
+    pub fn child(&self) -> Self {
+        Self::with_parent(self.inner.strict, Some(self.clone()))
+    }
+
     /// Adds delta to the WaitGroup counter.
     /// If the counter becomes zero, all threads blocked on [`wait`] are released.
     ///
@@ -379,7 +640,7 @@ impl AsyncWaitGroup {
     ///
     /// # Example
     /// ```rust
-    /// use wg::AsyncWaitGroup;
+    /// use lazyext_sync::AsyncWaitGroup;
     ///
     /// #[tokio::main(flavor = "multi_thread", worker_threads = 10)]
     /// async fn main() {
@@ -398,13 +659,42 @@ impl AsyncWaitGroup {
     /// }
     /// ```
     ///
+    /// # Panics
+    ///
+    /// Panics if the counter overflows, in debug builds or if the group is [`strict`]. The
+    /// counter saturates otherwise.
+    ///
     /// [`wait`]: struct.AsyncWaitGroup.html#method.wait
+    /// [`strict`]: struct.AsyncWaitGroup.html#method.strict
     pub fn add(&self, num: usize) -> Self {
-        self.inner.count.fetch_add(num, Ordering::SeqCst);
+        if let Err(e) = self.try_add(num) {
+            misuse(self.inner.strict, e);
+            self.inner.count.store(usize::MAX, Ordering::SeqCst);
+        }
+        self.clone()
+    }
 
-        Self {
-            inner: self.inner.clone(),
+    /// Adds delta to the counter like [`add`], or fails without changing it if the counter
+    /// would overflow.
+    ///
+    /// [`add`]: struct.AsyncWaitGroup.html#method.add
+    pub fn try_add(&self, num: usize) -> Result<Self, WaitGroupMisuse> {
+        // The parent must count this group before its counter leaves zero, or a concurrent
+        // `done` bringing it back to zero could finish the job in the parent first. The job is
+        // registered upfront and given back if the counter was not zero after all.
+        let parent = self.inner.parent.as_ref().filter(|_| num > 0);
+        if let Some(parent) = parent {
+            parent.add(1);
         }
+        let prev = self
+            .inner
+            .count
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |val| val.checked_add(num));
+        if let (Some(parent), Ok(1..) | Err(_)) = (parent, prev) {
+            parent.done();
+        }
+        prev.map_err(|count| WaitGroupMisuse::Overflow { count, delta: num })?;
+        Ok(self.clone())
     }
 
     /// done decrements the WaitGroup counter by one.
@@ -412,7 +702,7 @@ impl AsyncWaitGroup {
     /// # Example
     ///
     /// ```rust
-    /// use wg::AsyncWaitGroup;
+    /// use lazyext_sync::AsyncWaitGroup;
     ///
     /// #[tokio::main(flavor = "multi_thread", worker_threads = 10)]
     /// async fn main() {
@@ -425,43 +715,75 @@ impl AsyncWaitGroup {
     ///     });
     /// }
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the counter is already zero, in debug builds or if the group is [`strict`].
+    /// The call is ignored otherwise.
+    ///
+    /// [`strict`]: struct.AsyncWaitGroup.html#method.strict
     pub fn done(&self) {
-        let _ = self
+        if let Err(e) = self.try_done() {
+            misuse(self.inner.strict, e);
+        }
+    }
+
+    /// Decrements the counter by one like [`done`], or fails if it is already zero.
+    ///
+    /// [`done`]: struct.AsyncWaitGroup.html#method.done
+    pub fn try_done(&self) -> Result<(), WaitGroupMisuse> {
+        let prev = self
             .inner
             .count
-            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |val| {
-                // We are the last worker
-                if val == 1 {
-                    let waker;
-                    cfg_not_parking_lot_expr!(
-                        waker = self.inner.waker.lock().unwrap().take();
-                    );
-                    cfg_parking_lot_expr!(
-                        waker = self.inner.waker.lock().take();
-                    );
-                    if let Some(waker) = waker {
-                        waker.wake();
-                    }
-                    Some(0)
-                } else if val == 0 {
-                    None
-                } else {
-                    Some(val - 1)
-                }
-            });
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |val| val.checked_sub(1))
+            .map_err(|_| WaitGroupMisuse::DoneOnZero)?;
+
+        // We are the last worker. The wakers must be taken only after the counter hit zero,
+        // otherwise a concurrent poll can register its waker and still observe the old count.
+        if prev == 1 {
+            let wakers = lock(&self.inner.wakers).take();
+            wakers.for_each(Waker::wake);
+            if let Some(parent) = &self.inner.parent {
+                parent.done();
+            }
+        }
+        Ok(())
     }
 
-    /// waitings return how many jobs are waiting.
+    /// waitings return how many jobs are waiting, same as [`pending_jobs`].
+    ///
+    /// [`pending_jobs`]: struct.AsyncWaitGroup.html#method.pending_jobs
     pub fn waitings(&self) -> usize {
+        self.pending_jobs()
+    }
+
+    /// Returns the number of jobs which have been added but are not done yet.
+    pub fn pending_jobs(&self) -> usize {
         self.inner.count.load(Ordering::SeqCst)
     }
 
+    /// Returns the number of tasks whose [`wait`] future is pending.
+    ///
+    /// [`wait`]: struct.AsyncWaitGroup.html#method.wait
+    pub fn waiting_tasks(&self) -> usize {
+        self.inner.waiters.load(Ordering::SeqCst)
+    }
+
+    /// Returns the pending jobs and the waiting tasks. The two are read one after the other, so
+    /// they may be slightly out of sync while jobs finish concurrently.
+    pub fn metrics(&self) -> WaitGroupMetrics {
+        WaitGroupMetrics {
+            pending_jobs: self.pending_jobs(),
+            waiting_tasks: self.waiting_tasks(),
+        }
+    }
+
     /// wait blocks until the WaitGroup counter is zero.
     ///
     /// # Example
     ///
     /// ```rust
-    /// use wg::AsyncWaitGroup;
+    /// use lazyext_sync::AsyncWaitGroup;
     ///
     /// #[tokio::main(flavor = "multi_thread", worker_threads = 10)]
     /// async fn main() {
@@ -479,45 +801,99 @@ impl AsyncWaitGroup {
     /// }
     /// ```
     pub async fn wait(&self) {
-        WaitGroupFuture::new(&self.inner).await
+        WaitGroupFuture::new(&*self.inner).await
     }
-}
-
-struct WaitGroupFuture<'a> {
-    inner: &'a Arc<AsyncInner>,
-}
 
-impl<'a> WaitGroupFuture<'a> {
-    fn new(inner: &'a Arc<AsyncInner>) -> Self {
-        Self { inner }
+    /// Like [`wait`], but the returned future does not borrow the group, so it can be handed to
+    /// executors which require `'static` futures, e.g. `wasm_bindgen_futures::spawn_local` or
+    /// `future_to_promise` in the browser, where the blocking [`WaitGroup`] cannot be used.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lazyext_sync::AsyncWaitGroup;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() {
+    ///     let wg = AsyncWaitGroup::new();
+    ///     let t_wg = wg.add(1);
+    ///
+    ///     let waiter = tokio::spawn(wg.wait_owned());
+    ///     t_wg.done();
+    ///     waiter.await.unwrap();
+    /// }
+    /// ```
+    ///
+    /// [`wait`]: struct.AsyncWaitGroup.html#method.wait
+    /// [`WaitGroup`]: struct.WaitGroup.html
+    pub fn wait_owned(&self) -> impl Future<Output = ()> + Send + 'static {
+        WaitGroupFuture::new(self.inner.clone())
     }
 }
 
-impl Future for WaitGroupFuture<'_> {
-    type Output = ();
+struct WaitGroupFuture<I: Deref<Target = AsyncInner>> {
+    inner: I,
+    /// The id of the waker of this future in the group, once it has been registered.
+    id: Option<usize>,
+    /// Whether the future is counted in the waiters of the group.
+    waiting: bool,
+}
 
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let waker = cx.waker().clone();
+impl<I: Deref<Target = AsyncInner>> WaitGroupFuture<I> {
+    fn new(inner: I) -> Self {
+        Self {
+            inner,
+            id: None,
+            waiting: false,
+        }
+    }
 
-        let mut g: MutexGuard<Option<Waker>>;
-        cfg_not_parking_lot_expr! {
-            g = self.inner.waker.lock().unwrap();
-            *g = Some(waker);
+    fn set_waiting(&mut self, waiting: bool) {
+        match (self.waiting, waiting) {
+            (false, true) => self.inner.waiters.fetch_add(1, Ordering::SeqCst),
+            (true, false) => self.inner.waiters.fetch_sub(1, Ordering::SeqCst),
+            _ => return,
         };
+        self.waiting = waiting;
+    }
+}
 
-        cfg_parking_lot_expr! {
-            g = self.inner.waker.lock();
-            *g = Some(waker);
+impl<I: Deref<Target = AsyncInner>> Drop for WaitGroupFuture<I> {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            lock(&self.inner.wakers).remove(id);
         }
+        self.set_waiting(false);
+    }
+}
+
+impl<I: Deref<Target = AsyncInner> + Unpin> Future for WaitGroupFuture<I> {
+    type Output = ();
 
-        match self.inner.count.load(Ordering::Relaxed) {
-            0 => Poll::Ready(()),
-            _ => Poll::Pending,
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let mut wakers = lock(&this.inner.wakers);
+        let count = this.inner.count.load(Ordering::Acquire);
+        match count {
+            0 => {
+                if let Some(id) = this.id.take() {
+                    wakers.remove(id);
+                }
+                drop(wakers);
+                this.set_waiting(false);
+                Poll::Ready(())
+            }
+            _ => {
+                wakers.register(&mut this.id, cx.waker());
+                drop(wakers);
+                this.set_waiting(true);
+                Poll::Pending
+            }
         }
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(loom), not(target_arch = "wasm32")))]
 mod test {
     use super::*;
     use std::sync::atomic::{AtomicUsize, Ordering};
@@ -694,4 +1070,252 @@ mod test {
         wg.add(1);
         assert_eq!(wg.waitings(), 2);
     }
+
+    #[tokio::test]
+    async fn test_async_wait_owned() {
+        let wg = AsyncWaitGroup::new();
+        let t_wg = wg.add(2);
+        let waiter = tokio::spawn(wg.wait_owned());
+        drop(wg);
+
+        t_wg.done();
+        tokio::task::yield_now().await;
+        assert!(!waiter.is_finished());
+        t_wg.done();
+        waiter.await.unwrap();
+    }
+
+    #[test]
+    fn test_try_add_done() {
+        let wg = WaitGroup::new();
+        assert_eq!(wg.try_done(), Err(WaitGroupMisuse::DoneOnZero));
+        wg.try_add(usize::MAX - 1).unwrap();
+        assert_eq!(
+            wg.try_add(2).unwrap_err(),
+            WaitGroupMisuse::Overflow {
+                count: usize::MAX - 1,
+                delta: 2
+            }
+        );
+        assert_eq!(wg.waitings(), usize::MAX - 1);
+
+        let awg = AsyncWaitGroup::new();
+        assert_eq!(awg.try_done(), Err(WaitGroupMisuse::DoneOnZero));
+        awg.try_add(usize::MAX).unwrap();
+        assert!(awg.try_add(1).is_err());
+        assert_eq!(awg.try_done(), Ok(()));
+        assert_eq!(awg.waitings(), usize::MAX - 1);
+    }
+
+    #[tokio::test]
+    async fn test_async_try_done_wakes() {
+        let wg = AsyncWaitGroup::strict();
+        let t_wg = wg.try_add(1).unwrap();
+        let waiter = tokio::spawn(wg.wait_owned());
+        tokio::task::yield_now().await;
+        t_wg.try_done().unwrap();
+        waiter.await.unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows")]
+    fn test_strict_add_overflow() {
+        let wg = AsyncWaitGroup::strict();
+        wg.add(usize::MAX);
+        wg.add(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "counter is zero")]
+    fn test_strict_done_on_zero() {
+        WaitGroup::strict().done();
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "counter is zero"))]
+    fn test_lenient_done_on_zero() {
+        let wg = WaitGroup::new();
+        wg.done();
+        // Release builds clamp the counter instead.
+        assert_eq!(wg.waitings(), 0);
+        wg.add(usize::MAX);
+        wg.add(1);
+        assert_eq!(wg.waitings(), usize::MAX);
+    }
+
+    #[test]
+    fn test_metrics() {
+        let wg = WaitGroup::new();
+        let t_wg = wg.add(2);
+        let waiter = {
+            let wg = wg.clone();
+            std::thread::spawn(move || wg.wait())
+        };
+        while wg.waiting_tasks() == 0 {
+            std::thread::yield_now();
+        }
+        assert_eq!(
+            wg.metrics(),
+            WaitGroupMetrics {
+                pending_jobs: 2,
+                waiting_tasks: 1
+            }
+        );
+        t_wg.done();
+        t_wg.done();
+        waiter.join().unwrap();
+        assert_eq!(wg.metrics(), WaitGroupMetrics::default());
+    }
+
+    #[tokio::test]
+    async fn test_async_metrics() {
+        let wg = AsyncWaitGroup::new();
+        let t_wg = wg.add(1);
+        let waiter = tokio::spawn(wg.wait_owned());
+        while wg.waiting_tasks() == 0 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(wg.pending_jobs(), 1);
+
+        // A dropped future no longer counts as waiting.
+        waiter.abort();
+        let _ = waiter.await;
+        assert_eq!(
+            wg.metrics(),
+            WaitGroupMetrics {
+                pending_jobs: 1,
+                waiting_tasks: 0
+            }
+        );
+
+        t_wg.done();
+        wg.wait().await;
+        assert_eq!(wg.metrics(), WaitGroupMetrics::default());
+    }
+
+    #[tokio::test]
+    async fn test_async_concurrent_waiters() {
+        let wg = AsyncWaitGroup::new();
+        let t_wg = wg.add(1);
+        let waiters = (0..2)
+            .map(|_| tokio::spawn(wg.wait_owned()))
+            .collect::<Vec<_>>();
+        while wg.waiting_tasks() < 2 {
+            tokio::task::yield_now().await;
+        }
+
+        t_wg.done();
+        for waiter in waiters {
+            tokio::time::timeout(Duration::from_secs(5), waiter)
+                .await
+                .expect("every waiter is woken")
+                .unwrap();
+        }
+        assert_eq!(wg.waiting_tasks(), 0);
+    }
+
+    #[test]
+    fn test_wait_group_tree() {
+        let root = WaitGroup::strict();
+        let child = root.child();
+        let grandchild = child.child();
+        assert_eq!(root.waitings(), 0);
+
+        let ctr = Arc::new(AtomicUsize::new(0));
+        for wg in [&child, &grandchild, &grandchild] {
+            let t_wg = wg.add(1);
+            let ctrx = ctr.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(20));
+                ctrx.fetch_add(1, Ordering::Relaxed);
+                t_wg.done();
+            });
+        }
+        // The child counts once, for its own job and its child.
+        assert_eq!(root.waitings(), 1);
+        assert_eq!(child.waitings(), 2);
+
+        root.wait();
+        assert_eq!(ctr.load(Ordering::Relaxed), 3);
+        assert_eq!(child.waitings(), 0);
+
+        // The tree can be reused.
+        grandchild.add(1);
+        assert_eq!(root.waitings(), 1);
+        grandchild.done();
+        assert_eq!(root.waitings(), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_async_wait_group_tree() {
+        let root = AsyncWaitGroup::strict();
+        let ctr = Arc::new(AtomicUsize::new(0));
+        for _ in 0..3 {
+            let child = root.child();
+            for _ in 0..10 {
+                let t_wg = child.add(1);
+                let ctrx = ctr.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                    ctrx.fetch_add(1, Ordering::Relaxed);
+                    t_wg.done();
+                });
+            }
+        }
+        root.wait().await;
+        assert_eq!(ctr.load(Ordering::Relaxed), 30);
+        assert_eq!(root.waitings(), 0);
+
+        let child = root.child();
+        assert!(child.try_add(usize::MAX).is_ok());
+        assert!(child.try_add(1).is_err());
+        assert_eq!(root.waitings(), 1);
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod wasm_test {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use wasm_bindgen_futures::spawn_local;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    async fn test_async_wait_group() {
+        let wg = AsyncWaitGroup::new();
+        let ctr = Rc::new(Cell::new(0));
+
+        for _ in 0..5 {
+            let ctr = ctr.clone();
+            let wg = wg.add(1);
+            spawn_local(async move {
+                ctr.set(ctr.get() + 1);
+                wg.done();
+            });
+        }
+        wg.wait().await;
+        assert_eq!(ctr.get(), 5);
+        assert_eq!(wg.waitings(), 0);
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_async_wait_owned() {
+        let wg = AsyncWaitGroup::new();
+        let t_wg = wg.add(1);
+        let waiter = wg.wait_owned();
+        drop(wg);
+
+        // The spawned task outlives this scope, so it reports back through another group.
+        let finished = AsyncWaitGroup::new();
+        let f_wg = finished.add(1);
+        spawn_local(async move {
+            waiter.await;
+            f_wg.done();
+        });
+        spawn_local(async move { t_wg.done() });
+
+        finished.wait().await;
+        assert_eq!(finished.waitings(), 0);
+    }
 }
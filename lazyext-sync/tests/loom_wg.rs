@@ -0,0 +1,80 @@
+//! Model-checked tests of the wait groups, run with:
+//!
+//! ```bash
+//! RUSTFLAGS="--cfg loom" cargo test -p lazyext-sync --test loom_wg --release
+//! ```
+#![cfg(loom)]
+
+use lazyext_sync::{AsyncWaitGroup, WaitGroup};
+use loom::sync::atomic::{AtomicUsize, Ordering};
+use loom::sync::Arc;
+use loom::thread;
+
+#[test]
+fn loom_wait_group() {
+    loom::model(|| {
+        let wg = WaitGroup::new();
+        let ctr = Arc::new(AtomicUsize::new(0));
+
+        let handles = (0..2)
+            .map(|_| {
+                let t_wg = wg.add(1);
+                let ctrx = ctr.clone();
+                thread::spawn(move || {
+                    ctrx.fetch_add(1, Ordering::Relaxed);
+                    t_wg.done();
+                })
+            })
+            .collect::<Vec<_>>();
+
+        wg.wait();
+        assert_eq!(ctr.load(Ordering::Relaxed), 2);
+        for h in handles {
+            h.join().unwrap();
+        }
+    });
+}
+
+#[test]
+fn loom_async_wait_group() {
+    loom::model(|| {
+        let wg = AsyncWaitGroup::new();
+        let ctr = Arc::new(AtomicUsize::new(0));
+
+        let handles = (0..2)
+            .map(|_| {
+                let t_wg = wg.add(1);
+                let ctrx = ctr.clone();
+                thread::spawn(move || {
+                    ctrx.fetch_add(1, Ordering::Relaxed);
+                    t_wg.done();
+                })
+            })
+            .collect::<Vec<_>>();
+
+        loom::future::block_on(wg.wait());
+        assert_eq!(ctr.load(Ordering::Relaxed), 2);
+        for h in handles {
+            h.join().unwrap();
+        }
+    });
+}
+
+#[test]
+fn loom_async_wait_group_child() {
+    loom::model(|| {
+        let root = AsyncWaitGroup::strict();
+        let child = root.child();
+        let t_wg = child.add(1);
+
+        // A job is added to the child while its first one finishes concurrently.
+        let handle = thread::spawn(move || t_wg.done());
+        let t_wg = child.add(1);
+        t_wg.done();
+
+        loom::future::block_on(root.wait());
+        handle.join().unwrap();
+        assert_eq!(root.waitings(), 0);
+        assert_eq!(child.waitings(), 0);
+    });
+}
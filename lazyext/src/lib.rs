@@ -1,13 +1,59 @@
 //! Tons of extension utility functions for Rust.
 //!
+//! This crate re-exports the lazyext crates behind cargo features, so depending on it alone is enough:
+//!
+//! - `slice`: [`slice_ext`] (`lazyext-slice`), with its `std`, `alloc`, `bytes` and `slice-*` features forwarded.
+//! - `str`: [`str_ext`] (`lazyext-str`).
+//! - `sync`: [`sync`] (`lazyext-sync`), with its `parking_lot` feature forwarded.
+//! - `macros`: [`macros`] (`lazyext-macros`).
+//! - `derive`: [`derive`](mod@derive) (`lazyext-derive`).
+//!
+//! All of them are enabled by the default `full` feature.
+//!
+//! The macros and derives expand to paths of `lazyext_slice`, so crates using them must depend on
+//! `lazyext-slice` too.
 #![cfg_attr(not(feature = "std"), no_std)]
 #![doc(html_root_url = "https://docs.rs/lazyext/0.0.3")]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![cfg_attr(docsrs, allow(unused_attributes))]
 #![deny(missing_docs)]
 
+#[cfg(feature = "slice")]
+#[cfg_attr(docsrs, doc(cfg(feature = "slice")))]
 pub use lazyext_slice as slice_ext;
 
-// util macros
-// #[cfg(feature = "lazyext-macros")]
-// pub use lazyext_macros::{cfg_test, cfg_unix, cfg_windows};
+#[cfg(feature = "str")]
+#[cfg_attr(docsrs, doc(cfg(feature = "str")))]
+pub use lazyext_str as str_ext;
+
+#[cfg(feature = "sync")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sync")))]
+pub use lazyext_sync as sync;
+
+#[cfg(feature = "macros")]
+#[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
+pub use lazyext_macros as macros;
+
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use lazyext_derive as derive;
+
+/// Re-exports all the extension traits of the enabled lazyext crates, so they can be imported in one line.
+///
+/// # Example
+///
+/// ```rust
+/// use lazyext::prelude::*;
+///
+/// assert!(b"hello".has_prefix("he"));
+/// ```
+pub mod prelude {
+    #[cfg(feature = "slice")]
+    pub use lazyext_slice::prelude::*;
+
+    #[cfg(feature = "str")]
+    pub use lazyext_str::prelude::*;
+
+    #[cfg(feature = "sync")]
+    pub use lazyext_sync::MutexExt;
+}